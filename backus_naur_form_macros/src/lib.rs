@@ -0,0 +1,233 @@
+//! Proc macros for backus_naur_form_parser_and_compiler.
+//! Use them through the `macros` feature of backus_naur_form_parser_and_compiler instead of depending on this crate directly.
+
+use backus_naur_form_rule_parser::{
+    choice_weights, inline_choices, node_label, parse_rule, unsupported_array_choice, ParsedSymbol,
+    RuleError,
+};
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::quote;
+use syn::{
+    parse::{Parse, ParseStream},
+    parse_macro_input, Expr, Ident, LitStr, Token,
+};
+
+//A arm of the macro: `priority [priority] => [optional doc "doc string" =>] [rule] => [optional compile function]`
+struct Arm {
+    priority: Expr,
+    doc: Option<LitStr>,
+    rule: LitStr,
+    function_body: Option<Expr>,
+}
+
+impl Parse for Arm {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let keyword: Ident = input.parse()?;
+        if keyword != "priority" {
+            return Err(syn::Error::new(
+                keyword.span(),
+                "expected `priority`, every arm starts with `priority [number] =>`",
+            ));
+        }
+        if input.peek(Token![=>]) {
+            return Err(input.error("the priority is missing, expected `priority [number] =>`"));
+        }
+        let priority = input.parse()?;
+        input.parse::<Token![=>]>().map_err(|error| {
+            syn::Error::new(
+                error.span(),
+                "expected `=>` between the priority and the rule",
+            )
+        })?;
+        let doc = if input.peek(Ident) && input.fork().parse::<Ident>()? == "doc" {
+            input.parse::<Ident>()?;
+            let doc = input.parse()?;
+            input.parse::<Token![=>]>().map_err(|error| {
+                syn::Error::new(
+                    error.span(),
+                    "expected `=>` between the doc string and the rule",
+                )
+            })?;
+            Some(doc)
+        } else {
+            None
+        };
+        let rule = input.parse()?;
+        let function_body = if input.peek(Token![=>]) {
+            input.parse::<Token![=>]>()?;
+            let function_body: Expr = input.parse()?;
+            //compile functions take the token and the backus naur form
+            if let Expr::Closure(closure) = &function_body {
+                if closure.inputs.len() != 2 {
+                    return Err(syn::Error::new(
+                        closure.or2_token.span,
+                        format!(
+                            "the compile function takes {} argument(s), but it has to take two: the token and the backus naur form (for example `|token, _bnf| ...`)",
+                            closure.inputs.len()
+                        ),
+                    ));
+                }
+            }
+            Some(function_body)
+        } else {
+            None
+        };
+        Ok(Self {
+            priority,
+            doc,
+            rule,
+            function_body,
+        })
+    }
+}
+
+struct Arms(Vec<Arm>);
+
+impl Parse for Arms {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut arms = vec![];
+        while !input.is_empty() {
+            arms.push(input.parse()?);
+        }
+        Ok(Self(arms))
+    }
+}
+
+//Returns the span of the character at the position in the rule if the compiler supports it.
+//Otherwise the span of the whole rule is returned.
+fn span_in_rule(rule: &LitStr, error: &RuleError) -> Span {
+    let literal = rule.token();
+    let text = literal.to_string();
+    //the position can only be mapped for raw strings since other strings may contain escapes
+    match (text.starts_with('r'), text.find('"')) {
+        (true, Some(content_start)) => {
+            let start = content_start + 1 + error.position;
+            literal.subspan(start..start + 1).unwrap_or(rule.span())
+        }
+        _ => rule.span(),
+    }
+}
+
+///The same as the `backus_naur_form!` macro, but the rules are parsed at compile time.
+///Malformed rules are reported as compile errors that point at the rule,
+///and the resulting `BackusNaurForm` is built from already parsed rules, so no rule is parsed at runtime.
+///```rust, ignore
+///let bnf = static_backus_naur_form!(
+///    priority 0 => r#"<digit> ::= "1" | "2""# => |digit, _bnf| digit.get_terminals()
+///    priority 0 => doc "A number with any amount of digits" => r#"<number> ::= <digit> | <number> <number>"#
+///);
+///```
+#[proc_macro]
+pub fn static_backus_naur_form(input: TokenStream) -> TokenStream {
+    let Arms(arms) = parse_macro_input!(input as Arms);
+    let krate = quote!(::backus_naur_form_parser_and_compiler);
+
+    let mut statements = vec![];
+    for Arm {
+        priority,
+        doc,
+        rule,
+        function_body,
+    } in arms
+    {
+        let parsed_rules = match parse_rule(&rule.value()) {
+            Ok(parsed_rules) => parsed_rules,
+            Err(error) => {
+                let message = format!(
+                    "invalid rule: {} (at byte {} of the rule)",
+                    error.message, error.position
+                );
+                return syn::Error::new(span_in_rule(&rule, &error), message)
+                    .to_compile_error()
+                    .into();
+            }
+        };
+
+        if let Some(choice) = unsupported_array_choice(&parsed_rules[0]) {
+            let name = &parsed_rules[0].name;
+            let choice = choice
+                .iter()
+                .map(|symbol| match symbol {
+                    ParsedSymbol::Terminal(terminal) => format!("\"{terminal}\""),
+                    ParsedSymbol::NonTerminal(non_terminal) => format!("<{non_terminal}>"),
+                })
+                .collect::<Vec<_>>()
+                .join(" ");
+            let message = format!(
+                "the choice {choice} of <{name}> never matches because its symbols are turned into <{name}> on their own first, \
+write it as <{name}> <{name}> instead"
+            );
+            return syn::Error::new(rule.span(), message)
+                .to_compile_error()
+                .into();
+        }
+
+        let rule_value = rule.value();
+        let label = node_label(&rule_value);
+        if let Some(label) = label {
+            let name = &parsed_rules[0].name;
+            statements.push(quote! {
+                bnf.set_node_label(#name, #label);
+            });
+        }
+        if let Some(function_body) = function_body {
+            //the tokens of the rule are compiled under their label
+            let name = label.unwrap_or(&parsed_rules[0].name);
+            statements.push(quote! {
+                bnf.add_compile_function(#name, &#function_body);
+            });
+        }
+        if let Some(doc) = doc {
+            let name = &parsed_rules[0].name;
+            statements.push(quote! {
+                bnf.set_rule_doc(#name, #doc);
+            });
+        }
+        //the rule parsed, so finding the inline choices can't fail either
+        let inline_choices = inline_choices(&rule_value).unwrap_or_default();
+        let weights = choice_weights(&rule_value).unwrap_or_default();
+        for (index, parsed_rule) in parsed_rules.into_iter().enumerate() {
+            let name = parsed_rule.name;
+            let choices = parsed_rule.expression.into_iter().map(|choice| {
+                let symbols = choice.into_iter().map(|symbol| match symbol {
+                    ParsedSymbol::Terminal(terminal) => {
+                        quote!(#krate::Symbol::Terminal(#terminal.to_string()))
+                    }
+                    ParsedSymbol::NonTerminal(non_terminal) => {
+                        quote!(#krate::Symbol::NonTerminal(#non_terminal.to_string()))
+                    }
+                });
+                //collected, since a choice is a Vec or a SmallVec depending on the features of the crate
+                quote!([#(#symbols),*].into_iter().collect())
+            });
+            let choices = choices.collect::<Vec<_>>();
+            statements.push(quote! {
+                bnf.add_rule_from_expression(#name, vec![#(#choices),*], #priority);
+            });
+            //the inline choices belong to the rule itself, not to its inline groups
+            if index == 0 {
+                for choice in inline_choices.iter().map(|index| &choices[*index]) {
+                    statements.push(quote! {
+                        bnf.add_inline_choice(#name, #choice);
+                    });
+                }
+                for (choice, weight) in weights
+                    .iter()
+                    .map(|(index, weight)| (&choices[*index], weight))
+                {
+                    statements.push(quote! {
+                        bnf.set_choice_weight(#name, #choice, #weight);
+                    });
+                }
+            }
+        }
+    }
+
+    quote! {{
+        let mut bnf = #krate::BackusNaurForm::default();
+        #(#statements)*
+        bnf
+    }}
+    .into()
+}
@@ -0,0 +1,498 @@
+//! Parses backus naur form rules like `<stmt> ::= ("let" | "const") <ident>`.
+//! backus_naur_form_parser_and_compiler parses the rules with it at runtime and its static_backus_naur_form! macro at compile time,
+//! so both accept the same rules. Every malformed part of a rule is reported with its position.
+//! Use it through backus_naur_form_parser_and_compiler instead of depending on this crate directly.
+
+use std::{error::Error, fmt::Display};
+
+///A symbol of a choice.
+#[derive(PartialEq, Debug)]
+pub enum ParsedSymbol {
+    Terminal(String),
+    NonTerminal(String),
+}
+
+///A rule split into its name and its choices.
+#[derive(PartialEq, Debug)]
+pub struct ParsedRule {
+    pub name: String,
+    pub expression: Vec<Vec<ParsedSymbol>>,
+}
+
+///A rule with its inline groups, the indexes of its inline choices and the weights of its choices.
+#[derive(PartialEq, Debug)]
+pub struct ParsedRules {
+    ///The rule itself first, then its inline groups, see [parse_rule].
+    pub rules: Vec<ParsedRule>,
+    ///See [inline_choices].
+    pub inline_choices: Vec<usize>,
+    ///See [choice_weights].
+    pub weights: Vec<(usize, f64)>,
+}
+
+///A error in a rule. The position is the byte offset in the rule where the error is.
+#[derive(PartialEq, Debug)]
+pub struct RuleError {
+    pub message: String,
+    pub position: usize,
+}
+
+impl RuleError {
+    fn new(position: usize, message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+            position,
+        }
+    }
+}
+
+impl Display for RuleError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} at position {}", self.message, self.position)
+    }
+}
+
+impl Error for RuleError {}
+
+///Parses a rule like `<stmt> ::= ("let" | "const") <ident>`.
+///The first [ParsedRule] is the rule itself, the others are the inline groups which are lifted into their own hidden rules
+///(for example `<_stmt-group-0> ::= "let" | "const"`).
+pub fn parse_rule(rule: &str) -> Result<Vec<ParsedRule>, RuleError> {
+    parse_rules(rule).map(|parsed| parsed.rules)
+}
+
+///Returns the indexes of the choices of the rule that start with `@inline` (for example `[0]` for `<expr> ::= @inline <term> | "1"`).
+pub fn inline_choices(rule: &str) -> Result<Vec<usize>, RuleError> {
+    parse_rules(rule).map(|parsed| parsed.inline_choices)
+}
+
+///Returns the index and the weight (in percent) of every choice of the rule that starts with a weight
+///(for example `[(0, 90.0)]` for `<digit> ::= 90% "0" | "1"`).
+pub fn choice_weights(rule: &str) -> Result<Vec<(usize, f64)>, RuleError> {
+    parse_rules(rule).map(|parsed| parsed.weights)
+}
+
+///Returns the first choice of a "array" rule that never matches, like `<number> <digit>` in `<number> ::= <digit> | <number> <digit>`:
+///every `<digit>` is turned into a `<number>` on its own first. Such choices have to be written as `<number> <number>`.
+pub fn unsupported_array_choice(rule: &ParsedRule) -> Option<&[ParsedSymbol]> {
+    let is_self = |symbol: &ParsedSymbol| matches!(symbol, ParsedSymbol::NonTerminal(name) if *name == rule.name);
+    //the symbols that are turned into the symbol of the rule on their own, like <digit> in <number> ::= <digit>
+    let renamed = rule
+        .expression
+        .iter()
+        .filter_map(|choice| match choice.as_slice() {
+            [symbol @ ParsedSymbol::NonTerminal(_)] if !is_self(symbol) => Some(symbol),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    rule.expression
+        .iter()
+        .find(|choice| match choice.as_slice() {
+            [first, second] => {
+                (is_self(first) && renamed.contains(&second))
+                    || (renamed.contains(&first) && is_self(second))
+            }
+            _ => false,
+        })
+        .map(Vec::as_slice)
+}
+
+///Parses the rule into the rule itself and its inline groups, the indexes of the inline choices of the rule
+///and the weights of its choices.
+pub fn parse_rules(rule: &str) -> Result<ParsedRules, RuleError> {
+    let Some(operator_index) = rule.find("::=") else {
+        return Err(RuleError::new(
+            0,
+            "the replacement operator (::=) is missing in the rule",
+        ));
+    };
+    let (name, _) = split_node_label(rule[..operator_index].trim());
+    let name_start = rule.find(|ch: char| !ch.is_whitespace()).unwrap_or(0);
+    let Some(name) = name
+        .strip_prefix('<')
+        .and_then(|name| name.strip_suffix('>'))
+        .filter(|name| !name.is_empty())
+    else {
+        return Err(RuleError::new(
+            name_start,
+            "the name of the rule must be in angle brackets (for example <digit>)",
+        ));
+    };
+
+    //the base name and the parameters of the rule (for example "list" and "(x)" for `<list(x)>`)
+    let (base_name, parameters) = match name.find('(') {
+        Some(index) => name.split_at(index),
+        None => (name, ""),
+    };
+    let expression_start = operator_index + "::=".len();
+    let mut parser = Parser {
+        chars: rule[expression_start..]
+            .char_indices()
+            .map(|(index, ch)| (expression_start + index, ch))
+            .collect(),
+        index: 0,
+        base_name,
+        parameters,
+        groups: vec![],
+        inline_choices: vec![],
+        weights: vec![],
+    };
+    let expression = parser.parse_expression(None)?;
+
+    let rules = std::iter::once(ParsedRule {
+        name: name.to_string(),
+        expression,
+    })
+    .chain(parser.groups)
+    .collect();
+    Ok(ParsedRules {
+        rules,
+        inline_choices: parser.inline_choices,
+        weights: parser.weights,
+    })
+}
+
+///Returns the node label of a rule like `<additive-expression> -> Expr ::= ...` (`Expr`), or None if it has none.
+pub fn node_label(rule: &str) -> Option<&str> {
+    let operator_index = rule.find("::=")?;
+    split_node_label(rule[..operator_index].trim()).1
+}
+
+//Splits the name of a rule (everything in front of the ::=) into the name in angle brackets and the label after the `->`.
+//Names that end with a angle bracket have no label, so names that contain a "->" stay the same.
+fn split_node_label(name: &str) -> (&str, Option<&str>) {
+    if name.ends_with('>') {
+        return (name, None);
+    }
+    match name.rsplit_once("->") {
+        Some((name, label)) if !label.trim().is_empty() => (name.trim_end(), Some(label.trim())),
+        _ => (name, None),
+    }
+}
+
+struct Parser<'a> {
+    chars: Vec<(usize, char)>,
+    index: usize,
+    base_name: &'a str,
+    parameters: &'a str,
+    groups: Vec<ParsedRule>,
+    //the indexes of the choices of the rule that start with @inline
+    inline_choices: Vec<usize>,
+    //the indexes and the weights of the choices of the rule that start with a weight like 90%
+    weights: Vec<(usize, f64)>,
+}
+
+impl Parser<'_> {
+    fn next(&mut self) -> Option<(usize, char)> {
+        let next = self.chars.get(self.index).copied();
+        self.index += 1;
+        next
+    }
+
+    //Reads everything until the closing character and returns it.
+    fn read_until(&mut self, start: usize, closing: char, what: &str) -> Result<String, RuleError> {
+        let mut content = String::new();
+        loop {
+            match self.next() {
+                Some((_, ch)) if ch == closing => return Ok(content),
+                Some((_, ch)) => content.push(ch),
+                None => {
+                    return Err(RuleError::new(
+                        start,
+                        format!("the {what} is never closed (missing {closing})"),
+                    ))
+                }
+            }
+        }
+    }
+
+    //Parses the choices until the end of the rule or the end of the group that started at group_start.
+    fn parse_expression(
+        &mut self,
+        group_start: Option<usize>,
+    ) -> Result<Vec<Vec<ParsedSymbol>>, RuleError> {
+        let mut expression = vec![vec![]];
+
+        loop {
+            let Some((position, ch)) = self.next() else {
+                if let Some(group_start) = group_start {
+                    return Err(RuleError::new(group_start, "the group is never closed"));
+                }
+                break;
+            };
+            let choice = expression.last_mut().expect("there is always a choice");
+            match ch {
+                ch if ch.is_whitespace() => (),
+                '|' => expression.push(vec![]),
+                //ε marks a empty choice, it matches nothing
+                'ε' => (),
+                '"' => choice.push(ParsedSymbol::Terminal(self.read_until(
+                    position,
+                    '"',
+                    "string",
+                )?)),
+                '<' => {
+                    let name = self.read_until(position, '>', "non terminal")?;
+                    if name.is_empty() {
+                        return Err(RuleError::new(position, "the non terminal has no name"));
+                    }
+                    choice.push(ParsedSymbol::NonTerminal(name));
+                }
+                '(' => {
+                    let group = self.parse_expression(Some(position))?;
+                    let name = format!(
+                        "_{}-group-{}{}",
+                        self.base_name,
+                        self.groups.len(),
+                        self.parameters
+                    );
+                    self.groups.push(ParsedRule {
+                        name: name.to_string(),
+                        expression: group,
+                    });
+                    expression
+                        .last_mut()
+                        .expect("there is always a choice")
+                        .push(ParsedSymbol::NonTerminal(name));
+                }
+                //byte values like %x0D.0A, every byte is a terminal of its own
+                '%' => {
+                    let mut value = String::new();
+                    while let Some((_, ch)) = self.chars.get(self.index) {
+                        if !ch.is_ascii_alphanumeric() && *ch != '.' {
+                            break;
+                        }
+                        value.push(*ch);
+                        self.index += 1;
+                    }
+                    let bytes = parse_byte_values(&value)
+                        .map_err(|message| RuleError::new(position, message))?;
+                    choice.extend(
+                        bytes
+                            .into_iter()
+                            .map(|byte| ParsedSymbol::Terminal(char::from(byte).to_string())),
+                    );
+                }
+                //only the choices of the rule itself can be inline, at their start
+                '@' if group_start.is_none() => {
+                    let annotation = self.chars[self.index..]
+                        .iter()
+                        .take("inline".len())
+                        .map(|(_, ch)| ch)
+                        .collect::<String>();
+                    if annotation != "inline" {
+                        return Err(RuleError::new(
+                            position,
+                            "unknown annotation, expected @inline",
+                        ));
+                    }
+                    if !choice.is_empty() {
+                        return Err(RuleError::new(
+                            position,
+                            "@inline has to be at the start of a choice",
+                        ));
+                    }
+                    self.index += "inline".len();
+                    self.inline_choices.push(expression.len() - 1);
+                }
+                //only the choices of the rule itself can have a weight, in front of their symbols
+                ch if ch.is_ascii_digit() && group_start.is_none() && choice.is_empty() => {
+                    let mut number = ch.to_string();
+                    while let Some((_, ch)) = self.chars.get(self.index) {
+                        if !ch.is_ascii_digit() && *ch != '.' {
+                            break;
+                        }
+                        number.push(*ch);
+                        self.index += 1;
+                    }
+                    let weight = number.parse::<f64>().ok().filter(|_| {
+                        matches!(self.chars.get(self.index), Some((_, '%')))
+                    });
+                    let Some(weight) = weight else {
+                        return Err(RuleError::new(
+                            position,
+                            "a weight has to be a number followed by a percent sign (for example 90%)",
+                        ));
+                    };
+                    self.index += 1;
+                    self.weights.push((expression.len() - 1, weight));
+                }
+                ')' if group_start.is_some() => break,
+                ')' => {
+                    return Err(RuleError::new(
+                        position,
+                        "the closing parenthesis has no opening parenthesis",
+                    ))
+                }
+                ch => {
+                    return Err(RuleError::new(
+                        position,
+                        format!("unexpected character '{ch}', expected a terminal in double quotes, a non terminal in angle brackets, a byte value, a |, a ε, a group in parentheses or a weight at the start of a choice"),
+                    ))
+                }
+            }
+        }
+
+        Ok(expression)
+    }
+}
+
+///Parses a byte value like `x0D.0A` or `d13.10` (without the `%`) into its bytes.
+pub fn parse_byte_values(value: &str) -> Result<Vec<u8>, String> {
+    let (radix, digits) = match value.split_at_checked(1) {
+        Some(("x", digits)) => (16, digits),
+        Some(("d", digits)) => (10, digits),
+        _ => {
+            return Err(format!(
+                "the byte value %{value} must start with %x (hexadecimal) or %d (decimal)"
+            ))
+        }
+    };
+    digits
+        .split('.')
+        .map(|byte| {
+            u8::from_str_radix(byte, radix)
+                .map_err(|_| format!("\"{byte}\" in %{value} is not a valid byte"))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn terminal(terminal: &str) -> ParsedSymbol {
+        ParsedSymbol::Terminal(terminal.to_string())
+    }
+
+    fn non_terminal(non_terminal: &str) -> ParsedSymbol {
+        ParsedSymbol::NonTerminal(non_terminal.to_string())
+    }
+
+    #[test]
+    fn test_parse_rule() {
+        assert_eq!(
+            parse_rule(r#"<stmt> ::= ("let" | "const" ("!" | "?")) <ident> | ")""#),
+            Ok(vec![
+                ParsedRule {
+                    name: "stmt".to_string(),
+                    expression: vec![
+                        vec![non_terminal("_stmt-group-1"), non_terminal("ident")],
+                        vec![terminal(")")]
+                    ]
+                },
+                ParsedRule {
+                    name: "_stmt-group-0".to_string(),
+                    expression: vec![vec![terminal("!")], vec![terminal("?")]]
+                },
+                ParsedRule {
+                    name: "_stmt-group-1".to_string(),
+                    expression: vec![
+                        vec![terminal("let")],
+                        vec![terminal("const"), non_terminal("_stmt-group-0")]
+                    ]
+                },
+            ])
+        );
+    }
+
+    #[test]
+    fn test_empty_choices() {
+        assert_eq!(
+            parse_rule(r#"<sign> ::= "+" | | ("-" | ε)"#),
+            Ok(vec![
+                ParsedRule {
+                    name: "sign".to_string(),
+                    expression: vec![
+                        vec![terminal("+")],
+                        vec![],
+                        vec![non_terminal("_sign-group-0")]
+                    ]
+                },
+                ParsedRule {
+                    name: "_sign-group-0".to_string(),
+                    expression: vec![vec![terminal("-")], vec![]]
+                },
+            ])
+        );
+    }
+
+    #[test]
+    fn test_byte_values() {
+        assert_eq!(
+            parse_rule(r#"<crlf> ::= %x0D.0A|%d0 "a""#),
+            Ok(vec![ParsedRule {
+                name: "crlf".to_string(),
+                expression: vec![
+                    vec![terminal("\r"), terminal("\n")],
+                    vec![terminal("\0"), terminal("a")]
+                ]
+            }])
+        );
+    }
+
+    #[test]
+    fn test_node_labels() {
+        let rule = r#"<additive-expression> -> Expr ::= <term> "+" <term>"#;
+        assert_eq!(node_label(rule), Some("Expr"));
+        assert_eq!(parse_rule(rule).unwrap()[0].name, "additive-expression");
+        assert_eq!(node_label(r#"<a->b> ::= "->""#), None);
+        assert_eq!(parse_rule(r#"<a->b> ::= "->""#).unwrap()[0].name, "a->b");
+    }
+
+    #[test]
+    fn test_inline_choices() {
+        assert_eq!(
+            inline_choices(r#"<expr> ::= @inline <term> | "@inline" | @inline ("1" | "2")"#),
+            Ok(vec![0, 2])
+        );
+        assert_eq!(
+            parse_rule(r#"<expr> ::= @inline <term>"#),
+            parse_rule(r#"<expr> ::= <term>"#)
+        );
+        let position = |rule| inline_choices(rule).unwrap_err().position;
+        assert_eq!(position(r#"<expr> ::= <term> @inline"#), 18);
+        assert_eq!(position(r#"<expr> ::= @outline"#), 11);
+        assert_eq!(position(r#"<expr> ::= ("1" | @inline "2")"#), 18);
+    }
+
+    #[test]
+    fn test_choice_weights() {
+        assert_eq!(
+            choice_weights(r#"<digit> ::= 90% "0" | "1" | 2.5%"2" | "3%""#),
+            Ok(vec![(0, 90.0), (2, 2.5)])
+        );
+        assert_eq!(
+            parse_rule(r#"<digit> ::= 90% "0" | "1""#),
+            parse_rule(r#"<digit> ::= "0" | "1""#)
+        );
+        let position = |rule| choice_weights(rule).unwrap_err().position;
+        assert_eq!(position(r#"<digit> ::= 90 "0""#), 12);
+        assert_eq!(position(r#"<digit> ::= "0" 90%"#), 16);
+        assert_eq!(position(r#"<digit> ::= ("0" | 90% "1")"#), 19);
+    }
+
+    #[test]
+    fn test_unsupported_array_choice() {
+        let parsed_rules = parse_rule(r#"<number> ::= <digit> | <digit> <number>"#).unwrap();
+        assert_eq!(
+            unsupported_array_choice(&parsed_rules[0]),
+            Some(&[non_terminal("digit"), non_terminal("number")][..])
+        );
+        let is_supported = |rule| unsupported_array_choice(&parse_rule(rule).unwrap()[0]).is_none();
+        assert!(is_supported(r#"<number> ::= <digit> | <number> <number>"#));
+        assert!(is_supported(r#"<sum> ::= <digit> | <sum> "+""#));
+    }
+
+    #[test]
+    fn test_rule_errors() {
+        let position = |rule| parse_rule(rule).unwrap_err().position;
+
+        assert_eq!(position(r#"<digit> "1""#), 0);
+        assert_eq!(position(r#"digit ::= "1""#), 0);
+        assert_eq!(position(r#"<digit> ::= "1" | "2"#), 18);
+        assert_eq!(position(r#"<digit> ::= ("1" "2""#), 12);
+        assert_eq!(position(r#"<digit> ::= "1" 2"#), 16);
+        assert_eq!(position(r#"<byte> ::= %x1FF"#), 11);
+    }
+}
@@ -0,0 +1,120 @@
+//! Measures how long symbolizing takes for a few typical grammars.
+//! Run it with `cargo bench`, optionally with a filter like `cargo bench -- arithmetic`.
+//! Run it with `--features smallvec` (or other features) to compare the features.
+
+use std::{
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+use backus_naur_form_parser_and_compiler::{backus_naur_form, BackusNaurForm};
+
+//How long every benchmark runs at least.
+const MEASUREMENT_TIME: Duration = Duration::from_secs(2);
+
+fn arithmetic() -> BackusNaurForm<'static> {
+    backus_naur_form!(
+        priority 3 => r#"<digit> ::= "0" | "1" | "2" | "3" | "4" | "5" | "6" | "7" | "8" | "9""#
+        priority 2 => r#"<number> ::= <digit> | <number> <number>"#
+        priority 1 => r#"<product> ::= <number> "*" <number> | <product> "*" <number>"#
+        priority 0 => r#"<sum> ::= <number> "+" <number> | <product> "+" <number> | <sum> "+" <number> | <sum> "+" <product>"#
+    )
+}
+
+//Returns a input like `12*34+5*67+...` that is about length chars long.
+fn arithmetic_input(length: usize) -> String {
+    let mut input = "12*34".to_string();
+    while input.len() < length {
+        input.push_str("+5*67");
+    }
+    input
+}
+
+//The keywords of the keywords grammar.
+const KEYWORDS: [&str; 38] = [
+    "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum", "extern",
+    "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub",
+    "ref", "return", "self", "static", "struct", "super", "trait", "true", "type", "unsafe", "use",
+    "where", "while", "yield",
+];
+
+//A grammar with a lot of keywords, every keyword is a sequence of single char terminals.
+fn keywords() -> BackusNaurForm<'static> {
+    let keywords = KEYWORDS
+        .iter()
+        .map(|keyword| {
+            keyword
+                .chars()
+                .map(|char| format!(r#""{char}""#))
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+        .collect::<Vec<_>>()
+        .join(" | ");
+
+    let mut bnf = BackusNaurForm::default();
+    bnf.add_non_terminal_symbol_from_rule(&format!("<keyword> ::= {keywords}"), 3);
+    bnf.add_non_terminal_symbol_from_rule(r#"<name> ::= "x" | "y" | "z" | <name> <name>"#, 2);
+    bnf.add_non_terminal_symbol_from_rule(
+        r#"<statement> ::= <keyword> " " <name> ";" | <keyword> ";""#,
+        1,
+    );
+    bnf.add_non_terminal_symbol_from_rule(r#"<program> ::= <statement> | <program> <program>"#, 0);
+    bnf
+}
+
+//Returns a input like `as x;async yz;...` (every keyword once, then again) that is about length chars long.
+fn keywords_input(length: usize) -> String {
+    let names = ["x", "yz", "zxy"];
+    let mut input = String::new();
+    for (keyword, name) in KEYWORDS.iter().cycle().zip(names.iter().cycle()) {
+        if input.len() >= length {
+            break;
+        }
+        input.push_str(&format!("{keyword} {name};"));
+    }
+    input
+}
+
+//Runs f until MEASUREMENT_TIME is over and prints the mean time per run.
+fn bench(name: &str, filter: &Option<String>, mut f: impl FnMut()) {
+    if filter
+        .as_ref()
+        .is_some_and(|filter| !name.contains(filter.as_str()))
+    {
+        return;
+    }
+    //warm up
+    f();
+    let start = Instant::now();
+    let mut runs = 0;
+    while start.elapsed() < MEASUREMENT_TIME {
+        f();
+        runs += 1;
+    }
+    println!(
+        "{name:<30} {:>12.3?} per run ({runs} runs)",
+        start.elapsed() / runs
+    );
+}
+
+fn main() {
+    //cargo bench passes --bench, which isn't a filter
+    let filter = std::env::args().skip(1).find(|arg| !arg.starts_with("--"));
+
+    let bnf = arithmetic();
+    for length in [100, 1_000] {
+        let input = arithmetic_input(length);
+        bench(&format!("arithmetic/{length}"), &filter, || {
+            black_box(bnf.symbolize_string(black_box(&input)));
+        });
+    }
+
+    let bnf = keywords();
+    for length in [100, 1_000] {
+        let input = keywords_input(length);
+        bench(&format!("keywords/{length}"), &filter, || {
+            black_box(bnf.symbolize_string(black_box(&input)));
+        });
+    }
+}
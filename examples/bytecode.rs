@@ -0,0 +1,125 @@
+//! Compiles arithmetic into the bytecode of a small stack machine instead of a [String],
+//! using [NonTerminalToken::evaluate] to turn every token into the instructions that compute it.
+//! The machine is included, so the bytecode can be run right away.
+//!
+//! The left operand of `-` is pushed first and popped last, so the example only computes the right result
+//! if the children are evaluated from left to right. This makes it a test for that guarantee too.
+//!
+//! Run it with `cargo run --example bytecode -- "10-2*3-1"`.
+
+use backus_naur_form_parser_and_compiler::{
+    backus_naur_form, backus_naur_form::token::non_terminal_token::NonTerminalToken, BackusNaurForm,
+};
+
+fn grammar() -> BackusNaurForm<'static> {
+    backus_naur_form!(
+        priority 3 => r#"<digit> ::= "0" | "1" | "2" | "3" | "4" | "5" | "6" | "7" | "8" | "9""#
+        priority 2 => r#"<number> ::= <digit> | <number> <number>"#
+        priority 1 => r#"<product> ::= <number> "*" <number> | <product> "*" <number>"#
+        priority 1 => r#"<operator> ::= "+" | "-""#
+        priority 0 => r#"<expression> ::= <number> | <product> | <expression> <operator> <expression>"#
+    )
+}
+
+///A instruction of the stack machine.
+#[derive(PartialEq, Clone, Copy, Debug)]
+enum Instruction {
+    ///Pushes the value.
+    Push(i64),
+    ///Pops two values and pushes their sum.
+    Add,
+    ///Pops the right and then the left operand and pushes their difference.
+    Sub,
+    ///Pops two values and pushes their product.
+    Mul,
+}
+
+//Compiles a <expression> into bytecode that leaves its value on the stack.
+fn compile(expression: &NonTerminalToken) -> Vec<Instruction> {
+    expression.evaluate(|token, children: Vec<Vec<Instruction>>| {
+        match token.non_terminal_symbol.as_str() {
+            "number" => vec![Instruction::Push(
+                token
+                    .get_terminals()
+                    .parse()
+                    .expect("a number only contains digits"),
+            )],
+            //the digits are already part of the number
+            "digit" => vec![],
+            "operator" => match token.get_terminals().as_str() {
+                "+" => vec![Instruction::Add],
+                _ => vec![Instruction::Sub],
+            },
+            "product" => [children.concat(), vec![Instruction::Mul]].concat(),
+            //<expression> <operator> <expression>, the operator is applied after both operands
+            _ if children.len() == 3 => [&children[0][..], &children[2], &children[1]].concat(),
+            _ => children.concat(),
+        }
+    })
+}
+
+//Runs the bytecode and returns the value at the top of the stack.
+fn run(bytecode: &[Instruction]) -> Option<i64> {
+    let mut stack = vec![];
+    for instruction in bytecode {
+        let value = match instruction {
+            Instruction::Push(value) => *value,
+            operator => {
+                let rhs = stack.pop()?;
+                let lhs = stack.pop()?;
+                match operator {
+                    Instruction::Add => lhs + rhs,
+                    Instruction::Sub => lhs - rhs,
+                    _ => lhs * rhs,
+                }
+            }
+        };
+        stack.push(value);
+    }
+    stack.pop()
+}
+
+fn main() {
+    let input = std::env::args().nth(1).unwrap_or("10-2*3-1".to_string());
+    let bnf = grammar();
+    let ast = match bnf.parse(&input) {
+        Ok(ast) => ast,
+        Err(error) => {
+            eprintln!("{error}");
+            return;
+        }
+    };
+    let bytecode = compile(&ast);
+    for instruction in &bytecode {
+        println!("{instruction:?}");
+    }
+    println!("{input} = {:?}", run(&bytecode));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn compile_and_run(input: &str) -> Option<i64> {
+        run(&compile(&grammar().parse(input).unwrap()))
+    }
+
+    #[test]
+    fn test_compile() {
+        use Instruction::*;
+
+        assert_eq!(
+            compile(&grammar().parse("12-3*4").unwrap()),
+            vec![Push(12), Push(3), Push(4), Mul, Sub]
+        );
+    }
+
+    #[test]
+    fn test_run() {
+        assert_eq!(compile_and_run("42"), Some(42));
+        assert_eq!(compile_and_run("9-2-3"), Some(4));
+        assert_eq!(compile_and_run("2*3*4"), Some(24));
+        assert_eq!(compile_and_run("10-2*3-1"), Some(3));
+        assert_eq!(run(&[Instruction::Add]), None);
+    }
+}
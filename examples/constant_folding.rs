@@ -0,0 +1,95 @@
+//! Folds constant arithmetic in a AST before it is compiled.
+//! Every `<product>` of two `<number>`s is replaced by a single `<number>` that contains the result,
+//! so the AST of `2*3+4*5` turns into a AST of `6+20`.
+//!
+//! Run it with `cargo run --example constant_folding -- "2*3+4*5"`.
+
+use backus_naur_form_parser_and_compiler::{
+    backus_naur_form,
+    backus_naur_form::token::pattern::{nt, t},
+    BackusNaurForm, Symbol, Token,
+};
+
+fn grammar() -> BackusNaurForm<'static> {
+    backus_naur_form!(
+        priority 3 => r#"<digit> ::= "0" | "1" | "2" | "3" | "4" | "5" | "6" | "7" | "8" | "9""#
+        priority 2 => r#"<number> ::= <digit> | <number> <number>"#
+        priority 1 => r#"<product> ::= <number> "*" <number>"#
+        priority 0 => r#"<expression> ::= <number> | <product> | <expression> "+" <expression>"#
+    )
+}
+
+//Turns a value into a <number> token with one <digit> per digit.
+fn number_token(value: u64) -> Token {
+    let digits = value
+        .to_string()
+        .chars()
+        .map(|digit| {
+            Token::from_non_terminal("digit", vec![Token::from_terminal(&digit.to_string())])
+        })
+        .collect();
+    Token::from_non_terminal("number", digits)
+}
+
+//Replaces every `<product>` of two `<number>`s with a `<number>` of the result.
+//Products that overflow a u64 are left as they are. Returns the number of folded products.
+fn fold_constants(tokens: &mut [Token]) -> usize {
+    let mut folded = 0;
+    for token in tokens {
+        token.replace_all(&Symbol::non_terminal("product"), |product| {
+            let value = product.to_non_terminal_ref().and_then(|product| {
+                let captures = product.match_children(&[nt("number"), t("*"), nt("number")])?;
+                let lhs = captures[0].get_terminals().parse::<u64>().ok()?;
+                let rhs = captures[2].get_terminals().parse::<u64>().ok()?;
+                lhs.checked_mul(rhs)
+            });
+            match value {
+                Some(value) => {
+                    folded += 1;
+                    number_token(value)
+                }
+                None => product,
+            }
+        });
+    }
+    folded
+}
+
+fn main() {
+    let input = std::env::args().nth(1).unwrap_or("2*3+4*5+6".to_string());
+    let bnf = grammar();
+    let mut ast = bnf.symbolize_string(&input);
+    let folded = fold_constants(&mut ast);
+
+    let output = ast.iter().map(Token::get_terminals).collect::<String>();
+    println!("{input} => {output} ({folded} products folded)");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fold_constants() {
+        let bnf = grammar();
+        let mut ast = bnf.symbolize_string("2*3+4*25+6");
+
+        assert_eq!(fold_constants(&mut ast), 2);
+        assert_eq!(
+            ast.iter().map(Token::get_terminals).collect::<String>(),
+            "6+100+6"
+        );
+        //the folded AST has the same shape as the AST of the folded input
+        let mut expected = bnf.symbolize_string("6+100+6");
+        assert_eq!(fold_constants(&mut expected), 0);
+        assert_eq!(
+            ast.iter()
+                .map(|token| token.get_symbol().to_string())
+                .collect::<Vec<_>>(),
+            expected
+                .iter()
+                .map(|token| token.get_symbol().to_string())
+                .collect::<Vec<_>>()
+        );
+    }
+}
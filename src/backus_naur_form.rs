@@ -12,6 +12,8 @@
 //! ```
 //! The rules are raw string literals. (aka r#"{here comes the string}"#) so it can contain other strings.  
 //! Every rule has a priority. rules with higher priorites get evaluated first.
+//! Rules with the same priority are evaluated in the reverse order in which they were added,
+//! [BackusNaurForm::effective_order] lists the order of all rules.
 //! For example, this is nice for evaluating multiplications and divisions first before
 //! evaluating additions and subtractions.
 //!
@@ -35,25 +37,168 @@
 //! - `<token> ::= <...> <token>`
 //!
 //! The reason for this is simple: The algorithm turns every <digit> into a <number> and therefore theres no `<number> <digit>` or `<digit> <number>`.
+//! [BackusNaurForm::check_recursion] finds such choices and suggests the rewrite, grammar documents, the [backus_naur_form!](crate::backus_naur_form!) macro
+//! and the static_backus_naur_form! macro check it automatically.
+//! The `<token> <token>` choice nests the tokens in a unbalanced tree, [BackusNaurForm::set_flat_lists] turns them into one flat list instead
+//! (see the [list] module).
+//!
+//! ## Hidden symbols
+//! Non terminal symbols whose name starts with a underscore (for example `<_whitespace>`) are hidden.
+//! They are used like every other non terminal symbol while symbolizing, but they don't appear in the resulting AST.
+//! Instead, their child tokens are attached to their parent.
+//! Other symbols can be hidden with [BackusNaurForm::hide_symbol].
+//!
+//! ## Node labels
+//! A rule can give its tokens another name in the AST than the name of the symbol:
+//! ```rust, ignore
+//! backus_naur_form!(
+//!     priority 1 => r#"<multiplicative-expression> -> Expr ::= <digit> "*" <digit>"#
+//!     priority 0 => r#"<additive-expression> -> Expr ::= <multiplicative-expression> "+" <digit>"#
+//! )
+//! ```
+//! The rules still refer to `<multiplicative-expression>`, but both symbols appear as `Expr` in the AST,
+//! so the passes that work on the AST don't have to know the helper symbols of the grammar.
+//! Labels can also be set with [BackusNaurForm::set_node_label].
+//!
+//! ## Inline choices
+//! Choices that only pass another symbol through (like `<term>` in `<expr> ::= <term> | <expr> "+" <expr>`)
+//! nest the AST deeper without adding information. Choices that start with `@inline` don't create a token in the AST:
+//! ```rust, ignore
+//! backus_naur_form!(
+//!     priority 1 => r#"<term> ::= "1" | "2""#
+//!     priority 0 => r#"<expr> ::= @inline <term> | <expr> "+" <expr>"#
+//! )
+//! ```
+//! "1+2" is symbolized into a `<expr>` that contains a `<term>`, "+" and a `<term>` instead of `<expr>`s around every `<term>`.
+//! Only the tokens created from the inline choice are left out, the other choices of `<expr>` still create one.
+//! Choices can also be made inline with [BackusNaurForm::add_inline_choice].
+//!
+//! ## Empty choices
+//! A choice can be empty, which is written as `ε` or as nothing between two pipes:
+//! ```rust, ignore
+//! backus_naur_form!(
+//!     priority 2 => r#"<maybe-sign> ::= "+" | "-" | ε"#
+//!     priority 1 => r#"<digit> ::= "1" | "2" | "3" | "4" | "5" | "6" | "7" | "8" | "9""#
+//!     priority 0 => r#"<signed> ::= <maybe-sign> <digit>"#
+//! )
+//! ```
+//! A symbol with a empty choice may match nothing, so `<signed>` matches "+1" as well as "1".
+//! If it matches nothing, the symbol doesn't appear in the AST (the `<signed>` of "1" only contains the `<digit>`).
+//! Like with every other rule, the nullable symbol needs a higher priority than the rules that use it.
+//! Nothing is never matched on its own, so every reduction turns at least one token into a new one and symbolizing always ends:
+//! rules that only keep renaming the same tokens (for example two nullable symbols that use each other)
+//! stop with a [SymbolizeError::NonTermination].
+//! Every choice is expanded into a version for every combination of its nullable symbols that are left out,
+//! so a choice with more than 12 nullable symbols fails with a [SymbolizeError::TooManyChoiceVersions].
+//!
+//! # Compiling
+//! Compile functions turn the tokens of a symbol into a string (see [BackusNaurForm::add_compile_function]).
+//! Compiling is deterministic: the same tokens and the same compile functions always produce the same string, byte for byte.
+//! The functions are stored sorted by the names of their symbols and nothing depends on the iteration order of a hash map,
+//! the function of a parameterized rule is the one of the first template (in the order they were added) the rule is a use of.
 
+pub mod analysis;
+pub mod best_effort;
+mod bracket;
+pub mod bytes;
+mod char_classes;
+mod chunk;
+pub mod cnf;
+pub mod compiled;
+pub mod completion;
+pub mod corpus;
+pub mod coverage;
+pub mod cyk;
+#[cfg(feature = "encoding")]
+pub mod encoding;
+pub mod error;
+pub mod escape;
+pub mod format;
+pub mod generate;
+pub mod glr;
+pub mod grammar;
+pub mod graph;
+pub mod inline_test;
+pub mod left_recursion;
+pub mod lexer;
+pub mod lint;
+pub mod list;
+pub mod markdown;
+pub mod node_context;
+mod nullable;
+pub mod optimize;
+pub mod parse_forest;
+pub mod pipeline;
+pub mod preprocess;
+pub mod print;
+pub mod project;
+#[cfg(feature = "registry")]
+pub mod registry;
+pub mod rewrite;
 pub mod rule;
+mod rule_scan;
+pub mod skip;
+pub mod source_map;
+pub mod stats;
 pub mod symbol;
+mod symbol_id;
+pub mod symbol_kinds;
+pub mod symbolize_result;
+mod syntax_macro;
+mod template;
+#[cfg(test)]
+mod test_helpers;
+pub mod testing;
 pub mod token;
-use std::{collections::HashMap, fmt::Debug, ops::Range};
+pub mod trace;
+#[cfg(feature = "unicode")]
+pub mod unicode;
+#[cfg(feature = "notify")]
+pub mod watcher;
+use char_classes::CharClasses;
+use error::{ParseError, RootError, SymbolizeError};
+use node_context::NodeContext;
+use rule_scan::RuleScan;
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, HashMap, HashSet},
+    fmt::Debug,
+    ops::Range,
+};
 use token::{non_terminal_token::NonTerminalToken, Token};
 
 use symbol::{non_terminal_symbol::NonTerminalSymbol, Symbol};
+use symbol_id::{SymbolId, SymbolTable};
+use template::{canonical_name, map_non_terminal_names, Template};
 
 ///Rules are built like this: `<symbol> ::= expression`.  
 ///The body of a rule. It contains the different [Choice]s/ways to turn [Token] or [Token]s into a higher [NonTerminalToken].
 pub type Expression = Vec<Choice>;
 ///A Choice contains a way to turn [Token] or [Token]s into a higher [NonTerminalToken].
+#[cfg(not(feature = "smallvec"))]
 pub type Choice = Vec<Symbol>;
+///A Choice contains a way to turn [Token] or [Token]s into a higher [NonTerminalToken].  
+///Most choices have at most 4 symbols, so with the `smallvec` feature they are stored inline without a allocation.
+#[cfg(feature = "smallvec")]
+pub type Choice = smallvec::SmallVec<[Symbol; 4]>;
 ///A function that compiles a [NonTerminalToken] by turning it into a [String].  
 ///Takes following arguments:
 /// - The [NonTerminalToken] that should be compiled.
 /// - The [BackusNaurForm] that contains the rules and other compile functions.
 pub type CompileFunction<'a> = &'a dyn Fn(&NonTerminalToken, &BackusNaurForm) -> String;
+///A [CompileFunction] that also receives the [NodeContext] of the [NonTerminalToken] (its parent, sibling index and ancestors).  
+///Takes following arguments:
+/// - The [NonTerminalToken] that should be compiled.
+/// - The [NodeContext] of the [NonTerminalToken].
+/// - The [BackusNaurForm] that contains the rules and other compile functions.
+pub type ContextCompileFunction<'a> =
+    &'a dyn Fn(&NonTerminalToken, &NodeContext, &BackusNaurForm) -> String;
+
+type CompileCache = HashMap<(NonTerminalToken, Option<NodeContext>), String>;
+//Called for every reduction with the rule (and its priority), the range of the reduced tokens and the tokens themselves.
+//Returning a error stops the symbolization.
+type Observer<'o> = dyn FnMut(&(NonTerminalSymbol, usize), Range<usize>, &[Token]) -> Result<(), SymbolizeError>
+    + 'o;
 
 #[derive(Default)]
 pub struct BackusNaurForm<'a> {
@@ -62,17 +207,121 @@ pub struct BackusNaurForm<'a> {
     rules: Vec<(NonTerminalSymbol, usize)>,
     //The String is just a non terminal symbol name and the fn takes a token of that non terminal symbol and produces a string.
     //Essentially, this is for the translation from the tokenized vec to a new language.
-    compile_functions: HashMap<String, CompileFunction<'a>>,
+    //the maps are ordered, so everything that goes through the functions happens in the same order every time.
+    compile_functions: BTreeMap<String, CompileFunction<'a>>,
+    //the same as compile_functions, but the functions receive the context of the token too.
+    context_compile_functions: BTreeMap<String, ContextCompileFunction<'a>>,
+    //the results of compile_token if memoization is enabled.
+    //the context is None for tokens that are compiled by a function that doesn't receive the context.
+    compile_cache: Option<RefCell<CompileCache>>,
+    //the (opening, closing) brackets that are paired before any rule is applied.
+    //if this is empty, the bracket pre-pass is skipped.
+    bracket_pairs: Vec<(String, String)>,
+    //the terminals after which the tokens are split into chunks that are symbolized on their own.
+    //if this is empty, the chunk pre-pass is skipped.
+    chunk_delimiters: Vec<String>,
+    //parameterized rules (for example `<list(x)> ::= <x> | <list(x)> "," <list(x)>`).
+    //they are never applied themselves, every use of them is turned into a rule in rules instead.
+    templates: Vec<Template>,
+    //non terminal symbols that are hidden even though their name doesn't start with a underscore.
+    hidden_symbols: HashSet<String>,
+    //the names the tokens of non terminal symbols get in the AST instead of the names of the symbols, see set_node_label.
+    node_labels: HashMap<String, String>,
+    //the choices whose tokens are replaced by their child tokens in the AST, see add_inline_choice.
+    inline_choices: HashMap<String, Vec<Choice>>,
+    //if the nested tokens of list symbols are merged into one token, see the list module.
+    flat_lists: bool,
+    //the limits of set_max_input_length, set_max_token_count, set_max_memory and set_max_reductions.
+    max_input_length: Option<usize>,
+    max_token_count: Option<usize>,
+    max_memory: Option<usize>,
+    max_reductions: HashMap<String, usize>,
+    //the layout rules of the formatter, see the format module.
+    layout_rules: HashMap<String, format::LayoutRule>,
+    //run on the input before it is symbolized, in this order. see the preprocess module.
+    preprocessors: Vec<preprocess::Preprocessor<'a>>,
+    //the doc strings of the non terminal symbols, see set_rule_doc.
+    rule_docs: HashMap<String, String>,
+    //escapes the text that compile_tokens copies into the output, see the escape module.
+    escaper: Option<escape::Escaper>,
+    //what happens to the uses of symbols without a rule when a input is symbolized, see set_unknown_symbol_severity.
+    unknown_symbol_severity: lint::Severity,
+    //receives the warnings that are found while a input is symbolized, see set_diagnostic_handler.
+    diagnostic_handler: Option<lint::DiagnosticHandler<'a>>,
+    //the symbol whose tokens may appear between the symbols of a choice and the rules that skip it, see the skip module.
+    //if no rule was added to skipping_rules, every rule skips.
+    skip_symbol: Option<String>,
+    skipping_rules: HashSet<String>,
+    //the weights (in percent) of the choices when inputs are generated, see set_choice_weight.
+    choice_weights: HashMap<String, Vec<(Choice, f64)>>,
+    //the examples that the rules must accept or reject, see the inline_test module.
+    inline_tests: Vec<inline_test::InlineTest>,
+    //if the rules of every priority are applied until none of them can be applied anymore before the next priority, see set_priority_phases.
+    priority_phases: bool,
 }
 
 impl<'a> BackusNaurForm<'a> {
-    ///Used to add a new [NonTerminalSymbol] to the backus naur form.
+    ///Used to add a new [NonTerminalSymbol] to the backus naur form.  
+    ///If the [NonTerminalSymbol] has parameters (for example `<list(x)>`) it is added as a template instead.
+    ///Every use of a template (for example `<list(expr)>`) gets its own rule where the parameters are replaced.
     fn add_non_terminal_symbol(&mut self, non_terminal_symbol: NonTerminalSymbol, priority: usize) {
-        self.rules.push((non_terminal_symbol, priority));
+        self.clear_compile_cache();
+        let non_terminal_symbol = map_non_terminal_names(&non_terminal_symbol, &canonical_name);
+        match Template::from_symbol(&non_terminal_symbol, priority) {
+            Some(template) => self.templates.push(template),
+            None => self.rules.push((non_terminal_symbol, priority)),
+        }
+        template::instantiate_templates(&mut self.rules, &self.templates);
     }
 
+    ///Adds a rule like `<digit> ::= "1" | "2"` to the backus naur form.  
+    ///Rules can have parameters like `<list(x)> ::= <x> | <list(x)> "," <list(x)>`.
+    ///Those rules are templates that are only applied where they are used with arguments, for example in `<args> ::= <list(expr)>`.  
+    ///Choices can contain inline groups like `<stmt> ::= ("let" | "const") <ident>`.
+    ///Every group is turned into its own hidden rule with the same priority.  
+    ///A label after the name (`<additive-expression> -> Expr ::= ...`) is the name the tokens of the rule get in the AST,
+    ///see [set_node_label](BackusNaurForm::set_node_label).  
+    ///Choices that start with `@inline` (`<expr> ::= @inline <term> | ...`) don't create a token in the AST,
+    ///see [add_inline_choice](BackusNaurForm::add_inline_choice).  
+    ///A percentage in front of a choice (`<digit> ::= 90% "0" | "1"`) is the weight of the choice when inputs are generated,
+    ///see [set_choice_weight](BackusNaurForm::set_choice_weight).
     pub fn add_non_terminal_symbol_from_rule(&mut self, rule: &str, priority: usize) {
-        self.add_non_terminal_symbol(NonTerminalSymbol::from_rule(rule), priority);
+        let parsed = rule::parse(rule);
+        let non_terminal_symbols = parsed
+            .rules
+            .into_iter()
+            .map(rule::non_terminal_symbol)
+            .collect::<Vec<_>>();
+        if let Some(label) = backus_naur_form_rule_parser::node_label(rule) {
+            self.set_node_label(non_terminal_symbols[0].get_name(), label);
+        }
+        for index in parsed.inline_choices {
+            let symbol = &non_terminal_symbols[0];
+            self.add_inline_choice(symbol.get_name(), symbol.get_rule()[index].clone());
+        }
+        for (index, weight) in parsed.weights {
+            let symbol = &non_terminal_symbols[0];
+            self.set_choice_weight(symbol.get_name(), symbol.get_rule()[index].clone(), weight);
+        }
+        for non_terminal_symbol in non_terminal_symbols {
+            self.add_non_terminal_symbol(non_terminal_symbol, priority);
+        }
+    }
+
+    ///Adds a rule that is already split into its choices. The name excludes the angle brackets.  
+    ///For example the rule `<digit> ::= "1" | "2"` is the name "digit" and the expression
+    ///`vec![vec![Symbol::terminal("1")], vec![Symbol::terminal("2")]]`.  
+    ///This skips parsing the rule, which is what the static_backus_naur_form! macro (`macros` feature) uses.
+    pub fn add_rule_from_expression(
+        &mut self,
+        name: &str,
+        expression: Expression,
+        priority: usize,
+    ) {
+        self.add_non_terminal_symbol(
+            NonTerminalSymbol::new(name.to_string(), expression),
+            priority,
+        );
     }
 
     ///Returns true if the [BackusNaurForm] contains a [NonTerminalSymbol]  with the specified name.  
@@ -83,6 +332,43 @@ impl<'a> BackusNaurForm<'a> {
         })
     }
 
+    ///Returns the rule of the [NonTerminalSymbol] with the name (without the angle brackets), or None if there is no such rule.
+    ///If there are several rules for the name, the first one that was added is returned.
+    pub fn rule(&self, name: &str) -> Option<rule::Rule<'_>> {
+        let (non_terminal_symbol, priority) = self
+            .rules
+            .iter()
+            .find(|(non_terminal_symbol, _)| non_terminal_symbol.get_name() == name)?;
+        Some(rule::Rule::new(
+            non_terminal_symbol,
+            *priority,
+            self.rule_docs.get(name).map(|doc| doc.as_str()),
+        ))
+    }
+
+    ///Returns every rule in the order they were added, including the rules of inline groups and of the uses of parameterized rules.
+    pub fn rules(&self) -> Vec<rule::Rule<'_>> {
+        self.rules
+            .iter()
+            .map(|(non_terminal_symbol, priority)| {
+                let name = non_terminal_symbol.get_name();
+                rule::Rule::new(
+                    non_terminal_symbol,
+                    *priority,
+                    self.rule_docs.get(name).map(|doc| doc.as_str()),
+                )
+            })
+            .collect()
+    }
+
+    ///Sets the doc string of the [NonTerminalSymbol] with the name (without the angle brackets), for example `A decimal digit` for `<digit>`.
+    ///Doc strings don't change how anything is symbolized, they are for documentation and tools like hovers in editors.
+    ///They can be written in the backus_naur_form! macro (`priority 0 => doc "A decimal digit" => r#"<digit> ::= "1""#`)
+    ///and in [grammar documents](grammar) (lines that start with `##` in front of the rule).
+    pub fn set_rule_doc(&mut self, name: &str, doc: &str) {
+        self.rule_docs.insert(canonical_name(name), doc.to_string());
+    }
+
     ///This parses a string into a vector of [Token].  
     ///The vector of [Token]s is essentially the AST.  
     ///   
@@ -109,37 +395,272 @@ impl<'a> BackusNaurForm<'a> {
     ///                 "2"   "*"   "4"   "-"   "4"    "/"    "5"
     /// ```
     /// Notice the tree structure. This is the AST.
+    ///
+    /// # Panics
+    /// Panics in every case in which [try_symbolize_string](BackusNaurForm::try_symbolize_string) returns a [SymbolizeError],
+    /// for example if the brackets added with [add_bracket_pair](BackusNaurForm::add_bracket_pair) don't match up in the string,
+    /// a limit like [set_max_input_length](BackusNaurForm::set_max_input_length) is exceeded or the rules would be applied forever.
+    /// Use [try_symbolize_string](BackusNaurForm::try_symbolize_string) to get the error instead.
     pub fn symbolize_string(&self, string: &str) -> Vec<Token> {
-        let mut tokenized_string = characterize_string(string);
-        let mut modified_this_iteration;
-
-        let mut sorted_rules = self.rules.clone();
-        sorted_rules.sort_by_key(|(_, priority)| *priority);
-        sorted_rules.reverse();
-
-        loop {
-            modified_this_iteration = false;
-            sorted_rules.iter().for_each(|(non_terminal_symbol, _)| {
-                if non_terminal_symbol.further_symbolization_possible(&tokenized_string) {
-                    modified_this_iteration = true;
-                }
+        self.try_symbolize_string(string)
+            .unwrap_or_else(|error| panic!("failed to symbolize the string: {error}"))
+    }
 
-                non_terminal_symbol.symbolize_vec(&mut tokenized_string);
+    ///The same as [symbolize_string](BackusNaurForm::symbolize_string) but returns a [SymbolizeError] instead of panicking.  
+    ///This is also the case if the string, the amount of tokens, their memory or the reductions of a symbol exceed the limits set with
+    ///[set_max_input_length](BackusNaurForm::set_max_input_length), [set_max_token_count](BackusNaurForm::set_max_token_count),
+    ///[set_max_memory](BackusNaurForm::set_max_memory) and [set_max_reductions](BackusNaurForm::set_max_reductions).
+    pub fn try_symbolize_string(&self, string: &str) -> Result<Vec<Token>, SymbolizeError> {
+        self.symbolize_observed(string, true, &mut |_, _, _| Ok(()))
+    }
+
+    ///Symbolizes tokens that were made by a lexer of your own instead of a string, so the rules can match whole words or numbers
+    ///(for example a terminal token "let" matches the terminal "let" in a rule).
+    ///Every token can be a [TerminalToken](token::TerminalToken) of any length or even a [NonTerminalToken](token::non_terminal_token::NonTerminalToken).
+    ///The tokens are not preprocessed and the positions in a [SymbolizeError] are indexes of the tokens.
+    ///[set_max_input_length](BackusNaurForm::set_max_input_length) limits the amount of tokens.
+    ///
+    /// # Panics
+    /// Panics in every case in which [try_symbolize_tokens](BackusNaurForm::try_symbolize_tokens) returns a [SymbolizeError],
+    /// for example if the brackets added with [add_bracket_pair](BackusNaurForm::add_bracket_pair) don't match up in the tokens,
+    /// a limit like [set_max_input_length](BackusNaurForm::set_max_input_length) is exceeded or the rules would be applied forever.
+    /// Use [try_symbolize_tokens](BackusNaurForm::try_symbolize_tokens) to get the error instead.
+    pub fn symbolize_tokens(&self, tokens: Vec<Token>) -> Vec<Token> {
+        self.try_symbolize_tokens(tokens)
+            .unwrap_or_else(|error| panic!("failed to symbolize the tokens: {error}"))
+    }
+
+    ///The same as [symbolize_tokens](BackusNaurForm::symbolize_tokens) but returns a [SymbolizeError] instead of panicking.
+    pub fn try_symbolize_tokens(&self, tokens: Vec<Token>) -> Result<Vec<Token>, SymbolizeError> {
+        self.symbolize_tokens_observed(tokens, true, &mut |_, _, _| Ok(()))
+    }
+
+    //The same as try_symbolize_string, but observe is called for every reduction in the order they are applied.
+    //If pair_brackets is false, the bracket pairs are ignored.
+    fn symbolize_observed(
+        &self,
+        string: &str,
+        pair_brackets: bool,
+        observe: &mut Observer,
+    ) -> Result<Vec<Token>, SymbolizeError> {
+        let preprocessed = self.preprocess(string);
+        let tokenized_string = characterize_string(preprocessed.get_text());
+        self.symbolize_tokens_observed(tokenized_string, pair_brackets, observe)
+            .map_err(|error| preprocessed.map_error(error))
+    }
+
+    //The same as symbolize_observed, but for tokens.
+    fn symbolize_tokens_observed(
+        &self,
+        tokens: Vec<Token>,
+        pair_brackets: bool,
+        observe: &mut Observer,
+    ) -> Result<Vec<Token>, SymbolizeError> {
+        self.check_unknown_symbols()?;
+        if let Some(max) = self.max_input_length.filter(|max| tokens.len() > *max) {
+            return Err(SymbolizeError::InputTooLong {
+                length: tokens.len(),
+                max,
             });
+        }
+
+        //every input token is a token and every reduction creates another one
+        let mut token_count = tokens.len();
+        let mut memory = tokens.iter().map(token_memory).sum::<usize>();
+        if let Some(max) = self.max_memory.filter(|max| memory > *max) {
+            return Err(SymbolizeError::MemoryLimitExceeded { max });
+        }
+        //the reductions of the symbols that have a limit
+        let mut reductions = HashMap::new();
+        let mut observe =
+            |rule: &(NonTerminalSymbol, usize), range: Range<usize>, tokens: &[Token]| {
+                token_count += 1;
+                if let Some(max) = self.max_token_count.filter(|max| token_count > *max) {
+                    return Err(SymbolizeError::TooManyTokens { max });
+                }
+                let name = rule.0.get_name();
+                memory += size_of::<Token>() + name.len();
+                if let Some(max) = self.max_memory.filter(|max| memory > *max) {
+                    return Err(SymbolizeError::MemoryLimitExceeded { max });
+                }
+                if let Some(max) = self.max_reductions.get(name) {
+                    let count = reductions.entry(name.to_string()).or_insert(0);
+                    *count += 1;
+                    if *count > *max {
+                        return Err(SymbolizeError::TooManyReductions {
+                            symbol: name.to_string(),
+                            max: *max,
+                        });
+                    }
+                }
+                observe(rule, range, tokens)
+            };
+
+        let rules = self.symbolizing_rules()?;
+        #[cfg(feature = "unicode")]
+        let rules = self.add_unicode_class_rules(rules, &tokens);
+        let sorted_rules = sort_rules(&rules);
+        let table = SymbolTable::new(&sorted_rules);
 
-            if !modified_this_iteration {
-                break;
+        let symbolized = chunk::symbolize_chunked(tokens, &self.chunk_delimiters, |chunk| {
+            if self.bracket_pairs.is_empty() || !pair_brackets {
+                symbolize_to_fixed_point(
+                    &sorted_rules,
+                    &table,
+                    chunk,
+                    self.priority_phases,
+                    &mut observe,
+                )
+            } else {
+                bracket::symbolize_bracketed(chunk, &self.bracket_pairs, |region| {
+                    symbolize_to_fixed_point(
+                        &sorted_rules,
+                        &table,
+                        region,
+                        self.priority_phases,
+                        &mut observe,
+                    )
+                })
             }
+        })?;
+
+        let mut symbolized = if self.inline_choices.is_empty() {
+            symbolized
+        } else {
+            let inline_choices = self.symbolizing_inline_choices()?;
+            remove_inline_tokens(symbolized, &inline_choices)
+        };
+        if self.flat_lists {
+            list::flatten_lists(&mut symbolized, &self.list_symbols());
         }
+        let mut symbolized = remove_hidden_tokens(symbolized, &|name| self.is_hidden_symbol(name));
+        if !self.node_labels.is_empty() {
+            label_tokens(&mut symbolized, &self.node_labels);
+        }
+        Ok(symbolized)
+    }
+
+    ///Turns every priority into a phase: the rules of a priority are applied until none of them can be applied anymore,
+    ///then the rules of the next lower priority are applied, and a priority is never applied again once a lower one ran.  
+    ///By default all rules are applied in one loop: every iteration applies the rules from the highest to the lowest priority
+    ///and the loop repeats until nothing changes, so a rule with a higher priority can still be applied to the tokens that a rule
+    ///with a lower priority created. With phases every priority is a barrier, which makes grammars that express precedence
+    ///through priorities behave predictably for nested expressions. Phases are disabled by default.
+    pub fn set_priority_phases(&mut self, enabled: bool) {
+        self.clear_compile_cache();
+        self.priority_phases = enabled;
+    }
+
+    ///Limits the length (in chars) of the strings that are symbolized. Longer strings result in a [SymbolizeError::InputTooLong].  
+    ///None removes the limit, which is the default.
+    pub fn set_max_input_length(&mut self, max: Option<usize>) {
+        self.max_input_length = max;
+    }
 
-        tokenized_string
+    ///Limits the amount of tokens that are created while a string is symbolized (one for every char and one for every reduction).
+    ///If more tokens would be created, the symbolization stops with a [SymbolizeError::TooManyTokens].  
+    ///Together with [set_max_input_length](BackusNaurForm::set_max_input_length) this bounds the memory and the time a symbolization takes.  
+    ///None removes the limit, which is the default.
+    pub fn set_max_token_count(&mut self, max: Option<usize>) {
+        self.max_token_count = max;
+    }
+
+    ///Limits the memory (in bytes) that the tokens of a symbolization take, including the tokens of the input.
+    ///Every token is estimated as the size of a [Token] plus the length of its terminal or of the name of its symbol.
+    ///If the tokens would take more, the symbolization stops with a [SymbolizeError::MemoryLimitExceeded].  
+    ///Unlike [set_max_token_count](BackusNaurForm::set_max_token_count) this also bounds inputs with long terminals,
+    ///for example tokens from a lexer.  
+    ///None removes the limit, which is the default.
+    pub fn set_max_memory(&mut self, max: Option<usize>) {
+        self.max_memory = max;
+    }
+
+    ///Limits how often the rule of the non terminal symbol with the specified name is applied while a string is symbolized,
+    ///for example at most 1000 `<whitespace>` reductions. If it would be applied more often, the symbolization stops
+    ///with a [SymbolizeError::TooManyReductions]. This keeps a recursive symbol from blowing up on untrusted input.  
+    ///None removes the limit, which is the default. This assumes that the angle brackets are not included in the name.
+    pub fn set_max_reductions(&mut self, name: &str, max: Option<usize>) {
+        match max {
+            Some(max) => self.max_reductions.insert(canonical_name(name), max),
+            None => self.max_reductions.remove(&canonical_name(name)),
+        };
+    }
+
+    ///Hides a non terminal symbol. More information can be found in the [module documentation](self).  
+    ///This assumes that the angle brackets are not included in the name.
+    pub fn hide_symbol(&mut self, name: &str) {
+        self.hidden_symbols.insert(canonical_name(name));
+    }
+
+    ///Returns true if [Token]s of the non terminal symbol with the specified name don't appear in the AST.  
+    ///This is the case if the name starts with a underscore or if the symbol was hidden using [hide_symbol](BackusNaurForm::hide_symbol).
+    ///With the `unicode` feature the pseudo terminals of Unicode classes like `<L>` are hidden too.
+    pub fn is_hidden_symbol(&self, name: &str) -> bool {
+        #[cfg(feature = "unicode")]
+        if self.is_unicode_class(name) {
+            return true;
+        }
+        name.starts_with('_') || self.hidden_symbols.contains(name)
+    }
+
+    ///Gives the [Token]s of the non terminal symbol with the specified name the label as their name in the AST,
+    ///so the names of helper rules like `additive-expression` don't leak into the AST. Several symbols can have the same label.  
+    ///The rules still use the name of the symbol, everything that looks at the AST (for example compile functions
+    ///and [root_token](BackusNaurForm::root_token)) sees the label instead.
+    ///Labels can also be written in the rule: `<additive-expression> -> Expr ::= <term> "+" <term>`.
+    pub fn set_node_label(&mut self, name: &str, label: &str) {
+        self.clear_compile_cache();
+        self.node_labels
+            .insert(canonical_name(name), label.to_string());
+    }
+
+    ///Makes a choice of the rule of the non terminal symbol with the specified name inline:
+    ///the [Token]s created from it don't appear in the AST, their child tokens are attached to their parent instead.
+    ///This keeps pass-through choices like the `<term>` of `<expr> ::= <term> | <expr> "+" <expr>` from nesting the AST deeper.  
+    ///The choice can also be marked in the rule: `<expr> ::= @inline <term> | <expr> "+" <expr>`.
+    pub fn add_inline_choice(&mut self, name: &str, choice: Choice) {
+        self.clear_compile_cache();
+        //the names are stored the same way as the names of the rules
+        let symbol = map_non_terminal_names(
+            &NonTerminalSymbol::new(name.to_string(), vec![choice]),
+            &canonical_name,
+        );
+        self.inline_choices
+            .entry(symbol.get_name().to_string())
+            .or_default()
+            .extend(symbol.get_rule().iter().cloned());
+    }
+
+    ///Returns the name the [Token]s of the non terminal symbol have in the AST:
+    ///its [label](BackusNaurForm::set_node_label) or the name itself if it has none.
+    pub fn node_label<'b>(&'b self, name: &'b str) -> &'b str {
+        self.node_labels.get(name).map_or(name, String::as_str)
+    }
+
+    ///Adds a pair of brackets (for example `(` and `)`) that are matched before any rule is applied.  
+    ///Every bracketed region is symbolized on its own (innermost regions first) before the region around it is symbolized.
+    ///This makes deeply nested expressions a lot faster to symbolize.  
+    ///Mismatched brackets are reported as a [SymbolizeError] by [try_symbolize_string](BackusNaurForm::try_symbolize_string).
+    pub fn add_bracket_pair(&mut self, opening: &str, closing: &str) {
+        self.bracket_pairs
+            .push((opening.to_string(), closing.to_string()));
+    }
+
+    ///Adds a terminal (for example `\n`) after which the tokens are split into chunks before any rule is applied.  
+    ///Every chunk is symbolized on its own and the chunks are concatenated again, so no [Token] can span two chunks.
+    ///This bounds the time a symbolization takes for formats that are naturally line based.  
+    ///The delimiter is the last [Token] of its chunk, so rules can still match it. Brackets can't be paired across chunks.
+    ///More information can be found in the [chunk] module.
+    pub fn add_chunk_delimiter(&mut self, delimiter: &str) {
+        self.chunk_delimiters.push(delimiter.to_string());
     }
 
     ///This compiles a [String] using the backus naur form and the given Compilefunctions.  
     ///Only [Token]s at the uppermost level will be compiled.  
     ///
     /// Rules with higher priority will be applied first.  
-    /// Choices that are specified before other choices will be applied first.  
+    /// Choices that are specified before other choices will be applied first if they match at the same place.  
+    /// The exact order is described in the [trace] module.  
     /// For example, in the bellow example "a" would be applied before "b" in the `<letter>` non terminal symbol.
     /// ## Example
     ///Lets take this backus naur form as first example:
@@ -163,32 +684,140 @@ impl<'a> BackusNaurForm<'a> {
     ///
     /// If any of the tokens dont have CompileFunctions they will simply be mapped to the terminals they encompass.  
     /// In other words, either tokens get compiled or they won't be touched/modified at all.
+    ///
+    /// # Panics
+    /// Panics in the same cases as [symbolize_string](BackusNaurForm::symbolize_string).
+    /// Use [try_symbolize_string](BackusNaurForm::try_symbolize_string) and [compile_tokens](BackusNaurForm::compile_tokens) to get the error instead.
     pub fn compile_string(&self, string: &str) -> String {
-        let symbolized_string = self.symbolize_string(string);
-        symbolized_string
-            .into_iter()
-            .map(|token| match token {
+        self.compile_tokens(&self.symbolize_string(string))
+    }
+
+    ///Compiles tokens the same way [compile_string](BackusNaurForm::compile_string) compiles the tokens of a [String],
+    ///as if they were the uppermost tokens.
+    ///This is for tokens that are already symbolized (and maybe transformed), so they don't have to be turned back into a [String] first.  
+    ///The text of terminals and of tokens without a compile function is [escaped](BackusNaurForm::escape).
+    pub fn compile_tokens(&self, tokens: &[Token]) -> String {
+        tokens
+            .iter()
+            .enumerate()
+            .map(|(index, token)| match token {
                 Token::NonTerminalToken(non_terminal) => self
-                    .compile_token(&non_terminal)
-                    .unwrap_or(non_terminal.get_terminals()),
-                Token::Terminal(terminal) => terminal.to_string(),
+                    .compile_token_with_context(non_terminal, &NodeContext::top_level(index))
+                    .unwrap_or_else(|| self.escape(&non_terminal.get_terminals())),
+                Token::Terminal(terminal) => self.escape(terminal.get_terminals()),
             })
             .collect()
     }
 
+    ///Compiles the root [Token] of a tree, the same as [compile_tokens](BackusNaurForm::compile_tokens) with only the root.
+    pub fn compile_root(&self, root: &Token) -> String {
+        self.compile_tokens(std::slice::from_ref(root))
+    }
+
     ///Compiles a [NonTerminalToken] into a String.  
-    ///Returns none if there is no function that compiles this [NonTerminalToken].
+    ///Returns none if there is no function that compiles this [NonTerminalToken].  
+    ///A [NonTerminalToken] of a parameterized rule (for example `<list(expr)>`) is compiled by the function of its template (`<list(x)>`)
+    ///if it has no function of its own.  
+    ///[ContextCompileFunction]s receive a empty [NodeContext], use [compile_token_with_context](BackusNaurForm::compile_token_with_context) to pass on the context.
     pub fn compile_token(&self, non_terminal: &NonTerminalToken) -> Option<String> {
+        self.compile_token_with_context(non_terminal, &NodeContext::default())
+    }
+
+    ///The same as [compile_token](BackusNaurForm::compile_token) but the [NodeContext] of the [NonTerminalToken] is passed to its [ContextCompileFunction].  
+    ///If the [NonTerminalToken] has a [ContextCompileFunction] and a [CompileFunction], the [ContextCompileFunction] is used.
+    pub fn compile_token_with_context(
+        &self,
+        non_terminal: &NonTerminalToken,
+        context: &NodeContext,
+    ) -> Option<String> {
         let name = &non_terminal.non_terminal_symbol;
-        self.compile_functions
-            .get(name)
-            .map(|f| f(non_terminal, self))
+        let context_function = self.find_compile_function(&self.context_compile_functions, name);
+        let function = self.find_compile_function(&self.compile_functions, name);
+        let compile = || match (context_function, function) {
+            (Some(f), _) => Some(f(non_terminal, context, self)),
+            (None, Some(f)) => Some(f(non_terminal, self)),
+            (None, None) => None,
+        };
+
+        let Some(cache) = &self.compile_cache else {
+            return compile();
+        };
+        //the result of a function that doesn't receive the context is the same in every context
+        let key = (
+            non_terminal.clone(),
+            context_function.map(|_| context.clone()),
+        );
+        if let Some(result) = cache.borrow().get(&key) {
+            return Some(result.to_string());
+        }
+        //the cache isn't borrowed while compiling, since the function may compile other tokens
+        let result = compile()?;
+        cache.borrow_mut().insert(key, result.to_string());
+        Some(result)
+    }
+
+    ///Enables or disables the memoization of [compile_token](BackusNaurForm::compile_token).  
+    ///If it is enabled, the result of every compiled [NonTerminalToken] is remembered, so identical subtrees are only compiled once.
+    ///The results are keyed by the structure of the subtree (and the [NodeContext] for [ContextCompileFunction]s).
+    ///This is useful for inputs with many repeated fragments, but it only works if the compile functions always return the same result for the same subtree.  
+    ///Adding a compile function or a rule clears the remembered results.
+    pub fn set_compile_memoization(&mut self, enabled: bool) {
+        self.compile_cache = enabled.then(RefCell::default);
+    }
+
+    ///Forgets the results that were remembered because of [set_compile_memoization](BackusNaurForm::set_compile_memoization).
+    pub fn clear_compile_cache(&self) {
+        if let Some(cache) = &self.compile_cache {
+            cache.borrow_mut().clear();
+        }
+    }
+
+    //Returns the function of the symbol or of the template the symbol is a use of.
+    fn find_compile_function<'f, F>(
+        &self,
+        functions: &'f BTreeMap<String, F>,
+        name: &str,
+    ) -> Option<&'f F> {
+        functions.get(name).or_else(|| {
+            let template = self
+                .templates
+                .iter()
+                .find(|template| template.is_used_by(name))?;
+            functions.get(template.get_full_name())
+        })
+    }
+
+    ///Returns the names of the symbols that have a [CompileFunction] or a [ContextCompileFunction], sorted by name.
+    pub fn compiled_symbols(&self) -> Vec<&str> {
+        let mut names = self
+            .compile_functions
+            .keys()
+            .chain(self.context_compile_functions.keys())
+            .map(String::as_str)
+            .collect::<Vec<_>>();
+        names.sort_unstable();
+        names.dedup();
+        names
     }
 
     ///Used to add functions that compiles a [NonTerminalToken] into a [String].  
     pub fn add_compile_function(&mut self, non_terminal_symbol: &str, f: CompileFunction<'a>) {
+        self.clear_compile_cache();
         self.compile_functions
-            .insert(non_terminal_symbol.to_string(), f);
+            .insert(canonical_name(non_terminal_symbol), f);
+    }
+
+    ///Used to add functions that compile a [NonTerminalToken] into a [String] depending on where the [NonTerminalToken] is in the AST.  
+    ///For example a `<ident>` can be compiled differently inside of a `<declaration>` than inside of a `<call>`.
+    ///More information can be found in the [node_context] module.
+    pub fn add_context_compile_function(
+        &mut self,
+        non_terminal_symbol: &str,
+        f: ContextCompileFunction<'a>,
+    ) {
+        self.clear_compile_cache();
+        self.context_compile_functions
+            .insert(canonical_name(non_terminal_symbol), f);
     }
 
     ///This function tests wether the given [String] can be turned into exactly one [Token] - a root token.  
@@ -253,15 +882,105 @@ impl<'a> BackusNaurForm<'a> {
     /// ```
     /// This would be valid since the vector returned form [symbolize_string](BackusNaurForm::symbolize_string) will have a len == 1
     /// since everything would be encompassed by one `<syntax>` [NonTerminalSymbol].
+    ///
+    /// To find out why a [String] has no root [Token] (for example because a valid part is followed by trailing garbage), use [parse](BackusNaurForm::parse).
+    ///
+    /// # Panics
+    /// Panics in the same cases as [symbolize_string](BackusNaurForm::symbolize_string).
     pub fn compiles_to_root_token(&self, string: &str) -> bool {
         self.symbolize_string(string).len() == 1
     }
+
+    ///Symbolizes the [String] and returns its root [Token], the [NonTerminalToken] that spans from the start to the end of the [String].  
+    ///Unlike [compiles_to_root_token](BackusNaurForm::compiles_to_root_token) this tells apart a [String] that is fully valid
+    ///from a [String] that starts with a valid root [Token] but has trailing garbage after it.
+    ///In the second case a [ParseError::TrailingInput] with the span of the garbage is returned.
+    pub fn parse(&self, string: &str) -> Result<NonTerminalToken, ParseError> {
+        let mut tokens = self.try_symbolize_string(string)?.into_iter();
+        //the spans are found in the preprocessed string and mapped back into the original one
+        let preprocessed = self.preprocess(string);
+        let length = preprocessed.get_text().chars().count();
+        let Some(first) = tokens.next() else {
+            return Err(ParseError::EmptyInput);
+        };
+        let root = match first {
+            Token::NonTerminalToken(root) => root,
+            Token::Terminal(_) => {
+                //the input is valid up to the first non terminal token
+                let start = tokens
+                    .position(|token| !token.is_terminal())
+                    .map_or(length, |index| index + 1);
+                return Err(ParseError::NoRootToken {
+                    span: preprocessed.original_range(0..start),
+                });
+            }
+        };
+        let end = root.get_terminals().chars().count();
+        if end < length {
+            return Err(ParseError::TrailingInput {
+                root: root.non_terminal_symbol,
+                span: preprocessed.original_range(end..length),
+            });
+        }
+        Ok(root)
+    }
+
+    ///Symbolizes the [String] and returns the single [Token] it turns into.
+    ///Unlike [compiles_to_root_token](BackusNaurForm::compiles_to_root_token) this returns a [RootError::NoSingleRoot]
+    ///with the symbol and the span of every top level [Token] if there isn't exactly one.
+    pub fn root_token(&self, string: &str) -> Result<Token, RootError> {
+        let mut tokens = self.try_symbolize_string(string)?;
+        if tokens.len() == 1 {
+            return Ok(tokens.remove(0));
+        }
+        let preprocessed = self.preprocess(string);
+        let mut position = 0;
+        let tokens = tokens
+            .iter()
+            .map(|token| {
+                let start = position;
+                position += token.get_terminals().chars().count();
+                (
+                    token.get_type(),
+                    preprocessed.original_range(start..position),
+                )
+            })
+            .collect();
+        Err(RootError::NoSingleRoot { tokens })
+    }
+
+    ///The same as [try_symbolize_string](BackusNaurForm::try_symbolize_string), but every char has to be reduced into a non terminal token.
+    ///If terminals are left at the top level, a [SymbolizeError::Unreduced] with the span of the first run of them is returned.
+    ///This is for pipelines where a input that was only partially symbolized must not go on silently.
+    pub fn symbolize_strict(&self, string: &str) -> Result<Vec<Token>, SymbolizeError> {
+        let tokens = self.try_symbolize_string(string)?;
+        let Some(start) = tokens.iter().position(|token| token.is_terminal()) else {
+            return Ok(tokens);
+        };
+        let end = tokens[start..]
+            .iter()
+            .position(|token| !token.is_terminal())
+            .map_or(tokens.len(), |length| start + length);
+        //the spans are found in the preprocessed string and mapped back into the original one
+        let char_count = |tokens: &[Token]| {
+            tokens
+                .iter()
+                .map(|token| token.get_terminals().chars().count())
+                .sum::<usize>()
+        };
+        let span_start = char_count(&tokens[..start]);
+        let span = span_start..span_start + char_count(&tokens[start..end]);
+        Err(SymbolizeError::Unreduced {
+            span: self.preprocess(string).original_range(span),
+        })
+    }
 }
 
 ///Used to create [BackusNaurForm]s declaratively.  
 ///Following things need to be specified:
 ///- A priority. Rules with higher priority will be applied first.
-///- A rule. A rule is simply a raw string literal (for example `<abc> ::= "a" | "b" | "c"`). It must be a valid [BackusNaurForm] rule.
+///- A rule. A rule is simply a raw string literal (for example `<abc> ::= "a" | "b" | "c"`). It must be a valid [BackusNaurForm] rule.  
+///  Instead of a literal, any expression that can be borrowed as a &[str] can be used, for example a constant or a [String] made by [generated_rule!](crate::generated_rule).
 ///- A optional closure that takes in the specified [NonTerminalToken] by reference and outputs a [String].
 ///
 /// ## Syntax
@@ -269,6 +988,27 @@ impl<'a> BackusNaurForm<'a> {
 /// `priority [priority_number: usize] => <rule_name> ::= [tokens] => |[token_name: &NonTerminalToken] {[closure body]}`.  
 /// The last arrow (the closure) is optional. So this is valid too:  
 /// `priority [priority_number: usize] => <rule_name> ::= [tokens]`  
+/// A doc string can be written after the priority, see [set_rule_doc](BackusNaurForm::set_rule_doc):  
+/// `priority [priority_number: usize] => doc "A decimal digit" => <rule_name> ::= [tokens]`  
+/// Malformed arms (for example a missing `priority` or `=>`) are reported as compile errors that point out what is missing.  
+/// ```compile_fail
+/// //the arm doesn't start with `priority`
+/// let bnf = backus_naur_form_parser_and_compiler::backus_naur_form!(0 => r#"<digit> ::= "1""#);
+/// ```
+/// ```compile_fail
+/// //the `=>` between the priority and the rule is missing
+/// let bnf = backus_naur_form_parser_and_compiler::backus_naur_form!(priority 0 r#"<digit> ::= "1""#);
+/// ```
+/// ```compile_fail
+/// //the compile function has to take the token and the backus naur form
+/// let bnf = backus_naur_form_parser_and_compiler::backus_naur_form!(
+///     priority 0 => r#"<digit> ::= "1""# => |digit| digit.get_terminals()
+/// );
+/// ```
+///
+/// # Panics
+/// Panics if a rule is invalid or a choice has unsupported recursion like `<number> <digit>` in `<number> ::= <digit> | <number> <digit>`
+/// (see [check_recursion](BackusNaurForm::check_recursion)).
 ///
 /// ## Example
 ///   
@@ -295,37 +1035,264 @@ impl<'a> BackusNaurForm<'a> {
 /// ```
 #[macro_export]
 macro_rules! backus_naur_form {
-    ($(priority $priority:expr => $rule:expr $(=> $function_body:expr)?)+) => {{
+    //compile functions that aren't closures with two parameters are checked token by token
+    ($(priority $priority:expr => $rule:expr $(=> |$token:tt $(: $token_type:ty)?, $bnf:tt $(: $bnf_type:ty)?| $function_body:expr)?)+) => {
+        $crate::__collect_backus_naur_form_arms!({} [$([$priority] [] [$rule] [$(|$token $(: $token_type)?, $bnf $(: $bnf_type)?| $function_body)?])+])
+    };
+    //arms with doc strings can't be matched in one go, since `doc` could be the start of the rule too
+    (priority $($tokens:tt)*) => {
+        $crate::__collect_backus_naur_form_arms!({priority $($tokens)*} [] priority $($tokens)*)
+    };
+    //the arms are malformed, so the first malformed arm is reported
+    ($($tokens:tt)*) => {
+        $crate::__validate_backus_naur_form_arms!(@arm $($tokens)*)
+    };
+}
+
+//Collects the arms of a backus_naur_form! invocation as `[priority] [doc] [rule] [compile function]`, where the doc and the function may be empty,
+//and creates the backus naur form out of them.
+//The original tokens are kept in the braces, so a malformed invocation can be handed to __validate_backus_naur_form_arms.
+//Arms are read token by token, the states are:
+//- no state: the start of a arm (`priority`)
+//- @priority: the tokens of the priority until `=>` (and the doc string after it)
+//- @rule: the tokens of the rule until `=>`, the next `priority` or the end
+//- @function: the tokens of the compile function until the next `priority` or the end
+//Simple arms are collected in one step so long invocations don't reach the recursion limit.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __collect_backus_naur_form_arms {
+    ({$($original:tt)*} [$([$priority:expr] [$($doc:literal)?] [$rule:expr] [$($function_body:expr)?])+]) => {{
         let mut bnf = $crate::backus_naur_form::BackusNaurForm::default();
         $(
-            if let Some((name, _)) = $rule.split_once("::=") {
-                let _non_terminal_name = &name.trim()[1..name.len() - 2];
+            let rule: &str = &$rule;
+            bnf.add_non_terminal_symbol_from_rule(rule, $priority);
+            if let Some((name, _)) = rule.split_once("::=") {
+                let name = name.trim();
+                //a node label (`<name> -> Label`) may follow the name
+                let name = match name.rsplit_once("->") {
+                    Some((name, _)) if !name.ends_with('>') => name.trim_end(),
+                    _ => name,
+                };
+                let _non_terminal_name = &name[1..name.len() - 1];
+                $(
+                    //the tokens of the rule are compiled under their label
+                    let label = bnf.node_label(_non_terminal_name).to_string();
+                    bnf.add_compile_function(&label, &$function_body);
+                )?
                 $(
-                    bnf.add_compile_function(_non_terminal_name, &$function_body);
+                    bnf.set_rule_doc(_non_terminal_name, $doc);
                 )?
             } else {
-                panic!("the replacement operator (::=) is missing or invalid in the rule {}", $rule);
+                panic!("the replacement operator (::=) is missing or invalid in the rule {}", rule);
             }
-
-
-
-            bnf.add_non_terminal_symbol_from_rule($rule, $priority);
         )+
+        if let Err(error) = bnf.check_recursion() {
+            panic!("{}", error);
+        }
         bnf
     }};
+    ({$($original:tt)*} [$($arms:tt)*] priority $priority:tt => doc $doc:literal => $rule:tt priority $($rest:tt)*) => {
+        $crate::__collect_backus_naur_form_arms!({$($original)*} [$($arms)* [$priority] [$doc] [$rule] []] priority $($rest)*)
+    };
+    ({$($original:tt)*} [$($arms:tt)*] priority $priority:tt => $rule:tt priority $($rest:tt)*) => {
+        $crate::__collect_backus_naur_form_arms!({$($original)*} [$($arms)* [$priority] [] [$rule] []] priority $($rest)*)
+    };
+    ({$($original:tt)*} [$($arms:tt)*] priority $($rest:tt)*) => {
+        $crate::__collect_backus_naur_form_arms!(@priority {$($original)*} [$($arms)*] [] $($rest)*)
+    };
+
+    (@priority {$($original:tt)*} [$($arms:tt)*] [$($priority:tt)+] => doc $doc:literal => $($rest:tt)*) => {
+        $crate::__collect_backus_naur_form_arms!(@rule {$($original)*} [$($arms)*] [$($priority)+] [$doc] [] $($rest)*)
+    };
+    (@priority {$($original:tt)*} [$($arms:tt)*] [$($priority:tt)+] => $($rest:tt)*) => {
+        $crate::__collect_backus_naur_form_arms!(@rule {$($original)*} [$($arms)*] [$($priority)+] [] [] $($rest)*)
+    };
+    (@priority {$($original:tt)*} [$($arms:tt)*] [$($priority:tt)*] $token:tt $($rest:tt)*) => {
+        $crate::__collect_backus_naur_form_arms!(@priority {$($original)*} [$($arms)*] [$($priority)* $token] $($rest)*)
+    };
+
+    (@rule {$($original:tt)*} [$($arms:tt)*] $priority:tt $doc:tt [$($rule:tt)+]) => {
+        $crate::__collect_backus_naur_form_arms!({$($original)*} [$($arms)* $priority $doc [$($rule)+] []])
+    };
+    (@rule {$($original:tt)*} [$($arms:tt)*] $priority:tt $doc:tt [$($rule:tt)+] priority $($rest:tt)*) => {
+        $crate::__collect_backus_naur_form_arms!({$($original)*} [$($arms)* $priority $doc [$($rule)+] []] priority $($rest)*)
+    };
+    //closures with the wrong number of parameters are reported
+    (@rule {$($original:tt)*} [$($arms:tt)*] $priority:tt $doc:tt [$($rule:tt)+] => $(move)? || $($rest:tt)*) => {
+        $crate::__validate_backus_naur_form_arms!(@arm $($original)*)
+    };
+    (@rule {$($original:tt)*} [$($arms:tt)*] $priority:tt $doc:tt [$($rule:tt)+] => $(move)? | $token:tt $(: $token_type:ty)? | $($rest:tt)*) => {
+        $crate::__validate_backus_naur_form_arms!(@arm $($original)*)
+    };
+    (@rule {$($original:tt)*} [$($arms:tt)*] $priority:tt $doc:tt [$($rule:tt)+] => $(move)? | $token:tt $(: $token_type:ty)?, $bnf:tt $(: $bnf_type:ty)?, $($rest:tt)*) => {
+        $crate::__validate_backus_naur_form_arms!(@arm $($original)*)
+    };
+    (@rule {$($original:tt)*} [$($arms:tt)*] $priority:tt $doc:tt [$($rule:tt)+] => $($rest:tt)*) => {
+        $crate::__collect_backus_naur_form_arms!(@function {$($original)*} [$($arms)*] $priority $doc [$($rule)+] [] $($rest)*)
+    };
+    (@rule {$($original:tt)*} [$($arms:tt)*] $priority:tt $doc:tt [$($rule:tt)*] $token:tt $($rest:tt)*) => {
+        $crate::__collect_backus_naur_form_arms!(@rule {$($original)*} [$($arms)*] $priority $doc [$($rule)* $token] $($rest)*)
+    };
+
+    (@function {$($original:tt)*} [$($arms:tt)*] $priority:tt $doc:tt $rule:tt [$($function:tt)+]) => {
+        $crate::__collect_backus_naur_form_arms!({$($original)*} [$($arms)* $priority $doc $rule [$($function)+]])
+    };
+    (@function {$($original:tt)*} [$($arms:tt)*] $priority:tt $doc:tt $rule:tt [$($function:tt)+] priority $($rest:tt)*) => {
+        $crate::__collect_backus_naur_form_arms!({$($original)*} [$($arms)* $priority $doc $rule [$($function)+]] priority $($rest)*)
+    };
+    (@function {$($original:tt)*} [$($arms:tt)*] $priority:tt $doc:tt $rule:tt [$($function:tt)*] $token:tt $($rest:tt)*) => {
+        $crate::__collect_backus_naur_form_arms!(@function {$($original)*} [$($arms)*] $priority $doc $rule [$($function)* $token] $($rest)*)
+    };
+
+    //the arms are malformed
+    (@$state:ident {$($original:tt)*} $($rest:tt)*) => {
+        $crate::__validate_backus_naur_form_arms!(@arm $($original)*)
+    };
+    ({$($original:tt)*} $($rest:tt)*) => {
+        $crate::__validate_backus_naur_form_arms!(@arm $($original)*)
+    };
+}
+
+//Walks through the arms of a malformed backus_naur_form! invocation and emits a compile_error! for the first malformed arm.
+//Arms are read token by token, the states are:
+//- @arm: the start of a arm (`priority`)
+//- @priority: the tokens of the priority until `=>`
+//- @rule: the tokens of the rule until `=>`, the next `priority` or the end
+//- @function: the tokens of the compile function until the next `priority` or the end
+//- @closure: the parameters of the compile function if it is a closure
+//Simple arms are skipped in one step so long invocations don't reach the recursion limit.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __validate_backus_naur_form_arms {
+    (@arm) => {
+        compile_error!("backus_naur_form! needs at least one arm, for example `priority 0 => r#\"<digit> ::= \"1\"\"#`")
+    };
+    (@arm priority $priority:tt => $rule:tt => |$token:ident, $bnf:ident| { $($body:tt)* } priority $($rest:tt)*) => {
+        $crate::__validate_backus_naur_form_arms!(@arm priority $($rest)*)
+    };
+    (@arm priority $priority:tt => $rule:tt priority $($rest:tt)*) => {
+        $crate::__validate_backus_naur_form_arms!(@arm priority $($rest)*)
+    };
+    (@arm priority $($rest:tt)*) => {
+        $crate::__validate_backus_naur_form_arms!(@priority [] $($rest)*)
+    };
+    (@arm $token:tt $($rest:tt)*) => {
+        compile_error!(concat!(
+            "expected `priority`, every arm starts with `priority [number] => [rule]`, but this arm starts with `",
+            stringify!($token),
+            "`"
+        ))
+    };
+
+    (@priority [] => $($rest:tt)*) => {
+        compile_error!("the priority is missing, expected `priority [number] => [rule]`")
+    };
+    (@priority [$($priority:tt)+] => doc $doc:literal => $($rest:tt)*) => {
+        $crate::__validate_backus_naur_form_arms!(@rule [] $($rest)*)
+    };
+    (@priority [$($priority:tt)+] => $($rest:tt)*) => {
+        $crate::__validate_backus_naur_form_arms!(@rule [] $($rest)*)
+    };
+    (@priority [$($priority:tt)*] priority $($rest:tt)*) => {
+        compile_error!(concat!("expected `=>` between the priority and the rule in `priority ", stringify!($($priority)*), "`"))
+    };
+    (@priority [$($priority:tt)*]) => {
+        compile_error!(concat!("expected `=>` between the priority and the rule in `priority ", stringify!($($priority)*), "`"))
+    };
+    (@priority [$($priority:tt)*] $token:tt $($rest:tt)*) => {
+        $crate::__validate_backus_naur_form_arms!(@priority [$($priority)* $token] $($rest)*)
+    };
+
+    (@rule [] => $($rest:tt)*) => {
+        compile_error!("the rule is missing, expected `priority [number] => [rule]` where the rule is a string like `r#\"<digit> ::= \"1\"\"#`")
+    };
+    (@rule [] $(priority $($rest:tt)*)?) => {
+        compile_error!("the rule is missing, expected `priority [number] => [rule]` where the rule is a string like `r#\"<digit> ::= \"1\"\"#`")
+    };
+    (@rule [$($rule:tt)+]) => {
+        $crate::__validate_backus_naur_form_arms!(@end)
+    };
+    (@rule [$($rule:tt)+] priority $($rest:tt)*) => {
+        $crate::__validate_backus_naur_form_arms!(@arm priority $($rest)*)
+    };
+    (@rule [$($rule:tt)+] => $($rest:tt)*) => {
+        $crate::__validate_backus_naur_form_arms!(@function [] $($rest)*)
+    };
+    (@rule [$($rule:tt)*] $token:tt $($rest:tt)*) => {
+        $crate::__validate_backus_naur_form_arms!(@rule [$($rule)* $token] $($rest)*)
+    };
+
+    (@function [] $(priority $($rest:tt)*)?) => {
+        compile_error!("the compile function after `=>` is missing, expected a closure like `|token, _bnf| token.get_terminals()`")
+    };
+    (@function [$($function:tt)+]) => {
+        $crate::__validate_backus_naur_form_arms!(@closure [] $($function)+)
+    };
+    (@function [$($function:tt)+] priority $($rest:tt)*) => {
+        $crate::__validate_backus_naur_form_arms!(@closure [priority $($rest)*] $($function)+)
+    };
+    (@function [$($function:tt)*] $token:tt $($rest:tt)*) => {
+        $crate::__validate_backus_naur_form_arms!(@function [$($function)* $token] $($rest)*)
+    };
+
+    //checks the number of parameters if the compile function is a closure, the remaining arms are in the brackets
+    (@closure [$($rest:tt)*] $(move)? || $($body:tt)*) => {
+        compile_error!("the compile function takes no arguments, but it has to take two: the token and the backus naur form (for example `|token, _bnf| ...`)")
+    };
+    (@closure [$($rest:tt)*] $(move)? | $token:tt $(: $token_type:ty)? | $($body:tt)*) => {
+        compile_error!("the compile function takes one argument, but it has to take two: the token and the backus naur form (for example `|token, _bnf| ...`)")
+    };
+    (@closure [$($rest:tt)*] $(move)? | $token:tt $(: $token_type:ty)?, $bnf:tt $(: $bnf_type:ty)?, $($parameters:tt)+) => {
+        compile_error!("the compile function takes more than two arguments, but it has to take two: the token and the backus naur form (for example `|token, _bnf| ...`)")
+    };
+    (@closure [] $($function:tt)*) => {
+        $crate::__validate_backus_naur_form_arms!(@end)
+    };
+    (@closure [$($rest:tt)+] $($function:tt)*) => {
+        $crate::__validate_backus_naur_form_arms!(@arm $($rest)+)
+    };
+
+    //every arm looks fine, so one of the expressions is invalid
+    (@end) => {
+        compile_error!("the arms of backus_naur_form! are malformed, every priority, rule and compile function has to be a valid expression")
+    };
+}
+
+///Generates a rule whose choices are made by iterating over something, for example a range.  
+///Every item of the iterator is bound to the pattern after `for` and turned into one choice.
+///The choice is either a format string literal (which can use the bound variables) or any expression that implements [Display](std::fmt::Display).  
+///The result is a [String] that can be used as a rule in [backus_naur_form!](crate::backus_naur_form).
+///
+/// ## Example
+/// ```rust, ignore
+/// backus_naur_form!(
+///     //<digit> ::= "0" | "1" | "2" | "3" | "4" | "5" | "6" | "7" | "8" | "9"
+///     priority 0 => generated_rule!("digit", for digit in 0..=9 => r#""{digit}""#)
+///     //<letter> ::= "a" | "b" | ... | "z"
+///     priority 0 => generated_rule!("letter", for letter in 'a'..='z' => format!("\"{letter}\""))
+/// )
+/// ```
+#[macro_export]
+macro_rules! generated_rule {
+    ($name:expr, for $variable:pat in $iterator:expr => $choice:literal) => {
+        $crate::generated_rule!($name, for $variable in $iterator => format!($choice))
+    };
+    ($name:expr, for $variable:pat in $iterator:expr => $choice:expr) => {
+        format!(
+            "<{}> ::= {}",
+            $name,
+            ($iterator)
+                .into_iter()
+                .map(|$variable| ($choice).to_string())
+                .collect::<Vec<_>>()
+                .join(" | ")
+        )
+    };
 }
 
 impl Debug for BackusNaurForm<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let rules = self
-            .rules
-            .iter()
-            .fold(String::new(), |rule_set, (non_terminal_symbol, _)| {
-                let stringified_expression = stringify_expression(non_terminal_symbol.get_rule());
-                let name = non_terminal_symbol.get_name();
-                format!("{rule_set} \n <{name}> ::= {stringified_expression}")
-            });
-        write!(f, "{rules}")
+        write!(f, "{}", self.to_bnf_string(print::DEFAULT_WIDTH))
     }
 }
 
@@ -372,19 +1339,8 @@ where
     vec.insert(range.start, replace_with(removed_elements));
 }
 
-//used for the Debug implementation of BackusNaurForm.
-fn stringify_expression(expression: &Expression) -> String {
-    expression
-        .iter()
-        .enumerate()
-        .fold(String::new(), |expr, (index, choice)| {
-            let stringified_choice = stringify_choice(choice, index);
-            expr + &stringified_choice
-        })
-}
-
-//used for the Debug implementation of BackusNaurForm.
-//Helper function for stringify_expression.
+//Used by the lints and the optimizer to show choices.
+//Returns the choice like it is written in a rule, with a pipe in front of it if it isn't the first choice.
 fn stringify_choice(choice: &Choice, index: usize) -> String {
     choice.iter().fold(
         if index != 0 { "| " } else { "" }.to_string(),
@@ -395,12 +1351,221 @@ fn stringify_choice(choice: &Choice, index: usize) -> String {
     )
 }
 
+//Sorts the rules so the rules with the highest priority come first.
+fn sort_rules(rules: &[(NonTerminalSymbol, usize)]) -> Vec<&(NonTerminalSymbol, usize)> {
+    let mut sorted_rules = rules.iter().collect::<Vec<_>>();
+    sorted_rules.sort_by_key(|(_, priority)| *priority);
+    sorted_rules.reverse();
+    sorted_rules
+}
+
+//Applies the rules (which have to be sorted by priority, highest first) until no rule can be applied anymore.
+//The table has to be created from the sorted rules, the rules are matched against the ids of the tokens.
+//If phased is true, the rules of every priority are applied until none of them can be applied anymore before the next priority
+//is applied, and a priority is never applied again after that (see set_priority_phases).
+//observe is called for every reduction in the order they are applied.
+//Returns a SymbolizeError::NonTermination if the rules would be applied forever.
+fn symbolize_to_fixed_point(
+    sorted_rules: &[&(NonTerminalSymbol, usize)],
+    table: &SymbolTable,
+    mut tokenized_string: Vec<Token>,
+    phased: bool,
+    observe: &mut Observer,
+) -> Result<Vec<Token>, SymbolizeError> {
+    //the ids of the symbols of the tokens, they are replaced together with the tokens
+    let mut ids = table.token_ids(&tokenized_string);
+    CharClasses::new(sorted_rules).tag(
+        sorted_rules,
+        table,
+        &mut tokenized_string,
+        &mut ids,
+        observe,
+    )?;
+
+    //the amount of rules of every phase. without phases, every rule is in the same phase
+    let phase_lengths = if phased {
+        sorted_rules
+            .chunk_by(|a, b| a.1 == b.1)
+            .map(<[_]>::len)
+            .collect()
+    } else {
+        vec![sorted_rules.len()]
+    };
+    let mut start = 0;
+    for length in phase_lengths {
+        reduce_to_fixed_point(
+            sorted_rules,
+            table,
+            start..start + length,
+            &mut tokenized_string,
+            &mut ids,
+            observe,
+        )?;
+        start += length;
+    }
+    Ok(tokenized_string)
+}
+
+//Helper function for symbolize_to_fixed_point.
+//Applies the rules at the indexes of the phase until none of them can be applied anymore.
+//
+//Every iteration terminates or makes progress: the table has no empty choices, so every reduction replaces at least one token
+//and the amount of tokens never grows. A iteration either removes tokens or only renames them (for example `<a> ::= <b>`),
+//and since there are only finitely many ways to name the same amount of tokens, a renaming that doesn't end has to repeat a state.
+fn reduce_to_fixed_point(
+    sorted_rules: &[&(NonTerminalSymbol, usize)],
+    table: &SymbolTable,
+    phase: Range<usize>,
+    tokenized_string: &mut Vec<Token>,
+    ids: &mut Vec<SymbolId>,
+    observe: &mut Observer,
+) -> Result<(), SymbolizeError> {
+    //the states after every iteration. the rules only look at the symbols of the uppermost tokens,
+    //so if the same symbols come up again, the rules would repeat the same reductions forever.
+    //the tokens whose symbol isn't in any rule all have the same id, but since no rule can reduce them they never change anyway.
+    let mut seen_states = HashSet::from([ids.clone()]);
+    let mut token_count = ids.len();
+
+    loop {
+        //the first rule that modified the tokens this iteration
+        let mut modified_by = None;
+        let mut rule_scan = RuleScan::new(table);
+        for (index, (rule, rule_ids)) in sorted_rules
+            .iter()
+            .zip(table.rules())
+            .enumerate()
+            .take(phase.end)
+            .skip(phase.start)
+        {
+            //a rule that can't be applied wouldn't change anything
+            let Some(matches) = rule_scan.matches(index, ids) else {
+                continue;
+            };
+            let non_terminal_symbol = &rule.0;
+            modified_by.get_or_insert(non_terminal_symbol.get_name());
+
+            non_terminal_symbol.symbolize_vec(
+                rule_ids,
+                matches,
+                tokenized_string,
+                ids,
+                &mut |range, tokens| observe(rule, range, tokens),
+            )?;
+            rule_scan.tokens_modified();
+        }
+
+        let Some(modified_by) = modified_by else {
+            return Ok(());
+        };
+        debug_assert!(ids.len() <= token_count, "a reduction matched zero tokens");
+        //the states with more tokens can't come up again, so only the renamings of the current amount of tokens are kept
+        if ids.len() < token_count {
+            token_count = ids.len();
+            seen_states.clear();
+        }
+        if !seen_states.insert(ids.clone()) {
+            return Err(SymbolizeError::NonTermination {
+                rule: modified_by.to_string(),
+            });
+        }
+    }
+}
+
+//Returns the memory that set_max_memory counts for a token of the input: the size of a token and the length of its text.
+//The child tokens of a non terminal token are counted too.
+fn token_memory(token: &Token) -> usize {
+    match token {
+        Token::NonTerminalToken(non_terminal) => {
+            size_of::<Token>()
+                + non_terminal.non_terminal_symbol.len()
+                + non_terminal
+                    .get_child_tokens()
+                    .iter()
+                    .map(token_memory)
+                    .sum::<usize>()
+        }
+        Token::Terminal(terminal) => size_of::<Token>() + terminal.get_terminals().len(),
+    }
+}
+
+//Removes every NonTerminalToken of a hidden symbol from the tokens (and their descendants).
+//The child tokens of a removed token take its place.
+fn remove_hidden_tokens(tokens: Vec<Token>, is_hidden: &dyn Fn(&str) -> bool) -> Vec<Token> {
+    tokens
+        .into_iter()
+        .flat_map(|token| match token {
+            Token::NonTerminalToken(mut non_terminal) => {
+                let child_tokens = std::mem::take(non_terminal.get_child_tokens_mut());
+                let child_tokens = remove_hidden_tokens(child_tokens, is_hidden);
+                if is_hidden(&non_terminal.non_terminal_symbol) {
+                    child_tokens
+                } else {
+                    *non_terminal.get_child_tokens_mut() = child_tokens;
+                    vec![Token::NonTerminalToken(non_terminal)]
+                }
+            }
+            terminal => vec![terminal],
+        })
+        .collect()
+}
+
+//Removes every NonTerminalToken (and every descendant) that was created from one of the inline choices of its symbol.
+//The child tokens of a removed token take its place.
+fn remove_inline_tokens(
+    tokens: Vec<Token>,
+    inline_choices: &HashMap<String, Vec<Choice>>,
+) -> Vec<Token> {
+    tokens
+        .into_iter()
+        .flat_map(|token| match token {
+            Token::NonTerminalToken(mut non_terminal) => {
+                //the child tokens are the symbols of the choice the token was created from
+                let is_inline = inline_choices
+                    .get(&non_terminal.non_terminal_symbol)
+                    .is_some_and(|choices| {
+                        choices.iter().any(|choice| {
+                            let child_tokens = non_terminal.get_child_tokens();
+                            child_tokens.len() == choice.len()
+                                && child_tokens
+                                    .iter()
+                                    .zip(choice)
+                                    .all(|(token, symbol)| token.matches(symbol))
+                        })
+                    });
+                let child_tokens = std::mem::take(non_terminal.get_child_tokens_mut());
+                let child_tokens = remove_inline_tokens(child_tokens, inline_choices);
+                if is_inline {
+                    child_tokens
+                } else {
+                    *non_terminal.get_child_tokens_mut() = child_tokens;
+                    vec![Token::NonTerminalToken(non_terminal)]
+                }
+            }
+            terminal => vec![terminal],
+        })
+        .collect()
+}
+
+//Renames every NonTerminalToken (and its descendants) whose symbol has a label to the label.
+fn label_tokens(tokens: &mut [Token], labels: &HashMap<String, String>) {
+    for token in tokens {
+        if let Token::NonTerminalToken(non_terminal) = token {
+            if let Some(label) = labels.get(&non_terminal.non_terminal_symbol) {
+                non_terminal.non_terminal_symbol = label.to_string();
+            }
+            label_tokens(non_terminal.get_child_tokens_mut(), labels);
+        }
+    }
+}
+
 //Returns a vector of TerminalTokens where every TerminalToken contains exactly on character of the original string.
 //Its only a character each because the algorithm to turn summarize a range of tokens into a higher token needs that.
+//The offset of every TerminalToken is the index of its character.
 fn characterize_string(string: &str) -> Vec<Token> {
     string
         .chars()
-        .map(|char| Token::from_terminal(&char.to_string()))
+        .enumerate()
+        .map(|(position, char)| Token::from_terminal_at(&char.to_string(), position))
         .collect()
 }
 
@@ -408,6 +1573,7 @@ fn characterize_string(string: &str) -> Vec<Token> {
 mod tests {
     #![allow(clippy::single_range_in_vec_init)]
 
+    use crate::backus_naur_form::test_helpers::{digit, DIGIT_RULE};
     use rule::non_terminal_symbol_from_rule;
 
     use super::*;
@@ -416,14 +1582,14 @@ mod tests {
     fn test_backus_naur_form() {
         let bnf = backus_naur_form!(
             priority 0 => r#"<digit> ::= "0" | "1" | "2" | "3" | "4" | "5" | "6" | "7" | "8" | "9""#
-            priority 0 => r#"<number> ::= <digit> | <number> <digit>"#
+            priority 0 => r#"<number> ::= <digit> | <number> <number>"#
         );
         let mut rhs = BackusNaurForm::default();
         let non_terminal_symbol1 = non_terminal_symbol_from_rule(
             r#"<digit> ::= "0" | "1" | "2" | "3" | "4" | "5" | "6" | "7" | "8" | "9""#,
         );
         let non_terminal_symbol2 =
-            non_terminal_symbol_from_rule(r#"<number> ::= <digit> | <number> <digit>"#);
+            non_terminal_symbol_from_rule(r#"<number> ::= <digit> | <number> <number>"#);
         rhs.add_non_terminal_symbol(non_terminal_symbol1, 0);
         rhs.add_non_terminal_symbol(non_terminal_symbol2, 0);
         assert_eq!(bnf, rhs);
@@ -464,7 +1630,7 @@ mod tests {
     #[test]
     fn test_priority() {
         let bnf = backus_naur_form!(
-            priority 0 => r#"<digit> ::= "1" | "2""#
+            priority 0 => DIGIT_RULE
             priority 0 => r#"<sum> ::= <digit> "+" <digit>"#
             priority 1 => r#"<product> ::= <digit> "*" <digit>"#
         );
@@ -483,6 +1649,30 @@ mod tests {
         )
     }
 
+    #[test]
+    fn test_priority_phases() {
+        let mut bnf = backus_naur_form!(
+            priority 2 => r#"<a> ::= "x" | <b> "y""#
+            priority 1 => r#"<b> ::= <a> <a>"#
+        );
+        let a = |tokens| Token::from_non_terminal("a", tokens);
+        let b = |tokens| Token::from_non_terminal("b", tokens);
+        let x = || a(vec![Token::from_terminal("x")]);
+
+        //without phases <a> is applied again to the <b> that was created after it
+        assert_eq!(
+            bnf.symbolize_string("xxy"),
+            vec![a(vec![b(vec![x(), x()]), Token::from_terminal("y")])]
+        );
+
+        //with phases <a> can't be applied anymore once <b> was applied
+        bnf.set_priority_phases(true);
+        assert_eq!(
+            bnf.symbolize_string("xxy"),
+            vec![b(vec![x(), x()]), Token::from_terminal("y")]
+        );
+    }
+
     #[test]
     fn test_symbolization() {
         let expression = |vec| Token::from_non_terminal("expression", vec);
@@ -550,6 +1740,64 @@ mod tests {
         )
     }
 
+    #[test]
+    fn test_compiled_symbols() {
+        let mut bnf = backus_naur_form!(
+            priority 1 => DIGIT_RULE
+            priority 0 => r#"<sum> ::= <digit> "+" <digit>"# => |sum, bnf| {
+                bnf.compile_tokens(sum.get_child_tokens())
+            }
+        );
+        bnf.add_context_compile_function("digit", &|digit, context, _bnf| {
+            format!(
+                "{}@{}",
+                digit.get_terminals(),
+                context.sibling_index().unwrap_or_default()
+            )
+        });
+        bnf.add_compile_function("digit", &|digit, _bnf| digit.get_terminals());
+        bnf.add_compile_function("a-first", &|_, _bnf| String::new());
+
+        assert_eq!(bnf.compiled_symbols(), ["a-first", "digit", "sum"]);
+        //the context function of <digit> is used instead of the other one
+        assert_eq!(bnf.compile_string("1+2"), "1@0+2@2");
+    }
+
+    #[test]
+    fn test_compile_memoization() {
+        let calls = std::cell::Cell::new(0);
+        let compile_item = |item: &NonTerminalToken, _bnf: &BackusNaurForm| {
+            calls.set(calls.get() + 1);
+            item.get_terminals().to_uppercase()
+        };
+        let compile_list = |list: &NonTerminalToken, bnf: &BackusNaurForm| {
+            list.get_child_tokens()
+                .iter()
+                .filter_map(|item| bnf.compile_token(item.to_non_terminal_ref()?))
+                .collect::<String>()
+        };
+        let mut bnf = BackusNaurForm::default();
+        bnf.add_compile_function("item", &compile_item);
+        bnf.add_compile_function("list", &compile_list);
+        let item =
+            |terminal| Token::from_non_terminal("item", vec![Token::from_terminal(terminal)]);
+        let list = NonTerminalToken::new("list", vec![item("a"), item("b"), item("a"), item("a")]);
+
+        assert_eq!(bnf.compile_token(&list), Some("ABAA".to_string()));
+        assert_eq!(calls.get(), 4);
+
+        calls.set(0);
+        bnf.set_compile_memoization(true);
+        assert_eq!(bnf.compile_token(&list), Some("ABAA".to_string()));
+        assert_eq!(calls.get(), 2);
+        assert_eq!(bnf.compile_token(&list), Some("ABAA".to_string()));
+        assert_eq!(calls.get(), 2);
+
+        bnf.clear_compile_cache();
+        assert_eq!(bnf.compile_token(&list), Some("ABAA".to_string()));
+        assert_eq!(calls.get(), 4);
+    }
+
     #[test]
     fn test_compile_string() {
         let bnf = backus_naur_form!(
@@ -605,4 +1853,663 @@ mod tests {
             "4<here comes the operator>6".to_string()
         );
     }
+
+    #[test]
+    fn test_compile_tokens() {
+        let bnf = backus_naur_form!(
+            priority 1 => r#"<digit> ::= "1" | "2" | "3""# => |digit, _bnf| {
+                (digit.get_terminals().parse::<usize>().unwrap() * 2).to_string()
+            }
+            priority 0 => r#"<sum> ::= <digit> "+" <digit>"# => |sum, bnf| {
+                let digits = sum
+                    .get_child_tokens_of_type(&Symbol::non_terminal("digit"))
+                    .into_iter()
+                    .map(|digit| bnf.compile_token(digit.to_non_terminal_ref().unwrap()).unwrap())
+                    .collect::<Vec<_>>();
+                digits.join(" plus ")
+            }
+        );
+
+        //a tree that was transformed is compiled without turning it back into a string
+        let mut tokens = bnf.symbolize_string("2+3?");
+        if let Token::NonTerminalToken(sum) = &mut tokens[0] {
+            sum.get_child_tokens_mut()[2] =
+                Token::from_non_terminal("digit", vec![Token::from_terminal("1")]);
+        }
+        assert_eq!(bnf.compile_tokens(&tokens), "4 plus 2?");
+        assert_eq!(bnf.compile_root(&tokens[0]), "4 plus 2");
+    }
+
+    #[test]
+    fn test_bracket_pairs() {
+        let mut bnf = backus_naur_form!(
+            priority 0 => DIGIT_RULE
+            priority 0 => r#"<expression> ::= <digit> | "(" <expression> ")" | <expression> "+" <expression>"#
+        );
+        let symbolized_without_brackets = bnf.symbolize_string("(1+(2+1))");
+        bnf.add_bracket_pair("(", ")");
+
+        assert_eq!(
+            bnf.symbolize_string("(1+(2+1))"),
+            symbolized_without_brackets
+        );
+        assert_eq!(
+            bnf.try_symbolize_string("(1+2"),
+            Err(SymbolizeError::UnclosedBracket {
+                bracket: "(".to_string(),
+                position: 0
+            })
+        );
+    }
+
+    #[test]
+    fn test_chunk_delimiters() {
+        let mut bnf = backus_naur_form!(
+            priority 2 => DIGIT_RULE
+            priority 1 => r#"<pair> ::= <digit> ";" <digit>"#
+            priority 0 => r#"<line> ::= <digit> ";" | <digit>"#
+        );
+        let unchunked = bnf.symbolize_string("1;2");
+        assert_eq!(unchunked.len(), 1);
+        assert_eq!(unchunked[0].get_symbol(), "pair");
+
+        bnf.add_chunk_delimiter(";");
+        let tokens = bnf.symbolize_string("1;2");
+        assert_eq!(
+            tokens.iter().map(Token::get_symbol).collect::<Vec<_>>(),
+            ["line", "line"]
+        );
+        assert_eq!(tokens[0].get_terminals(), "1;");
+
+        bnf.add_bracket_pair("(", ")");
+        assert_eq!(
+            bnf.try_symbolize_string("1;(2"),
+            Err(SymbolizeError::UnclosedBracket {
+                bracket: "(".to_string(),
+                position: 2
+            })
+        );
+    }
+
+    #[test]
+    fn test_parameterized_rules() {
+        let bnf = backus_naur_form!(
+            priority 0 => r#"<list(x)> ::= <x> | <list(x)> "," <list(x)>"# => |list, _bnf| {
+                format!("[{}]", list.get_terminals())
+            }
+            priority 0 => DIGIT_RULE
+            priority 0 => r#"<letter> ::= "a" | "b""#
+            priority 0 => r#"<args> ::= "(" <list(digit)> ")" | "(" <list(letter)> ")""#
+        );
+
+        assert!(bnf.contains_symbol("list(digit)"));
+        assert!(bnf.contains_symbol("list(letter)"));
+        assert!(!bnf.contains_symbol("list(x)"));
+        assert!(bnf.compiles_to_root_token("(1,2,1)"));
+        assert!(bnf.compiles_to_root_token("(a,b)"));
+        assert!(!bnf.compiles_to_root_token("(a,1)"));
+        assert_eq!(bnf.compile_string("1,2"), "[1,2]");
+    }
+
+    #[test]
+    fn test_hidden_symbols() {
+        let mut bnf = backus_naur_form!(
+            priority 0 => DIGIT_RULE
+            priority 0 => r#"<_whitespace> ::= " ""#
+            priority 0 => r#"<sum> ::= <digit> ("+" | "-") <digit>
+                                     | <digit> <_whitespace> "*" <_whitespace> <digit>"#
+        );
+
+        assert_eq!(
+            bnf.symbolize_string("1 * 2"),
+            vec![Token::from_non_terminal(
+                "sum",
+                vec![
+                    digit("1"),
+                    Token::from_terminal(" "),
+                    Token::from_terminal("*"),
+                    Token::from_terminal(" "),
+                    digit("2")
+                ]
+            )]
+        );
+        //the inline group is hidden too
+        assert_eq!(
+            bnf.symbolize_string("1-2"),
+            vec![Token::from_non_terminal(
+                "sum",
+                vec![digit("1"), Token::from_terminal("-"), digit("2")]
+            )]
+        );
+
+        bnf.hide_symbol("digit");
+        assert_eq!(
+            bnf.symbolize_string("1-2"),
+            vec![Token::from_non_terminal(
+                "sum",
+                vec![
+                    Token::from_terminal("1"),
+                    Token::from_terminal("-"),
+                    Token::from_terminal("2")
+                ]
+            )]
+        );
+    }
+
+    #[test]
+    fn test_node_labels() {
+        let mut bnf = backus_naur_form!(
+            priority 2 => DIGIT_RULE
+            priority 1 => r#"<multiplicative-expression> -> Expr ::= <digit> "*" <digit>"#
+            priority 0 => r#"<additive-expression> -> Expr ::= <multiplicative-expression> "+" <digit>"# => |token, bnf| {
+                format!("({})", bnf.compile_tokens(token.get_child_tokens()))
+            }
+        );
+
+        //the rules still use the names of the symbols, only the tokens in the AST are renamed
+        assert_eq!(
+            bnf.symbolize_string("1*2+1"),
+            vec![Token::from_non_terminal(
+                "Expr",
+                vec![
+                    Token::from_non_terminal(
+                        "Expr",
+                        vec![digit("1"), Token::from_terminal("*"), digit("2")]
+                    ),
+                    Token::from_terminal("+"),
+                    digit("1")
+                ]
+            )]
+        );
+        assert_eq!(bnf.node_label("additive-expression"), "Expr");
+        assert_eq!(bnf.node_label("digit"), "digit");
+        assert_eq!(bnf.compile_string("1*2+1"), "((1*2)+1)");
+
+        bnf.set_node_label("digit", "Digit");
+        assert_eq!(bnf.root_token("1").unwrap().get_symbol(), "Digit");
+    }
+
+    #[test]
+    fn test_inline_choices() {
+        let mut bnf = backus_naur_form!(
+            priority 2 => r#"<sign> ::= "-" | ε"#
+            priority 1 => r#"<term> ::= <sign> "1" | <sign> "2""#
+            priority 0 => r#"<expr> ::= @inline <term> | <expr> "+" <expr>"#
+        );
+        let term = |digit| Token::from_non_terminal("term", vec![Token::from_terminal(digit)]);
+
+        //the <expr>s around the <term>s are left out, the one created by the other choice stays
+        assert_eq!(
+            bnf.symbolize_string("1+2"),
+            vec![Token::from_non_terminal(
+                "expr",
+                vec![term("1"), Token::from_terminal("+"), term("2")]
+            )]
+        );
+        assert_eq!(bnf.symbolize_string("2"), vec![term("2")]);
+
+        //versions of the choice without the nullable symbols are inline too
+        bnf.add_inline_choice(
+            "term",
+            vec![Symbol::non_terminal("sign"), Symbol::terminal("1")]
+                .into_iter()
+                .collect(),
+        );
+        assert_eq!(bnf.symbolize_string("1"), vec![Token::from_terminal("1")]);
+        assert_eq!(bnf.symbolize_string("2"), vec![term("2")]);
+    }
+
+    #[test]
+    fn test_symbolize_tokens() {
+        let bnf = backus_naur_form!(
+            priority 1 => r#"<keyword> ::= "let" | "const""#
+            priority 0 => r#"<declaration> ::= <keyword> "x" "=" "42""#
+        );
+        let lexed = ["let", "x", "=", "42"]
+            .into_iter()
+            .map(Token::from_terminal)
+            .collect::<Vec<_>>();
+
+        let tokens = bnf.symbolize_tokens(lexed.clone());
+        assert_eq!(tokens.len(), 1);
+        assert_eq!(tokens[0].get_symbol(), "declaration");
+        assert_eq!(tokens[0].get_terminals(), "letx=42");
+        //the characterized string can't match the multi char terminals
+        assert_ne!(bnf.symbolize_string("letx=42"), tokens);
+        assert_eq!(
+            bnf.try_symbolize_tokens(lexed[1..].to_vec()),
+            Ok(lexed[1..].to_vec())
+        );
+    }
+
+    #[test]
+    fn test_terminal_offsets() {
+        let bnf = backus_naur_form!(
+            priority 1 => DIGIT_RULE
+            priority 0 => r#"<sum> ::= <digit> "+" <digit>"#
+        );
+        let tokens = bnf.symbolize_string("ä 1+2");
+        let offsets = |token: &Token| match token {
+            Token::Terminal(terminal) => vec![(terminal.offset(), terminal.length())],
+            Token::NonTerminalToken(non_terminal) => non_terminal
+                .get_descendant_tokens()
+                .into_iter()
+                .filter_map(|token| token.to_terminal_token_ref())
+                .map(|terminal| (terminal.offset(), terminal.length()))
+                .collect(),
+        };
+
+        assert_eq!(
+            tokens.iter().flat_map(offsets).collect::<Vec<_>>(),
+            vec![
+                (Some(0), 1),
+                (Some(1), 1),
+                (Some(2), 1),
+                (Some(3), 1),
+                (Some(4), 1)
+            ]
+        );
+        //the offset doesn't change the equality of tokens
+        assert_eq!(tokens[0], Token::from_terminal("ä"));
+        assert_eq!(
+            Token::from_terminal("ä").to_terminal().unwrap().offset(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_root_token() {
+        let bnf = backus_naur_form!(
+            priority 1 => DIGIT_RULE
+            priority 0 => r#"<sum> ::= <digit> "+" <digit>"#
+        );
+
+        assert_eq!(
+            bnf.root_token("1+2").map(|root| root.get_terminals()),
+            Ok("1+2".to_string())
+        );
+        let error = bnf.root_token("1+2?").unwrap_err();
+        assert_eq!(
+            error,
+            RootError::NoSingleRoot {
+                tokens: vec![
+                    (Symbol::non_terminal("sum"), 0..3),
+                    (Symbol::terminal("?"), 3..4)
+                ]
+            }
+        );
+        assert_eq!(
+            error.to_string(),
+            "expected a single root token but the input has 2 top level tokens: <sum> at 0..3, \"?\" at 3..4"
+        );
+        assert_eq!(
+            bnf.root_token(""),
+            Err(RootError::NoSingleRoot { tokens: vec![] })
+        );
+    }
+
+    #[test]
+    fn test_symbolize_strict() {
+        let bnf = backus_naur_form!(
+            priority 1 => DIGIT_RULE
+            priority 0 => r#"<sum> ::= <digit> "+" <digit>"#
+        );
+
+        assert_eq!(bnf.symbolize_strict("1+2"), Ok(bnf.symbolize_string("1+2")));
+        assert_eq!(
+            bnf.symbolize_strict("1+2 ?1"),
+            Err(SymbolizeError::Unreduced { span: 3..5 })
+        );
+        assert_eq!(
+            bnf.symbolize_strict("-1"),
+            Err(SymbolizeError::Unreduced { span: 0..1 })
+        );
+    }
+
+    #[test]
+    fn test_parse() {
+        let bnf = backus_naur_form!(
+            priority 1 => DIGIT_RULE
+            priority 0 => r#"<sum> ::= <digit> "+" <digit>"#
+        );
+
+        assert_eq!(
+            bnf.parse("1+2").map(|root| root.non_terminal_symbol),
+            Ok("sum".to_string())
+        );
+        assert_eq!(
+            bnf.parse("1+2+-"),
+            Err(ParseError::TrailingInput {
+                root: "sum".to_string(),
+                span: 3..5
+            })
+        );
+        assert_eq!(
+            bnf.parse("-+1"),
+            Err(ParseError::NoRootToken { span: 0..2 })
+        );
+        assert_eq!(bnf.parse("-+"), Err(ParseError::NoRootToken { span: 0..2 }));
+        assert_eq!(bnf.parse(""), Err(ParseError::EmptyInput));
+    }
+
+    #[test]
+    fn test_empty_choices() {
+        let bnf = backus_naur_form!(
+            priority 2 => r#"<maybe-sign> ::= "+" | "-" | ε"#
+            priority 1 => DIGIT_RULE
+            priority 0 => r#"<signed> ::= <maybe-sign> <digit> <maybe-sign> |"#
+        );
+        let sign = |sign| Token::from_non_terminal("maybe-sign", vec![Token::from_terminal(sign)]);
+
+        assert_eq!(
+            bnf.symbolize_string("+1-"),
+            vec![Token::from_non_terminal(
+                "signed",
+                vec![sign("+"), digit("1"), sign("-")]
+            )]
+        );
+        assert_eq!(
+            bnf.symbolize_string("2-"),
+            vec![Token::from_non_terminal(
+                "signed",
+                vec![digit("2"), sign("-")]
+            )]
+        );
+        assert_eq!(
+            bnf.symbolize_string("1"),
+            vec![Token::from_non_terminal("signed", vec![digit("1")])]
+        );
+        assert_eq!(bnf.count_derivations("signed", "-2"), Some(1));
+    }
+
+    #[test]
+    fn test_zero_length_matches() {
+        //<nothing> only matches nothing, so it never becomes a token
+        let bnf = backus_naur_form!(
+            priority 1 => r#"<nothing> ::= ε"#
+            priority 0 => r#"<one> ::= <nothing> "1" <nothing>"#
+        );
+        assert_eq!(
+            bnf.try_symbolize_string("11"),
+            Ok(vec![
+                Token::from_non_terminal("one", vec![Token::from_terminal("1")]),
+                Token::from_non_terminal("one", vec![Token::from_terminal("1")])
+            ])
+        );
+        assert_eq!(bnf.try_symbolize_string(""), Ok(vec![]));
+
+        //<a> and <b> can both match nothing and turn into each other, which stops instead of looping
+        let bnf = backus_naur_form!(
+            priority 1 => r#"<a> ::= <b> | ε"#
+            priority 0 => r#"<b> ::= <a> | "1" | ε"#
+        );
+        assert_eq!(
+            bnf.try_symbolize_string("1"),
+            Err(SymbolizeError::NonTermination {
+                rule: "a".to_string()
+            })
+        );
+        assert_eq!(bnf.try_symbolize_string(""), Ok(vec![]));
+    }
+
+    #[test]
+    fn test_non_termination() {
+        //the recursive choice turns a <loop> into a <loop> forever
+        let bnf = backus_naur_form!(
+            priority 0 => r#"<loop> ::= "1" | <loop>"#
+        );
+        assert_eq!(
+            bnf.try_symbolize_string("1"),
+            Err(SymbolizeError::NonTermination {
+                rule: "loop".to_string()
+            })
+        );
+
+        //<a> and <b> keep turning into each other
+        let bnf = backus_naur_form!(
+            priority 1 => r#"<a> ::= "1" | <b>"#
+            priority 0 => r#"<b> ::= <a>"#
+        );
+        assert_eq!(
+            bnf.try_symbolize_string("1"),
+            Err(SymbolizeError::NonTermination {
+                rule: "a".to_string()
+            })
+        );
+        assert_eq!(bnf.try_symbolize_string("2"), Ok(characterize_string("2")));
+    }
+
+    #[test]
+    fn test_limits() {
+        let mut bnf = backus_naur_form!(
+            priority 1 => DIGIT_RULE
+            priority 0 => r#"<number> ::= <digit> | <number> <number>"#
+        );
+        bnf.set_max_input_length(Some(3));
+        assert!(bnf.try_symbolize_string("121").is_ok());
+        assert_eq!(
+            bnf.try_symbolize_string("1212"),
+            Err(SymbolizeError::InputTooLong { length: 4, max: 3 })
+        );
+
+        //"12" creates 2 chars, 2 <digit>s, 2 <number>s and the <number> around them
+        bnf.set_max_input_length(None);
+        bnf.set_max_token_count(Some(7));
+        assert!(bnf.try_symbolize_string("12").is_ok());
+        assert_eq!(
+            bnf.try_symbolize_string("121"),
+            Err(SymbolizeError::TooManyTokens { max: 7 })
+        );
+        bnf.set_max_token_count(None);
+        assert!(bnf.try_symbolize_string("1212").is_ok());
+
+        //"12" reduces <number> three times
+        bnf.set_max_reductions("number", Some(3));
+        assert!(bnf.try_symbolize_string("12").is_ok());
+        assert_eq!(
+            bnf.try_symbolize_string("121"),
+            Err(SymbolizeError::TooManyReductions {
+                symbol: "number".to_string(),
+                max: 3
+            })
+        );
+        bnf.set_max_reductions("number", None);
+        assert!(bnf.try_symbolize_string("121").is_ok());
+
+        //"1" takes the char and a <digit> and a <number> around it
+        let memory = 3 * size_of::<Token>() + "1".len() + "digit".len() + "number".len();
+        bnf.set_max_memory(Some(memory));
+        assert!(bnf.try_symbolize_string("1").is_ok());
+        assert_eq!(
+            bnf.try_symbolize_string("2"),
+            Ok(vec![Token::from_non_terminal(
+                "number",
+                vec![Token::from_non_terminal(
+                    "digit",
+                    vec![Token::from_terminal("2")]
+                )]
+            )])
+        );
+        assert_eq!(
+            bnf.try_symbolize_string("12"),
+            Err(SymbolizeError::MemoryLimitExceeded { max: memory })
+        );
+        bnf.set_max_memory(None);
+    }
+
+    #[test]
+    fn test_fuzz_corpus() {
+        //every file in a directory of the corpus as (name, content), sorted by name
+        let files = |directory: &str| {
+            let directory = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+                .join("fuzz_corpus")
+                .join(directory);
+            let mut paths = std::fs::read_dir(directory)
+                .unwrap()
+                .map(|entry| entry.unwrap().path())
+                .collect::<Vec<_>>();
+            paths.sort();
+            paths
+                .into_iter()
+                .map(|path| {
+                    let content =
+                        String::from_utf8_lossy(&std::fs::read(&path).unwrap()).to_string();
+                    let name = path.file_name().unwrap().to_string_lossy().to_string();
+                    (name, content)
+                })
+                .collect::<Vec<_>>()
+        };
+        let inputs = files("inputs");
+        assert!(!inputs.is_empty());
+        //the inputs that are valid in a grammar as (grammar, input, symbol of the root)
+        let valid = [
+            ("arithmetic.bnf", "arithmetic.txt", "expr"),
+            ("arithmetic.bnf", "deep_nesting.txt", "expr"),
+            ("macros.bnf", "lists.txt", "expr"),
+            ("nullable.bnf", "single.txt", "list"),
+            ("unbalanced_tree.bnf", "long_run.txt", "list"),
+        ];
+        let mut checked = 0;
+
+        for (grammar, document) in files("grammars") {
+            //malformed grammars have to be rejected with a error instead of a panic
+            let Ok(mut bnf) = BackusNaurForm::from_grammar_document(&document, &HashMap::new())
+            else {
+                continue;
+            };
+            bnf.set_max_input_length(Some(10_000));
+            bnf.set_max_token_count(Some(100_000));
+            bnf.set_max_memory(Some(1 << 24));
+            for (name, input) in &inputs {
+                let symbolized = bnf.try_symbolize_string(input);
+                if let Some((_, _, root)) = valid
+                    .iter()
+                    .find(|valid| (valid.0, valid.1) == (&grammar, name))
+                {
+                    assert!(
+                        matches!(symbolized.as_deref(), Ok([token]) if token.get_symbol() == *root),
+                        "{name} is not a single <{root}> in {grammar}: {symbolized:?}"
+                    );
+                    checked += 1;
+                }
+            }
+            let _ = bnf.try_symbolize_string(&document);
+            let _ = bnf.run_inline_tests();
+            let _ = bnf.to_bnf_string(80);
+        }
+        assert_eq!(checked, valid.len());
+    }
+
+    #[cfg(feature = "macros")]
+    #[test]
+    fn test_static_backus_naur_form() {
+        let bnf = crate::static_backus_naur_form!(
+            priority 0 => r#"<digit> ::= "1" | "2""# => |digit, _bnf| {
+                (digit.get_terminals().parse::<usize>().unwrap() * 2).to_string()
+            }
+            priority 1 => doc "Adds or subtracts two digits" => r#"<sum> ::= <digit> ("+" | "-") <digit>"#
+        );
+        let rhs = backus_naur_form!(
+            priority 0 => DIGIT_RULE
+            priority 1 => r#"<sum> ::= <digit> ("+" | "-") <digit>"#
+        );
+
+        assert_eq!(bnf, rhs);
+        assert_eq!(bnf.compile_string("1"), "2");
+        assert_eq!(
+            bnf.rule("sum").unwrap().doc(),
+            Some("Adds or subtracts two digits")
+        );
+        assert_eq!(
+            crate::static_backus_naur_form!(priority 0 => r#"<sign> ::= "+" | | ε"#),
+            backus_naur_form!(priority 0 => r#"<sign> ::= "+" | | ε"#)
+        );
+
+        let bnf = crate::static_backus_naur_form!(
+            priority 0 => r#"<plus> -> Sign ::= "+""# => |_token, _bnf| "plus".to_string()
+        );
+        assert_eq!(bnf.node_label("plus"), "Sign");
+        assert_eq!(bnf.compile_string("+"), "plus");
+
+        let bnf = crate::static_backus_naur_form!(
+            priority 1 => r#"<digit> ::= "1""#
+            priority 0 => r#"<number> ::= @inline <digit> | "2""#
+        );
+        assert_eq!(
+            bnf.symbolize_string("12"),
+            vec![
+                Token::from_non_terminal("digit", vec![Token::from_terminal("1")]),
+                Token::from_non_terminal("number", vec![Token::from_terminal("2")])
+            ]
+        );
+
+        let bnf = crate::static_backus_naur_form!(priority 0 => r#"<digit> ::= 90% "1" | "2""#);
+        let one = [Symbol::terminal("1")].into_iter().collect::<Choice>();
+        assert_eq!(bnf.choice_weight("digit", &one), Some(90.0));
+    }
+
+    #[test]
+    fn test_rule_docs() {
+        const SUM_RULE: &str = r#"<sum> ::= <digit> "+" <digit>"#;
+        let bnf = backus_naur_form!(
+            priority 1 => doc "A decimal digit" => r#"<digit> ::= "1" | "2""# => |digit, _bnf| {
+                digit.get_terminals()
+            }
+            priority 0 => SUM_RULE
+            priority 0 => doc "Two sums" => generated_rule!("sums", for _ in 0..1 => "<sum> <sum>")
+        );
+
+        let digit = bnf.rule("digit").unwrap();
+        assert_eq!(digit.doc(), Some("A decimal digit"));
+        assert_eq!(digit.get_priority(), 1);
+        assert_eq!(digit.get_expression().len(), 2);
+        assert_eq!(bnf.rule("sum").unwrap().doc(), None);
+        assert_eq!(bnf.rule("sums").unwrap().doc(), Some("Two sums"));
+        assert!(bnf.rule("number").is_none());
+        assert_eq!(bnf.compile_string("1"), "1");
+    }
+
+    #[test]
+    fn test_rules() {
+        let bnf = backus_naur_form!(
+            priority 1 => DIGIT_RULE
+            priority 0 => r#"<number> ::= <digit> | <number> <number>"#
+            priority 0 => r#"<sum> ::= <sum> "+" <number> | <number>"#
+        );
+        let rules = bnf.rules();
+
+        assert_eq!(
+            rules.iter().map(|rule| rule.get_name()).collect::<Vec<_>>(),
+            ["digit", "number", "sum"]
+        );
+        assert!(!rules[0].is_recursive());
+        assert!(rules[1].is_recursive());
+        assert!(rules[1].is_left_recursive());
+        assert_eq!(
+            rules[1].recursive_choices(),
+            vec![&Choice::from_iter([
+                Symbol::non_terminal("number"),
+                Symbol::non_terminal("number")
+            ])]
+        );
+        assert!(rules[2].is_left_recursive());
+        assert_eq!(rules[2].get_priority(), 0);
+    }
+
+    #[test]
+    fn test_generated_rules() {
+        const NUMBER_RULE: &str = "<number> ::= <digit> | <number> <number>";
+        let bnf = backus_naur_form!(
+            priority 0 => generated_rule!("digit", for digit in 0..=9 => r#""{digit}""#)
+            priority 0 => NUMBER_RULE
+            priority 0 => generated_rule!("sign", for sign in ["+", "-"] => format!("\"{sign}\""))
+        );
+        let rhs = backus_naur_form!(
+            priority 0 => r#"<digit> ::= "0" | "1" | "2" | "3" | "4" | "5" | "6" | "7" | "8" | "9""#
+            priority 0 => r#"<number> ::= <digit> | <number> <number>"#
+            priority 0 => r#"<sign> ::= "+" | "-""#
+        );
+
+        assert_eq!(bnf, rhs);
+    }
 }
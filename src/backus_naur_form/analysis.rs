@@ -0,0 +1,183 @@
+//! Contains utilities for the analysis passes of compilers that are made with a [BackusNaurForm](super::BackusNaurForm).
+//!
+//! [ScopedSymbolTable] maps names to values (for example variables to their types) in nested scopes.
+//! [ScopedSymbolTable::walk] goes through a AST and opens a new scope for every token of the specified symbols (for example `<block>`):
+//! ```rust, ignore
+//! let mut table = ScopedSymbolTable::new();
+//! table.walk(&ast, &["block"], &mut |token, table| {
+//!     if token.get_symbol() == "declaration" {
+//!         table.insert(token.get_terminals(), ());
+//!     }
+//! });
+//! ```
+//! Compile functions only receive the [BackusNaurForm](super::BackusNaurForm), so to use a table inside of them,
+//! the closures can share a `RefCell<ScopedSymbolTable<K, V>>`.
+
+use std::{borrow::Borrow, collections::HashMap, hash::Hash};
+
+use super::token::Token;
+
+///A symbol table with nested scopes. Lookups search the innermost scope first.  
+///There is always at least one scope, the global scope, which can't be popped.
+#[derive(Clone, Debug)]
+pub struct ScopedSymbolTable<K, V> {
+    //the outermost scope first
+    scopes: Vec<HashMap<K, V>>,
+}
+
+impl<K: Eq + Hash, V> Default for ScopedSymbolTable<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Eq + Hash, V> ScopedSymbolTable<K, V> {
+    ///Creates a table that only has the global scope.
+    pub fn new() -> Self {
+        Self {
+            scopes: vec![HashMap::new()],
+        }
+    }
+
+    ///Opens a new innermost scope.
+    pub fn push_scope(&mut self) {
+        self.scopes.push(HashMap::new());
+    }
+
+    ///Closes the innermost scope and returns its entries.
+    ///Returns None if the innermost scope is the global scope, which can't be closed.
+    pub fn pop_scope(&mut self) -> Option<HashMap<K, V>> {
+        if self.scopes.len() == 1 {
+            return None;
+        }
+        self.scopes.pop()
+    }
+
+    ///Returns the number of open scopes, the global scope included.
+    pub fn depth(&self) -> usize {
+        self.scopes.len()
+    }
+
+    ///Adds the entry to the innermost scope. If the innermost scope already had the key, its old value is returned.
+    ///Entries of outer scopes with the same key are shadowed until the scope is closed.
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        self.innermost_scope_mut().insert(key, value)
+    }
+
+    ///Returns the value of the key in the innermost scope that has the key.
+    pub fn lookup<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.scopes.iter().rev().find_map(|scope| scope.get(key))
+    }
+
+    ///The same as [lookup](ScopedSymbolTable::lookup) but returns a mutable reference.
+    pub fn lookup_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.scopes
+            .iter_mut()
+            .rev()
+            .find_map(|scope| scope.get_mut(key))
+    }
+
+    ///Returns the value of the key if the innermost scope has the key. Outer scopes are ignored.
+    ///This is useful to find redeclarations.
+    pub fn lookup_in_current_scope<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.scopes.last()?.get(key)
+    }
+
+    fn innermost_scope_mut(&mut self) -> &mut HashMap<K, V> {
+        self.scopes
+            .last_mut()
+            .expect("the global scope is never popped")
+    }
+
+    ///Visits the token and its descendants in pre-order.
+    ///A new scope is opened before a token of one of the scope symbols (without angle brackets) is visited
+    ///and closed after its descendants were visited, so the entries the visitor adds inside of it disappear afterwards.
+    pub fn walk(
+        &mut self,
+        token: &Token,
+        scope_symbols: &[&str],
+        visitor: &mut dyn FnMut(&Token, &mut Self),
+    ) {
+        let opens_scope = !token.is_terminal() && scope_symbols.contains(&token.get_symbol());
+        if opens_scope {
+            self.push_scope();
+        }
+        visitor(token, self);
+        if let Token::NonTerminalToken(non_terminal) = token {
+            for child in non_terminal.get_child_tokens() {
+                self.walk(child, scope_symbols, visitor);
+            }
+        }
+        if opens_scope {
+            self.pop_scope();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scoped_symbol_table() {
+        let mut table = ScopedSymbolTable::new();
+        table.insert("x", 1);
+        table.push_scope();
+        table.insert("x", 2);
+        table.insert("y", 3);
+
+        assert_eq!(table.lookup("x"), Some(&2));
+        assert_eq!(table.lookup_in_current_scope("y"), Some(&3));
+        *table.lookup_mut("y").unwrap() += 1;
+        assert_eq!(table.pop_scope().map(|scope| scope.len()), Some(2));
+        assert_eq!(table.lookup("x"), Some(&1));
+        assert_eq!(table.lookup("y"), None);
+        assert_eq!(table.pop_scope(), None);
+        assert_eq!(table.depth(), 1);
+    }
+
+    #[test]
+    fn test_walk() {
+        let declaration =
+            |name| Token::from_non_terminal("declaration", vec![Token::from_terminal(name)]);
+        let usage = |name| Token::from_non_terminal("usage", vec![Token::from_terminal(name)]);
+        let block = |tokens| Token::from_non_terminal("block", tokens);
+        //{ let a; { let b; a; b; } b; }
+        let ast = block(vec![
+            declaration("a"),
+            block(vec![declaration("b"), usage("a"), usage("b")]),
+            usage("b"),
+        ]);
+
+        let mut undeclared = vec![];
+        let mut table = ScopedSymbolTable::new();
+        table.walk(
+            &ast,
+            &["block"],
+            &mut |token, table| match token.get_symbol() {
+                "declaration" => {
+                    table.insert(token.get_terminals(), ());
+                }
+                "usage" if table.lookup(&token.get_terminals()).is_none() => {
+                    undeclared.push(token.get_terminals())
+                }
+                _ => (),
+            },
+        );
+
+        assert_eq!(undeclared, ["b"]);
+        assert_eq!(table.depth(), 1);
+    }
+}
@@ -0,0 +1,106 @@
+//! Contains [BackusNaurForm::symbolize_best_effort], which turns any input into a tree with a single root.
+//! It is meant for tools like outline views and hovers in editors, which need a tree of the same shape even if the input is broken.
+//!
+//! The root is a synthetic [ROOT_SYMBOL] token. Its children are the tokens [BackusNaurForm::symbolize_string] returns,
+//! except that every run of chars that no rule matched is wrapped into a [ERROR_SYMBOL] token:
+//! ```rust, ignore
+//! //with <sum> ::= <digit> "+" <digit>
+//! bnf.symbolize_best_effort("1+2 ?!")
+//! //<#root>
+//! //  <sum> (1+2)
+//! //  <#error> ( ?!)
+//! ```
+//! If the brackets of the input don't match up, the input is symbolized without pairing the brackets first.
+
+use super::{
+    characterize_string,
+    token::{non_terminal_token::NonTerminalToken, Token},
+    BackusNaurForm,
+};
+
+///The name of the synthetic root token of [BackusNaurForm::symbolize_best_effort].
+///Names that start with `#` are reserved for these synthetic tokens, so rules shouldn't use them.
+pub const ROOT_SYMBOL: &str = "#root";
+///The name of the tokens that contain the chars no rule matched.
+pub const ERROR_SYMBOL: &str = "#error";
+
+impl BackusNaurForm<'_> {
+    ///Symbolizes the string and always returns a single root token. More information can be found in the [best_effort](self) module.
+    pub fn symbolize_best_effort(&self, string: &str) -> NonTerminalToken {
+        //if the input can't be symbolized at all (for example because it is too long), the chars are left as they are
+        let tokens = self
+            .try_symbolize_string(string)
+            .or_else(|_| self.symbolize_observed(string, false, &mut |_, _, _| Ok(())))
+            .unwrap_or_else(|_| characterize_string(self.preprocess(string).get_text()));
+
+        let mut children = vec![];
+        //the chars since the last non terminal token
+        let mut unmatched = vec![];
+        for token in tokens {
+            match token {
+                Token::Terminal(_) => unmatched.push(token),
+                Token::NonTerminalToken(_) => {
+                    if !unmatched.is_empty() {
+                        children.push(Token::from_non_terminal(
+                            ERROR_SYMBOL,
+                            std::mem::take(&mut unmatched),
+                        ));
+                    }
+                    children.push(token);
+                }
+            }
+        }
+        if !unmatched.is_empty() {
+            children.push(Token::from_non_terminal(ERROR_SYMBOL, unmatched));
+        }
+        NonTerminalToken::new(ROOT_SYMBOL, children)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backus_naur_form::test_helpers::{digit, DIGIT_RULE};
+
+    #[test]
+    fn test_symbolize_best_effort() {
+        let mut bnf = crate::backus_naur_form!(
+            priority 1 => DIGIT_RULE
+            priority 0 => r#"<sum> ::= <digit> "+" <digit>"#
+        );
+        bnf.add_bracket_pair("(", ")");
+        let error = |chars: &str| {
+            Token::from_non_terminal(
+                ERROR_SYMBOL,
+                chars
+                    .chars()
+                    .map(|ch| Token::from_terminal(&ch.to_string()))
+                    .collect(),
+            )
+        };
+
+        assert_eq!(
+            bnf.symbolize_best_effort("?1+2!!"),
+            NonTerminalToken::new(
+                ROOT_SYMBOL,
+                vec![
+                    error("?"),
+                    Token::from_non_terminal(
+                        "sum",
+                        vec![digit("1"), Token::from_terminal("+"), digit("2")]
+                    ),
+                    error("!!")
+                ]
+            )
+        );
+        //the closing bracket has no opening bracket
+        assert_eq!(
+            bnf.symbolize_best_effort("1)"),
+            NonTerminalToken::new(ROOT_SYMBOL, vec![digit("1"), error(")")])
+        );
+        assert_eq!(
+            bnf.symbolize_best_effort(""),
+            NonTerminalToken::new(ROOT_SYMBOL, vec![])
+        );
+    }
+}
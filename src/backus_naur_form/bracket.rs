@@ -0,0 +1,175 @@
+//! The bracket pre-pass pairs opening and closing bracket terminals (for example `(` and `)`) before any rule is applied.
+//! Every bracketed region gets symbolized on its own, innermost regions first.
+//! After that the symbolized region is put back between its brackets and the enclosing region is symbolized.
+//!
+//! For example the string `(1+(2*3))` would be symbolized like this:
+//! 1. `2*3` is symbolized.
+//! 2. `1+(<symbolized 2*3>)` is symbolized.
+//! 3. `(<symbolized 1+(...)>)` is symbolized.
+//!
+//! This way the rules only ever scan already reduced regions instead of rescanning the whole string over and over again.
+//! Rules can still match the brackets themselves (for example `<expression> ::= "(" <expression> ")"`).
+
+use super::{error::SymbolizeError, token::Token};
+
+//A region that is still open while going through the tokens.
+struct Region {
+    //The index of the bracket pair, the opening bracket token and its position.
+    //None for the outermost region which isn't enclosed by brackets.
+    opening: Option<(usize, Token, usize)>,
+    tokens: Vec<Token>,
+}
+
+impl Region {
+    fn new(opening: Option<(usize, Token, usize)>) -> Self {
+        Self {
+            opening,
+            tokens: Vec::new(),
+        }
+    }
+}
+
+//Returns true if the token is a terminal that is exactly the bracket.
+fn is_bracket(token: &Token, bracket: &str) -> bool {
+    token.is_terminal() && token.get_symbol() == bracket
+}
+
+///Symbolizes the tokens region by region. The regions are denoted by the bracket pairs (opening, closing).
+///symbolize is called once for every bracketed region (without its brackets) and once for the whole vector.
+///Returns an error if the brackets in the tokens don't match up or if symbolize returns an error.
+pub(crate) fn symbolize_bracketed<F>(
+    tokens: Vec<Token>,
+    bracket_pairs: &[(String, String)],
+    mut symbolize: F,
+) -> Result<Vec<Token>, SymbolizeError>
+where
+    F: FnMut(Vec<Token>) -> Result<Vec<Token>, SymbolizeError>,
+{
+    let mut regions = vec![Region::new(None)];
+
+    for (position, token) in tokens.into_iter().enumerate() {
+        let innermost_pair = regions
+            .last()
+            .and_then(|region| region.opening.as_ref())
+            .map(|(pair_index, _, _)| *pair_index);
+
+        //closing the innermost region is checked first so brackets like quotes can have the same opening and closing bracket.
+        if let Some(pair_index) = innermost_pair {
+            if is_bracket(&token, &bracket_pairs[pair_index].1) {
+                let region = regions.pop().expect("the innermost region exists");
+                let (_, opening, _) = region.opening.expect("the innermost region is bracketed");
+                let parent = &mut regions
+                    .last_mut()
+                    .expect("the outermost region is never closed")
+                    .tokens;
+                parent.push(opening);
+                parent.append(&mut symbolize(region.tokens)?);
+                parent.push(token);
+                continue;
+            }
+        }
+
+        if let Some(pair_index) = bracket_pairs
+            .iter()
+            .position(|(opening, _)| is_bracket(&token, opening))
+        {
+            regions.push(Region::new(Some((pair_index, token, position))));
+            continue;
+        }
+
+        if let Some((_, closing)) = bracket_pairs
+            .iter()
+            .find(|(_, closing)| is_bracket(&token, closing))
+        {
+            return Err(match innermost_pair {
+                Some(pair_index) => SymbolizeError::MismatchedBracket {
+                    expected: bracket_pairs[pair_index].1.to_string(),
+                    found: closing.to_string(),
+                    position,
+                },
+                None => SymbolizeError::UnexpectedClosingBracket {
+                    bracket: closing.to_string(),
+                    position,
+                },
+            });
+        }
+
+        regions
+            .last_mut()
+            .expect("the outermost region is never closed")
+            .tokens
+            .push(token);
+    }
+
+    //every region except the outermost one should have been closed.
+    //the error points to the first bracket that was never closed.
+    if let Some((_, opening, position)) = regions.get(1).and_then(|region| region.opening.as_ref())
+    {
+        return Err(SymbolizeError::UnclosedBracket {
+            bracket: opening.get_symbol().to_string(),
+            position: *position,
+        });
+    }
+
+    let outermost = regions.pop().expect("the outermost region is never closed");
+    symbolize(outermost.tokens)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backus_naur_form::characterize_string;
+
+    fn pairs() -> Vec<(String, String)> {
+        vec![
+            ("(".to_string(), ")".to_string()),
+            ("{".to_string(), "}".to_string()),
+        ]
+    }
+
+    #[test]
+    fn test_regions_are_symbolized_innermost_first() {
+        let mut symbolized_regions = vec![];
+        let tokens = symbolize_bracketed(characterize_string("a(b{c})d"), &pairs(), |tokens| {
+            symbolized_regions.push(
+                tokens
+                    .iter()
+                    .map(|token| token.get_terminals())
+                    .collect::<String>(),
+            );
+            Ok(tokens)
+        })
+        .unwrap();
+
+        assert_eq!(symbolized_regions, vec!["c", "b{c}", "a(b{c})d"]);
+        assert_eq!(tokens, characterize_string("a(b{c})d"));
+    }
+
+    #[test]
+    fn test_bracket_errors() {
+        let symbolize = |string| symbolize_bracketed(characterize_string(string), &pairs(), Ok);
+
+        assert_eq!(
+            symbolize("(1}"),
+            Err(SymbolizeError::MismatchedBracket {
+                expected: ")".to_string(),
+                found: "}".to_string(),
+                position: 2
+            })
+        );
+        assert_eq!(
+            symbolize("1)"),
+            Err(SymbolizeError::UnexpectedClosingBracket {
+                bracket: ")".to_string(),
+                position: 1
+            })
+        );
+        assert_eq!(
+            symbolize("({1}"),
+            Err(SymbolizeError::UnclosedBracket {
+                bracket: "(".to_string(),
+                position: 0
+            })
+        );
+    }
+}
@@ -0,0 +1,82 @@
+//! Symbolizes bytes instead of strings, so simple binary formats (for example the framing of a protocol) can be described too.
+//! Every byte is turned into the char with the same value (`0x0A` turns into `'\n'` and `0xFF` into `'ÿ'`),
+//! so a byte can be written as a terminal in double quotes if it is printable, and as a byte value otherwise:
+//! ```rust, ignore
+//! backus_naur_form!(
+//!     priority 1 => r#"<crlf> ::= %x0D.0A"#
+//!     priority 1 => r#"<payload-byte> ::= %x00 | %x01 | %d2 | "A""#
+//!     priority 0 => r#"<frame> ::= %x02 <payload> %x03 <crlf>"#
+//! )
+//! ```
+//! Byte values are written like in ABNF: `%x` followed by hexadecimal digits or `%d` followed by decimal digits.
+//! Several bytes can be joined with dots, so `%x0D.0A` is the same as `%x0D %x0A`.
+//! Byte values can be used in string rules too, but only bytes up to `%x7F` are the same as the chars they stand for in a UTF-8 string.
+
+use super::{error::SymbolizeError, token::Token, BackusNaurForm};
+
+pub(crate) use backus_naur_form_rule_parser::parse_byte_values;
+
+///Returns the string in which every byte is the char with the same value.
+pub fn string_from_bytes(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| char::from(*byte)).collect()
+}
+
+///Turns a string that was made with [string_from_bytes] back into its bytes.
+///Returns None if the string contains a char that isn't a byte.
+pub fn bytes_from_string(string: &str) -> Option<Vec<u8>> {
+    string.chars().map(|char| u8::try_from(char).ok()).collect()
+}
+
+impl Token {
+    ///Returns the terminals of the [Token] as bytes, for [Token]s of [symbolize_bytes](BackusNaurForm::symbolize_bytes).
+    ///Returns None if the terminals contain a char that isn't a byte.
+    pub fn get_bytes(&self) -> Option<Vec<u8>> {
+        bytes_from_string(&self.get_terminals())
+    }
+}
+
+impl BackusNaurForm<'_> {
+    ///The same as [symbolize_string](BackusNaurForm::symbolize_string), but for bytes. More information can be found in the [bytes](self) module.
+    ///
+    /// # Panics
+    /// Panics in the same cases as [symbolize_string](BackusNaurForm::symbolize_string).
+    pub fn symbolize_bytes(&self, bytes: &[u8]) -> Vec<Token> {
+        self.symbolize_string(&string_from_bytes(bytes))
+    }
+
+    ///The same as [symbolize_bytes](BackusNaurForm::symbolize_bytes) but returns a [SymbolizeError] instead of panicking.
+    pub fn try_symbolize_bytes(&self, bytes: &[u8]) -> Result<Vec<Token>, SymbolizeError> {
+        self.try_symbolize_string(&string_from_bytes(bytes))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::backus_naur_form;
+
+    use super::*;
+
+    #[test]
+    fn test_parse_byte_values() {
+        assert_eq!(parse_byte_values("x0D.0a"), Ok(vec![13, 10]));
+        assert_eq!(parse_byte_values("d255"), Ok(vec![255]));
+        assert!(parse_byte_values("d256").is_err());
+        assert!(parse_byte_values("b101").is_err());
+    }
+
+    #[test]
+    fn test_symbolize_bytes() {
+        let bnf = backus_naur_form!(
+            priority 2 => r#"<byte> ::= %x00 | %x01 | %xFF | "A""#
+            priority 1 => r#"<payload> ::= <byte> | <payload> <payload>"#
+            priority 0 => r#"<frame> ::= %x02 <payload> %x03 %x0D.0A"#
+        );
+        let tokens = bnf.symbolize_bytes(&[0x02, 0x00, b'A', 0xFF, 0x03, b'\r', b'\n']);
+
+        assert_eq!(tokens.len(), 1);
+        assert_eq!(tokens[0].get_symbol(), "frame");
+        let payload = &tokens[0].to_non_terminal_ref().unwrap().get_child_tokens()[1];
+        assert_eq!(payload.get_bytes(), Some(vec![0x00, b'A', 0xFF]));
+        assert_eq!(bnf.symbolize_bytes(&[0x02, 0x03, 0x0D, 0x0A]).len(), 4);
+    }
+}
@@ -0,0 +1,164 @@
+//! A fast path for rules like `<digit> ::= "0" | "1" | ... | "9"` whose choices are all single chars.
+//! The first reduction wave usually consists of these rules and it is the biggest one, since every char of the input is a token.
+//! Instead of scanning the tokens once for every choice, every char is looked up once in a table of char classes.
+//!
+//! Only the rules that are applied before every other rule are tagged this way, so the AST is the same as without the fast path.
+
+use std::collections::HashMap;
+
+use super::{
+    error::SymbolizeError,
+    symbol::non_terminal_symbol::NonTerminalSymbol,
+    symbol::Symbol,
+    symbol_id::{SymbolId, SymbolTable},
+    token::Token,
+    Observer,
+};
+
+//Maps chars to the index of the rule that turns them into a token.
+pub(crate) struct CharClasses {
+    //the table for ascii chars, which are by far the most common ones
+    ascii: [Option<usize>; 128],
+    other: HashMap<char, usize>,
+    //the amount of rules at the start of the sorted rules that are char classes
+    rule_count: usize,
+}
+
+impl CharClasses {
+    //Collects the char classes of the rules (which have to be sorted by priority, highest first).
+    //Only the rules before the first rule that isn't a char class are collected, since the rules after it
+    //could only be applied after the rule that isn't a char class.
+    pub(crate) fn new(sorted_rules: &[&(NonTerminalSymbol, usize)]) -> Self {
+        let mut char_classes = Self {
+            ascii: [None; 128],
+            other: HashMap::new(),
+            rule_count: 0,
+        };
+        for (index, (non_terminal_symbol, _)) in sorted_rules.iter().enumerate() {
+            let Some(chars) = single_chars(non_terminal_symbol) else {
+                break;
+            };
+            //a char that an earlier rule already took never reaches this rule
+            for char in chars {
+                match char_classes.ascii.get_mut(char as usize) {
+                    Some(rule) => _ = rule.get_or_insert(index),
+                    None => _ = char_classes.other.entry(char).or_insert(index),
+                }
+            }
+            char_classes.rule_count = index + 1;
+        }
+        char_classes
+    }
+
+    //Returns the index of the rule that turns the char into a token.
+    fn rule_of(&self, char: char) -> Option<usize> {
+        match self.ascii.get(char as usize) {
+            Some(rule) => *rule,
+            None => self.other.get(&char).copied(),
+        }
+    }
+
+    //Turns every terminal char that belongs to a char class into a token of its rule.
+    //observe is called rule by rule, the same way as if the rules were applied one after another.
+    //The ids of the tagged tokens are updated with the ids of the table.
+    pub(crate) fn tag(
+        &self,
+        sorted_rules: &[&(NonTerminalSymbol, usize)],
+        table: &SymbolTable,
+        tokens: &mut [Token],
+        ids: &mut [SymbolId],
+        observe: &mut Observer,
+    ) -> Result<(), SymbolizeError> {
+        if self.rule_count == 0 {
+            return Ok(());
+        }
+        //the positions of the tokens that every rule tags
+        let mut positions = vec![vec![]; self.rule_count];
+        for (position, token) in tokens.iter().enumerate() {
+            let Token::Terminal(terminal) = token else {
+                continue;
+            };
+            let mut chars = terminal.get_terminals().chars();
+            if let (Some(char), None) = (chars.next(), chars.next()) {
+                if let Some(rule) = self.rule_of(char) {
+                    positions[rule].push(position);
+                }
+            }
+        }
+
+        for ((rule, rule_ids), positions) in sorted_rules.iter().zip(table.rules()).zip(positions) {
+            for position in positions {
+                observe(
+                    rule,
+                    position..position + 1,
+                    &tokens[position..position + 1],
+                )?;
+                let terminal = std::mem::replace(&mut tokens[position], Token::from_terminal(""));
+                tokens[position] = Token::from_non_terminal(rule.0.get_name(), vec![terminal]);
+                ids[position] = rule_ids.id;
+            }
+        }
+        Ok(())
+    }
+}
+
+//Returns the chars of the rule if every choice of it is a single terminal that is a single char.
+fn single_chars(non_terminal_symbol: &NonTerminalSymbol) -> Option<Vec<char>> {
+    non_terminal_symbol
+        .get_rule()
+        .iter()
+        .map(|choice| match choice.as_slice() {
+            [Symbol::Terminal(terminal)] => {
+                let mut chars = terminal.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(char), None) => Some(char),
+                    _ => None,
+                }
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::backus_naur_form;
+
+    #[test]
+    fn test_char_classes() {
+        let bnf = backus_naur_form!(
+            priority 3 => r#"<digit> ::= "1" | "2" | "3""#
+            priority 3 => r#"<letter> ::= "a" | "ä" | "1""#
+            priority 2 => r#"<sign> ::= "-" | "--""#
+            priority 1 => r#"<plus> ::= "+""#
+            priority 0 => r#"<number> ::= <digit> | <number> <number>"#
+        );
+        let (tokens, reductions) = bnf.symbolize_with_trace("ä12-+").unwrap();
+        let symbols = |tokens: &[crate::Token]| {
+            tokens
+                .iter()
+                .map(|token| token.get_symbol().to_string())
+                .collect::<Vec<_>>()
+        };
+
+        //rules with the same priority are applied in the reverse order, so "1" is a <letter>
+        assert_eq!(
+            symbols(&tokens),
+            vec!["letter", "letter", "number", "sign", "plus"]
+        );
+        //<sign> isn't a char class, so <plus> isn't tagged before the other rules either
+        assert_eq!(
+            reductions
+                .iter()
+                .take(4)
+                .map(|reduction| (reduction.symbol.as_str(), reduction.range.clone()))
+                .collect::<Vec<_>>(),
+            vec![
+                ("letter", 0..1),
+                ("letter", 1..2),
+                ("digit", 2..3),
+                ("sign", 3..4)
+            ]
+        );
+    }
+}
@@ -0,0 +1,126 @@
+//! The chunk pre-pass splits the tokens at hard delimiters (for example newlines) before any rule is applied.
+//! Every chunk is symbolized on its own and the symbolized chunks are concatenated again.
+//!
+//! For example with the delimiter `\n` the string `a=1\nb=2` would be symbolized like this:
+//! 1. `a=1\n` is symbolized.
+//! 2. `b=2` is symbolized.
+//!
+//! The rules only ever scan a single chunk, so the time a symbolization takes grows with the length of the chunks
+//! instead of the length of the whole string. The delimiter stays at the end of its chunk,
+//! so rules can still match it (for example `<line> ::= <assignment> "\n"`), but no token can span two chunks.
+
+use super::{error::SymbolizeError, token::Token};
+
+//Returns true if the token is a terminal that is exactly one of the delimiters.
+fn is_delimiter(token: &Token, delimiters: &[String]) -> bool {
+    token.is_terminal()
+        && delimiters
+            .iter()
+            .any(|delimiter| token.get_symbol() == delimiter)
+}
+
+///Symbolizes the tokens chunk by chunk. Every chunk ends after a delimiter or at the end of the tokens.
+///symbolize is called once for every chunk, the positions of its errors are moved to the position of the chunk.
+pub(crate) fn symbolize_chunked<F>(
+    tokens: Vec<Token>,
+    delimiters: &[String],
+    mut symbolize: F,
+) -> Result<Vec<Token>, SymbolizeError>
+where
+    F: FnMut(Vec<Token>) -> Result<Vec<Token>, SymbolizeError>,
+{
+    if delimiters.is_empty() {
+        return symbolize(tokens);
+    }
+
+    let mut symbolized = vec![];
+    let mut chunk = vec![];
+    //the position of the first token of the chunk
+    let mut start = 0;
+    let length = tokens.len();
+    for (position, token) in tokens.into_iter().enumerate() {
+        let ends_chunk = is_delimiter(&token, delimiters);
+        chunk.push(token);
+        if ends_chunk || position + 1 == length {
+            let tokens = symbolize(std::mem::take(&mut chunk))
+                .map_err(|error| shift_position(error, start))?;
+            symbolized.extend(tokens);
+            start = position + 1;
+        }
+    }
+    Ok(symbolized)
+}
+
+//Moves the position of a bracket error from the chunk into the whole tokens.
+fn shift_position(error: SymbolizeError, start: usize) -> SymbolizeError {
+    match error {
+        SymbolizeError::MismatchedBracket {
+            expected,
+            found,
+            position,
+        } => SymbolizeError::MismatchedBracket {
+            expected,
+            found,
+            position: start + position,
+        },
+        SymbolizeError::UnexpectedClosingBracket { bracket, position } => {
+            SymbolizeError::UnexpectedClosingBracket {
+                bracket,
+                position: start + position,
+            }
+        }
+        SymbolizeError::UnclosedBracket { bracket, position } => SymbolizeError::UnclosedBracket {
+            bracket,
+            position: start + position,
+        },
+        error => error,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::backus_naur_form::characterize_string;
+
+    use super::*;
+
+    #[test]
+    fn test_symbolize_chunked() {
+        let mut chunks = vec![];
+        let tokens = symbolize_chunked(
+            characterize_string("a\nbc\n\nd"),
+            &["\n".to_string()],
+            |chunk| {
+                chunks.push(
+                    chunk
+                        .iter()
+                        .map(|token| token.get_terminals())
+                        .collect::<String>(),
+                );
+                Ok(chunk)
+            },
+        );
+
+        assert_eq!(tokens, Ok(characterize_string("a\nbc\n\nd")));
+        assert_eq!(chunks, ["a\n", "bc\n", "\n", "d"]);
+
+        //the error in the second chunk is moved behind the first one
+        let error = symbolize_chunked(
+            characterize_string("ab\nc("),
+            &["\n".to_string()],
+            |chunk| match chunk.len() {
+                2 => Err(SymbolizeError::UnclosedBracket {
+                    bracket: "(".to_string(),
+                    position: 1,
+                }),
+                _ => Ok(chunk),
+            },
+        );
+        assert_eq!(
+            error,
+            Err(SymbolizeError::UnclosedBracket {
+                bracket: "(".to_string(),
+                position: 4
+            })
+        );
+    }
+}
@@ -0,0 +1,255 @@
+//! Contains [ChomskyNormalForm], a version of the rules of a [BackusNaurForm] where every choice is either a single char
+//! or exactly two non terminal symbols. It is used by the CYK parser (see the [cyk](super::cyk) module).
+//!
+//! The conversion works like this:
+//! - Terminals are split into their chars, so `"let"` is the same as `"l" "e" "t"`.
+//! - Chars in choices with more than one symbol are replaced by hidden helper symbols (for example `<_cnf-0> ::= "+"`).
+//! - Choices with more than two symbols are split into a chain of hidden helper symbols.
+//! - Choices with a single non terminal symbol (for example `<value> ::= <number>`) are replaced by the choices of that symbol.
+//!   The replaced symbols are remembered in [CnfRule::units], so the original AST can be rebuilt.
+//!
+//! Parameterized rules are only converted through their uses. Empty terminals are skipped.
+//! Empty choices are removed the same way as in [BackusNaurForm::symbolize_string], so nullable symbols that match nothing don't appear in the AST.
+
+use std::collections::HashSet;
+
+use super::{symbol::Symbol, BackusNaurForm};
+
+///The body of a [CnfRule].
+#[derive(PartialEq, Eq, Hash, Clone, Debug)]
+pub enum CnfBody {
+    Terminal(char),
+    Pair(String, String),
+}
+
+///A rule of a [ChomskyNormalForm].
+#[derive(PartialEq, Clone, Debug)]
+pub struct CnfRule {
+    ///The name of the symbol the rule is for.
+    pub name: String,
+    ///The non terminal symbols between the symbol and the body that were removed with the unit rules, outermost first.
+    ///For example `<value> ::= <number>` and `<number> ::= <digit> <digit>` turn into the rule
+    ///`<value> ::= <digit> <digit>` where the units are `["number"]`.
+    pub units: Vec<String>,
+    pub body: CnfBody,
+}
+
+///The rules of a [BackusNaurForm] in chomsky normal form. Created with [BackusNaurForm::to_cnf].
+///More information can be found in the [cnf](self) module.
+#[derive(PartialEq, Clone, Debug)]
+pub struct ChomskyNormalForm {
+    pub(crate) rules: Vec<CnfRule>,
+    //the symbols of the backus naur form that are hidden, helper symbols are always hidden
+    pub(crate) hidden_symbols: HashSet<String>,
+}
+
+impl ChomskyNormalForm {
+    ///Returns the rules in chomsky normal form.
+    pub fn rules(&self) -> &[CnfRule] {
+        &self.rules
+    }
+
+    ///Returns true if tokens of the symbol don't appear in the AST (see [BackusNaurForm::is_hidden_symbol]).
+    pub(crate) fn is_hidden_symbol(&self, name: &str) -> bool {
+        name.starts_with('_') || self.hidden_symbols.contains(name)
+    }
+}
+
+//A symbol of a choice after the terminals were split into chars.
+#[derive(PartialEq, Clone, Debug)]
+enum Item {
+    Char(char),
+    NonTerminal(String),
+}
+
+//Creates the names of the helper symbols.
+struct HelperNames<'a> {
+    bnf: &'a BackusNaurForm<'a>,
+    next: usize,
+}
+
+impl HelperNames<'_> {
+    fn next_name(&mut self) -> String {
+        loop {
+            let name = format!("_cnf-{}", self.next);
+            self.next += 1;
+            if !self.bnf.contains_symbol(&name) {
+                return name;
+            }
+        }
+    }
+}
+
+impl BackusNaurForm<'_> {
+    ///Converts the rules into chomsky normal form. More information can be found in the [cnf](self) module.
+    ///
+    /// # Panics
+    /// Panics if a choice would be expanded into too many versions (see [SymbolizeError::TooManyChoiceVersions](super::SymbolizeError::TooManyChoiceVersions)).
+    pub fn to_cnf(&self) -> ChomskyNormalForm {
+        let mut helper_names = HelperNames { bnf: self, next: 0 };
+        //the helper symbols of the chars in the order they were created
+        let mut char_helpers: Vec<(char, String)> = vec![];
+        let mut rules = vec![];
+        //the rules with a single non terminal symbol as (name, symbol)
+        let mut unit_rules: Vec<(String, String)> = vec![];
+
+        for (non_terminal_symbol, _) in self
+            .symbolizing_rules()
+            .unwrap_or_else(|error| panic!("{error}"))
+            .iter()
+        {
+            let name = non_terminal_symbol.get_name();
+            for choice in non_terminal_symbol.get_rule() {
+                let items = choice
+                    .iter()
+                    .flat_map(|symbol| match symbol {
+                        Symbol::Terminal(terminal) => terminal.chars().map(Item::Char).collect(),
+                        Symbol::NonTerminal(name) => vec![Item::NonTerminal(name.to_string())],
+                    })
+                    .collect::<Vec<_>>();
+
+                let mut names = match items.as_slice() {
+                    [] => continue,
+                    [Item::Char(ch)] => {
+                        rules.push(CnfRule {
+                            name: name.to_string(),
+                            units: vec![],
+                            body: CnfBody::Terminal(*ch),
+                        });
+                        continue;
+                    }
+                    [Item::NonTerminal(symbol)] => {
+                        unit_rules.push((name.to_string(), symbol.to_string()));
+                        continue;
+                    }
+                    items => items
+                        .iter()
+                        .map(|item| match item {
+                            Item::NonTerminal(name) => name.to_string(),
+                            Item::Char(ch) => {
+                                match char_helpers.iter().find(|(other, _)| other == ch) {
+                                    Some((_, helper)) => helper.to_string(),
+                                    None => {
+                                        let helper = helper_names.next_name();
+                                        char_helpers.push((*ch, helper.to_string()));
+                                        helper
+                                    }
+                                }
+                            }
+                        })
+                        .collect::<Vec<_>>(),
+                };
+
+                //`<a> ::= <b> <c> <d>` turns into `<a> ::= <b> <_cnf-n>` and `<_cnf-n> ::= <c> <d>`
+                let mut rule_name = name.to_string();
+                while names.len() > 2 {
+                    let helper = helper_names.next_name();
+                    let first = names.remove(0);
+                    rules.push(CnfRule {
+                        name: rule_name,
+                        units: vec![],
+                        body: CnfBody::Pair(first, helper.to_string()),
+                    });
+                    rule_name = helper;
+                }
+                rules.push(CnfRule {
+                    name: rule_name,
+                    units: vec![],
+                    body: CnfBody::Pair(names[0].to_string(), names[1].to_string()),
+                });
+            }
+        }
+
+        for (ch, helper) in char_helpers {
+            rules.push(CnfRule {
+                name: helper,
+                units: vec![],
+                body: CnfBody::Terminal(ch),
+            });
+        }
+
+        let rules = eliminate_unit_rules(rules, &unit_rules);
+        let hidden_symbols = self
+            .rules
+            .iter()
+            .map(|(non_terminal_symbol, _)| non_terminal_symbol.get_name())
+            .filter(|name| self.is_hidden_symbol(name))
+            .map(str::to_string)
+            .collect();
+        ChomskyNormalForm {
+            rules,
+            hidden_symbols,
+        }
+    }
+}
+
+//Adds the rules that replace the unit rules.
+//Every path of unit rules that doesn't visit a symbol twice gets its own rules, so every derivation stays distinct.
+fn eliminate_unit_rules(rules: Vec<CnfRule>, unit_rules: &[(String, String)]) -> Vec<CnfRule> {
+    let mut symbols: Vec<&String> = vec![];
+    for (name, _) in unit_rules {
+        if !symbols.contains(&name) {
+            symbols.push(name);
+        }
+    }
+    let mut result = rules.to_vec();
+
+    for start in symbols {
+        //the paths that still have to be followed, the first symbol of a path is the start
+        let mut paths = vec![vec![start.to_string()]];
+        while let Some(path) = paths.pop() {
+            let last = path.last().expect("paths are never empty");
+            for (_, symbol) in unit_rules.iter().filter(|(name, _)| name == last) {
+                if path.contains(symbol) {
+                    continue;
+                }
+                let mut units = path[1..].to_vec();
+                units.push(symbol.to_string());
+                for rule in rules.iter().filter(|rule| rule.name == *symbol) {
+                    result.push(CnfRule {
+                        name: start.to_string(),
+                        units: units.iter().chain(&rule.units).cloned().collect(),
+                        body: rule.body.clone(),
+                    });
+                }
+                let mut next_path = path.to_vec();
+                next_path.push(symbol.to_string());
+                paths.push(next_path);
+            }
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_cnf() {
+        let bnf = crate::backus_naur_form!(
+            priority 0 => r#"<digit> ::= "1""#
+            priority 0 => r#"<value> ::= <digit>"#
+            priority 0 => r#"<sum> ::= <value> "+" <value>"#
+        );
+        let cnf = bnf.to_cnf();
+        let rule = |name: &str, units: &[&str], body| CnfRule {
+            name: name.to_string(),
+            units: units.iter().map(|unit| unit.to_string()).collect(),
+            body,
+        };
+        let pair = |first: &str, second: &str| CnfBody::Pair(first.to_string(), second.to_string());
+
+        assert_eq!(
+            cnf.rules(),
+            &[
+                rule("digit", &[], CnfBody::Terminal('1')),
+                rule("sum", &[], pair("value", "_cnf-1")),
+                rule("_cnf-1", &[], pair("_cnf-0", "value")),
+                rule("_cnf-0", &[], CnfBody::Terminal('+')),
+                rule("value", &["digit"], CnfBody::Terminal('1')),
+            ]
+        );
+    }
+}
@@ -0,0 +1,544 @@
+//! Grammars can be saved after their rules are parsed, so applications with large grammars don't have to parse them again at every startup.
+//! ```rust, ignore
+//! let grammar = CompiledGrammar::load_or_compile("grammar.bnfc", &document, &FileResolver::new())?;
+//! let bnf = grammar.to_backus_naur_form();
+//! ```
+//! A [CompiledGrammar] contains the rules the way they are after parsing them (inline groups are lifted and templates are instantiated)
+//! and a hash of the grammar document and every document it includes.
+//! [load_or_compile](CompiledGrammar::load_or_compile) only uses the saved grammar if the hash still matches the documents.
+//!
+//! The doc strings, the node labels, the `@inline` choices, the weights of the choices and the `%test` inline tests are saved too,
+//! so the loaded grammar makes the same AST as the document.
+//!
+//! The saved file starts with `BNFC`, followed by the version of the format and the hash of the documents.
+//! Files with a different version can't be loaded, they have to be compiled again.
+
+use std::{collections::HashMap, path::Path};
+
+use super::{
+    error::{CompiledGrammarError, GrammarError},
+    grammar::{self, GrammarResolver},
+    inline_test::InlineTest,
+    symbol::{non_terminal_symbol::NonTerminalSymbol, Symbol},
+    template::Template,
+    BackusNaurForm, Choice,
+};
+
+//The first bytes of every saved grammar.
+const MAGIC: &[u8; 4] = b"BNFC";
+///The version of the format that [CompiledGrammar::save] writes. It changes whenever the format changes.
+pub const FORMAT_VERSION: u32 = 3;
+
+///The parsed rules of a grammar document together with a hash of the document they were parsed from.
+#[derive(PartialEq, Debug, Clone)]
+pub struct CompiledGrammar {
+    source_hash: u64,
+    rules: Vec<(NonTerminalSymbol, usize)>,
+    templates: Vec<Template>,
+    //the doc strings of the rules, sorted by the names of the rules so the saved file is always the same
+    rule_docs: Vec<(String, String)>,
+    //the node labels, inline choices and choice weights of the rules, sorted by the names of the rules too
+    node_labels: Vec<(String, String)>,
+    inline_choices: Vec<(String, Vec<Choice>)>,
+    choice_weights: Vec<(String, Vec<(Choice, f64)>)>,
+    inline_tests: Vec<InlineTest>,
+}
+
+impl CompiledGrammar {
+    ///Parses the grammar document (see [BackusNaurForm::from_grammar_document]) and every document it includes.
+    pub fn compile<R: GrammarResolver>(document: &str, resolver: &R) -> Result<Self, GrammarError> {
+        let bnf = BackusNaurForm::from_grammar_document(document, resolver)?;
+        Ok(Self::from_backus_naur_form(
+            Self::source_hash(document, resolver)?,
+            bnf,
+        ))
+    }
+
+    ///Resolves the document at the path using the resolver and compiles it (see [BackusNaurForm::load_grammar]).
+    pub fn compile_file<R: GrammarResolver>(
+        path: &str,
+        resolver: &R,
+    ) -> Result<Self, GrammarError> {
+        let (name, contents) = resolver.resolve(path, None)?;
+        let bnf = BackusNaurForm::load_grammar(path, resolver)?;
+        Ok(Self::from_backus_naur_form(
+            hash_sources(&grammar::document_sources(&name, &contents, resolver)?),
+            bnf,
+        ))
+    }
+
+    //Takes the parts of the backus naur form that a grammar document can set.
+    fn from_backus_naur_form(source_hash: u64, bnf: BackusNaurForm) -> Self {
+        Self {
+            source_hash,
+            rule_docs: sorted(&bnf.rule_docs),
+            node_labels: sorted(&bnf.node_labels),
+            inline_choices: sorted(&bnf.inline_choices),
+            choice_weights: sorted(&bnf.choice_weights),
+            inline_tests: bnf.inline_tests,
+            rules: bnf.rules,
+            templates: bnf.templates,
+        }
+    }
+
+    ///Returns the hash of the grammar document and every document it includes.
+    ///The rules are not parsed for this, the documents are only read to find the includes.
+    pub fn source_hash<R: GrammarResolver>(
+        document: &str,
+        resolver: &R,
+    ) -> Result<u64, GrammarError> {
+        Ok(hash_sources(&grammar::document_sources(
+            "<document>",
+            document,
+            resolver,
+        )?))
+    }
+
+    ///Returns the hash of the documents the grammar was compiled from.
+    pub fn get_source_hash(&self) -> u64 {
+        self.source_hash
+    }
+
+    ///Returns true if the grammar was compiled from the document (and the documents it includes) as they are now.
+    pub fn is_up_to_date<R: GrammarResolver>(
+        &self,
+        document: &str,
+        resolver: &R,
+    ) -> Result<bool, GrammarError> {
+        Ok(Self::source_hash(document, resolver)? == self.source_hash)
+    }
+
+    ///Returns every [Symbol] of the grammar: the name of every rule (as [Symbol::NonTerminal]) followed by the symbols of its choices.
+    ///The rules of templates come after the other rules. A symbol is returned every time it appears.
+    pub fn get_symbols(&self) -> impl Iterator<Item = Symbol> + '_ {
+        self.rules
+            .iter()
+            .map(|(symbol, _)| symbol)
+            .chain(self.templates.iter().map(|template| template.get_symbol()))
+            .flat_map(|symbol| {
+                std::iter::once(Symbol::NonTerminal(symbol.get_name().to_string()))
+                    .chain(symbol.get_rule().iter().flatten().cloned())
+            })
+    }
+
+    ///Creates a [BackusNaurForm] with the rules of the grammar.
+    pub fn to_backus_naur_form(&self) -> BackusNaurForm<'static> {
+        BackusNaurForm {
+            rules: self.rules.clone(),
+            templates: self.templates.clone(),
+            rule_docs: self.rule_docs.iter().cloned().collect(),
+            node_labels: self.node_labels.iter().cloned().collect(),
+            inline_choices: self.inline_choices.iter().cloned().collect(),
+            choice_weights: self.choice_weights.iter().cloned().collect(),
+            inline_tests: self.inline_tests.clone(),
+            ..Default::default()
+        }
+    }
+
+    ///Writes the grammar to the file at the path. The file is created if it doesn't exist and overwritten otherwise.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), CompiledGrammarError> {
+        std::fs::write(path.as_ref(), self.to_bytes())
+            .map_err(|error| io_error(path.as_ref(), error))
+    }
+
+    ///Reads a grammar that was written with [save](CompiledGrammar::save).
+    ///Returns a [CompiledGrammarError::UnsupportedVersion] if the file was written with a different version of the format.
+    ///This doesn't check if the grammar is up to date, see [is_up_to_date](CompiledGrammar::is_up_to_date).
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, CompiledGrammarError> {
+        let bytes = std::fs::read(path.as_ref()).map_err(|error| io_error(path.as_ref(), error))?;
+        Self::from_bytes(&bytes)
+    }
+
+    ///Loads the grammar from the path if it is up to date with the document.
+    ///Otherwise the document is compiled and saved to the path, so the next call can load it.
+    ///Files that can't be loaded (for example because they don't exist or have a different version) are replaced too.
+    pub fn load_or_compile<R: GrammarResolver>(
+        path: impl AsRef<Path>,
+        document: &str,
+        resolver: &R,
+    ) -> Result<Self, CompiledGrammarError> {
+        let source_hash = Self::source_hash(document, resolver)?;
+        if let Ok(grammar) = Self::load(path.as_ref()) {
+            if grammar.source_hash == source_hash {
+                return Ok(grammar);
+            }
+        }
+        let grammar = Self::compile(document, resolver)?;
+        grammar.save(path)?;
+        Ok(grammar)
+    }
+
+    //Encodes the grammar in the saved format.
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = MAGIC.to_vec();
+        bytes.extend(FORMAT_VERSION.to_le_bytes());
+        bytes.extend(self.source_hash.to_le_bytes());
+        let templates = self
+            .templates
+            .iter()
+            .map(|template| (template.get_symbol(), template.get_priority()))
+            .collect::<Vec<_>>();
+        let rules = self
+            .rules
+            .iter()
+            .map(|(symbol, priority)| (symbol, *priority))
+            .collect::<Vec<_>>();
+        for rules in [rules, templates] {
+            write_length(&mut bytes, rules.len());
+            for (symbol, priority) in rules {
+                write_rule(&mut bytes, symbol, priority);
+            }
+        }
+        write_length(&mut bytes, self.rule_docs.len());
+        for (name, doc) in &self.rule_docs {
+            write_string(&mut bytes, name);
+            write_string(&mut bytes, doc);
+        }
+        write_length(&mut bytes, self.node_labels.len());
+        for (name, label) in &self.node_labels {
+            write_string(&mut bytes, name);
+            write_string(&mut bytes, label);
+        }
+        write_length(&mut bytes, self.inline_choices.len());
+        for (name, choices) in &self.inline_choices {
+            write_string(&mut bytes, name);
+            write_length(&mut bytes, choices.len());
+            for choice in choices {
+                write_choice(&mut bytes, choice);
+            }
+        }
+        write_length(&mut bytes, self.choice_weights.len());
+        for (name, weights) in &self.choice_weights {
+            write_string(&mut bytes, name);
+            write_length(&mut bytes, weights.len());
+            for (choice, weight) in weights {
+                write_choice(&mut bytes, choice);
+                bytes.extend(weight.to_le_bytes());
+            }
+        }
+        write_length(&mut bytes, self.inline_tests.len());
+        for test in &self.inline_tests {
+            write_string(&mut bytes, &test.symbol);
+            write_string(&mut bytes, &test.input);
+            bytes.push(test.accepts as u8);
+        }
+        bytes
+    }
+
+    //Decodes a grammar that was encoded with to_bytes.
+    fn from_bytes(bytes: &[u8]) -> Result<Self, CompiledGrammarError> {
+        let mut reader = Reader { bytes, position: 0 };
+        if reader.take(MAGIC.len())? != MAGIC {
+            return Err(corrupt("the file is not a compiled grammar"));
+        }
+        let version = u32::from_le_bytes(reader.take_array()?);
+        if version != FORMAT_VERSION {
+            return Err(CompiledGrammarError::UnsupportedVersion {
+                found: version,
+                supported: FORMAT_VERSION,
+            });
+        }
+        let source_hash = u64::from_le_bytes(reader.take_array()?);
+        let rules = reader.read_rules()?;
+        let templates = reader
+            .read_rules()?
+            .iter()
+            .map(|(symbol, priority)| {
+                Template::from_symbol(symbol, *priority)
+                    .ok_or(corrupt("a template has no parameters"))
+            })
+            .collect::<Result<_, _>>()?;
+        let rule_docs = (0..reader.read_length()?)
+            .map(|_| Ok((reader.read_string()?, reader.read_string()?)))
+            .collect::<Result<_, CompiledGrammarError>>()?;
+        let node_labels = (0..reader.read_length()?)
+            .map(|_| Ok((reader.read_string()?, reader.read_string()?)))
+            .collect::<Result<_, CompiledGrammarError>>()?;
+        let inline_choices = (0..reader.read_length()?)
+            .map(|_| {
+                let name = reader.read_string()?;
+                let choices = (0..reader.read_length()?)
+                    .map(|_| reader.read_choice())
+                    .collect::<Result<_, _>>()?;
+                Ok((name, choices))
+            })
+            .collect::<Result<_, CompiledGrammarError>>()?;
+        let choice_weights = (0..reader.read_length()?)
+            .map(|_| {
+                let name = reader.read_string()?;
+                let weights = (0..reader.read_length()?)
+                    .map(|_| {
+                        Ok((
+                            reader.read_choice()?,
+                            f64::from_le_bytes(reader.take_array()?),
+                        ))
+                    })
+                    .collect::<Result<_, CompiledGrammarError>>()?;
+                Ok((name, weights))
+            })
+            .collect::<Result<_, CompiledGrammarError>>()?;
+        let inline_tests = (0..reader.read_length()?)
+            .map(|_| {
+                Ok(InlineTest {
+                    symbol: reader.read_string()?,
+                    input: reader.read_string()?,
+                    accepts: match reader.take(1)?[0] {
+                        0 => false,
+                        1 => true,
+                        _ => return Err(corrupt("a inline test neither accepts nor rejects")),
+                    },
+                })
+            })
+            .collect::<Result<_, CompiledGrammarError>>()?;
+        if reader.position != bytes.len() {
+            return Err(corrupt("there are bytes after the last inline test"));
+        }
+        Ok(Self {
+            source_hash,
+            rules,
+            templates,
+            rule_docs,
+            node_labels,
+            inline_choices,
+            choice_weights,
+            inline_tests,
+        })
+    }
+}
+
+//Returns the entries of the map sorted by the names of the rules.
+fn sorted<V: Clone>(map: &HashMap<String, V>) -> Vec<(String, V)> {
+    let mut entries = map
+        .iter()
+        .map(|(name, value)| (name.to_string(), value.clone()))
+        .collect::<Vec<_>>();
+    entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+    entries
+}
+
+//The 64 bit FNV-1a hash. Unlike the hasher of the standard library, it is the same on every platform and with every version of Rust.
+const FNV_OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+const FNV_PRIME: u64 = 0x100000001b3;
+
+//Hashes the contents of the documents.
+fn hash_sources(sources: &[String]) -> u64 {
+    sources.iter().fold(FNV_OFFSET_BASIS, |hash, source| {
+        //the length separates the documents, so moving text from one document into another changes the hash
+        let hash = fnv1a(hash, &(source.len() as u64).to_le_bytes());
+        fnv1a(hash, source.as_bytes())
+    })
+}
+
+fn fnv1a(hash: u64, bytes: &[u8]) -> u64 {
+    bytes.iter().fold(hash, |hash, byte| {
+        (hash ^ *byte as u64).wrapping_mul(FNV_PRIME)
+    })
+}
+
+fn io_error(path: &Path, error: std::io::Error) -> CompiledGrammarError {
+    CompiledGrammarError::Io {
+        path: path.display().to_string(),
+        message: error.to_string(),
+    }
+}
+
+fn corrupt(message: &str) -> CompiledGrammarError {
+    CompiledGrammarError::Corrupt {
+        message: message.to_string(),
+    }
+}
+
+//Lengths are written as u32, since no grammar has more than u32::MAX rules, choices or bytes in a name.
+fn write_length(bytes: &mut Vec<u8>, length: usize) {
+    bytes.extend((length as u32).to_le_bytes());
+}
+
+fn write_string(bytes: &mut Vec<u8>, string: &str) {
+    write_length(bytes, string.len());
+    bytes.extend(string.as_bytes());
+}
+
+//A rule is written as its name, its priority and its choices. Every symbol starts with 0 if it is a terminal and 1 otherwise.
+fn write_rule(bytes: &mut Vec<u8>, symbol: &NonTerminalSymbol, priority: usize) {
+    write_string(bytes, symbol.get_name());
+    bytes.extend((priority as u64).to_le_bytes());
+    write_length(bytes, symbol.get_rule().len());
+    for choice in symbol.get_rule() {
+        write_choice(bytes, choice);
+    }
+}
+
+fn write_choice(bytes: &mut Vec<u8>, choice: &Choice) {
+    write_length(bytes, choice.len());
+    for symbol in choice {
+        match symbol {
+            Symbol::Terminal(terminal) => {
+                bytes.push(0);
+                write_string(bytes, terminal);
+            }
+            Symbol::NonTerminal(non_terminal) => {
+                bytes.push(1);
+                write_string(bytes, non_terminal);
+            }
+        }
+    }
+}
+
+//Reads the values that were written with the write functions.
+struct Reader<'b> {
+    bytes: &'b [u8],
+    position: usize,
+}
+
+impl Reader<'_> {
+    fn take(&mut self, length: usize) -> Result<&[u8], CompiledGrammarError> {
+        let taken = self
+            .bytes
+            .get(self.position..self.position + length)
+            .ok_or(corrupt("the file ends unexpectedly"))?;
+        self.position += length;
+        Ok(taken)
+    }
+
+    fn take_array<const N: usize>(&mut self) -> Result<[u8; N], CompiledGrammarError> {
+        Ok(self.take(N)?.try_into().expect("take returns N bytes"))
+    }
+
+    fn read_length(&mut self) -> Result<usize, CompiledGrammarError> {
+        Ok(u32::from_le_bytes(self.take_array()?) as usize)
+    }
+
+    fn read_string(&mut self) -> Result<String, CompiledGrammarError> {
+        let length = self.read_length()?;
+        String::from_utf8(self.take(length)?.to_vec())
+            .map_err(|_| corrupt("a name is not valid UTF-8"))
+    }
+
+    fn read_rules(&mut self) -> Result<Vec<(NonTerminalSymbol, usize)>, CompiledGrammarError> {
+        (0..self.read_length()?)
+            .map(|_| {
+                let name = self.read_string()?;
+                let priority = u64::from_le_bytes(self.take_array()?) as usize;
+                let expression = (0..self.read_length()?)
+                    .map(|_| self.read_choice())
+                    .collect::<Result<_, _>>()?;
+                Ok((NonTerminalSymbol::new(name, expression), priority))
+            })
+            .collect()
+    }
+
+    fn read_choice(&mut self) -> Result<Choice, CompiledGrammarError> {
+        (0..self.read_length()?)
+            .map(|_| match self.take(1)?[0] {
+                0 => Ok(Symbol::Terminal(self.read_string()?)),
+                1 => Ok(Symbol::NonTerminal(self.read_string()?)),
+                _ => Err(corrupt("a symbol is neither a terminal nor a non terminal")),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    //Returns a path in the temporary directory that no other test uses.
+    fn temporary_path(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("{name}-{}.bnfc", std::process::id()))
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let documents = HashMap::from([(
+            "digits.bnf".to_string(),
+            r#"priority 2 => <digit> ::= "1" | "2""#.to_string(),
+        )]);
+        let document = r#"%include "digits.bnf"
+priority 1 => <list(x)> ::= <x> | <list(x)> "," <x>
+## Digits with an optional sign
+<numbers> ::= <list(digit)> | ("+" | "-") <list(digit)>"#;
+        let grammar = CompiledGrammar::compile(document, &documents).unwrap();
+        let path = temporary_path("test_save_and_load");
+        grammar.save(&path).unwrap();
+        let loaded = CompiledGrammar::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(loaded, grammar);
+        assert_eq!(
+            loaded.to_backus_naur_form().rule("numbers").unwrap().doc(),
+            Some("Digits with an optional sign")
+        );
+        let bnf = BackusNaurForm::from_grammar_document(document, &documents).unwrap();
+        assert_eq!(loaded.to_backus_naur_form(), bnf);
+        assert_eq!(
+            loaded.to_backus_naur_form().symbolize_string("-1,2"),
+            bnf.symbolize_string("-1,2")
+        );
+
+        //a change in a included document makes the grammar outdated
+        let changed = HashMap::from([(
+            "digits.bnf".to_string(),
+            r#"priority 2 => <digit> ::= "1" | "2" | "3""#.to_string(),
+        )]);
+        assert_eq!(grammar.is_up_to_date(document, &documents), Ok(true));
+        assert_eq!(grammar.is_up_to_date(document, &changed), Ok(false));
+    }
+
+    #[test]
+    fn test_save_and_load_ast() {
+        let document = r#"priority 1 => <digit> -> Digit ::= "1" | "2"
+<number> -> Number ::= <digit> | @inline "(" <number> ")" | 25% <number> <number>
+%test <number> accepts "12" rejects "1a""#;
+        let grammar = CompiledGrammar::compile(document, &HashMap::new()).unwrap();
+        let path = temporary_path("test_save_and_load_ast");
+        grammar.save(&path).unwrap();
+        let loaded = CompiledGrammar::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(loaded, grammar);
+
+        let loaded = loaded.to_backus_naur_form();
+        let bnf = BackusNaurForm::from_grammar_document(document, &HashMap::new()).unwrap();
+        assert_eq!(
+            loaded.symbolize_string("1(2)"),
+            bnf.symbolize_string("1(2)")
+        );
+        assert_eq!(loaded.symbolize_string("1(2)")[0].get_symbol(), "Number");
+        let choice = &bnf.rule("number").unwrap().get_expression()[2];
+        assert_eq!(loaded.choice_weight("number", choice), Some(25.0));
+        assert_eq!(loaded.inline_tests(), bnf.inline_tests());
+    }
+
+    #[test]
+    fn test_load_or_compile() {
+        let documents = HashMap::new();
+        let path = temporary_path("test_load_or_compile");
+        let grammar =
+            CompiledGrammar::load_or_compile(&path, r#"<digit> ::= "1""#, &documents).unwrap();
+        assert_eq!(CompiledGrammar::load(&path), Ok(grammar));
+
+        //the saved grammar is outdated, so it is compiled and saved again
+        let grammar =
+            CompiledGrammar::load_or_compile(&path, r#"<digit> ::= "2""#, &documents).unwrap();
+        assert_eq!(CompiledGrammar::load(&path), Ok(grammar));
+
+        let bytes = std::fs::read(&path).unwrap();
+        let mut other_version = bytes.clone();
+        other_version[4] = 0;
+        std::fs::write(&path, &other_version).unwrap();
+        assert_eq!(
+            CompiledGrammar::load(&path),
+            Err(CompiledGrammarError::UnsupportedVersion {
+                found: 0,
+                supported: FORMAT_VERSION
+            })
+        );
+        std::fs::write(&path, &bytes[..10]).unwrap();
+        assert!(matches!(
+            CompiledGrammar::load(&path),
+            Err(CompiledGrammarError::Corrupt { .. })
+        ));
+        std::fs::remove_file(&path).unwrap();
+    }
+}
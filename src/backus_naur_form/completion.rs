@@ -0,0 +1,127 @@
+//! Contains [CompletionItem], a suggestion for a terminal that could be typed next.
+//! It is meant for autocompletion in REPLs and editors of languages that are made with a [BackusNaurForm]:
+//! ```rust, ignore
+//! for item in bnf.completions("pri") {
+//!     println!("{} (insert \"{}\")", item.terminal, item.remaining);
+//! }
+//! ```
+//! The input is parsed with the GLR parser (see the [glr](super::glr) module), starting from every rule
+//! that isn't used by another rule. Every terminal that can follow the input in one of the parses is suggested.
+//! If the input ends in the middle of a terminal, the rest of that terminal is suggested.
+
+use super::{
+    glr::{recognize, Automaton, Grammar, GrammarSymbol},
+    symbol::Symbol,
+    BackusNaurForm,
+};
+
+///A terminal that could validly extend a input. Created with [BackusNaurForm::completions].
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Debug)]
+pub struct CompletionItem {
+    ///The whole terminal (for example `let`).
+    pub terminal: String,
+    ///The part of the terminal that has to be added to the input (for example `t` if the input ends with `le`).
+    pub remaining: String,
+    ///The name of the non terminal symbol whose rule contains the terminal.
+    pub symbol: String,
+}
+
+impl BackusNaurForm<'_> {
+    ///Returns the terminals that could validly extend the input at its end, sorted by the terminal.
+    ///Returns a empty vector if the input can't be the start of anything.
+    ///More information can be found in the [completion](self) module.
+    ///
+    /// # Panics
+    /// Panics if a choice would be expanded into too many versions (see [SymbolizeError::TooManyChoiceVersions](super::SymbolizeError::TooManyChoiceVersions)).
+    pub fn completions(&self, partial_input: &str) -> Vec<CompletionItem> {
+        let roots = self.root_symbols();
+        let grammar = Grammar::new(self, &roots);
+        let automaton = Automaton::new(&grammar);
+        let input = partial_input.chars().collect::<Vec<_>>();
+        let (_, states) = recognize(&grammar, &automaton, &input);
+
+        let mut items = vec![];
+        for (production, dot) in states
+            .into_iter()
+            .flat_map(|state| automaton.states[state].iter())
+        {
+            let production = &grammar.productions[*production];
+            let (Some(GrammarSymbol::Char(_)), Some(Some((terminal, index)))) =
+                (production.rhs.get(*dot), production.terminals.get(*dot))
+            else {
+                continue;
+            };
+            items.push(CompletionItem {
+                terminal: terminal.to_string(),
+                remaining: terminal.chars().skip(*index).collect(),
+                symbol: grammar.names[production.lhs].to_string(),
+            });
+        }
+        items.sort();
+        items.dedup();
+        items
+    }
+
+    //Returns the names of the rules that aren't used by other rules.
+    //If every rule is used by another rule, every rule is returned.
+    fn root_symbols(&self) -> Vec<&str> {
+        let is_used = |name: &str| {
+            let symbol = Symbol::NonTerminal(name.to_string());
+            self.rules
+                .iter()
+                .filter(|(non_terminal_symbol, _)| non_terminal_symbol.get_name() != name)
+                .flat_map(|(non_terminal_symbol, _)| {
+                    non_terminal_symbol.get_rule().iter().flatten()
+                })
+                .any(|other| *other == symbol)
+        };
+        let names = self
+            .rules
+            .iter()
+            .map(|(non_terminal_symbol, _)| non_terminal_symbol.get_name());
+        let roots = names
+            .clone()
+            .filter(|name| !is_used(name))
+            .collect::<Vec<_>>();
+        if roots.is_empty() {
+            names.collect()
+        } else {
+            roots
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backus_naur_form::test_helpers::DIGIT_RULE;
+
+    #[test]
+    fn test_completions() {
+        let bnf = crate::backus_naur_form!(
+            priority 1 => DIGIT_RULE
+            priority 0 => r#"<stmt> ::= "let" <digit> | "print" <digit> | "print" "!""#
+        );
+        let item = |terminal: &str, remaining: &str, symbol: &str| CompletionItem {
+            terminal: terminal.to_string(),
+            remaining: remaining.to_string(),
+            symbol: symbol.to_string(),
+        };
+
+        assert_eq!(
+            bnf.completions(""),
+            vec![item("let", "let", "stmt"), item("print", "print", "stmt")]
+        );
+        assert_eq!(bnf.completions("pri"), vec![item("print", "nt", "stmt")]);
+        assert_eq!(
+            bnf.completions("print"),
+            vec![
+                item("!", "!", "stmt"),
+                item("1", "1", "digit"),
+                item("2", "2", "digit")
+            ]
+        );
+        assert_eq!(bnf.completions("let1"), vec![]);
+        assert_eq!(bnf.completions("x"), vec![]);
+    }
+}
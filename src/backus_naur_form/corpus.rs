@@ -0,0 +1,212 @@
+//! Checks a grammar against a directory of examples, as a safety net when the rules change:
+//! ```text
+//! corpus/
+//!   pass/   examples that must be symbolized into the start symbol
+//!     sum.txt
+//!   fail/   examples that must not be symbolized into the start symbol
+//!     trailing_plus.txt
+//! ```
+//! ```rust, ignore
+//! let report = bnf.validate_corpus("corpus", "sum")?;
+//! assert!(report.is_ok(), "{report}");
+//! ```
+//! A example succeeds if its whole content is symbolized into a single [Token](super::token::Token) of the start symbol
+//! (see [root_token](BackusNaurForm::root_token)). Both directories are optional and the examples are checked in the order of their file names.
+
+use std::{
+    fmt::Display,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use super::{
+    error::{CorpusError, RootError},
+    BackusNaurForm,
+};
+
+///The reason why a positive example of a corpus failed.
+#[derive(PartialEq, Debug, Clone)]
+pub enum CorpusFailure {
+    ///The example wasn't symbolized into a single token.
+    NoRoot(RootError),
+    ///The example was symbolized into a single token of another symbol than the start symbol.
+    WrongRoot { symbol: String },
+}
+
+impl Display for CorpusFailure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CorpusFailure::NoRoot(error) => write!(f, "{error}"),
+            CorpusFailure::WrongRoot { symbol } => {
+                write!(
+                    f,
+                    "the root token is <{symbol}> instead of the start symbol"
+                )
+            }
+        }
+    }
+}
+
+///The result of [validate_corpus](BackusNaurForm::validate_corpus).
+#[derive(PartialEq, Debug, Clone, Default)]
+pub struct CorpusReport {
+    ///The amount of examples that behaved as expected.
+    pub passed: usize,
+    ///The positive examples that weren't symbolized into the start symbol.
+    pub failed_positives: Vec<(PathBuf, CorpusFailure)>,
+    ///The negative examples that were symbolized into the start symbol.
+    pub accepted_negatives: Vec<PathBuf>,
+}
+
+impl CorpusReport {
+    ///Returns true if every example behaved as expected.
+    pub fn is_ok(&self) -> bool {
+        self.failed_positives.is_empty() && self.accepted_negatives.is_empty()
+    }
+}
+
+impl Display for CorpusReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} passed, {} failed",
+            self.passed,
+            self.failed_positives.len() + self.accepted_negatives.len()
+        )?;
+        for (path, failure) in &self.failed_positives {
+            write!(f, "\n{} should pass: {failure}", path.display())?;
+        }
+        for path in &self.accepted_negatives {
+            write!(f, "\n{} should fail but passed", path.display())?;
+        }
+        Ok(())
+    }
+}
+
+impl BackusNaurForm<'_> {
+    ///Symbolizes every example in the `pass` and `fail` directories of the directory and reports
+    ///which positive examples aren't symbolized into the start symbol and which negative ones wrongly are.
+    ///More information can be found in the [corpus](self) module.
+    pub fn validate_corpus(
+        &self,
+        directory: impl AsRef<Path>,
+        start_symbol: &str,
+    ) -> Result<CorpusReport, CorpusError> {
+        let directory = directory.as_ref();
+        if !directory.is_dir() {
+            return Err(CorpusError::Io {
+                path: directory.display().to_string(),
+                message: "not a directory".to_string(),
+            });
+        }
+
+        let mut report = CorpusReport::default();
+        for path in examples(&directory.join("pass"))? {
+            match self.root_token(&read_example(&path)?) {
+                Ok(root) if root.get_symbol() == start_symbol => report.passed += 1,
+                Ok(root) => report.failed_positives.push((
+                    path,
+                    CorpusFailure::WrongRoot {
+                        symbol: root.get_symbol().to_string(),
+                    },
+                )),
+                Err(error) => report
+                    .failed_positives
+                    .push((path, CorpusFailure::NoRoot(error))),
+            }
+        }
+        for path in examples(&directory.join("fail"))? {
+            match self.root_token(&read_example(&path)?) {
+                Ok(root) if root.get_symbol() == start_symbol => {
+                    report.accepted_negatives.push(path)
+                }
+                _ => report.passed += 1,
+            }
+        }
+        Ok(report)
+    }
+}
+
+//Returns the files in the directory sorted by their names, or nothing if the directory doesn't exist.
+fn examples(directory: &Path) -> Result<Vec<PathBuf>, CorpusError> {
+    if !directory.exists() {
+        return Ok(vec![]);
+    }
+    let io_error = |error: std::io::Error| CorpusError::Io {
+        path: directory.display().to_string(),
+        message: error.to_string(),
+    };
+    let mut paths = vec![];
+    for entry in fs::read_dir(directory).map_err(io_error)? {
+        let path = entry.map_err(io_error)?.path();
+        if path.is_file() {
+            paths.push(path);
+        }
+    }
+    paths.sort();
+    Ok(paths)
+}
+
+fn read_example(path: &Path) -> Result<String, CorpusError> {
+    fs::read_to_string(path).map_err(|error| CorpusError::Io {
+        path: path.display().to_string(),
+        message: error.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::backus_naur_form;
+    use crate::backus_naur_form::test_helpers::DIGIT_RULE;
+
+    use super::*;
+
+    #[test]
+    fn test_validate_corpus() {
+        let bnf = backus_naur_form!(
+            priority 1 => DIGIT_RULE
+            priority 0 => r#"<sum> ::= <digit> "+" <digit>"#
+        );
+        let directory =
+            std::env::temp_dir().join(format!("test_validate_corpus-{}", std::process::id()));
+        fs::create_dir_all(directory.join("pass")).unwrap();
+        fs::create_dir_all(directory.join("fail")).unwrap();
+        fs::write(directory.join("pass/a.txt"), "1+2").unwrap();
+        fs::write(directory.join("pass/b.txt"), "1+").unwrap();
+        fs::write(directory.join("pass/c.txt"), "2").unwrap();
+        fs::write(directory.join("fail/a.txt"), "1+2+").unwrap();
+        fs::write(directory.join("fail/b.txt"), "2+2").unwrap();
+
+        let report = bnf.validate_corpus(&directory, "sum").unwrap();
+        assert!(!report.is_ok());
+        assert_eq!(report.passed, 2);
+        assert_eq!(
+            report
+                .failed_positives
+                .iter()
+                .map(|(path, _)| path.file_name().unwrap().to_str().unwrap())
+                .collect::<Vec<_>>(),
+            vec!["b.txt", "c.txt"]
+        );
+        assert!(matches!(
+            report.failed_positives[0].1,
+            CorpusFailure::NoRoot(_)
+        ));
+        assert_eq!(
+            report.failed_positives[1].1,
+            CorpusFailure::WrongRoot {
+                symbol: "digit".to_string()
+            }
+        );
+        assert_eq!(
+            report.accepted_negatives,
+            vec![directory.join("fail/b.txt")]
+        );
+
+        fs::remove_dir_all(&directory).unwrap();
+        assert!(matches!(
+            bnf.validate_corpus(&directory, "sum"),
+            Err(CorpusError::Io { .. })
+        ));
+    }
+}
@@ -0,0 +1,118 @@
+//! Shows which parts of a input the grammar understood, for example to highlight them in an editor:
+//! ```rust, ignore
+//! //with <sum> ::= <digit> "+" <digit>
+//! bnf.coverage("1+2 ?")
+//! //[Some("sum"), Some("sum"), Some("sum"), None, None]
+//! ```
+//! Every char of the input is mapped to the topmost non terminal symbol that covers it, or None if no rule matched it.
+//! The input is symbolized with [symbolize_best_effort](BackusNaurForm::symbolize_best_effort), so this works for any input.
+//!
+//! [unparsed_regions](BackusNaurForm::unparsed_regions) answers the same question the other way around and lists the parts of the input no rule matched:
+//! ```rust, ignore
+//! bnf.unparsed_regions("1+2 ?")
+//! //[(3..5, " ?")]
+//! ```
+
+use std::ops::Range;
+
+use super::{best_effort::ERROR_SYMBOL, token::Token, BackusNaurForm};
+
+impl BackusNaurForm<'_> {
+    ///Returns the name of the topmost non terminal symbol that covers each char of the string (None if no rule matched the char).
+    ///More information can be found in the [coverage](self) module.
+    ///
+    ///The returned [Vec] has one entry for every char of the string, even if preprocessors changed the string.
+    ///Chars that a preprocessor removed are None.
+    pub fn coverage(&self, string: &str) -> Vec<Option<String>> {
+        let preprocessed = self.preprocess(string);
+        let root = self.symbolize_best_effort(string);
+
+        let mut coverage = vec![None; string.chars().count()];
+        let mut position = 0;
+        for token in root.get_child_tokens() {
+            let symbol = match token {
+                Token::NonTerminalToken(non_terminal)
+                    if non_terminal.non_terminal_symbol != ERROR_SYMBOL =>
+                {
+                    Some(&non_terminal.non_terminal_symbol)
+                }
+                _ => None,
+            };
+            for _ in token.get_terminals().chars() {
+                //chars that a preprocessor inserted share the position of the chars they replaced
+                let covered = &mut coverage[preprocessed.original_position(position)];
+                if covered.is_none() {
+                    *covered = symbol.cloned();
+                }
+                position += 1;
+            }
+        }
+        coverage
+    }
+
+    ///Returns the runs of chars that are left as terminals at the top level after symbolizing the string, with their text.
+    ///The ranges are char indexes into the string (not into the preprocessed string).
+    pub fn unparsed_regions(&self, string: &str) -> Vec<(Range<usize>, String)> {
+        let preprocessed = self.preprocess(string);
+        let root = self.symbolize_best_effort(string);
+
+        let mut regions = vec![];
+        let mut position = 0;
+        for token in root.get_child_tokens() {
+            let length = token.get_terminals().chars().count();
+            if token.get_symbol() == ERROR_SYMBOL {
+                let range = preprocessed.original_range(position..position + length);
+                let text = string.chars().skip(range.start).take(range.len()).collect();
+                regions.push((range, text));
+            }
+            position += length;
+        }
+        regions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::backus_naur_form;
+    use crate::backus_naur_form::test_helpers::DIGIT_RULE;
+
+    #[test]
+    fn test_coverage() {
+        let mut bnf = backus_naur_form!(
+            priority 1 => DIGIT_RULE
+            priority 0 => r#"<sum> ::= <digit> "+" <digit>"#
+        );
+        let sum = Some("sum".to_string());
+        let digit = Some("digit".to_string());
+
+        assert_eq!(
+            bnf.coverage("1+2 ?2"),
+            vec![sum.clone(), sum.clone(), sum.clone(), None, None, digit]
+        );
+        assert_eq!(bnf.coverage(""), vec![]);
+
+        bnf.add_preprocessor(crate::backus_naur_form::preprocess::strip_bom);
+        assert_eq!(
+            bnf.coverage("\u{feff}1+2"),
+            vec![None, sum.clone(), sum.clone(), sum]
+        );
+    }
+
+    #[test]
+    fn test_unparsed_regions() {
+        let bnf = backus_naur_form!(
+            priority 1 => DIGIT_RULE
+            priority 0 => r#"<sum> ::= <digit> "+" <digit>"#
+        );
+
+        assert_eq!(
+            bnf.unparsed_regions("?1+2 ?!2-"),
+            vec![
+                (0..1, "?".to_string()),
+                (4..7, " ?!".to_string()),
+                (8..9, "-".to_string())
+            ]
+        );
+        assert_eq!(bnf.unparsed_regions("1+2"), vec![]);
+    }
+}
@@ -0,0 +1,213 @@
+//! Contains [CykParse], the result of the CYK parser.
+//! Unlike [BackusNaurForm::symbolize_string], the CYK parser finds every derivation of the input,
+//! no matter how the rules are written (recursive rules like `<sum> ::= <sum> "+" <digit>` work too).
+//! It always takes O(n³) time for a input with n chars, so it is meant for checking grammars and smaller inputs.
+//!
+//! The rules are converted into a [ChomskyNormalForm] first, so terminals are matched char by char.
+//! Derivations that only differ in a cycle of unit rules (for example `<a> ::= <b>` and `<b> ::= <a>`) are not counted.
+//! ```rust, ignore
+//! let parse = bnf.parse_cyk("1+2+3");
+//! assert!(parse.is_match("sum"));
+//! assert_eq!(parse.derivation_count("sum"), Some(2));
+//! let ast = parse.tree("sum").unwrap();
+//! ```
+
+use std::{borrow::Cow, collections::HashMap};
+
+use super::{
+    cnf::{ChomskyNormalForm, CnfBody},
+    remove_hidden_tokens,
+    token::Token,
+    BackusNaurForm,
+};
+
+//The number of derivations, None if the number is too big for a u128.
+type Count = Option<u128>;
+
+fn add(first: Count, second: Count) -> Count {
+    first?.checked_add(second?)
+}
+
+fn multiply(first: Count, second: Count) -> Count {
+    first?.checked_mul(second?)
+}
+
+///The result of parsing a input with the CYK parser. More information can be found in the [cyk](self) module.
+#[derive(Clone, Debug)]
+pub struct CykParse<'a> {
+    cnf: Cow<'a, ChomskyNormalForm>,
+    input: Vec<char>,
+    //the number of derivations of every symbol for every part of the input
+    //indexed by [length - 1][start]
+    table: Vec<Vec<HashMap<String, Count>>>,
+}
+
+impl ChomskyNormalForm {
+    ///Parses the input with the CYK parser.
+    pub fn parse_cyk(&self, input: &str) -> CykParse<'_> {
+        CykParse::new(Cow::Borrowed(self), input)
+    }
+}
+
+impl BackusNaurForm<'_> {
+    ///Converts the rules into chomsky normal form and parses the input with the CYK parser.
+    ///Use [to_cnf](BackusNaurForm::to_cnf) and [ChomskyNormalForm::parse_cyk] to parse several inputs without converting the rules every time.
+    ///
+    /// # Panics
+    /// Panics if a choice would be expanded into too many versions (see [SymbolizeError::TooManyChoiceVersions](super::SymbolizeError::TooManyChoiceVersions)).
+    pub fn parse_cyk(&self, input: &str) -> CykParse<'static> {
+        CykParse::new(Cow::Owned(self.to_cnf()), input)
+    }
+}
+
+impl<'a> CykParse<'a> {
+    fn new(cnf: Cow<'a, ChomskyNormalForm>, input: &str) -> Self {
+        let input = input.chars().collect::<Vec<_>>();
+        let mut table: Vec<Vec<HashMap<String, Count>>> = vec![];
+
+        for length in 1..=input.len() {
+            let mut row = vec![];
+            for start in 0..=input.len() - length {
+                let mut counts: HashMap<String, Count> = HashMap::new();
+                for rule in &cnf.rules {
+                    let count = match &rule.body {
+                        CnfBody::Terminal(ch) if length == 1 && input[start] == *ch => Some(1),
+                        CnfBody::Terminal(_) => continue,
+                        CnfBody::Pair(first, second) => {
+                            (1..length).try_fold(0, |count: u128, split| {
+                                let first = table[split - 1][start].get(first);
+                                let second = table[length - split - 1][start + split].get(second);
+                                match (first, second) {
+                                    (Some(first), Some(second)) => {
+                                        count.checked_add(multiply(*first, *second)?)
+                                    }
+                                    _ => Some(count),
+                                }
+                            })
+                        }
+                    };
+                    if count != Some(0) {
+                        let total = counts.entry(rule.name.to_string()).or_insert(Some(0));
+                        *total = add(*total, count);
+                    }
+                }
+                row.push(counts);
+            }
+            table.push(row);
+        }
+
+        Self { cnf, input, table }
+    }
+
+    //Returns the number of derivations of the symbol for the part of the input.
+    fn count(&self, symbol: &str, start: usize, length: usize) -> Count {
+        self.table[length - 1][start]
+            .get(symbol)
+            .copied()
+            .unwrap_or(Some(0))
+    }
+
+    ///Returns true if the whole input can be derived from the non terminal symbol (without angle brackets).
+    pub fn is_match(&self, symbol: &str) -> bool {
+        !self.input.is_empty() && self.count(symbol, 0, self.input.len()) != Some(0)
+    }
+
+    ///Returns the number of distinct ASTs of the whole input with the non terminal symbol as root.
+    ///Returns None if the number is too big for a u128.
+    pub fn derivation_count(&self, symbol: &str) -> Option<u128> {
+        match self.input.len() {
+            0 => Some(0),
+            length => self.count(symbol, 0, length),
+        }
+    }
+
+    ///Returns one AST of the whole input with the non terminal symbol as root, or None if the input can't be derived from it.
+    ///Like [BackusNaurForm::symbolize_string], the result is a vector since hidden symbols are removed.
+    pub fn tree(&self, symbol: &str) -> Option<Vec<Token>> {
+        if !self.is_match(symbol) {
+            return None;
+        }
+        let tree = self.build(symbol, 0, self.input.len());
+        Some(remove_hidden_tokens(vec![tree], &|name| {
+            self.cnf.is_hidden_symbol(name)
+        }))
+    }
+
+    //Builds a token of the symbol for the part of the input. The part has to be derivable from the symbol.
+    fn build(&self, symbol: &str, start: usize, length: usize) -> Token {
+        let derivable = |symbol: &str, start, length| self.count(symbol, start, length) != Some(0);
+        for rule in self.cnf.rules.iter().filter(|rule| rule.name == symbol) {
+            let child_tokens = match &rule.body {
+                CnfBody::Terminal(ch) if length == 1 && self.input[start] == *ch => {
+                    vec![Token::from_terminal(&ch.to_string())]
+                }
+                CnfBody::Terminal(_) => continue,
+                CnfBody::Pair(first, second) => {
+                    let Some(split) = (1..length).find(|&split| {
+                        derivable(first, start, split)
+                            && derivable(second, start + split, length - split)
+                    }) else {
+                        continue;
+                    };
+                    vec![
+                        self.build(first, start, split),
+                        self.build(second, start + split, length - split),
+                    ]
+                }
+            };
+
+            //the units are the symbols between the rule and its body, outermost first
+            let names = std::iter::once(symbol)
+                .chain(rule.units.iter().map(String::as_str))
+                .collect::<Vec<_>>();
+            let (innermost, outer) = names.split_last().expect("the symbol is always a name");
+            let token = Token::from_non_terminal(innermost, child_tokens);
+            return outer.iter().rev().fold(token, |token, name| {
+                Token::from_non_terminal(name, vec![token])
+            });
+        }
+        unreachable!("the part of the input is derivable from <{symbol}>")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backus_naur_form::test_helpers::DIGIT_RULE;
+
+    #[test]
+    fn test_parse_cyk() {
+        let bnf = crate::backus_naur_form!(
+            priority 0 => DIGIT_RULE
+            priority 0 => r#"<value> ::= <digit>"#
+            priority 0 => r#"<sum> ::= <sum> "+" <sum> | <value>"#
+        );
+        let parse = bnf.parse_cyk("1+2+1");
+
+        assert!(parse.is_match("sum"));
+        assert!(!parse.is_match("value"));
+        assert_eq!(parse.derivation_count("sum"), Some(2));
+        assert_eq!(bnf.parse_cyk("1+2+1+2").derivation_count("sum"), Some(5));
+        assert_eq!(bnf.parse_cyk("1+").tree("sum"), None);
+
+        let value = |digit| {
+            Token::from_non_terminal(
+                "sum",
+                vec![Token::from_non_terminal(
+                    "value",
+                    vec![Token::from_non_terminal(
+                        "digit",
+                        vec![Token::from_terminal(digit)],
+                    )],
+                )],
+            )
+        };
+        assert_eq!(
+            bnf.parse_cyk("1+2").tree("sum"),
+            Some(vec![Token::from_non_terminal(
+                "sum",
+                vec![value("1"), Token::from_terminal("+"), value("2")]
+            )])
+        );
+    }
+}
@@ -0,0 +1,193 @@
+//! Symbolizes bytes in other encodings than UTF-8 (`encoding` feature), so DSL files don't have to be converted before they are symbolized:
+//! ```rust, ignore
+//! let bytes = std::fs::read("legacy.dsl")?;
+//! let tokens = bnf.symbolize_bytes_with_encoding(&bytes, Encoding::Detect);
+//! ```
+//! The bytes are decoded into a string first, so the rules are written for the decoded chars and not for the bytes
+//! (unlike with [symbolize_bytes](BackusNaurForm::symbolize_bytes)). A byte order mark that belongs to the encoding is removed.
+//!
+//! Invalid byte sequences are replaced with `U+FFFD` in [DecodeMode::Lossy].
+//! In [DecodeMode::Strict] they are a [SymbolizeError::InvalidEncoding] with the position of the first of them.
+
+use encoding_rs::{CoderResult, DecoderResult, UTF_16BE, UTF_16LE, UTF_8};
+
+use super::{bytes::string_from_bytes, error::SymbolizeError, token::Token, BackusNaurForm};
+
+///The encoding of the bytes that are symbolized.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum Encoding {
+    Utf8,
+    Utf16Le,
+    Utf16Be,
+    ///ISO-8859-1, every byte is the char with the same value. Latin-1 bytes are never invalid.
+    Latin1,
+    ///The encoding of the byte order mark if there is one, otherwise UTF-8 if the bytes are valid UTF-8 and Latin-1 if they aren't.
+    Detect,
+}
+
+///What happens to byte sequences that are invalid in the encoding.
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Default)]
+pub enum DecodeMode {
+    ///They are replaced with `U+FFFD`.
+    #[default]
+    Lossy,
+    ///They are a [SymbolizeError::InvalidEncoding].
+    Strict,
+}
+
+///Decodes the bytes into a string. More information can be found in the [encoding](self) module.
+pub fn decode(
+    bytes: &[u8],
+    encoding: Encoding,
+    mode: DecodeMode,
+) -> Result<String, SymbolizeError> {
+    let encoding = match encoding {
+        Encoding::Utf8 => UTF_8,
+        Encoding::Utf16Le => UTF_16LE,
+        Encoding::Utf16Be => UTF_16BE,
+        Encoding::Latin1 => return Ok(string_from_bytes(bytes)),
+        Encoding::Detect => match encoding_rs::Encoding::for_bom(bytes) {
+            Some((encoding, _)) => encoding,
+            None if std::str::from_utf8(bytes).is_ok() => UTF_8,
+            None => return Ok(string_from_bytes(bytes)),
+        },
+    };
+    let bom_length = match encoding_rs::Encoding::for_bom(bytes) {
+        Some((bom_encoding, length)) if bom_encoding == encoding => length,
+        _ => 0,
+    };
+    let bytes = &bytes[bom_length..];
+
+    let mut decoder = encoding.new_decoder_without_bom_handling();
+    let mut decoded = String::with_capacity(bytes.len());
+    let mut read = 0;
+    loop {
+        match mode {
+            DecodeMode::Lossy => {
+                let (result, read_now, _) =
+                    decoder.decode_to_string(&bytes[read..], &mut decoded, true);
+                read += read_now;
+                match result {
+                    CoderResult::InputEmpty => return Ok(decoded),
+                    CoderResult::OutputFull => decoded.reserve(bytes.len() - read + 4),
+                }
+            }
+            DecodeMode::Strict => {
+                let (result, read_now) = decoder.decode_to_string_without_replacement(
+                    &bytes[read..],
+                    &mut decoded,
+                    true,
+                );
+                read += read_now;
+                match result {
+                    DecoderResult::InputEmpty => return Ok(decoded),
+                    DecoderResult::OutputFull => decoded.reserve(bytes.len() - read + 4),
+                    DecoderResult::Malformed(invalid, after) => {
+                        //the decoder has already read the invalid bytes and the bytes after them
+                        return Err(SymbolizeError::InvalidEncoding {
+                            encoding: encoding.name().to_string(),
+                            position: bom_length + read - invalid as usize - after as usize,
+                        });
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl BackusNaurForm<'_> {
+    ///Decodes the bytes with the encoding and symbolizes the string, invalid byte sequences are replaced with `U+FFFD`.
+    ///More information can be found in the [encoding](self) module.
+    ///
+    /// # Panics
+    /// Panics in the same cases as [symbolize_string](BackusNaurForm::symbolize_string).
+    pub fn symbolize_bytes_with_encoding(&self, bytes: &[u8], encoding: Encoding) -> Vec<Token> {
+        self.try_symbolize_bytes_with_encoding(bytes, encoding, DecodeMode::Lossy)
+            .unwrap_or_else(|error| panic!("failed to symbolize the bytes: {error}"))
+    }
+
+    ///The same as [symbolize_bytes_with_encoding](BackusNaurForm::symbolize_bytes_with_encoding),
+    ///but returns a [SymbolizeError] instead of panicking and invalid byte sequences are handled as the mode says.
+    pub fn try_symbolize_bytes_with_encoding(
+        &self,
+        bytes: &[u8],
+        encoding: Encoding,
+        mode: DecodeMode,
+    ) -> Result<Vec<Token>, SymbolizeError> {
+        self.try_symbolize_string(&decode(bytes, encoding, mode)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode() {
+        let utf16le = [0xFF, 0xFE, b'a', 0, 0xE4, 0];
+        assert_eq!(
+            decode(&utf16le, Encoding::Utf16Le, DecodeMode::Strict),
+            Ok("aä".to_string())
+        );
+        assert_eq!(
+            decode(&utf16le, Encoding::Detect, DecodeMode::Strict),
+            Ok("aä".to_string())
+        );
+        assert_eq!(
+            decode(&[0, b'a'], Encoding::Utf16Be, DecodeMode::Strict),
+            Ok("a".to_string())
+        );
+        assert_eq!(
+            decode(&[b'a', 0xE4], Encoding::Latin1, DecodeMode::Strict),
+            Ok("aä".to_string())
+        );
+        assert_eq!(
+            decode(&[b'a', 0xE4], Encoding::Detect, DecodeMode::Strict),
+            Ok("aä".to_string())
+        );
+        assert_eq!(
+            decode("\u{FEFF}aä".as_bytes(), Encoding::Utf8, DecodeMode::Strict),
+            Ok("aä".to_string())
+        );
+
+        assert_eq!(
+            decode(&[b'a', 0xE4, b'b'], Encoding::Utf8, DecodeMode::Lossy),
+            Ok("a\u{FFFD}b".to_string())
+        );
+        assert_eq!(
+            decode(
+                &[0xEF, 0xBB, 0xBF, b'a', 0xE4, b'b'],
+                Encoding::Utf8,
+                DecodeMode::Strict
+            ),
+            Err(SymbolizeError::InvalidEncoding {
+                encoding: "UTF-8".to_string(),
+                position: 4
+            })
+        );
+    }
+
+    #[test]
+    fn test_symbolize_bytes_with_encoding() {
+        let bnf = crate::backus_naur_form!(
+            priority 1 => r#"<letter> ::= "ä" | "ö""#
+            priority 0 => r#"<word> ::= <letter> | <word> <word>"#
+        );
+        let utf16be = [0, 0xE4, 0, 0xF6];
+        assert_eq!(
+            bnf.symbolize_bytes_with_encoding(&utf16be, Encoding::Utf16Be),
+            bnf.symbolize_string("äö")
+        );
+        assert_eq!(
+            bnf.try_symbolize_bytes_with_encoding(
+                &[0, 0xE4, 0],
+                Encoding::Utf16Be,
+                DecodeMode::Strict
+            ),
+            Err(SymbolizeError::InvalidEncoding {
+                encoding: "UTF-16BE".to_string(),
+                position: 2
+            })
+        );
+    }
+}
@@ -0,0 +1,371 @@
+use std::{error::Error, fmt::Display, ops::Range};
+
+use super::{lint::UnknownSymbol, symbol::Symbol, token::TokenIndex};
+
+///Errors that can occur while symbolizing a [String] with a [BackusNaurForm](super::BackusNaurForm).
+///Positions are character indexes into the symbolized [String].
+#[derive(PartialEq, Debug, Clone)]
+pub enum SymbolizeError {
+    ///A closing bracket was found that doesn't belong to the innermost open bracket.
+    ///For example the `]` in `(1]`.
+    MismatchedBracket {
+        expected: String,
+        found: String,
+        position: usize,
+    },
+    ///A closing bracket was found while no bracket was open.
+    ///For example the `)` in `1)`.
+    UnexpectedClosingBracket { bracket: String, position: usize },
+    ///A opening bracket was never closed.
+    ///The position is the position of the opening bracket.
+    UnclosedBracket { bracket: String, position: usize },
+    ///The rules would be applied forever because they keep turning the same tokens into each other
+    ///(for example `<a> ::= <b>` and `<b> ::= <a>`). The rule is the first one that was applied in the repeating part.
+    NonTermination { rule: String },
+    ///The string is longer than the limit set with [set_max_input_length](super::BackusNaurForm::set_max_input_length).
+    ///The length is the amount of chars of the string.
+    InputTooLong { length: usize, max: usize },
+    ///More tokens would be created than the limit set with [set_max_token_count](super::BackusNaurForm::set_max_token_count) allows.
+    TooManyTokens { max: usize },
+    ///The tokens would take more memory (in bytes) than the limit set with [set_max_memory](super::BackusNaurForm::set_max_memory) allows.
+    MemoryLimitExceeded { max: usize },
+    ///The rule of the symbol would be applied more often than the limit set with
+    ///[set_max_reductions](super::BackusNaurForm::set_max_reductions) allows.
+    TooManyReductions { symbol: String, max: usize },
+    ///A [TerminalSource](super::lexer::TerminalSource) couldn't turn a part of the input into a token.
+    ///The span is the byte range of that part in the input of the lexer.
+    Lex { span: Range<usize> },
+    ///Terminals were left at the top level by [symbolize_strict](super::BackusNaurForm::symbolize_strict).
+    ///The span is the first run of them.
+    Unreduced { span: Range<usize> },
+    ///A choice uses a non terminal symbol that has no rule and
+    ///[set_unknown_symbol_severity](super::BackusNaurForm::set_unknown_symbol_severity) is [Severity::Error](super::lint::Severity::Error).
+    UnknownSymbol(UnknownSymbol),
+    ///The bytes passed to [try_symbolize_bytes_with_encoding](super::BackusNaurForm::try_symbolize_bytes_with_encoding)
+    ///aren't valid in the encoding and the [DecodeMode](super::encoding::DecodeMode) is strict.
+    ///The position is the byte index of the first invalid byte sequence.
+    InvalidEncoding { encoding: String, position: usize },
+    ///A choice of the rule would be expanded into more than 4096 versions before symbolizing
+    ///because of its nullable symbols (for example a choice with 13 symbols that can be empty)
+    ///and the gaps between its symbols that may contain the [skip symbol](super::BackusNaurForm::set_skip_symbol).
+    TooManyChoiceVersions { rule: String, max: usize },
+}
+
+impl Display for SymbolizeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SymbolizeError::MismatchedBracket {
+                expected,
+                found,
+                position,
+            } => write!(
+                f,
+                "expected the closing bracket \"{expected}\" but found \"{found}\" at position {position}"
+            ),
+            SymbolizeError::UnexpectedClosingBracket { bracket, position } => write!(
+                f,
+                "the closing bracket \"{bracket}\" at position {position} has no opening bracket"
+            ),
+            SymbolizeError::UnclosedBracket { bracket, position } => write!(
+                f,
+                "the opening bracket \"{bracket}\" at position {position} is never closed"
+            ),
+            SymbolizeError::NonTermination { rule } => write!(
+                f,
+                "the rule <{rule}> is applied over and over again without ever finishing"
+            ),
+            SymbolizeError::InputTooLong { length, max } => write!(
+                f,
+                "the input is {length} chars long but at most {max} chars are allowed"
+            ),
+            SymbolizeError::TooManyTokens { max } => {
+                write!(f, "symbolizing the input creates more than {max} tokens")
+            }
+            SymbolizeError::MemoryLimitExceeded { max } => {
+                write!(f, "symbolizing the input takes more than {max} bytes")
+            }
+            SymbolizeError::TooManyReductions { symbol, max } => write!(
+                f,
+                "the rule <{symbol}> is applied more than {max} times while symbolizing the input"
+            ),
+            SymbolizeError::Lex { span } => {
+                write!(f, "the input at {span:?} could not be turned into a token")
+            }
+            SymbolizeError::Unreduced { span } => {
+                write!(f, "no rule matched the input at {span:?}")
+            }
+            SymbolizeError::UnknownSymbol(unknown) => write!(f, "{unknown}"),
+            SymbolizeError::InvalidEncoding { encoding, position } => write!(
+                f,
+                "the input is not valid {encoding}, the bytes at position {position} are invalid"
+            ),
+            SymbolizeError::TooManyChoiceVersions { rule, max } => write!(
+                f,
+                "a choice of the rule <{rule}> would be expanded into more than {max} versions"
+            ),
+        }
+    }
+}
+
+impl Error for SymbolizeError {}
+
+///Errors that can occur while parsing a [String] into a single root token with [BackusNaurForm::parse](super::BackusNaurForm::parse).
+///Spans are character indexes into the parsed [String].
+#[derive(PartialEq, Debug, Clone)]
+pub enum ParseError {
+    ///The [String] could not be symbolized.
+    Symbolize(SymbolizeError),
+    ///The [String] is empty, so there is nothing to parse.
+    EmptyInput,
+    ///No non terminal token starts at the beginning of the [String].
+    ///The span is the part of the [String] before the first non terminal token (or the whole [String] if there is none).
+    NoRootToken { span: Range<usize> },
+    ///The beginning of the [String] is a valid root token, but it isn't followed by the end of the [String].
+    ///The span is the trailing part that doesn't belong to the root token.
+    TrailingInput { root: String, span: Range<usize> },
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseError::Symbolize(error) => write!(f, "{error}"),
+            ParseError::EmptyInput => write!(f, "the input is empty"),
+            ParseError::NoRootToken { span } => write!(
+                f,
+                "the input from position {} to {} doesn't match any rule",
+                span.start, span.end
+            ),
+            ParseError::TrailingInput { root, span } => write!(
+                f,
+                "expected the end of the input after <{root}> but found more input from position {} to {}",
+                span.start, span.end
+            ),
+        }
+    }
+}
+
+impl Error for ParseError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ParseError::Symbolize(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<SymbolizeError> for ParseError {
+    fn from(error: SymbolizeError) -> Self {
+        ParseError::Symbolize(error)
+    }
+}
+
+///Errors that can occur while getting the root token of a [String] with [BackusNaurForm::root_token](super::BackusNaurForm::root_token).
+#[derive(PartialEq, Debug, Clone)]
+pub enum RootError {
+    ///The [String] could not be symbolized.
+    Symbolize(SymbolizeError),
+    ///The [String] wasn't symbolized into exactly one token.
+    ///Contains the symbol and the span (character indexes into the [String]) of every top level token, which is empty for a empty [String].
+    NoSingleRoot { tokens: Vec<(Symbol, Range<usize>)> },
+}
+
+impl Display for RootError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RootError::Symbolize(error) => write!(f, "{error}"),
+            RootError::NoSingleRoot { tokens } => {
+                write!(
+                    f,
+                    "expected a single root token but the input has {} top level tokens",
+                    tokens.len()
+                )?;
+                for (index, (symbol, span)) in tokens.iter().enumerate() {
+                    let separator = if index == 0 { ": " } else { ", " };
+                    match symbol {
+                        Symbol::Terminal(terminal) => write!(f, "{separator}\"{terminal}\"")?,
+                        Symbol::NonTerminal(non_terminal) => {
+                            write!(f, "{separator}<{non_terminal}>")?
+                        }
+                    }
+                    write!(f, " at {span:?}")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl Error for RootError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            RootError::Symbolize(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<SymbolizeError> for RootError {
+    fn from(error: SymbolizeError) -> Self {
+        RootError::Symbolize(error)
+    }
+}
+
+///Errors that can occur while loading a grammar document.
+///Lines start at 1.
+#[derive(PartialEq, Debug, Clone)]
+pub enum GrammarError {
+    ///A document could not be read.
+    Io { path: String, message: String },
+    ///A document that is included with `%include` could not be found.
+    IncludeNotFound { path: String },
+    ///A line of a document is not a valid rule, directive or comment.
+    Syntax {
+        document: String,
+        line: usize,
+        message: String,
+    },
+    ///A choice of a "array" rule never matches because it isn't written as `<rule> <rule>`,
+    ///see [check_recursion](super::BackusNaurForm::check_recursion).
+    ///The choice and the suggestion are written like in a rule, for example `<number> <digit>` and `<number> <number>`.
+    UnsupportedRecursion {
+        rule: String,
+        choice: String,
+        suggestion: String,
+    },
+}
+
+impl Display for GrammarError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GrammarError::Io { path, message } => write!(f, "failed to read \"{path}\": {message}"),
+            GrammarError::IncludeNotFound { path } => {
+                write!(f, "the included grammar \"{path}\" could not be found")
+            }
+            GrammarError::Syntax {
+                document,
+                line,
+                message,
+            } => write!(f, "{document}:{line}: {message}"),
+            GrammarError::UnsupportedRecursion {
+                rule,
+                choice,
+                suggestion,
+            } => write!(
+                f,
+                "the choice {choice} of <{rule}> never matches because its symbols are turned into <{rule}> on their own first, \
+write it as {suggestion} instead"
+            ),
+        }
+    }
+}
+
+impl Error for GrammarError {}
+
+///Errors that can occur while saving or loading a [CompiledGrammar](super::compiled::CompiledGrammar).
+#[derive(PartialEq, Debug, Clone)]
+pub enum CompiledGrammarError {
+    ///A file could not be read or written.
+    Io { path: String, message: String },
+    ///The file is not a compiled grammar or it is damaged.
+    Corrupt { message: String },
+    ///The file was written with a different version of the format.
+    UnsupportedVersion { found: u32, supported: u32 },
+    ///The grammar document could not be loaded.
+    Grammar(GrammarError),
+}
+
+impl Display for CompiledGrammarError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CompiledGrammarError::Io { path, message } => {
+                write!(f, "failed to access \"{path}\": {message}")
+            }
+            CompiledGrammarError::Corrupt { message } => {
+                write!(f, "the compiled grammar is invalid: {message}")
+            }
+            CompiledGrammarError::UnsupportedVersion { found, supported } => write!(
+                f,
+                "the compiled grammar has version {found} but only version {supported} is supported"
+            ),
+            CompiledGrammarError::Grammar(error) => write!(f, "{error}"),
+        }
+    }
+}
+
+impl Error for CompiledGrammarError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            CompiledGrammarError::Grammar(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<GrammarError> for CompiledGrammarError {
+    fn from(error: GrammarError) -> Self {
+        CompiledGrammarError::Grammar(error)
+    }
+}
+
+///Errors that can occur while validating a example corpus with [BackusNaurForm::validate_corpus](super::BackusNaurForm::validate_corpus).
+#[derive(PartialEq, Debug, Clone)]
+pub enum CorpusError {
+    ///A directory or a example could not be read.
+    Io { path: String, message: String },
+}
+
+impl Display for CorpusError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CorpusError::Io { path, message } => write!(f, "failed to read \"{path}\": {message}"),
+        }
+    }
+}
+
+impl Error for CorpusError {}
+
+///Errors that can occur while rewriting a [String] with [BackusNaurForm::try_rewrite](super::BackusNaurForm::try_rewrite).
+#[derive(PartialEq, Debug, Clone)]
+pub enum RewriteError {
+    ///The [String] could not be symbolized.
+    Symbolize(SymbolizeError),
+    ///No token exists at the [TokenIndex].
+    InvalidIndex { index: TokenIndex },
+    ///Both edits replace the same part of the [String], for example because one token contains the other.
+    OverlappingEdits {
+        first: TokenIndex,
+        second: TokenIndex,
+    },
+}
+
+impl Display for RewriteError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RewriteError::Symbolize(error) => write!(f, "{error}"),
+            RewriteError::InvalidIndex { index } => {
+                write!(f, "no token exists at the index {:?}", index.indexes())
+            }
+            RewriteError::OverlappingEdits { first, second } => write!(
+                f,
+                "the edits of the tokens at {:?} and {:?} overlap",
+                first.indexes(),
+                second.indexes()
+            ),
+        }
+    }
+}
+
+impl Error for RewriteError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            RewriteError::Symbolize(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<SymbolizeError> for RewriteError {
+    fn from(error: SymbolizeError) -> Self {
+        RewriteError::Symbolize(error)
+    }
+}
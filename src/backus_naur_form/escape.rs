@@ -0,0 +1,116 @@
+//! Escaping for compile targets that build strings in another language, so every compile function doesn't hand-roll its own escaping:
+//! ```rust, ignore
+//! let mut bnf = grammar();
+//! bnf.set_escaper(Some(Escaper::html()));
+//! bnf.add_compile_function("text", &|text, bnf| format!("<p>{}</p>", bnf.escape(&text.get_terminals())));
+//! ```
+//! The escaper set with [set_escaper](BackusNaurForm::set_escaper) is the default of the target:
+//! [compile_tokens](BackusNaurForm::compile_tokens) escapes the text it copies into the output on its own
+//! (terminals and tokens without a compile function), compile functions escape the text they insert with [escape](BackusNaurForm::escape).
+//! The text compile functions return is never escaped again, since it is already in the language of the target.
+
+use super::BackusNaurForm;
+
+///Replaces chars with escape sequences, for example `<` with `&lt;` for HTML.
+#[derive(PartialEq, Eq, Debug, Clone, Default)]
+pub struct Escaper {
+    //the char and the text that replaces it
+    replacements: Vec<(char, String)>,
+}
+
+impl Escaper {
+    ///Creates a escaper that doesn't replace anything. Replacements are added with [with](Escaper::with).
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    ///Escapes the chars that have a meaning in HTML (and in XML): `&`, `<`, `>`, `"` and `'`.
+    pub fn html() -> Self {
+        Self::new()
+            .with('&', "&amp;")
+            .with('<', "&lt;")
+            .with('>', "&gt;")
+            .with('"', "&quot;")
+            .with('\'', "&#39;")
+    }
+
+    ///Escapes text for a SQL string literal in single quotes by doubling the quotes.
+    ///This is only meant for literals, values should still be passed as parameters where the database allows it.
+    pub fn sql() -> Self {
+        Self::new().with('\'', "''")
+    }
+
+    ///Adds a replacement. If the char already has one, it is replaced.
+    pub fn with(mut self, char: char, replacement: &str) -> Self {
+        self.replacements.retain(|(replaced, _)| *replaced != char);
+        self.replacements.push((char, replacement.to_string()));
+        self
+    }
+
+    ///Returns the text with every char that has a replacement replaced.
+    pub fn escape(&self, text: &str) -> String {
+        let mut escaped = String::with_capacity(text.len());
+        for char in text.chars() {
+            match self
+                .replacements
+                .iter()
+                .find(|(replaced, _)| *replaced == char)
+            {
+                Some((_, replacement)) => escaped.push_str(replacement),
+                None => escaped.push(char),
+            }
+        }
+        escaped
+    }
+}
+
+impl BackusNaurForm<'_> {
+    ///Sets the escaper of the compile target, None removes it (which is the default).
+    ///More information can be found in the [escape](self) module.
+    pub fn set_escaper(&mut self, escaper: Option<Escaper>) {
+        self.clear_compile_cache();
+        self.escaper = escaper;
+    }
+
+    ///Escapes the text with the escaper of the compile target, or returns it as it is if there is none.
+    pub fn escape(&self, text: &str) -> String {
+        match &self.escaper {
+            Some(escaper) => escaper.escape(text),
+            None => text.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_escaper() {
+        assert_eq!(
+            Escaper::html().escape(r#"<a href="x">Tom & 'Jerry'</a>"#),
+            "&lt;a href=&quot;x&quot;&gt;Tom &amp; &#39;Jerry&#39;&lt;/a&gt;"
+        );
+        assert_eq!(Escaper::sql().escape("it's"), "it''s");
+        assert_eq!(
+            Escaper::new()
+                .with('\n', "\\n")
+                .with('\n', "\\N")
+                .escape("a\nb"),
+            "a\\Nb"
+        );
+
+        let mut bnf = crate::backus_naur_form!(
+            priority 1 => r#"<word> ::= "a" | "b""#
+            priority 0 => r#"<bold> ::= "*" <word> "*""# => |bold, bnf| {
+                format!("<b>{}</b>", bnf.compile_tokens(&bold.get_child_tokens()[1..2]))
+            }
+        );
+        assert_eq!(bnf.compile_string("*a*<b"), "<b>a</b><b");
+
+        //only the text that isn't compiled by a function is escaped
+        bnf.set_escaper(Some(Escaper::html()));
+        assert_eq!(bnf.escape("<"), "&lt;");
+        assert_eq!(bnf.compile_string("*a*<b"), "<b>a</b>&lt;b");
+    }
+}
@@ -0,0 +1,275 @@
+//! Contains [LayoutRule], which describes how the tokens of a symbol are laid out by [BackusNaurForm::format_string].
+//! The formatter re-emits the input in a canonical style, so it can be used to build a formatter for a language:
+//! ```rust, ignore
+//! bnf.add_layout_rule("assignment", LayoutRule { separator: " ".to_string(), ..Default::default() });
+//! bnf.add_layout_rule("statement", LayoutRule { line_break: true, ..Default::default() });
+//! bnf.add_layout_rule("block", LayoutRule { indent: true, ..Default::default() });
+//! bnf.format_string("{a=b;c=a;}")
+//! //{
+//! //    a = b;
+//! //    c = a;
+//! //}
+//! ```
+//! The grammar doesn't need rules for the whitespace of the input. The input is symbolized with the whitespace as tokens first,
+//! then the whitespace next to a matched token (a token of a non terminal symbol) is dropped and the rest is symbolized again,
+//! so `c =a` is still a assignment. Whitespace chars that are part of a terminal of the grammar are never dropped.
+//! Dropped whitespace between two matched tokens that no rule joined is written back, so `a b` doesn't become `ab`.
+//! Symbols without a [LayoutRule] are written without any whitespace.
+
+use std::collections::HashMap;
+
+use super::{
+    canonical_name,
+    symbol::Symbol,
+    token::{non_terminal_token::NonTerminalToken, Token},
+    BackusNaurForm,
+};
+
+///The string that is written once for every level of indentation.
+pub const INDENTATION: &str = "    ";
+
+///Describes how the children of the tokens of a symbol are laid out. Added with [BackusNaurForm::add_layout_rule].
+#[derive(PartialEq, Eq, Clone, Default, Debug)]
+pub struct LayoutRule {
+    ///The string that is written between two children (for example `" "`). It is left out at the start of a line.
+    pub separator: String,
+    ///Puts the token on its own line. The token starts a new line and whatever follows it starts a new line too.
+    pub line_break: bool,
+    ///Indents the lines that start inside of a non terminal child by one more level than the token.
+    ///The lines that start with a terminal child (like a closing `}`) aren't indented.
+    pub indent: bool,
+}
+
+impl BackusNaurForm<'_> {
+    ///Adds the [LayoutRule] of a symbol, replacing the previous one. More information can be found in the [format](self) module.
+    pub fn add_layout_rule(&mut self, non_terminal_symbol: &str, layout_rule: LayoutRule) {
+        self.layout_rules
+            .insert(canonical_name(non_terminal_symbol), layout_rule);
+    }
+
+    ///Symbolizes the string and writes it back in the style of the [LayoutRule]s.
+    ///Chars that no rule matched are written as they are. More information about the whitespace can be found in the [format](self) module.
+    ///
+    /// # Panics
+    /// Panics in the same cases as [symbolize_string](BackusNaurForm::symbolize_string).
+    /// Use [try_symbolize_string](BackusNaurForm::try_symbolize_string) to check the string first.
+    pub fn format_string(&self, string: &str) -> String {
+        let terminals = self
+            .rules
+            .iter()
+            .flat_map(|(non_terminal_symbol, _)| non_terminal_symbol.get_rule().iter().flatten())
+            .filter_map(|symbol| match symbol {
+                Symbol::Terminal(terminal) => Some(terminal.as_str()),
+                Symbol::NonTerminal(_) => None,
+            })
+            .collect::<String>();
+        let is_whitespace = |token: &Token| match token {
+            Token::Terminal(terminal) => terminal
+                .get_terminals()
+                .chars()
+                .all(|char| char.is_whitespace() && !terminals.contains(char)),
+            Token::NonTerminalToken(_) => false,
+        };
+        let is_matched = |token: Option<&Token>| matches!(token, Some(Token::NonTerminalToken(_)));
+
+        let mut tokens = self.symbolize_string(string);
+        //the dropped whitespace with the amount of chars of the tokens in front of it, sorted by that amount
+        let mut dropped: Vec<(usize, String)> = vec![];
+        //symbolizing without the whitespace can join tokens, which then may have whitespace next to them too
+        loop {
+            let mut kept: Vec<Token> = vec![];
+            //the whitespace that is dropped now with the amount of chars of the tokens in front of it, whitespace included
+            let mut dropping: Vec<(usize, String)> = vec![];
+            let mut length = 0;
+            let mut index = 0;
+            while index < tokens.len() {
+                let end = (index..tokens.len())
+                    .find(|end| !is_whitespace(&tokens[*end]))
+                    .unwrap_or(tokens.len())
+                    .max(index + 1);
+                let run = &tokens[index..end];
+                if is_whitespace(&run[0])
+                    && (is_matched(kept.last()) || is_matched(tokens.get(end)))
+                {
+                    dropping.push((length, run.iter().map(Token::get_terminals).collect()));
+                } else {
+                    kept.extend_from_slice(run);
+                }
+                length += chars_count(run);
+                index = end;
+            }
+            if dropping.is_empty() {
+                break;
+            }
+
+            //the amount of dropped chars in front of a amount of chars
+            let dropped_before = |at: usize| {
+                dropping
+                    .iter()
+                    .filter(|(start, _)| *start < at)
+                    .map(|(_, whitespace)| whitespace.chars().count())
+                    .sum::<usize>()
+            };
+            for (at, _) in &mut dropped {
+                *at -= dropped_before(*at);
+            }
+            for (at, whitespace) in &dropping {
+                dropped.push((at - dropped_before(*at), whitespace.clone()));
+            }
+            dropped.sort_by_key(|(at, _)| *at);
+            tokens = self.symbolize_tokens(kept);
+        }
+
+        //the dropped whitespace is written back if the tokens on both sides of it are matched tokens that no rule joined
+        let mut dropped = dropped.into_iter().peekable();
+        let mut formatter = Formatter::new(self);
+        let mut length = 0;
+        for (position, token) in tokens.iter().enumerate() {
+            //the whitespace inside of the tokens before was joined by a rule, so the layout rules take care of it
+            while dropped.next_if(|(at, _)| *at < length).is_some() {}
+            while let Some((_, whitespace)) = dropped.next_if(|(at, _)| *at == length) {
+                let apart =
+                    position > 0 && is_matched(tokens.get(position - 1)) && is_matched(Some(token));
+                if apart && !formatter.line_break {
+                    formatter.output.push_str(&whitespace);
+                }
+            }
+            formatter.write(token, 0);
+            length += chars_count(std::slice::from_ref(token));
+        }
+        formatter.output
+    }
+
+    ///Writes the token in the style of the [LayoutRule]s. The token is treated as if it was at the uppermost level.
+    pub fn format_token(&self, non_terminal: &NonTerminalToken) -> String {
+        let mut formatter = Formatter::new(self);
+        formatter.write_non_terminal(non_terminal, 0);
+        formatter.output
+    }
+}
+
+//Returns the amount of chars of the terminals of the tokens.
+fn chars_count(tokens: &[Token]) -> usize {
+    tokens
+        .iter()
+        .map(|token| token.get_terminals().chars().count())
+        .sum()
+}
+
+//Writes tokens into the output while keeping track of the line breaks and separators that are still to be written.
+struct Formatter<'b> {
+    layout_rules: &'b HashMap<String, LayoutRule>,
+    output: String,
+    //true if the next terminal has to start a new line
+    line_break: bool,
+    //the separator that is written in front of the next terminal, unless it starts a new line
+    separator: Option<&'b str>,
+}
+
+impl<'b> Formatter<'b> {
+    fn new(bnf: &'b BackusNaurForm) -> Self {
+        Self {
+            layout_rules: &bnf.layout_rules,
+            output: String::new(),
+            line_break: false,
+            separator: None,
+        }
+    }
+
+    fn write(&mut self, token: &Token, level: usize) {
+        match token {
+            Token::Terminal(terminal) => self.write_terminal(&terminal.to_string(), level),
+            Token::NonTerminalToken(non_terminal) => self.write_non_terminal(non_terminal, level),
+        }
+    }
+
+    fn write_non_terminal(&mut self, non_terminal: &NonTerminalToken, level: usize) {
+        let layout_rules = self.layout_rules;
+        let layout_rule = layout_rules.get(&non_terminal.non_terminal_symbol);
+        let line_break = layout_rule.is_some_and(|layout_rule| layout_rule.line_break);
+        let child_level =
+            level + layout_rule.is_some_and(|layout_rule| layout_rule.indent) as usize;
+
+        self.line_break |= line_break;
+        for (index, child) in non_terminal.get_child_tokens().iter().enumerate() {
+            if index > 0 {
+                self.separator = layout_rule.map(|layout_rule| layout_rule.separator.as_str());
+            }
+            match child {
+                Token::Terminal(_) => self.write(child, level),
+                Token::NonTerminalToken(_) => self.write(child, child_level),
+            }
+        }
+        self.line_break |= line_break;
+    }
+
+    fn write_terminal(&mut self, terminal: &str, level: usize) {
+        if self.line_break {
+            if !self.output.is_empty() {
+                self.output.push('\n');
+            }
+            self.output.push_str(&INDENTATION.repeat(level));
+        } else if let Some(separator) = self.separator {
+            self.output.push_str(separator);
+        }
+        self.line_break = false;
+        self.separator = None;
+        self.output.push_str(terminal);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_string() {
+        let mut bnf = crate::backus_naur_form!(
+            priority 3 => r#"<ident> ::= "a" | "b" | "c""#
+            priority 2 => r#"<assignment> ::= <ident> "=" <ident>"#
+            priority 1 => r#"<statement> ::= <assignment> ";" | <block>"#
+            priority 1 => r#"<statements> ::= <statement> | <statements> <statements>"#
+            priority 0 => r#"<block> ::= "{" <statements> "}""#
+        );
+        bnf.add_layout_rule(
+            "assignment",
+            LayoutRule {
+                separator: " ".to_string(),
+                ..Default::default()
+            },
+        );
+        bnf.add_layout_rule(
+            "statement",
+            LayoutRule {
+                line_break: true,
+                ..Default::default()
+            },
+        );
+        bnf.add_layout_rule(
+            "block",
+            LayoutRule {
+                indent: true,
+                ..Default::default()
+            },
+        );
+
+        assert_eq!(
+            bnf.format_string("{a=b;  c =a;\n}"),
+            "{\n    a = b;\n    c = a;\n}"
+        );
+        //the formatted string is formatted the same way again
+        assert_eq!(
+            bnf.format_string("{\n    a = b;\n    c = a;\n}"),
+            "{\n    a = b;\n    c = a;\n}"
+        );
+        assert_eq!(
+            bnf.format_string("{a=b;{c=a;}}"),
+            "{\n    a = b;\n    {\n        c = a;\n    }\n}"
+        );
+        //chars that no rule matched are kept
+        assert_eq!(bnf.format_string("a = b ?"), "a = b?");
+        //matched tokens that no rule joined keep the whitespace between them
+        assert_eq!(bnf.format_string(" a  b"), "a  b");
+        assert_eq!(bnf.format_string("a=b; c"), "a = b;\nc");
+    }
+}
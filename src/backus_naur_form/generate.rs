@@ -0,0 +1,113 @@
+//! Generates strings out of the rules of a [BackusNaurForm], for example to show examples of a symbol in its documentation:
+//! ```rust, ignore
+//! //with <number> ::= <digit> | <number> <number> and <digit> ::= "1" | "2"
+//! bnf.shortest_example("number")
+//! //Some("1")
+//! bnf.examples("number", 3)
+//! //["1", "11"]
+//! ```
+//! The strings are derived from the rules, without looking at the priorities.
+//! So a example is a string the rules describe, but the priorities may still turn it into other tokens when it is symbolized.
+
+use std::collections::HashMap;
+
+use super::{symbol::Symbol, BackusNaurForm, Choice};
+
+impl BackusNaurForm<'_> {
+    ///Returns the shortest string (in chars) that the rules of the non terminal symbol with the name can be derived into.
+    ///Returns None if there is no rule with the name or if its rules never end (for example `<a> ::= "a" <a>`).
+    pub fn shortest_example(&self, name: &str) -> Option<String> {
+        self.shortest_examples().remove(name)
+    }
+
+    ///Returns up to max examples for the non terminal symbol with the name, one for every choice of its rules.
+    ///Every symbol of a choice is replaced by its [shortest example](BackusNaurForm::shortest_example).
+    ///Choices that contain a symbol without a example are skipped and every example is only returned once.
+    pub fn examples(&self, name: &str, max: usize) -> Vec<String> {
+        let shortest = self.shortest_examples();
+        let mut examples: Vec<String> = vec![];
+        for (non_terminal_symbol, _) in &self.rules {
+            if non_terminal_symbol.get_name() != name {
+                continue;
+            }
+            for choice in non_terminal_symbol.get_rule() {
+                if examples.len() == max {
+                    return examples;
+                }
+                if let Some(example) = example_of_choice(choice, &shortest) {
+                    if !examples.contains(&example) {
+                        examples.push(example);
+                    }
+                }
+            }
+        }
+        examples
+    }
+
+    //Returns the shortest example of every non terminal symbol that has one.
+    //The examples are improved until none of them gets shorter anymore.
+    pub(super) fn shortest_examples(&self) -> HashMap<&str, String> {
+        let mut shortest: HashMap<&str, String> = HashMap::new();
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for (non_terminal_symbol, _) in &self.rules {
+                for choice in non_terminal_symbol.get_rule() {
+                    let Some(example) = example_of_choice(choice, &shortest) else {
+                        continue;
+                    };
+                    let name = non_terminal_symbol.get_name();
+                    let is_shorter = shortest
+                        .get(name)
+                        .is_none_or(|current| example.chars().count() < current.chars().count());
+                    if is_shorter {
+                        shortest.insert(name, example);
+                        changed = true;
+                    }
+                }
+            }
+        }
+        shortest
+    }
+}
+
+//Joins the terminals of the choice and the examples of its non terminal symbols.
+//Returns None if a non terminal symbol has no example.
+pub(super) fn example_of_choice(
+    choice: &Choice,
+    examples: &HashMap<&str, String>,
+) -> Option<String> {
+    choice
+        .iter()
+        .map(|symbol| match symbol {
+            Symbol::Terminal(terminal) => Some(terminal.as_str()),
+            Symbol::NonTerminal(name) => {
+                examples.get(name.as_str()).map(|example| example.as_str())
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::backus_naur_form;
+    use crate::backus_naur_form::test_helpers::DIGIT_RULE;
+
+    #[test]
+    fn test_examples() {
+        let bnf = backus_naur_form!(
+            priority 1 => DIGIT_RULE
+            priority 0 => r#"<number> ::= <number> <number> | <digit>"#
+            priority 0 => r#"<sum> ::= <number> "+" <number> | <sum> "+" <sum>"#
+            priority 0 => r#"<endless> ::= "a" <endless>"#
+        );
+
+        assert_eq!(bnf.shortest_example("sum").as_deref(), Some("1+1"));
+        assert_eq!(bnf.shortest_example("endless"), None);
+        assert_eq!(bnf.shortest_example("unknown"), None);
+        assert_eq!(bnf.examples("digit", 5), vec!["1", "2"]);
+        assert_eq!(bnf.examples("digit", 1), vec!["1"]);
+        assert_eq!(bnf.examples("number", 5), vec!["11", "1"]);
+        assert_eq!(bnf.examples("endless", 5), Vec::<String>::new());
+    }
+}
@@ -0,0 +1,422 @@
+//! Contains the GLR parser, which finds every derivation of a input and returns them as a [ParseForest].
+//! It is meant for ambiguous grammars: [BackusNaurForm::symbolize_string] silently returns whichever AST
+//! the rules happen to make first, while the GLR parser returns all of them.
+//! ```rust, ignore
+//! let forest = bnf.parse_glr("sum", "1+2+3").unwrap();
+//! assert_eq!(forest.derivation_count(), Some(2));
+//! for ast in forest.derivations() {
+//!     ...
+//! }
+//! ```
+//! The parser builds a LR(0) automaton from the rules and follows every action of a state in parallel
+//! using a graph structured stack (the algorithm of tomita).
+//! Like in the [cnf](super::cnf) module, terminals are matched char by char, empty terminals are skipped
+//! and parameterized rules are only used through their uses.
+
+use std::collections::{HashMap, HashSet};
+
+use super::{
+    parse_forest::{ForestBuilder, ForestSymbol, ParseForest},
+    symbol::Symbol,
+    BackusNaurForm,
+};
+
+#[derive(PartialEq, Eq, Hash, Clone, Copy, PartialOrd, Ord, Debug)]
+pub(crate) enum GrammarSymbol {
+    Char(char),
+    NonTerminal(usize),
+}
+
+pub(crate) struct Production {
+    pub lhs: usize,
+    pub rhs: Vec<GrammarSymbol>,
+    //the terminal every char of the rhs comes from and the index of the char in that terminal
+    pub terminals: Vec<Option<(String, usize)>>,
+}
+
+//The rules as productions. The first productions are `<start> ::= <root>`, one for every root.
+pub(crate) struct Grammar {
+    pub names: Vec<String>,
+    pub productions: Vec<Production>,
+}
+
+impl Grammar {
+    //the start symbol is always the first name
+    pub(crate) const START: usize = 0;
+
+    pub(crate) fn new(bnf: &BackusNaurForm, roots: &[&str]) -> Self {
+        let mut names: Vec<String> = vec![];
+        let mut index_of = |name: &str| match names.iter().position(|other| other == name) {
+            Some(index) => index,
+            None => {
+                names.push(name.to_string());
+                names.len() - 1
+            }
+        };
+
+        //the start symbol has no name since it never appears in the forest
+        let start = index_of("");
+        let mut productions = roots
+            .iter()
+            .map(|root| Production {
+                lhs: start,
+                rhs: vec![GrammarSymbol::NonTerminal(index_of(root))],
+                terminals: vec![None],
+            })
+            .collect::<Vec<_>>();
+        for (non_terminal_symbol, _) in bnf
+            .symbolizing_rules()
+            .unwrap_or_else(|error| panic!("{error}"))
+            .iter()
+        {
+            let lhs = index_of(non_terminal_symbol.get_name());
+            for choice in non_terminal_symbol.get_rule() {
+                let (rhs, terminals): (Vec<_>, Vec<_>) = choice
+                    .iter()
+                    .flat_map(|symbol| match symbol {
+                        Symbol::Terminal(terminal) => terminal
+                            .chars()
+                            .enumerate()
+                            .map(|(index, ch)| {
+                                (GrammarSymbol::Char(ch), Some((terminal.to_string(), index)))
+                            })
+                            .collect(),
+                        Symbol::NonTerminal(name) => {
+                            vec![(GrammarSymbol::NonTerminal(index_of(name)), None)]
+                        }
+                    })
+                    .unzip();
+                if !rhs.is_empty() {
+                    productions.push(Production {
+                        lhs,
+                        rhs,
+                        terminals,
+                    });
+                }
+            }
+        }
+
+        Self { names, productions }
+    }
+}
+
+//A LR(0) item: the production and the position of the dot.
+pub(crate) type Item = (usize, usize);
+
+//The LR(0) automaton of a grammar.
+pub(crate) struct Automaton {
+    //the closed item set of every state
+    pub states: Vec<Vec<Item>>,
+    pub gotos: Vec<HashMap<GrammarSymbol, usize>>,
+}
+
+impl Automaton {
+    pub(crate) fn new(grammar: &Grammar) -> Self {
+        let closure = |kernel: Vec<Item>| {
+            let mut items = kernel;
+            let mut index = 0;
+            while index < items.len() {
+                let (production, dot) = items[index];
+                if let Some(GrammarSymbol::NonTerminal(symbol)) =
+                    grammar.productions[production].rhs.get(dot)
+                {
+                    for (other, _) in grammar
+                        .productions
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, other)| other.lhs == *symbol)
+                    {
+                        if !items.contains(&(other, 0)) {
+                            items.push((other, 0));
+                        }
+                    }
+                }
+                index += 1;
+            }
+            items
+        };
+
+        let start_kernel = grammar
+            .productions
+            .iter()
+            .enumerate()
+            .filter(|(_, production)| production.lhs == Grammar::START)
+            .map(|(production, _)| (production, 0))
+            .collect::<Vec<_>>();
+        let mut kernels = vec![start_kernel.to_vec()];
+        let mut states = vec![closure(start_kernel)];
+        let mut gotos = vec![];
+        let mut state = 0;
+        while state < states.len() {
+            //the kernels of the next states grouped by the symbol after the dot
+            let mut next_kernels: Vec<(GrammarSymbol, Vec<Item>)> = vec![];
+            for &(production, dot) in &states[state] {
+                let Some(symbol) = grammar.productions[production].rhs.get(dot) else {
+                    continue;
+                };
+                match next_kernels.iter_mut().find(|(other, _)| other == symbol) {
+                    Some((_, kernel)) => kernel.push((production, dot + 1)),
+                    None => next_kernels.push((*symbol, vec![(production, dot + 1)])),
+                }
+            }
+
+            let mut state_gotos = HashMap::new();
+            for (symbol, mut kernel) in next_kernels {
+                kernel.sort();
+                let next_state = match kernels.iter().position(|other| *other == kernel) {
+                    Some(next_state) => next_state,
+                    None => {
+                        kernels.push(kernel.to_vec());
+                        states.push(closure(kernel));
+                        states.len() - 1
+                    }
+                };
+                state_gotos.insert(symbol, next_state);
+            }
+            gotos.push(state_gotos);
+            state += 1;
+        }
+
+        Self { states, gotos }
+    }
+}
+
+//A node of the graph structured stack.
+struct StackNode {
+    state: usize,
+    level: usize,
+    //the previous nodes and the forest nodes of the symbols between them and this node
+    edges: Vec<(usize, usize)>,
+}
+
+//Returns every path of the length that goes back from the node (starting with the edge if there is one).
+//Every path is the node at its end and the forest nodes along it from left to right.
+fn paths(
+    stack: &[StackNode],
+    node: usize,
+    length: usize,
+    edge: Option<(usize, usize)>,
+) -> Vec<(usize, Vec<usize>)> {
+    if length == 0 {
+        return vec![(node, vec![])];
+    }
+    let edges = match edge {
+        Some(edge) => vec![edge],
+        None => stack[node].edges.to_vec(),
+    };
+    edges
+        .into_iter()
+        .flat_map(|(previous, forest_node)| {
+            paths(stack, previous, length - 1, None).into_iter().map(
+                move |(end, mut forest_nodes)| {
+                    forest_nodes.push(forest_node);
+                    (end, forest_nodes)
+                },
+            )
+        })
+        .collect()
+}
+
+//Runs the GLR parser over the input.
+//Returns the forest and the states of the stack that are left after the last char (none if the input is invalid).
+pub(crate) fn recognize(
+    grammar: &Grammar,
+    automaton: &Automaton,
+    input: &[char],
+) -> (ForestBuilder, Vec<usize>) {
+    let mut forest = ForestBuilder::default();
+    let mut stack = vec![StackNode {
+        state: 0,
+        level: 0,
+        edges: vec![],
+    }];
+    //the nodes of the current level
+    let mut level_nodes = vec![0];
+
+    for level in 0..=input.len() {
+        //reduce until no reduction is possible anymore
+        //new nodes are reduced along all of their edges, nodes that get a new edge only along that edge
+        let mut work = level_nodes
+            .iter()
+            .map(|node| (*node, None))
+            .collect::<Vec<_>>();
+        while let Some((node, edge)) = work.pop() {
+            for &(production, dot) in &automaton.states[stack[node].state] {
+                let Production { lhs, rhs, .. } = &grammar.productions[production];
+                if dot != rhs.len() || *lhs == Grammar::START {
+                    continue;
+                }
+                for (end, children) in paths(&stack, node, rhs.len(), edge) {
+                    let Some(&next_state) =
+                        automaton.gotos[stack[end].state].get(&GrammarSymbol::NonTerminal(*lhs))
+                    else {
+                        continue;
+                    };
+                    let forest_node = forest.node(
+                        ForestSymbol::NonTerminal(grammar.names[*lhs].to_string()),
+                        stack[end].level,
+                        level,
+                    );
+                    forest.add_alternative(forest_node, children);
+
+                    match level_nodes
+                        .iter()
+                        .find(|other| stack[**other].state == next_state)
+                    {
+                        Some(&other) => {
+                            if !stack[other].edges.contains(&(end, forest_node)) {
+                                stack[other].edges.push((end, forest_node));
+                                work.push((other, Some((end, forest_node))));
+                            }
+                        }
+                        None => {
+                            stack.push(StackNode {
+                                state: next_state,
+                                level,
+                                edges: vec![(end, forest_node)],
+                            });
+                            level_nodes.push(stack.len() - 1);
+                            work.push((stack.len() - 1, None));
+                        }
+                    }
+                }
+            }
+        }
+
+        let Some(&ch) = input.get(level) else {
+            break;
+        };
+        //shift the next char
+        let terminal_node = forest.node(ForestSymbol::Terminal(ch), level, level + 1);
+        let mut next_level_nodes: Vec<usize> = vec![];
+        for node in level_nodes {
+            let Some(&next_state) =
+                automaton.gotos[stack[node].state].get(&GrammarSymbol::Char(ch))
+            else {
+                continue;
+            };
+            match next_level_nodes
+                .iter()
+                .find(|other| stack[**other].state == next_state)
+            {
+                Some(&other) => stack[other].edges.push((node, terminal_node)),
+                None => {
+                    stack.push(StackNode {
+                        state: next_state,
+                        level: level + 1,
+                        edges: vec![(node, terminal_node)],
+                    });
+                    next_level_nodes.push(stack.len() - 1);
+                }
+            }
+        }
+        level_nodes = next_level_nodes;
+    }
+    let states = level_nodes.iter().map(|node| stack[*node].state).collect();
+    (forest, states)
+}
+
+impl BackusNaurForm<'_> {
+    ///Parses the input with the GLR parser and returns every derivation of it from the non terminal symbol (without angle brackets).
+    ///Returns None if the input can't be derived from the symbol.
+    ///More information can be found in the [glr](self) module.
+    ///
+    /// # Panics
+    /// Panics if a choice would be expanded into too many versions (see [SymbolizeError::TooManyChoiceVersions](super::SymbolizeError::TooManyChoiceVersions)).
+    pub fn parse_glr(&self, symbol: &str, input: &str) -> Option<ParseForest> {
+        let grammar = Grammar::new(self, &[symbol]);
+        let automaton = Automaton::new(&grammar);
+        let input = input.chars().collect::<Vec<_>>();
+
+        let (forest, _) = recognize(&grammar, &automaton, &input);
+        let root = forest.find(
+            ForestSymbol::NonTerminal(symbol.to_string()),
+            0,
+            input.len(),
+        )?;
+        let hidden_symbols = grammar
+            .names
+            .iter()
+            .filter(|name| self.is_hidden_symbol(name))
+            .cloned()
+            .collect::<HashSet<_>>();
+        let forest = forest.build(root, hidden_symbols);
+        (forest.derivation_count() != Some(0)).then_some(forest)
+    }
+
+    ///Returns the number of distinct ASTs of the input with the non terminal symbol (without angle brackets) as root.
+    ///This is useful to check that a change to the rules actually made them less ambiguous.  
+    ///Returns 0 if the input can't be derived from the symbol and None if the number is too big for a u128.
+    pub fn count_derivations(&self, symbol: &str, input: &str) -> Option<u128> {
+        match self.parse_glr(symbol, input) {
+            Some(forest) => forest.derivation_count(),
+            None => Some(0),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::backus_naur_form::test_helpers::{digit, DIGIT_RULE};
+    use crate::backus_naur_form::token::Token;
+
+    #[test]
+    fn test_parse_glr() {
+        let bnf = crate::backus_naur_form!(
+            priority 0 => DIGIT_RULE
+            priority 0 => r#"<sum> ::= <sum> "+" <sum> | <digit>"#
+        );
+        let forest = bnf.parse_glr("sum", "1+2+1").unwrap();
+
+        assert_eq!(forest.derivation_count(), Some(2));
+        assert!(forest.is_ambiguous());
+        assert_eq!(
+            bnf.parse_glr("sum", "1+2+1+2+1")
+                .unwrap()
+                .derivation_count(),
+            Some(14)
+        );
+        assert!(bnf.parse_glr("sum", "1+").is_none());
+
+        let summand = |terminal| Token::from_non_terminal("sum", vec![digit(terminal)]);
+        let sum = |left, right| {
+            Token::from_non_terminal("sum", vec![left, Token::from_terminal("+"), right])
+        };
+        let derivations = forest.derivations().collect::<Vec<_>>();
+        assert_eq!(derivations.len(), 2);
+        assert!(derivations.contains(&vec![sum(sum(summand("1"), summand("2")), summand("1"))]));
+        assert!(derivations.contains(&vec![sum(summand("1"), sum(summand("2"), summand("1")))]));
+    }
+
+    #[test]
+    fn test_count_derivations() {
+        let ambiguous = crate::backus_naur_form!(
+            priority 0 => DIGIT_RULE
+            priority 0 => r#"<sum> ::= <sum> "+" <sum> | <digit>"#
+        );
+        let unambiguous = crate::backus_naur_form!(
+            priority 0 => DIGIT_RULE
+            priority 0 => r#"<sum> ::= <sum> "+" <digit> | <digit>"#
+        );
+
+        assert_eq!(ambiguous.count_derivations("sum", "1+2+1+2"), Some(5));
+        assert_eq!(unambiguous.count_derivations("sum", "1+2+1+2"), Some(1));
+        assert_eq!(unambiguous.count_derivations("sum", "1+"), Some(0));
+        //the number of derivations of "1+1+...+1" is a catalan number, which doesn't fit into a u128 for 100 ones
+        assert_eq!(
+            ambiguous.count_derivations("sum", &vec!["1"; 100].join("+")),
+            None
+        );
+    }
+
+    #[test]
+    fn test_unit_rule_cycles() {
+        let bnf = crate::backus_naur_form!(
+            priority 0 => r#"<a> ::= <b> | "x""#
+            priority 0 => r#"<b> ::= <a> | "y""#
+        );
+
+        assert_eq!(bnf.parse_glr("a", "x").unwrap().derivation_count(), Some(1));
+        assert_eq!(bnf.parse_glr("a", "y").unwrap().derivation_count(), Some(1));
+    }
+}
@@ -0,0 +1,735 @@
+//! Grammar documents are files that contain the rules of a [BackusNaurForm].
+//! They are written like the arms of the backus_naur_form! macro, but without the raw string literals:
+//! ```text
+//! # comments start with a hash
+//! %include "digits.bnf"
+//! ## doc comments start with two hashes and document the rule after them
+//! priority 1 => <product> ::= <number> "*" <number>
+//! <number> ::= <digit>
+//!            | <number> <number>
+//! ```
+//! - A rule starts on a line that contains `::=` and begins with either `<` or `priority`.
+//!   Every following line that is not a rule, a directive or a comment belongs to that rule too.
+//! - The priority is optional and defaults to 0.
+//! - Lines that start with `##` are the doc string of the next rule (see [set_rule_doc](BackusNaurForm::set_rule_doc)).
+//!   The lines of a doc string that spans several lines are joined with line breaks.
+//! - `%include "path"` adds the rules of another document. Every document is only included once,
+//!   so shared fragments can be included by several documents.
+//! - `%define name "a" "b" <c>` defines a constant for a set of terminals (or non terminals) that several rules use.
+//!   `%name` in the rules after the definition (in this document and in the documents included after it) stands for
+//!   the group `("a" | "b" | <c>)`, so `<expr> ::= <term> %op <term>` matches every operator of `%define op "+" "-"`.
+//!   A constant can only be defined once and its name can't be a byte value like `x0A`.
+//! - `%macro list(item, sep) ::= <item> | <list(item, sep)> "sep" <list(item, sep)>` defines a syntax macro for a shape that several rules use.
+//!   A use like `<list(expr, ",")>` becomes a rule where every `<item>` is `<expr>` and every `"sep"` is `","`,
+//!   its tokens appear as `<list>` in the AST. More information can be found in the [syntax_macro](super::syntax_macro) module.
+//!
+//! Loading a document fails with a [GrammarError::UnsupportedRecursion](super::error::GrammarError::UnsupportedRecursion)
+//! if a "array" rule isn't written as `<rule> <rule>` (see [check_recursion](BackusNaurForm::check_recursion)).
+//!
+//! Included documents are found through a [GrammarResolver].
+//! The [FileResolver] looks for them next to the including document and in its search paths.
+
+use std::{
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
+};
+
+use super::{
+    bytes::parse_byte_values, error::GrammarError, inline_test::parse_test_directive, rule,
+    symbol::non_terminal_symbol::NonTerminalSymbol, syntax_macro::SyntaxMacros,
+    template::parse_application, BackusNaurForm,
+};
+
+///Finds the documents that are included with `%include`.
+pub trait GrammarResolver {
+    ///Resolves the path of a `%include` directive.
+    ///including is the name of the document that contains the directive (None if the path is the root document).
+    ///Returns the unique name of the resolved document and its contents.
+    ///The name is used to include every document only once and to report errors.
+    fn resolve(
+        &self,
+        path: &str,
+        including: Option<&str>,
+    ) -> Result<(String, String), GrammarError>;
+}
+
+///Resolves included documents from the file system.
+///A path is first looked up relative to the directory of the including document and then in every search path in the order they were added.
+#[derive(Default, Clone, Debug)]
+pub struct FileResolver {
+    search_paths: Vec<PathBuf>,
+}
+
+impl FileResolver {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    ///Adds a directory in which included documents are looked for.
+    pub fn add_search_path(&mut self, path: impl Into<PathBuf>) {
+        self.search_paths.push(path.into());
+    }
+}
+
+impl GrammarResolver for FileResolver {
+    fn resolve(
+        &self,
+        path: &str,
+        including: Option<&str>,
+    ) -> Result<(String, String), GrammarError> {
+        let relative_to_including = match including {
+            Some(including) => Path::new(including)
+                .parent()
+                .map(|directory| directory.join(path))
+                .unwrap_or(PathBuf::from(path)),
+            None => PathBuf::from(path),
+        };
+        let file = std::iter::once(relative_to_including)
+            .chain(
+                self.search_paths
+                    .iter()
+                    .map(|directory| directory.join(path)),
+            )
+            .find(|candidate| candidate.is_file())
+            .ok_or(GrammarError::IncludeNotFound {
+                path: path.to_string(),
+            })?;
+
+        let io_error = |error: std::io::Error| GrammarError::Io {
+            path: file.display().to_string(),
+            message: error.to_string(),
+        };
+        let name = file.canonicalize().map_err(io_error)?;
+        let contents = std::fs::read_to_string(&file).map_err(io_error)?;
+        Ok((name.display().to_string(), contents))
+    }
+}
+
+///Resolves included documents from memory. The keys are the paths and the values the contents of the documents.
+impl GrammarResolver for HashMap<String, String> {
+    fn resolve(
+        &self,
+        path: &str,
+        _including: Option<&str>,
+    ) -> Result<(String, String), GrammarError> {
+        self.get(path)
+            .map(|contents| (path.to_string(), contents.to_string()))
+            .ok_or(GrammarError::IncludeNotFound {
+                path: path.to_string(),
+            })
+    }
+}
+
+//A item of a grammar document.
+#[derive(PartialEq, Debug)]
+enum Item {
+    Include(String),
+    Define {
+        name: String,
+        alternatives: Vec<String>,
+    },
+    Macro {
+        name: String,
+        parameters: Vec<String>,
+        body: String,
+    },
+    Rule {
+        rule: String,
+        priority: usize,
+        doc: Option<String>,
+    },
+    Test {
+        name: String,
+        inputs: Vec<(String, bool)>,
+    },
+}
+
+//Returns true if the line starts a new rule.
+fn is_rule_start(line: &str) -> bool {
+    (line.starts_with('<') || line.starts_with("priority")) && line.contains("::=")
+}
+
+//Parses the optional `priority n =>` in front of a rule.
+fn parse_priority(line: &str) -> Result<(usize, &str), String> {
+    let Some(rest) = line.strip_prefix("priority") else {
+        return Ok((0, line));
+    };
+    let Some((priority, rule)) = rest.split_once("=>") else {
+        return Err("the arrow (=>) after the priority is missing".to_string());
+    };
+    let priority = priority
+        .trim()
+        .parse::<usize>()
+        .map_err(|_| format!("\"{}\" is not a valid priority", priority.trim()))?;
+    Ok((priority, rule.trim()))
+}
+
+//Returns true if the char can be part of the name of a constant.
+fn is_constant_char(char: char) -> bool {
+    char.is_alphanumeric() || char == '-' || char == '_'
+}
+
+//Splits the value of a %define into its terminals and non terminals, for example `"+" "-" <op>` into `"+"`, `"-"` and `<op>`.
+fn split_alternatives(value: &str) -> Result<Vec<String>, String> {
+    let mut alternatives = vec![];
+    let mut rest = value.trim();
+    while !rest.is_empty() {
+        let end = match rest.chars().next() {
+            Some('"') => rest[1..].find('"').map(|end| end + 2),
+            Some('<') => rest.find('>').map(|end| end + 1),
+            _ => {
+                return Err(format!(
+                    "expected a terminal or a non terminal in %define but found \"{rest}\""
+                ))
+            }
+        }
+        .ok_or(format!("\"{rest}\" is never closed"))?;
+        alternatives.push(rest[..end].to_string());
+        rest = rest[end..].trim_start();
+    }
+    if alternatives.is_empty() {
+        return Err("%define needs at least one terminal or non terminal".to_string());
+    }
+    Ok(alternatives)
+}
+
+//Replaces every `%name` outside of strings and non terminals in the rule with the group of the constant.
+fn expand_constants(rule: &str, constants: &HashMap<String, String>) -> Result<String, String> {
+    let mut expanded = String::with_capacity(rule.len());
+    let mut in_string = false;
+    let mut in_non_terminal = false;
+    let mut chars = rule.chars().peekable();
+    while let Some(char) = chars.next() {
+        match char {
+            '"' if !in_non_terminal => in_string = !in_string,
+            '<' if !in_string => in_non_terminal = true,
+            '>' if !in_string => in_non_terminal = false,
+            '%' if !in_string && !in_non_terminal => {
+                let mut name = String::new();
+                while let Some(char) = chars.next_if(|char| is_constant_char(*char)) {
+                    name.push(char);
+                }
+                match constants.get(&name) {
+                    Some(group) => expanded.push_str(group),
+                    //the percent sign of a weight like `90% "0"`
+                    None if name.is_empty() => expanded.push('%'),
+                    //byte values like %x0A are left to the rule parser
+                    None if parse_byte_values(&name).is_ok() => {
+                        expanded.push_str(&format!("%{name}"))
+                    }
+                    None => return Err(format!("the constant %{name} is not defined")),
+                }
+                continue;
+            }
+            _ => {}
+        }
+        expanded.push(char);
+    }
+    Ok(expanded)
+}
+
+//Parses a document into its items. Every item comes with the line it starts on.
+fn parse_items(document_name: &str, document: &str) -> Result<Vec<(usize, Item)>, GrammarError> {
+    let syntax_error = |line, message| GrammarError::Syntax {
+        document: document_name.to_string(),
+        line,
+        message,
+    };
+    let mut items = vec![];
+    //the lines of the doc comment in front of the next rule
+    let mut doc: Option<String> = None;
+
+    for (index, line) in document.lines().enumerate() {
+        let line_number = index + 1;
+        let trimmed = line.trim();
+
+        if let Some(doc_line) = trimmed.strip_prefix("##") {
+            let doc = doc.get_or_insert_with(String::new);
+            if !doc.is_empty() {
+                doc.push('\n');
+            }
+            doc.push_str(doc_line.trim());
+            continue;
+        }
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+
+        if let Some(directive) = trimmed.strip_prefix('%') {
+            let (name, argument) = directive.split_once(' ').unwrap_or((directive, ""));
+            let item = match name {
+                "include" => {
+                    let path = argument
+                        .trim()
+                        .strip_prefix('"')
+                        .and_then(|path| path.strip_suffix('"'))
+                        .ok_or(syntax_error(
+                            line_number,
+                            "the path of %include must be in double quotes".to_string(),
+                        ))?;
+                    Item::Include(path.to_string())
+                }
+                "define" => {
+                    let (name, alternatives) = argument
+                        .trim()
+                        .split_once(' ')
+                        .unwrap_or((argument.trim(), ""));
+                    if name.is_empty() || !name.chars().all(is_constant_char) {
+                        return Err(syntax_error(
+                            line_number,
+                            format!("\"{name}\" is not a valid name for %define"),
+                        ));
+                    }
+                    //the constant would shadow the byte value
+                    if parse_byte_values(name).is_ok() {
+                        return Err(syntax_error(
+                            line_number,
+                            format!("%{name} is a byte value and can't be defined as a constant"),
+                        ));
+                    }
+                    let alternatives = split_alternatives(alternatives)
+                        .map_err(|message| syntax_error(line_number, message))?;
+                    Item::Define {
+                        name: name.to_string(),
+                        alternatives,
+                    }
+                }
+                "macro" => {
+                    let (head, body) = argument.split_once("::=").ok_or(syntax_error(
+                        line_number,
+                        "the replacement operator (::=) is missing in %macro".to_string(),
+                    ))?;
+                    let (name, parameters) = parse_application(head.trim())
+                        .filter(|(name, _)| !name.is_empty() && name.chars().all(is_constant_char))
+                        .ok_or(syntax_error(
+                            line_number,
+                            format!(
+                                "\"{}\" is not a valid name for %macro, it needs parameters like list(item, sep)",
+                                head.trim()
+                            ),
+                        ))?;
+                    Item::Macro {
+                        name: name.to_string(),
+                        parameters,
+                        body: body.trim().to_string(),
+                    }
+                }
+                "test" => {
+                    let (name, inputs) = parse_test_directive(argument)
+                        .map_err(|message| syntax_error(line_number, message))?;
+                    Item::Test { name, inputs }
+                }
+                _ => {
+                    return Err(syntax_error(
+                        line_number,
+                        format!("unknown directive %{name}"),
+                    ))
+                }
+            };
+            items.push((line_number, item));
+            continue;
+        }
+
+        if is_rule_start(trimmed) {
+            let (priority, rule) =
+                parse_priority(trimmed).map_err(|message| syntax_error(line_number, message))?;
+            items.push((
+                line_number,
+                Item::Rule {
+                    rule: rule.to_string(),
+                    priority,
+                    doc: doc.take(),
+                },
+            ));
+            continue;
+        }
+
+        //the line continues the last rule
+        match items.last_mut() {
+            Some((_, Item::Rule { rule, .. })) => {
+                rule.push('\n');
+                rule.push_str(line);
+            }
+            _ => {
+                return Err(syntax_error(
+                    line_number,
+                    "expected a rule, a directive or a comment".to_string(),
+                ))
+            }
+        }
+    }
+
+    Ok(items)
+}
+
+//The constants and macros that the rules of the documents that are loaded can use.
+#[derive(Default)]
+struct Definitions {
+    //the names of the constants and the groups they stand for
+    constants: HashMap<String, String>,
+    macros: SyntaxMacros,
+}
+
+//Adds the rules of the document and every document it includes to the backus naur form.
+fn load_document<R: GrammarResolver>(
+    bnf: &mut BackusNaurForm,
+    document_name: &str,
+    document: &str,
+    resolver: &R,
+    loaded_documents: &mut HashSet<String>,
+    definitions: &mut Definitions,
+) -> Result<(), GrammarError> {
+    let syntax_error = |line, message| GrammarError::Syntax {
+        document: document_name.to_string(),
+        line,
+        message,
+    };
+    for (line, item) in parse_items(document_name, document)? {
+        match item {
+            Item::Include(path) => {
+                let (name, contents) = resolver.resolve(&path, Some(document_name))?;
+                if loaded_documents.insert(name.to_string()) {
+                    load_document(
+                        bnf,
+                        &name,
+                        &contents,
+                        resolver,
+                        loaded_documents,
+                        definitions,
+                    )?;
+                }
+            }
+            Item::Define { name, alternatives } => {
+                if definitions.constants.contains_key(&name) {
+                    return Err(syntax_error(
+                        line,
+                        format!("the constant %{name} is already defined"),
+                    ));
+                }
+                let group = format!("({})", alternatives.join(" | "));
+                definitions.constants.insert(name, group);
+            }
+            Item::Macro {
+                name,
+                parameters,
+                body,
+            } => {
+                let body = expand_constants(&body, &definitions.constants)
+                    .map_err(|message| syntax_error(line, message))?;
+                definitions
+                    .macros
+                    .define(&name, parameters, &body)
+                    .map_err(|message| syntax_error(line, message))?;
+            }
+            Item::Rule {
+                rule,
+                priority,
+                doc,
+            } => {
+                let rule = expand_constants(&rule, &definitions.constants)
+                    .map_err(|message| syntax_error(line, message))?;
+                let (rule, instances) = definitions
+                    .macros
+                    .expand(&rule)
+                    .map_err(|message| syntax_error(line, message))?;
+                for rule in std::iter::once(&rule).chain(&instances) {
+                    rule::check_rule(rule).map_err(|message| syntax_error(line, message))?;
+                }
+                bnf.add_non_terminal_symbol_from_rule(&rule, priority);
+                if let Some(doc) = doc {
+                    let non_terminal_symbol = NonTerminalSymbol::from_rule(&rule);
+                    bnf.set_rule_doc(non_terminal_symbol.get_name(), &doc);
+                }
+                //added after the rule, so the uses of macros are applied before it
+                for instance in instances {
+                    bnf.add_non_terminal_symbol_from_rule(&instance, priority);
+                }
+            }
+            Item::Test { name, inputs } => {
+                for (input, accepts) in inputs {
+                    bnf.add_inline_test(&name, &input, accepts);
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+//Returns the contents of the document and of every document it includes, in the order they are loaded.
+//The rules of the documents aren't parsed.
+pub(crate) fn document_sources<R: GrammarResolver>(
+    document_name: &str,
+    document: &str,
+    resolver: &R,
+) -> Result<Vec<String>, GrammarError> {
+    let mut sources = vec![document.to_string()];
+    collect_includes(
+        document_name,
+        document,
+        resolver,
+        &mut HashSet::from([document_name.to_string()]),
+        &mut sources,
+    )?;
+    Ok(sources)
+}
+
+//Helper function for document_sources.
+//Adds the contents of every document the document includes (and the documents they include) to the sources.
+fn collect_includes<R: GrammarResolver>(
+    document_name: &str,
+    document: &str,
+    resolver: &R,
+    loaded_documents: &mut HashSet<String>,
+    sources: &mut Vec<String>,
+) -> Result<(), GrammarError> {
+    for (_, item) in parse_items(document_name, document)? {
+        if let Item::Include(path) = item {
+            let (name, contents) = resolver.resolve(&path, Some(document_name))?;
+            if loaded_documents.insert(name.to_string()) {
+                sources.push(contents.to_string());
+                collect_includes(&name, &contents, resolver, loaded_documents, sources)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+impl BackusNaurForm<'_> {
+    ///Creates a [BackusNaurForm] from a grammar document. More information about grammar documents can be found in the [grammar](self) module.
+    ///Included documents are resolved using the resolver.
+    pub fn from_grammar_document<R: GrammarResolver>(
+        document: &str,
+        resolver: &R,
+    ) -> Result<Self, GrammarError> {
+        let mut bnf = Self::default();
+        load_document(
+            &mut bnf,
+            "<document>",
+            document,
+            resolver,
+            &mut HashSet::new(),
+            &mut Definitions::default(),
+        )?;
+        bnf.check_recursion()?;
+        Ok(bnf)
+    }
+
+    ///Resolves the document at the path using the resolver and creates a [BackusNaurForm] from it.
+    ///For example `BackusNaurForm::load_grammar("grammar.bnf", &FileResolver::new())`.
+    pub fn load_grammar<R: GrammarResolver>(
+        path: &str,
+        resolver: &R,
+    ) -> Result<Self, GrammarError> {
+        let (name, contents) = resolver.resolve(path, None)?;
+        let mut bnf = Self::default();
+        let mut loaded_documents = HashSet::from([name.to_string()]);
+        load_document(
+            &mut bnf,
+            &name,
+            &contents,
+            resolver,
+            &mut loaded_documents,
+            &mut Definitions::default(),
+        )?;
+        bnf.check_recursion()?;
+        Ok(bnf)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::backus_naur_form::test_helpers::DIGIT_RULE;
+    use crate::backus_naur_form::token::Token;
+
+    use super::*;
+
+    #[test]
+    fn test_parse_items() {
+        let document = r#"
+# digits
+%include "digits.bnf"
+## A number
+## with several digits
+priority 1 => <number> ::= <digit>
+    | <number> <number>
+<sign> ::= "+" | "-"
+"#;
+        assert_eq!(
+            parse_items("test", document),
+            Ok(vec![
+                (3, Item::Include("digits.bnf".to_string())),
+                (
+                    6,
+                    Item::Rule {
+                        rule: "<number> ::= <digit>\n    | <number> <number>".to_string(),
+                        priority: 1,
+                        doc: Some("A number\nwith several digits".to_string())
+                    }
+                ),
+                (
+                    8,
+                    Item::Rule {
+                        rule: r#"<sign> ::= "+" | "-""#.to_string(),
+                        priority: 0,
+                        doc: None
+                    }
+                ),
+            ])
+        );
+        assert_eq!(
+            parse_items("test", "%import \"a\""),
+            Err(GrammarError::Syntax {
+                document: "test".to_string(),
+                line: 1,
+                message: "unknown directive %import".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn test_includes() {
+        let documents = HashMap::from([
+            (
+                "main.bnf".to_string(),
+                "%include \"digits.bnf\"\n%include \"number.bnf\"\n<sum> ::= <number> \"+\" <number>"
+                    .to_string(),
+            ),
+            (
+                "number.bnf".to_string(),
+                "%include \"digits.bnf\"\n## A number\n<number> -> Number ::= <digit> | <number> <number>"
+                    .to_string(),
+            ),
+            (
+                "digits.bnf".to_string(),
+                DIGIT_RULE.to_string(),
+            ),
+        ]);
+        let bnf = BackusNaurForm::load_grammar("main.bnf", &documents).unwrap();
+        let expected = crate::backus_naur_form!(
+            priority 0 => DIGIT_RULE
+            priority 0 => r#"<number> ::= <digit> | <number> <number>"#
+            priority 0 => r#"<sum> ::= <number> "+" <number>"#
+        );
+
+        //digits.bnf is included twice but its rules are only added once
+        assert_eq!(bnf, expected);
+        assert_eq!(bnf.rule("number").unwrap().doc(), Some("A number"));
+        assert_eq!(bnf.node_label("number"), "Number");
+        assert_eq!(
+            BackusNaurForm::load_grammar("missing.bnf", &documents),
+            Err(GrammarError::IncludeNotFound {
+                path: "missing.bnf".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn test_defines() {
+        let document = r#"
+%define op "+" "-" <times>
+<times> ::= "*"
+<digit> ::= "1" | "2"
+<expr> ::= <digit> %op <digit> | "%op" %op
+"#;
+        let bnf = BackusNaurForm::from_grammar_document(document, &HashMap::new()).unwrap();
+        let expected = crate::backus_naur_form!(
+            priority 0 => r#"<times> ::= "*""#
+            priority 0 => DIGIT_RULE
+            priority 0 => r#"<expr> ::= <digit> ("+" | "-" | <times>) <digit> | "%op" ("+" | "-" | <times>)"#
+        );
+        assert_eq!(bnf, expected);
+        //byte values aren't constants
+        assert_eq!(
+            BackusNaurForm::from_grammar_document("<crlf> ::= %x0D.0A", &HashMap::new()),
+            Ok(crate::backus_naur_form!(priority 0 => r#"<crlf> ::= %x0D.0A"#))
+        );
+
+        let error = |line, message: &str| {
+            Err(GrammarError::Syntax {
+                document: "<document>".to_string(),
+                line,
+                message: message.to_string(),
+            })
+        };
+        assert_eq!(
+            BackusNaurForm::from_grammar_document("<a> ::= %op", &HashMap::new()),
+            error(1, "the constant %op is not defined")
+        );
+        assert_eq!(
+            BackusNaurForm::from_grammar_document(
+                "%define op \"+\"\n%define op \"-\"",
+                &HashMap::new()
+            ),
+            error(2, "the constant %op is already defined")
+        );
+        assert_eq!(
+            BackusNaurForm::from_grammar_document("%define x0A \"+\"", &HashMap::new()),
+            error(1, "%x0A is a byte value and can't be defined as a constant")
+        );
+        assert_eq!(
+            BackusNaurForm::from_grammar_document("%define op + -", &HashMap::new()),
+            error(
+                1,
+                "expected a terminal or a non terminal in %define but found \"+ -\""
+            )
+        );
+    }
+
+    #[test]
+    fn test_macros() {
+        let document = r#"
+%macro list(item, sep) ::= <item> | <list(item, sep)> "sep" <list(item, sep)>
+%macro pair(first, second) ::= <first> "=" <second>
+priority 2 => <name> ::= "a" | "b"
+priority 1 => <entries> ::= <list(pair(name, name), ";")>
+<args> ::= "(" <list(name, ",")> ")"
+"#;
+        let bnf = BackusNaurForm::from_grammar_document(document, &HashMap::new()).unwrap();
+        let expected = crate::backus_naur_form!(
+            priority 2 => r#"<name> ::= "a" | "b""#
+            priority 1 => r#"<entries> ::= <list-0>"#
+            priority 1 => r#"<list-0> ::= <pair-1> | <list-0> ";" <list-0>"#
+            priority 1 => r#"<pair-1> ::= <name> "=" <name>"#
+            priority 0 => r#"<args> ::= "(" <list-2> ")""#
+            priority 0 => r#"<list-2> ::= <name> | <list-2> "," <list-2>"#
+        );
+        assert_eq!(bnf, expected);
+        assert_eq!(bnf.node_label("list-2"), "list");
+
+        let pair = |first, second| {
+            Token::from_non_terminal(
+                "pair",
+                vec![
+                    Token::from_non_terminal("name", vec![Token::from_terminal(first)]),
+                    Token::from_terminal("="),
+                    Token::from_non_terminal("name", vec![Token::from_terminal(second)]),
+                ],
+            )
+        };
+        let list = |child_tokens| Token::from_non_terminal("list", child_tokens);
+        assert_eq!(
+            bnf.symbolize_string("a=b;b=a"),
+            vec![Token::from_non_terminal(
+                "entries",
+                vec![list(vec![
+                    list(vec![pair("a", "b")]),
+                    Token::from_terminal(";"),
+                    list(vec![pair("b", "a")])
+                ])]
+            )]
+        );
+
+        assert_eq!(
+            BackusNaurForm::from_grammar_document(
+                "%macro list(item, sep) ::= <item>\n<a> ::= <list(b)>",
+                &HashMap::new()
+            ),
+            Err(GrammarError::Syntax {
+                document: "<document>".to_string(),
+                line: 2,
+                message: "the macro %list has 2 parameters but is used with 1 arguments"
+                    .to_string()
+            })
+        );
+    }
+}
@@ -0,0 +1,407 @@
+//! Contains [DependencyGraph], which shows which non terminal symbols the rules of every non terminal symbol use:
+//! ```rust, ignore
+//! //with <digit> ::= "1" | "2" and <number> ::= <digit> | <number> <number>
+//! let graph = bnf.dependency_graph();
+//! graph.dependencies("number")
+//! //["digit", "number"]
+//! println!("{}", graph.to_dot());
+//! ```
+//! [to_dot](DependencyGraph::to_dot) writes the graph in the DOT language of Graphviz, so large grammars can be looked at as a picture.
+//! The symbols and references that are part of a cycle are drawn red.
+//!
+//! [topological_order](BackusNaurForm::topological_order) sorts the symbols so every symbol comes after the symbols it uses,
+//! which is the order a bottom-up pass over the symbol kinds needs.
+//!
+//! Tools like completion and highlighting often need to know where a terminal or a symbol can appear:
+//! ```rust, ignore
+//! //with <digit> ::= "1" | "2", <number> ::= <digit> | <number> <number> and <sum> ::= <number> "+" <number>
+//! bnf.symbols_matching_terminal("+")
+//! //["sum"]
+//! bnf.symbols_that_can_contain("digit")
+//! //["number", "sum"]
+//! bnf.symbols_starting_with_terminal("1")
+//! //["digit", "number", "sum"]
+//! ```
+
+use std::collections::HashMap;
+
+use super::{nullable::nullable_symbols, symbol::Symbol, BackusNaurForm};
+
+///The non terminal symbols of a [BackusNaurForm] and the non terminal symbols their rules use. Created with [BackusNaurForm::dependency_graph].
+#[derive(PartialEq, Clone, Debug)]
+pub struct DependencyGraph {
+    //the symbols that have a rule, in the order their first rule was added
+    symbols: Vec<String>,
+    //the symbols every symbol uses, in the order they appear in its rules and without duplicates
+    dependencies: HashMap<String, Vec<String>>,
+    //every cycle is sorted by name and the cycles are sorted by their first name
+    cycles: Vec<Vec<String>>,
+}
+
+impl DependencyGraph {
+    ///Returns the names of the non terminal symbols that have a rule, in the order their first rule was added.
+    pub fn symbols(&self) -> &[String] {
+        &self.symbols
+    }
+
+    ///Returns the names of the non terminal symbols that the rules of the symbol use.
+    ///A symbol that is used but has no rule has no dependencies.
+    pub fn dependencies(&self, name: &str) -> &[String] {
+        self.dependencies
+            .get(name)
+            .map_or(&[], |dependencies| dependencies)
+    }
+
+    ///Returns the names of the non terminal symbols whose rules use the symbol, in the order of [symbols](DependencyGraph::symbols).
+    pub fn dependents(&self, name: &str) -> Vec<&str> {
+        self.symbols
+            .iter()
+            .filter(|symbol| self.dependencies(symbol).iter().any(|used| used == name))
+            .map(String::as_str)
+            .collect()
+    }
+
+    ///Returns the groups of non terminal symbols that refer to each other (directly or indirectly).
+    ///This is the same as the recursion cycles of [stats](BackusNaurForm::stats).
+    pub fn cycles(&self) -> &[Vec<String>] {
+        &self.cycles
+    }
+
+    ///Returns true if the symbol is part of a cycle.
+    pub fn is_recursive(&self, name: &str) -> bool {
+        self.cycle_of(name).is_some()
+    }
+
+    ///Returns the graph in the DOT language, for example to render it with Graphviz.
+    ///Symbols and references that are part of a cycle are red, symbols that are used but have no rule are dashed.
+    pub fn to_dot(&self) -> String {
+        let mut dot = "digraph grammar {\n".to_string();
+        let mut nodes = self.symbols.iter().collect::<Vec<_>>();
+        for symbol in &self.symbols {
+            for used in self.dependencies(symbol) {
+                if !nodes.contains(&used) {
+                    nodes.push(used);
+                }
+            }
+        }
+        for node in nodes {
+            let attributes = if self.is_recursive(node) {
+                " [color=red]"
+            } else if !self.symbols.contains(node) {
+                " [style=dashed]"
+            } else {
+                ""
+            };
+            dot.push_str(&format!("    \"{node}\"{attributes};\n"));
+        }
+        for symbol in &self.symbols {
+            for used in self.dependencies(symbol) {
+                let in_cycle = self
+                    .cycle_of(symbol)
+                    .is_some_and(|cycle| cycle.contains(used));
+                let attributes = if in_cycle { " [color=red]" } else { "" };
+                dot.push_str(&format!("    \"{symbol}\" -> \"{used}\"{attributes};\n"));
+            }
+        }
+        dot.push('}');
+        dot
+    }
+
+    //Returns the cycle the symbol is part of.
+    fn cycle_of(&self, name: &str) -> Option<&Vec<String>> {
+        self.cycles
+            .iter()
+            .find(|cycle| cycle.iter().any(|symbol| symbol == name))
+    }
+}
+
+//Finds the strongly connected components of the graph using the algorithm of tarjan.
+//A component is finished after all components it refers to, so the components are in dependency order.
+struct ComponentFinder<'a> {
+    edges: &'a HashMap<&'a str, Vec<&'a str>>,
+    index: usize,
+    indexes: HashMap<&'a str, usize>,
+    low_links: HashMap<&'a str, usize>,
+    stack: Vec<&'a str>,
+    components: Vec<Vec<String>>,
+}
+
+impl<'a> ComponentFinder<'a> {
+    fn visit(&mut self, node: &'a str) {
+        self.indexes.insert(node, self.index);
+        self.low_links.insert(node, self.index);
+        self.index += 1;
+        self.stack.push(node);
+
+        for &next in self.edges.get(node).into_iter().flatten() {
+            if !self.indexes.contains_key(next) {
+                self.visit(next);
+                let low_link = self.low_links[node].min(self.low_links[next]);
+                self.low_links.insert(node, low_link);
+            } else if self.stack.contains(&next) {
+                let low_link = self.low_links[node].min(self.indexes[next]);
+                self.low_links.insert(node, low_link);
+            }
+        }
+
+        if self.low_links[node] == self.indexes[node] {
+            let position = self
+                .stack
+                .iter()
+                .rposition(|&other| other == node)
+                .expect("the node is on the stack");
+            let mut component = self
+                .stack
+                .split_off(position)
+                .into_iter()
+                .map(str::to_string)
+                .collect::<Vec<_>>();
+            component.sort();
+            self.components.push(component);
+        }
+    }
+}
+
+impl BackusNaurForm<'_> {
+    ///Returns which non terminal symbols the rules of every non terminal symbol use.
+    ///More information can be found in the [graph](self) module.
+    pub fn dependency_graph(&self) -> DependencyGraph {
+        let mut symbols: Vec<String> = vec![];
+        let mut dependencies: HashMap<String, Vec<String>> = HashMap::new();
+        for (non_terminal_symbol, _) in &self.rules {
+            let name = non_terminal_symbol.get_name();
+            if !symbols.iter().any(|symbol| symbol == name) {
+                symbols.push(name.to_string());
+            }
+            let used = dependencies.entry(name.to_string()).or_default();
+            for symbol in non_terminal_symbol.get_rule().iter().flatten() {
+                if let Symbol::NonTerminal(used_name) = symbol {
+                    if !used.contains(used_name) {
+                        used.push(used_name.to_string());
+                    }
+                }
+            }
+        }
+
+        let mut cycles = self
+            .dependency_components()
+            .into_iter()
+            .filter(|component| {
+                //a single symbol is only a cycle if it refers to itself
+                component.len() > 1
+                    || dependencies
+                        .get(&component[0])
+                        .is_some_and(|used| used.contains(&component[0]))
+            })
+            .collect::<Vec<_>>();
+        cycles.sort();
+
+        DependencyGraph {
+            symbols,
+            dependencies,
+            cycles,
+        }
+    }
+
+    ///Returns the non terminal symbols in dependency order: every symbol comes after the symbols its rules use,
+    ///so a bottom-up pass can handle the symbols in this order.
+    ///Symbols that refer to each other (see [cycles](DependencyGraph::cycles)) can't be ordered and are returned as one group,
+    ///every other symbol is a group of its own. Symbols that are used but have no rule are left out.
+    pub fn topological_order(&self) -> Vec<Vec<String>> {
+        let has_rule = |name: &String| {
+            self.rules
+                .iter()
+                .any(|(non_terminal_symbol, _)| non_terminal_symbol.get_name() == name)
+        };
+        self.dependency_components()
+            .into_iter()
+            .filter(|component| component.iter().all(has_rule))
+            .collect()
+    }
+
+    ///Returns the non terminal symbols that can contain the terminal, directly or through the symbols their rules use.
+    ///The symbols are in the order their first rule was added.
+    pub fn symbols_matching_terminal(&self, terminal: &str) -> Vec<&str> {
+        self.symbols_containing(
+            &|symbol| matches!(symbol, Symbol::Terminal(text) if text == terminal),
+            false,
+        )
+    }
+
+    ///Returns the non terminal symbols that can contain the non terminal symbol with the name (without the angle brackets),
+    ///directly or through the symbols their rules use. A recursive symbol can contain itself.
+    ///The symbols are in the order their first rule was added.
+    pub fn symbols_that_can_contain(&self, name: &str) -> Vec<&str> {
+        self.symbols_containing(
+            &|symbol| matches!(symbol, Symbol::NonTerminal(used) if used == name),
+            false,
+        )
+    }
+
+    ///Returns the non terminal symbols whose tokens can start with the terminal, for example to find the symbols
+    ///that can follow the cursor while typing. Symbols that can match nothing are skipped at the start of a choice.
+    ///The symbols are in the order their first rule was added.
+    pub fn symbols_starting_with_terminal(&self, terminal: &str) -> Vec<&str> {
+        self.symbols_containing(
+            &|symbol| matches!(symbol, Symbol::Terminal(text) if text == terminal),
+            true,
+        )
+    }
+
+    //Returns the non terminal symbols with a choice that contains a target symbol or a symbol that was found before,
+    //until no more symbols are found. If at_start is true, only the start of the choices (up to the first symbol that can't match nothing) counts.
+    fn symbols_containing(&self, is_target: &dyn Fn(&Symbol) -> bool, at_start: bool) -> Vec<&str> {
+        let nullable = nullable_symbols(&self.rules);
+        let mut found: Vec<&str> = vec![];
+        loop {
+            let new_symbol = self
+                .rules
+                .iter()
+                .map(|(non_terminal_symbol, _)| non_terminal_symbol)
+                .filter(|non_terminal_symbol| !found.contains(&non_terminal_symbol.get_name()))
+                .find(|non_terminal_symbol| {
+                    non_terminal_symbol.get_rule().iter().any(|choice| {
+                        for symbol in choice {
+                            let is_found = matches!(symbol, Symbol::NonTerminal(name) if found.contains(&name.as_str()));
+                            if is_target(symbol) || is_found {
+                                return true;
+                            }
+                            let is_nullable = matches!(symbol, Symbol::NonTerminal(name) if nullable.contains(&name.as_str()));
+                            if at_start && !is_nullable {
+                                return false;
+                            }
+                        }
+                        false
+                    })
+                });
+            match new_symbol {
+                Some(non_terminal_symbol) => found.push(non_terminal_symbol.get_name()),
+                None => break,
+            }
+        }
+
+        let mut symbols: Vec<&str> = vec![];
+        for (non_terminal_symbol, _) in &self.rules {
+            let name = non_terminal_symbol.get_name();
+            if found.contains(&name) && !symbols.contains(&name) {
+                symbols.push(name);
+            }
+        }
+        symbols
+    }
+
+    //Returns the groups of non terminal symbols that refer to each other, every symbol that isn't part of a cycle is a group of its own.
+    //The groups are in dependency order: a group comes after the groups it uses.
+    //Every group is sorted by name.
+    pub(crate) fn dependency_components(&self) -> Vec<Vec<String>> {
+        let mut edges: HashMap<&str, Vec<&str>> = HashMap::new();
+        for (non_terminal_symbol, _) in &self.rules {
+            let referenced = edges.entry(non_terminal_symbol.get_name()).or_default();
+            for symbol in non_terminal_symbol.get_rule().iter().flatten() {
+                if let Symbol::NonTerminal(name) = symbol {
+                    referenced.push(name);
+                }
+            }
+        }
+        //visit the rules in the order they were added, so the result doesn't depend on the order of the hash map
+        let mut component_finder = ComponentFinder {
+            edges: &edges,
+            index: 0,
+            indexes: HashMap::new(),
+            low_links: HashMap::new(),
+            stack: vec![],
+            components: vec![],
+        };
+        for (non_terminal_symbol, _) in &self.rules {
+            if !component_finder
+                .indexes
+                .contains_key(non_terminal_symbol.get_name())
+            {
+                component_finder.visit(non_terminal_symbol.get_name());
+            }
+        }
+        component_finder.components
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::backus_naur_form::test_helpers::DIGIT_RULE;
+    #[test]
+    fn test_dependency_graph() {
+        let bnf = crate::backus_naur_form!(
+            priority 1 => DIGIT_RULE
+            priority 0 => r#"<number> ::= <digit> | <number> <number>"#
+            priority 0 => r#"<sum> ::= <number> "+" <value>"#
+            priority 0 => r#"<value> ::= <sum> | <number> | <call>"#
+        );
+        let graph = bnf.dependency_graph();
+
+        assert_eq!(graph.symbols(), ["digit", "number", "sum", "value"]);
+        assert_eq!(graph.dependencies("number"), ["digit", "number"]);
+        assert_eq!(graph.dependencies("call"), Vec::<String>::new());
+        assert_eq!(graph.dependents("number"), ["number", "sum", "value"]);
+        assert_eq!(
+            graph.cycles(),
+            [
+                vec!["number".to_string()],
+                vec!["sum".to_string(), "value".to_string()]
+            ]
+        );
+        assert_eq!(
+            bnf.topological_order(),
+            [
+                vec!["digit".to_string()],
+                vec!["number".to_string()],
+                vec!["sum".to_string(), "value".to_string()]
+            ]
+        );
+        assert_eq!(bnf.symbols_matching_terminal("+"), ["sum", "value"]);
+        assert_eq!(bnf.symbols_matching_terminal("-"), Vec::<&str>::new());
+        assert_eq!(
+            bnf.symbols_that_can_contain("digit"),
+            ["number", "sum", "value"]
+        );
+        assert_eq!(bnf.symbols_that_can_contain("sum"), ["sum", "value"]);
+        assert_eq!(bnf.symbols_starting_with_terminal("+"), Vec::<&str>::new());
+        assert_eq!(
+            bnf.symbols_starting_with_terminal("1"),
+            ["digit", "number", "sum", "value"]
+        );
+        assert!(graph.is_recursive("value"));
+        assert!(!graph.is_recursive("digit"));
+        assert_eq!(
+            graph.to_dot(),
+            r#"digraph grammar {
+    "digit";
+    "number" [color=red];
+    "sum" [color=red];
+    "value" [color=red];
+    "call" [style=dashed];
+    "number" -> "digit";
+    "number" -> "number" [color=red];
+    "sum" -> "number";
+    "sum" -> "value" [color=red];
+    "value" -> "sum" [color=red];
+    "value" -> "number";
+    "value" -> "call";
+}"#
+        );
+    }
+
+    #[test]
+    fn test_symbols_starting_with_terminal() {
+        let bnf = crate::backus_naur_form!(
+            priority 2 => r#"<sign> ::= "-" | ε"#
+            priority 1 => DIGIT_RULE
+            priority 0 => r#"<signed> ::= <sign> <digit>"#
+            priority 0 => r#"<negated> ::= "-" <signed>"#
+        );
+        //<sign> can match nothing, so <signed> can start with a digit too
+        assert_eq!(bnf.symbols_starting_with_terminal("1"), ["digit", "signed"]);
+        assert_eq!(
+            bnf.symbols_starting_with_terminal("-"),
+            ["sign", "signed", "negated"]
+        );
+    }
+}
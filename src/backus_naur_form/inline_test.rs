@@ -0,0 +1,222 @@
+//! Examples that are attached to the rules they test, so a grammar and its tests stay together.
+//! In grammar documents they are written with `%test`:
+//! ```text
+//! <digit> ::= "1" | "2" | "3"
+//! <number> ::= <digit> | <number> <number>
+//! %test <number> accepts "123" "1" rejects "12a" ""
+//! ```
+//! ```rust, ignore
+//! let failures = bnf.run_inline_tests().into_iter().filter(|result| !result.passed()).collect::<Vec<_>>();
+//! assert!(failures.is_empty(), "{failures:?}");
+//! ```
+//! A input is accepted if it is symbolized into a single [Token](super::token::Token) of the symbol
+//! (see [root_token](BackusNaurForm::root_token)), the same way [validate_corpus](BackusNaurForm::validate_corpus) checks its examples.
+//! Tests can also be added with [add_inline_test](BackusNaurForm::add_inline_test).
+
+use std::fmt::Display;
+
+use super::{error::RootError, template::canonical_name, BackusNaurForm};
+
+///A input that the rule of a symbol must accept or reject.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct InlineTest {
+    ///The name of the symbol without the angle brackets.
+    pub symbol: String,
+    pub input: String,
+    ///True if the input must be symbolized into the symbol, false if it must not.
+    pub accepts: bool,
+}
+
+///The reason why a [InlineTest] failed.
+#[derive(PartialEq, Debug, Clone)]
+pub enum InlineTestFailure {
+    ///The input must be accepted but wasn't symbolized into a single token.
+    NoRoot(RootError),
+    ///The input must be accepted but was symbolized into a single token of another symbol.
+    WrongRoot { symbol: String },
+    ///The input must be rejected but was symbolized into the symbol.
+    Accepted,
+}
+
+impl Display for InlineTestFailure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InlineTestFailure::NoRoot(error) => write!(f, "{error}"),
+            InlineTestFailure::WrongRoot { symbol } => {
+                write!(f, "the root token is <{symbol}>")
+            }
+            InlineTestFailure::Accepted => write!(f, "the input was accepted"),
+        }
+    }
+}
+
+///The result of a [InlineTest], returned by [run_inline_tests](BackusNaurForm::run_inline_tests).
+#[derive(PartialEq, Debug, Clone)]
+pub struct InlineTestResult {
+    pub test: InlineTest,
+    ///None if the test passed.
+    pub failure: Option<InlineTestFailure>,
+}
+
+impl InlineTestResult {
+    pub fn passed(&self) -> bool {
+        self.failure.is_none()
+    }
+}
+
+impl Display for InlineTestResult {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let verb = if self.test.accepts {
+            "accepts"
+        } else {
+            "rejects"
+        };
+        write!(f, "<{}> {verb} \"{}\": ", self.test.symbol, self.test.input)?;
+        match &self.failure {
+            Some(failure) => write!(f, "failed, {failure}"),
+            None => write!(f, "passed"),
+        }
+    }
+}
+
+impl BackusNaurForm<'_> {
+    ///Adds a input that the rule of the non terminal symbol with the specified name must accept (or reject if accepts is false).
+    ///This assumes that the angle brackets are not included in the name.
+    ///More information can be found in the [inline_test](self) module.
+    pub fn add_inline_test(&mut self, name: &str, input: &str, accepts: bool) {
+        self.inline_tests.push(InlineTest {
+            symbol: canonical_name(name),
+            input: input.to_string(),
+            accepts,
+        });
+    }
+
+    ///Returns the inline tests in the order they were added.
+    pub fn inline_tests(&self) -> &[InlineTest] {
+        &self.inline_tests
+    }
+
+    ///Runs every inline test and returns their results in the order the tests were added.
+    pub fn run_inline_tests(&self) -> Vec<InlineTestResult> {
+        self.inline_tests
+            .iter()
+            .map(|test| {
+                let failure = match (self.root_token(&test.input), test.accepts) {
+                    (Ok(root), true) if root.get_symbol() == test.symbol => None,
+                    (Ok(root), true) => Some(InlineTestFailure::WrongRoot {
+                        symbol: root.get_symbol().to_string(),
+                    }),
+                    (Err(error), true) => Some(InlineTestFailure::NoRoot(error)),
+                    (Ok(root), false) if root.get_symbol() == test.symbol => {
+                        Some(InlineTestFailure::Accepted)
+                    }
+                    (_, false) => None,
+                };
+                InlineTestResult {
+                    test: test.clone(),
+                    failure,
+                }
+            })
+            .collect()
+    }
+}
+
+///Parses the argument of a `%test` directive like `<number> accepts "123" rejects "12a"`
+///into the name of the symbol and the inputs with true if they must be accepted.
+pub(super) fn parse_test_directive(
+    argument: &str,
+) -> Result<(String, Vec<(String, bool)>), String> {
+    let argument = argument.trim();
+    let (name, mut rest) = argument
+        .strip_prefix('<')
+        .and_then(|rest| rest.split_once('>'))
+        .ok_or(
+            "%test needs a symbol in angle brackets, for example %test <number> accepts \"1\"",
+        )?;
+
+    let mut inputs = vec![];
+    let mut accepts = None;
+    loop {
+        rest = rest.trim_start();
+        if rest.is_empty() {
+            break;
+        }
+        if let Some(after) = rest.strip_prefix("accepts") {
+            accepts = Some(true);
+            rest = after;
+        } else if let Some(after) = rest.strip_prefix("rejects") {
+            accepts = Some(false);
+            rest = after;
+        } else if let Some(after) = rest.strip_prefix('"') {
+            let accepts =
+                accepts.ok_or(format!("expected accepts or rejects in front of \"{after}"))?;
+            let (input, after) = after
+                .split_once('"')
+                .ok_or(format!("the string \"{after} is never closed"))?;
+            inputs.push((input.to_string(), accepts));
+            rest = after;
+        } else {
+            return Err(format!(
+                "expected accepts, rejects or a input in double quotes but found {rest}"
+            ));
+        }
+    }
+    if inputs.is_empty() {
+        return Err(format!("%test <{name}> has no inputs"));
+    }
+    Ok((name.to_string(), inputs))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backus_naur_form::error::GrammarError;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_inline_tests() {
+        let document = r#"
+<digit> ::= "1" | "2" | "3"
+<number> ::= <digit> | <number> <number>
+%test <number> accepts "123" "1" rejects "12a"
+%test <digit> accepts "2" rejects "3"
+%test <number> rejects "2"
+"#;
+        let bnf = BackusNaurForm::from_grammar_document(document, &HashMap::new()).unwrap();
+        assert_eq!(bnf.inline_tests().len(), 6);
+        let results = bnf.run_inline_tests();
+        let failures = results
+            .iter()
+            .filter(|result| !result.passed())
+            .map(|result| result.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            failures,
+            vec![
+                "<digit> accepts \"2\": failed, the root token is <number>",
+                "<number> rejects \"2\": failed, the input was accepted",
+            ]
+        );
+        assert!(results[2].passed());
+
+        assert_eq!(
+            parse_test_directive(r#"<a> accepts "x" rejects "y" "z""#),
+            Ok((
+                "a".to_string(),
+                vec![
+                    ("x".to_string(), true),
+                    ("y".to_string(), false),
+                    ("z".to_string(), false)
+                ]
+            ))
+        );
+        assert_eq!(
+            BackusNaurForm::from_grammar_document(r#"%test <a> "x""#, &HashMap::new()),
+            Err(GrammarError::Syntax {
+                document: "<document>".to_string(),
+                line: 1,
+                message: "expected accepts or rejects in front of \"x\"".to_string()
+            })
+        );
+    }
+}
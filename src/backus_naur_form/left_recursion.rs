@@ -0,0 +1,478 @@
+//! Contains [BackusNaurForm::eliminate_left_recursion], which rewrites left recursive rules like `<sum> ::= <sum> "+" <digit> | <digit>`
+//! so they can be symbolized.
+//!
+//! Such rules don't work as they are written (see the [backus_naur_form](super) module):
+//! every `<digit>` is turned into a `<sum>` before `"+" <digit>` can be matched, so the rest of the recursive choice never matches.
+//! The transformation moves the rest of every left recursive choice into a primed helper symbol that is applied before the rule:
+//! ```text
+//! <sum> ::= <sum> "+" <digit> | <digit>
+//! ```
+//! turns into
+//! ```text
+//! <sum> ::= <digit> | <sum> <sum'>
+//! <sum'> ::= "+" <digit>
+//! ```
+//! The helper symbols appear in the AST. [LeftRecursionMapping::normalize] removes them again,
+//! so the AST looks like it was made by the original rules.
+//!
+//! "Array" rules like `<number> ::= <digit> | <number> <digit>` have to be written as `<number> ::= <digit> | <number> <number>` instead.
+//! [BackusNaurForm::check_recursion] finds the choices that aren't, grammar documents, the backus_naur_form! macro
+//! and the static_backus_naur_form! macro (`macros` feature) are checked automatically. [BackusNaurForm::rewrite_array_recursion] rewrites them,
+//! and [ArrayRecursionMapping::normalize] turns the AST back into the one the original rules describe:
+//! ```text
+//! 123 with <number> ::= <digit> | <number> <digit>
+//! <number>
+//!   <number>
+//!     <number> <digit> 1
+//!     <digit> 2
+//!   <digit> 3
+//! ```
+
+use std::collections::HashMap;
+
+use super::{
+    error::GrammarError,
+    remove_hidden_tokens,
+    rule::Rule,
+    stringify_choice,
+    symbol::{non_terminal_symbol::NonTerminalSymbol, Symbol},
+    token::{non_terminal_token::NonTerminalToken, Token},
+    BackusNaurForm, Choice,
+};
+
+///Maps the helper symbols that were introduced by [BackusNaurForm::eliminate_left_recursion] to the symbols they were made for.
+#[derive(PartialEq, Clone, Debug, Default)]
+pub struct LeftRecursionMapping {
+    helpers: HashMap<String, String>,
+}
+
+impl LeftRecursionMapping {
+    ///Returns the name of the symbol the helper symbol was made for (for example "sum" for "sum'").
+    ///Returns None if the name isn't a helper symbol.
+    pub fn original_symbol(&self, helper: &str) -> Option<&str> {
+        self.helpers.get(helper).map(String::as_str)
+    }
+
+    ///Returns true if no rule was left recursive.
+    pub fn is_empty(&self) -> bool {
+        self.helpers.is_empty()
+    }
+
+    ///Removes every helper [Token] from the tokens. The child tokens of a helper token take its place.
+    ///The result is the AST the original left recursive rules describe, for example
+    ///`<sum>(<sum>(<digit>) <sum'>("+" <digit>))` turns into `<sum>(<sum>(<digit>) "+" <digit>)`.
+    pub fn normalize(&self, tokens: Vec<Token>) -> Vec<Token> {
+        remove_hidden_tokens(tokens, &|name| self.helpers.contains_key(name))
+    }
+}
+
+///Records the rules that were rewritten by [BackusNaurForm::rewrite_array_recursion].
+#[derive(PartialEq, Clone, Debug, Default)]
+pub struct ArrayRecursionMapping {
+    //the names of the rewritten rules, wether the rewritten choices were left recursive (`<number> <digit>`)
+    //or right recursive (`<digit> <number>`) and the rewritten choices without the recursive symbol (`<digit>`)
+    rules: HashMap<String, (bool, Vec<Choice>)>,
+}
+
+//A element of a list of a rewritten rule.
+enum Element {
+    //the child tokens of a token that matched the rest of a rewritten choice, like the `<digit>` of `<number> <digit>`
+    Rest(Vec<Token>),
+    //a token that was made by another choice, like `"(" <number> ")"`
+    Whole(Token),
+}
+
+impl ArrayRecursionMapping {
+    ///Returns true if the rule with the name was rewritten.
+    pub fn is_rewritten(&self, name: &str) -> bool {
+        self.rules.contains_key(name)
+    }
+
+    ///Returns true if no rule was rewritten.
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    ///Turns every [Token] of a rewritten rule back into the tokens the original choice would have made.
+    ///For `<number> ::= <digit> | <number> <digit>` the digits are nested from the left, for example
+    ///`<number>(<number>(<number>(<digit>) <digit>) <digit>)`. This also works on flat lists (see [set_flat_lists](BackusNaurForm::set_flat_lists)).
+    ///The tokens of the other choices of the rule (like `"(" <number> ")"`) are a element of the list on their own.
+    pub fn normalize(&self, tokens: Vec<Token>) -> Vec<Token> {
+        tokens
+            .into_iter()
+            .map(|token| self.normalize_token(token))
+            .collect()
+    }
+
+    //Helper function for normalize.
+    fn normalize_token(&self, token: Token) -> Token {
+        let Token::NonTerminalToken(mut non_terminal) = token else {
+            return token;
+        };
+        let name = non_terminal.non_terminal_symbol.to_string();
+        let Some((left_recursive, rests)) = self.rules.get(&name) else {
+            let child_tokens = std::mem::take(non_terminal.get_child_tokens_mut());
+            *non_terminal.get_child_tokens_mut() = self.normalize(child_tokens);
+            return Token::NonTerminalToken(non_terminal);
+        };
+        let left_recursive = *left_recursive;
+
+        let mut elements = vec![];
+        collect_elements(non_terminal, &name, rests, &mut elements);
+        if !left_recursive {
+            elements.reverse();
+        }
+        let mut elements = elements.into_iter().map(|element| match element {
+            Element::Rest(tokens) => Element::Rest(self.normalize(tokens)),
+            //only the child tokens are normalized, the token itself isn't a list
+            Element::Whole(Token::NonTerminalToken(mut non_terminal)) => {
+                let child_tokens = std::mem::take(non_terminal.get_child_tokens_mut());
+                *non_terminal.get_child_tokens_mut() = self.normalize(child_tokens);
+                Element::Whole(Token::NonTerminalToken(non_terminal))
+            }
+            element => element,
+        });
+        let first = match elements.next().expect("a list has at least one element") {
+            Element::Rest(tokens) => Token::from_non_terminal(&name, tokens),
+            Element::Whole(token) => token,
+        };
+        elements.fold(first, |list, element| {
+            let mut tokens = match element {
+                Element::Rest(tokens) => tokens,
+                Element::Whole(token) => vec![token],
+            };
+            if left_recursive {
+                tokens.insert(0, list);
+            } else {
+                tokens.push(list);
+            }
+            Token::from_non_terminal(&name, tokens)
+        })
+    }
+}
+
+//Adds the elements of a token of a rewritten rule from left to right. The child tokens are split into tokens of the rule
+//and matches of the rests of the rewritten choices. If there are several, the token was made by the list choice
+//(or is a flat list) and the tokens of the rule are searched further.
+//Otherwise the token is a element: a match of a rest (like `<number>(<digit>)`) or a token of another choice.
+fn collect_elements(
+    mut non_terminal: NonTerminalToken,
+    name: &str,
+    rests: &[Choice],
+    elements: &mut Vec<Element>,
+) {
+    let child_tokens = non_terminal.get_child_tokens();
+    let mut parts = vec![];
+    let mut position = 0;
+    while position < child_tokens.len() {
+        if child_tokens[position].is_non_terminal_named(name) {
+            parts.push(1);
+            position += 1;
+            continue;
+        }
+        let rest = rests.iter().find(|rest| {
+            child_tokens[position..].len() >= rest.len()
+                && rest
+                    .iter()
+                    .zip(&child_tokens[position..])
+                    .all(|(symbol, token)| token.matches(symbol))
+        });
+        match rest {
+            Some(rest) if !rest.is_empty() => {
+                parts.push(rest.len());
+                position += rest.len();
+            }
+            _ => {
+                elements.push(Element::Whole(Token::NonTerminalToken(non_terminal)));
+                return;
+            }
+        }
+    }
+
+    let is_single_rest = parts.len() == 1 && !child_tokens[0].is_non_terminal_named(name);
+    if parts.len() < 2 && !is_single_rest {
+        elements.push(Element::Whole(Token::NonTerminalToken(non_terminal)));
+        return;
+    }
+    let mut child_tokens = std::mem::take(non_terminal.get_child_tokens_mut()).into_iter();
+    for length in parts {
+        let part = child_tokens.by_ref().take(length).collect::<Vec<_>>();
+        match <[Token; 1]>::try_from(part) {
+            Ok([Token::NonTerminalToken(child)]) if child.non_terminal_symbol == name => {
+                collect_elements(child, name, rests, elements);
+            }
+            Ok([token]) => elements.push(Element::Rest(vec![token])),
+            Err(part) => elements.push(Element::Rest(part)),
+        }
+    }
+}
+
+impl BackusNaurForm<'_> {
+    ///Rewrites every directly left recursive rule and returns the [LeftRecursionMapping] of the helper symbols.
+    ///The helper symbols get a higher priority than the rule, so they are applied first.
+    ///Choices like `<number> ::= <number> <number>` are left alone since they already work.
+    ///More information can be found in the [left_recursion](self) module.
+    pub fn eliminate_left_recursion(&mut self) -> LeftRecursionMapping {
+        let mut mapping = LeftRecursionMapping::default();
+
+        for rule_index in 0..self.rules.len() {
+            let (non_terminal_symbol, priority) = &self.rules[rule_index];
+            let name = non_terminal_symbol.get_name().to_string();
+            let own_symbol = Symbol::NonTerminal(name.to_string());
+            let (rests, mut choices): (Vec<Choice>, Vec<Choice>) = non_terminal_symbol
+                .get_rule()
+                .iter()
+                .cloned()
+                .partition(|choice| {
+                    choice.first() == Some(&own_symbol)
+                        && choice.len() > 1
+                        && choice[1..] != [own_symbol.clone()]
+                });
+            if rests.is_empty() {
+                continue;
+            }
+
+            let helper = std::iter::successors(Some(format!("{name}'")), |helper| {
+                Some(format!("{helper}'"))
+            })
+            .find(|helper| !self.contains_symbol(helper) && !mapping.helpers.contains_key(helper))
+            .expect("there are infinitely many names");
+            let rests = rests
+                .into_iter()
+                .map(|choice| Choice::from(&choice[1..]))
+                .collect();
+            choices.push(Choice::from_iter([
+                own_symbol,
+                Symbol::NonTerminal(helper.to_string()),
+            ]));
+
+            let helper_priority = priority + 1;
+            self.rules[rule_index].0 = NonTerminalSymbol::new(name.to_string(), choices);
+            self.rules.push((
+                NonTerminalSymbol::new(helper.to_string(), rests),
+                helper_priority,
+            ));
+            mapping.helpers.insert(helper, name);
+        }
+
+        mapping
+    }
+
+    ///Rewrites the choices that [check_recursion](BackusNaurForm::check_recursion) reports into the form that works,
+    ///for example `<number> ::= <digit> | <number> <digit>` into `<number> ::= <digit> | <number> <number>`.
+    ///Both match the same strings, but the AST is a different one. The returned [ArrayRecursionMapping] turns it back.
+    ///More information can be found in the [left_recursion](self) module.
+    pub fn rewrite_array_recursion(&mut self) -> ArrayRecursionMapping {
+        let mut mapping = ArrayRecursionMapping::default();
+        for rule_index in 0..self.rules.len() {
+            let (non_terminal_symbol, priority) = &self.rules[rule_index];
+            let rule = Rule::new(non_terminal_symbol, *priority, None);
+            let unsupported = rule.unsupported_array_choices();
+            let Some(first) = unsupported.first() else {
+                continue;
+            };
+            let name = rule.get_name().to_string();
+            let own_symbol = Symbol::NonTerminal(name.to_string());
+            let list_choice = Choice::from_iter([own_symbol.clone(), own_symbol.clone()]);
+            let left_recursive = first[0] == own_symbol;
+            let rests = unsupported
+                .iter()
+                .map(|choice| {
+                    if choice[0] == own_symbol {
+                        Choice::from(&choice[1..])
+                    } else {
+                        Choice::from(&choice[..choice.len() - 1])
+                    }
+                })
+                .collect();
+
+            let mut choices: Vec<Choice> = vec![];
+            for choice in rule.get_expression() {
+                if !unsupported.contains(&choice) {
+                    choices.push(choice.clone());
+                } else if !choices.contains(&list_choice) {
+                    choices.push(list_choice.clone());
+                }
+            }
+            self.rules[rule_index].0 = NonTerminalSymbol::new(name.to_string(), choices);
+            mapping.rules.insert(name, (left_recursive, rests));
+        }
+        mapping
+    }
+
+    ///Checks that no rule has a choice like `<number> <digit>` in `<number> ::= <digit> | <number> <digit>`, which never matches
+    ///(see [unsupported_array_choices](super::rule::Rule::unsupported_array_choices)).
+    ///Returns a [GrammarError::UnsupportedRecursion] with the rewrite that works for the first such choice.
+    ///This is done when a grammar document is loaded or a [backus_naur_form!](crate::backus_naur_form!) is created,
+    ///rules that are added in other ways can be checked with this.
+    pub fn check_recursion(&self) -> Result<(), GrammarError> {
+        for rule in self.rules() {
+            if let Some(choice) = rule.unsupported_array_choices().first() {
+                let name = rule.get_name();
+                return Err(GrammarError::UnsupportedRecursion {
+                    rule: name.to_string(),
+                    choice: stringify_choice(choice, 0).trim_end().to_string(),
+                    suggestion: format!("<{name}> <{name}>"),
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::backus_naur_form::test_helpers::{digit, DIGIT_RULE};
+    use std::collections::HashMap;
+
+    use crate::backus_naur_form::{error::GrammarError, token::Token, BackusNaurForm};
+
+    //Creates a backus naur form out of the rules and their priorities without checking the recursion like backus_naur_form! does.
+    fn unchecked_backus_naur_form(rules: &[(&str, usize)]) -> BackusNaurForm<'static> {
+        let mut bnf = BackusNaurForm::default();
+        for (rule, priority) in rules {
+            bnf.add_non_terminal_symbol_from_rule(rule, *priority);
+        }
+        bnf
+    }
+
+    #[test]
+    fn test_check_recursion() {
+        let bnf = unchecked_backus_naur_form(&[
+            (DIGIT_RULE, 1),
+            (r#"<number> ::= <digit> | <number> <digit>"#, 0),
+        ]);
+        let error = bnf.check_recursion().unwrap_err();
+        assert_eq!(
+            error,
+            GrammarError::UnsupportedRecursion {
+                rule: "number".to_string(),
+                choice: "<number> <digit>".to_string(),
+                suggestion: "<number> <number>".to_string()
+            }
+        );
+        assert_eq!(
+            error.to_string(),
+            "the choice <number> <digit> of <number> never matches because its symbols are turned into <number> on their own first, \
+write it as <number> <number> instead"
+        );
+
+        let document = "<digit> ::= \"1\"\n<number> ::= <digit> | <digit> <number>";
+        assert!(matches!(
+            BackusNaurForm::from_grammar_document(document, &HashMap::new()),
+            Err(GrammarError::UnsupportedRecursion { .. })
+        ));
+        let document = "<digit> ::= \"1\"\n<number> ::= <digit> | <number> <number>";
+        assert!(BackusNaurForm::from_grammar_document(document, &HashMap::new()).is_ok());
+    }
+
+    #[test]
+    #[should_panic(expected = "write it as <number> <number> instead")]
+    fn test_backus_naur_form_macro_checks_recursion() {
+        crate::backus_naur_form!(
+            priority 1 => DIGIT_RULE
+            priority 0 => r#"<number> ::= <digit> | <number> <digit>"#
+        );
+    }
+
+    #[test]
+    fn test_rewrite_array_recursion() {
+        let mut bnf = unchecked_backus_naur_form(&[
+            (r#"<digit> ::= "1" | "2" | "3""#, 2),
+            (r#"<number> ::= <digit> | <number> <digit>"#, 1),
+            (r#"<word> ::= <letter> | <letter> <word>"#, 1),
+            (r#"<letter> ::= "a" | "b""#, 0),
+        ]);
+        assert_ne!(bnf.symbolize_string("123").len(), 1);
+
+        let mapping = bnf.rewrite_array_recursion();
+        assert!(mapping.is_rewritten("number") && mapping.is_rewritten("word"));
+        assert!(!mapping.is_rewritten("digit"));
+        assert!(bnf.check_recursion().is_ok());
+
+        let number = |child_tokens| Token::from_non_terminal("number", child_tokens);
+        let tokens = mapping.normalize(bnf.symbolize_string("123"));
+        assert_eq!(
+            tokens,
+            vec![number(vec![
+                number(vec![number(vec![digit("1")]), digit("2")]),
+                digit("3")
+            ])]
+        );
+        //the tokens of flat lists are turned back too
+        bnf.set_flat_lists(true);
+        assert_eq!(mapping.normalize(bnf.symbolize_string("123")), tokens);
+
+        let letter =
+            |letter| Token::from_non_terminal("letter", vec![Token::from_terminal(letter)]);
+        let word = |child_tokens| Token::from_non_terminal("word", child_tokens);
+        assert_eq!(
+            mapping.normalize(bnf.symbolize_string("ab")),
+            vec![word(vec![letter("a"), word(vec![letter("b")])])]
+        );
+    }
+
+    #[test]
+    fn test_rewrite_array_recursion_with_other_choices() {
+        let mut bnf = unchecked_backus_naur_form(&[
+            (r#"<digit> ::= "1" | "2" | "3""#, 1),
+            (
+                r#"<number> ::= <digit> | "(" <number> ")" | <number> <digit>"#,
+                0,
+            ),
+        ]);
+        let mapping = bnf.rewrite_array_recursion();
+
+        let number = |child_tokens| Token::from_non_terminal("number", child_tokens);
+        let terminal = Token::from_terminal;
+        //the group is a element of the list and its own list is normalized too
+        let group = number(vec![
+            terminal("("),
+            number(vec![number(vec![digit("2")]), digit("3")]),
+            terminal(")"),
+        ]);
+        assert_eq!(
+            mapping.normalize(bnf.symbolize_string("1(23)")),
+            vec![number(vec![number(vec![digit("1")]), group.clone()])]
+        );
+        assert_eq!(
+            mapping.normalize(bnf.symbolize_string("(23)1")),
+            vec![number(vec![group, digit("1")])]
+        );
+    }
+
+    #[test]
+    fn test_eliminate_left_recursion() {
+        let mut bnf = crate::backus_naur_form!(
+            priority 0 => DIGIT_RULE
+            priority 0 => r#"<sum> ::= <sum> "+" <digit> | <digit>"#
+            priority 0 => r#"<number> ::= <number> <number> | "0""#
+        );
+        assert_ne!(bnf.symbolize_string("1+2+1").len(), 1);
+
+        let mapping = bnf.eliminate_left_recursion();
+        let tokens = mapping.normalize(bnf.symbolize_string("1+2+1"));
+        let plus = || Token::from_terminal("+");
+
+        assert_eq!(mapping.original_symbol("sum'"), Some("sum"));
+        assert_eq!(mapping.original_symbol("number'"), None);
+        assert_eq!(
+            tokens,
+            vec![Token::from_non_terminal(
+                "sum",
+                vec![
+                    Token::from_non_terminal(
+                        "sum",
+                        vec![
+                            Token::from_non_terminal("sum", vec![digit("1")]),
+                            plus(),
+                            digit("2")
+                        ]
+                    ),
+                    plus(),
+                    digit("1")
+                ]
+            )]
+        );
+    }
+}
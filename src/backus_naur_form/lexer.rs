@@ -0,0 +1,230 @@
+//! Feeds the tokens of a lexer into the rules, so a lexer can turn the input into words and numbers and the rules build the AST out of them.
+//! Any lexer can be used by implementing [TerminalSource]. With the `logos` feature, every [logos::Lexer] whose tokens implement [TerminalKind] is one:
+//! ```rust, ignore
+//! #[derive(Logos)]
+//! #[logos(skip r"[ \t\n]+")]
+//! enum Kind {
+//!     #[token("let")]
+//!     Let,
+//!     #[token("=")]
+//!     Equals,
+//!     #[regex("[a-z]+")]
+//!     Identifier,
+//!     #[regex("[0-9]+")]
+//!     Number,
+//! }
+//!
+//! impl TerminalKind for Kind {
+//!     fn terminal_name(&self) -> Option<&str> {
+//!         match self {
+//!             Kind::Identifier => Some("ident"),
+//!             Kind::Number => Some("number"),
+//!             _ => None,
+//!         }
+//!     }
+//! }
+//!
+//! let bnf = backus_naur_form!(
+//!     priority 0 => r#"<declaration> ::= "let" <ident> "=" <number>"#
+//! );
+//! let tokens = bnf.symbolize_source(Kind::lexer("let x = 42"))?;
+//! ```
+//! Tokens of a kind without a name are the terminal of their text (`"let"` and `"="`),
+//! tokens of a kind with a name are a token of a non terminal symbol with that name (`<number>`) which contains their text.
+//! So the rules can match every number at once and the text of the number is still in the AST.
+//!
+//! [terminal_alphabet](BackusNaurForm::terminal_alphabet) lists every terminal of the rules,
+//! so a lexer can be configured from the grammar instead of repeating its keywords and operators:
+//! ```rust, ignore
+//! for terminal in bnf.terminal_alphabet().keys() {
+//!     lexer_builder.add_literal(terminal);
+//! }
+//! ```
+
+use std::{collections::BTreeMap, ops::Range};
+
+use super::{error::SymbolizeError, symbol::Symbol, token::Token, BackusNaurForm};
+
+///Something that turns the input into tokens, usually a lexer.
+pub trait TerminalSource {
+    ///Returns the next token or None at the end of the input.
+    ///Returns the byte range of the input as the error if that part can't be turned into a token.
+    fn next_token(&mut self) -> Option<Result<Token, Range<usize>>>;
+}
+
+///The kinds of tokens of a lexer. The name decides which symbol the rules see for a token of the kind.
+pub trait TerminalKind {
+    ///Returns the name of the non terminal symbol of the tokens of this kind,
+    ///or None if the text of the token is a terminal on its own (for example for keywords and operators).
+    fn terminal_name(&self) -> Option<&str>;
+}
+
+///Returns the token for the text of a token of the kind.
+pub fn token_of_kind<K: TerminalKind>(kind: &K, text: &str) -> Token {
+    match kind.terminal_name() {
+        Some(name) => Token::from_non_terminal(name, vec![Token::from_terminal(text)]),
+        None => Token::from_terminal(text),
+    }
+}
+
+#[cfg(feature = "logos")]
+impl<'s, K> TerminalSource for logos::Lexer<'s, K>
+where
+    K: logos::Logos<'s, Source = str> + TerminalKind,
+{
+    fn next_token(&mut self) -> Option<Result<Token, Range<usize>>> {
+        let kind = self.next()?;
+        Some(match kind {
+            Ok(kind) => Ok(token_of_kind(&kind, self.slice())),
+            Err(_) => Err(self.span()),
+        })
+    }
+}
+
+impl BackusNaurForm<'_> {
+    ///Symbolizes the tokens of the source with [try_symbolize_tokens](BackusNaurForm::try_symbolize_tokens).
+    ///Returns a [SymbolizeError::Lex] if the source can't turn a part of the input into a token.
+    pub fn symbolize_source(
+        &self,
+        mut source: impl TerminalSource,
+    ) -> Result<Vec<Token>, SymbolizeError> {
+        let mut tokens = vec![];
+        while let Some(token) = source.next_token() {
+            tokens.push(token.map_err(|span| SymbolizeError::Lex { span })?);
+        }
+        self.try_symbolize_tokens(tokens)
+    }
+
+    ///Returns every distinct terminal of the rules with the names of the non terminal symbols whose rules use it,
+    ///for example to configure a lexer with the keywords and operators of the grammar.
+    ///The terminals are sorted and the symbols are in the order their first rule was added.
+    pub fn terminal_alphabet(&self) -> BTreeMap<&str, Vec<&str>> {
+        let mut alphabet: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+        for (non_terminal_symbol, _) in &self.rules {
+            let name = non_terminal_symbol.get_name();
+            for symbol in non_terminal_symbol.get_rule().iter().flatten() {
+                if let Symbol::Terminal(terminal) = symbol {
+                    let symbols = alphabet.entry(terminal).or_default();
+                    if !symbols.contains(&name) {
+                        symbols.push(name);
+                    }
+                }
+            }
+        }
+        alphabet
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::backus_naur_form;
+
+    use super::*;
+
+    //Splits the input at spaces, numbers are named and "?" can't be lexed.
+    struct Words<'s> {
+        words: std::str::SplitWhitespace<'s>,
+        input: &'s str,
+    }
+
+    struct Word(bool);
+
+    impl TerminalKind for Word {
+        fn terminal_name(&self) -> Option<&str> {
+            self.0.then_some("number")
+        }
+    }
+
+    impl TerminalSource for Words<'_> {
+        fn next_token(&mut self) -> Option<Result<Token, Range<usize>>> {
+            let word = self.words.next()?;
+            if word == "?" {
+                let start = word.as_ptr() as usize - self.input.as_ptr() as usize;
+                return Some(Err(start..start + 1));
+            }
+            let is_number = word.chars().all(|char| char.is_ascii_digit());
+            Some(Ok(token_of_kind(&Word(is_number), word)))
+        }
+    }
+
+    fn words(input: &str) -> Words<'_> {
+        Words {
+            words: input.split_whitespace(),
+            input,
+        }
+    }
+
+    #[test]
+    fn test_symbolize_source() {
+        let bnf = backus_naur_form!(
+            priority 0 => r#"<declaration> ::= "let" "x" "=" <number>"#
+        );
+        let tokens = bnf.symbolize_source(words("let x = 42")).unwrap();
+
+        assert_eq!(tokens.len(), 1);
+        assert_eq!(tokens[0].get_symbol(), "declaration");
+        assert_eq!(tokens[0].get_terminals(), "letx=42");
+        assert_eq!(
+            bnf.symbolize_source(words("let x = ?")),
+            Err(SymbolizeError::Lex { span: 8..9 })
+        );
+    }
+
+    #[test]
+    fn test_terminal_alphabet() {
+        let bnf = backus_naur_form!(
+            priority 1 => r#"<operator> ::= "+" | "-""#
+            priority 0 => r#"<declaration> ::= "let" <ident> "=" <number> | "let" <ident> "=" "-" <number>"#
+        );
+        let alphabet = bnf.terminal_alphabet();
+
+        assert_eq!(
+            alphabet.keys().copied().collect::<Vec<_>>(),
+            ["+", "-", "=", "let"]
+        );
+        assert_eq!(alphabet["-"], ["operator", "declaration"]);
+        assert_eq!(alphabet["let"], ["declaration"]);
+    }
+
+    #[cfg(feature = "logos")]
+    #[test]
+    fn test_logos_lexer() {
+        use logos::Logos;
+
+        #[derive(Logos)]
+        #[logos(skip r"[ ]+")]
+        enum Kind {
+            #[token("let")]
+            Let,
+            #[token("=")]
+            Equals,
+            #[regex("[a-z]+")]
+            Identifier,
+            #[regex("[0-9]+")]
+            Number,
+        }
+
+        impl TerminalKind for Kind {
+            fn terminal_name(&self) -> Option<&str> {
+                match self {
+                    Kind::Let | Kind::Equals => None,
+                    Kind::Identifier => Some("identifier"),
+                    Kind::Number => Some("number"),
+                }
+            }
+        }
+
+        let bnf = backus_naur_form!(
+            priority 0 => r#"<declaration> ::= "let" <identifier> "=" <number>"#
+        );
+        let tokens = bnf
+            .symbolize_source(Kind::lexer("let answer = 42"))
+            .unwrap();
+        assert_eq!(tokens.len(), 1);
+        assert_eq!(tokens[0].get_terminals(), "letanswer=42");
+        assert_eq!(
+            bnf.symbolize_source(Kind::lexer("let answer = 4!")),
+            Err(SymbolizeError::Lex { span: 14..15 })
+        );
+    }
+}
@@ -0,0 +1,579 @@
+//! Checks the rules of a [BackusNaurForm] for mistakes that don't stop it from symbolizing, but make it symbolize something else than intended:
+//! ```rust, ignore
+//! let config = "root = program\nsubsumed-choice = error".parse::<LintConfig>()?;
+//! for diagnostic in bnf.lint(&config) {
+//!     println!("{diagnostic}");
+//!     //warning[unused-symbol]: <letter> is not used by the root rule <program>
+//! }
+//! ```
+//! The checks are:
+//! - [Lint::UnusedSymbol]: a rule that can't be reached from the root rule. Hidden rules are skipped, they are often used to drop whitespace.
+//! - [Lint::ShadowedTerminal]: a choice of only terminals that another rule matches first, because it has a higher priority
+//!   (or the same priority and was added later).
+//! - [Lint::SubsumedChoice]: a choice that starts with a choice that comes before it in the same rule, so the earlier choice always matches first.
+//! - [Lint::UnsupportedRecursion]: a left recursive choice like `<sum> ::= <sum> "+" <digit> | <digit>` that never matches,
+//!   because every `<digit>` is turned into a `<sum>` first (see [eliminate_left_recursion](BackusNaurForm::eliminate_left_recursion)).
+//! - [Lint::MissingRootRule]: there is no rule that could be the root.
+//! - [Lint::UnknownSymbol]: a choice uses a non terminal symbol that has no rule, so the choice never matches
+//!   (unless the tokens of the symbol come from a [lexer](super::lexer)).
+//!
+//! The root rule is set with [LintConfig::set_root]. Otherwise it is the last added rule that no other rule uses,
+//! since grammars are usually written from the smallest rules up to the root.
+//!
+//! Every lint has a [Severity] that can be changed per project, for example in a config file that is [parsed](LintConfig::from_str) into a [LintConfig]:
+//! ```text
+//! # the root of the grammar
+//! root = program
+//! unused-symbol = allow
+//! shadowed-terminal = error
+//! ```
+//!
+//! Unknown symbols can also be checked every time a input is symbolized with [set_unknown_symbol_severity](BackusNaurForm::set_unknown_symbol_severity),
+//! so a typo in a rule is found by the first parse instead of silently never matching.
+//! The warnings are passed to the function set with [set_diagnostic_handler](BackusNaurForm::set_diagnostic_handler):
+//! ```rust, ignore
+//! bnf.set_unknown_symbol_severity(Severity::Warning);
+//! bnf.set_diagnostic_handler(|diagnostic| eprintln!("{diagnostic}"));
+//! ```
+
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Display,
+    str::FromStr,
+};
+
+use super::{
+    error::SymbolizeError, sort_rules, stringify_choice, symbol::Symbol, BackusNaurForm, Choice,
+};
+
+///How much a [Diagnostic] matters.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Debug, Hash, Default)]
+pub enum Severity {
+    ///The lint is not checked.
+    #[default]
+    Allow,
+    Warning,
+    Error,
+}
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Severity::Allow => write!(f, "allow"),
+            Severity::Warning => write!(f, "warning"),
+            Severity::Error => write!(f, "error"),
+        }
+    }
+}
+
+///The checks of [BackusNaurForm::lint]. More information can be found in the [lint](self) module.
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Hash)]
+pub enum Lint {
+    UnusedSymbol,
+    ShadowedTerminal,
+    SubsumedChoice,
+    UnsupportedRecursion,
+    MissingRootRule,
+    UnknownSymbol,
+}
+
+impl Lint {
+    ///Every lint in the order they are checked.
+    pub const ALL: [Lint; 6] = [
+        Lint::UnusedSymbol,
+        Lint::ShadowedTerminal,
+        Lint::SubsumedChoice,
+        Lint::UnsupportedRecursion,
+        Lint::MissingRootRule,
+        Lint::UnknownSymbol,
+    ];
+
+    ///Returns the name of the lint that is used in config files and diagnostics, for example `unused-symbol`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Lint::UnusedSymbol => "unused-symbol",
+            Lint::ShadowedTerminal => "shadowed-terminal",
+            Lint::SubsumedChoice => "subsumed-choice",
+            Lint::UnsupportedRecursion => "unsupported-recursion",
+            Lint::MissingRootRule => "missing-root-rule",
+            Lint::UnknownSymbol => "unknown-symbol",
+        }
+    }
+
+    ///Returns the severity of the lint if the [LintConfig] doesn't change it.
+    ///Only [Lint::UnsupportedRecursion] is a error by default, since such a choice can never match.
+    pub fn default_severity(&self) -> Severity {
+        match self {
+            Lint::UnsupportedRecursion => Severity::Error,
+            _ => Severity::Warning,
+        }
+    }
+}
+
+///A function that receives the warnings that are found while a input is symbolized, see [BackusNaurForm::set_diagnostic_handler].
+pub type DiagnosticHandler<'a> = Box<dyn Fn(&Diagnostic) + 'a>;
+
+///A problem that [BackusNaurForm::lint] found.
+#[derive(PartialEq, Clone, Debug)]
+pub struct Diagnostic {
+    pub lint: Lint,
+    pub severity: Severity,
+    ///The name of the non terminal symbol whose rule has the problem, None if the problem is not about a single rule.
+    pub symbol: Option<String>,
+    pub message: String,
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}[{}]: {}",
+            self.severity,
+            self.lint.name(),
+            self.message
+        )
+    }
+}
+
+///A non terminal symbol that a choice uses but that has no rule, see [BackusNaurForm::unknown_symbols].
+#[derive(PartialEq, Eq, Clone, Debug, Hash)]
+pub struct UnknownSymbol {
+    ///The name of the rule that uses the symbol.
+    pub rule: String,
+    ///The index of the choice in the rule.
+    pub choice: usize,
+    ///The name of the symbol.
+    pub symbol: String,
+}
+
+impl Display for UnknownSymbol {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "the choice {} of <{}> uses <{}> which has no rule",
+            self.choice, self.rule, self.symbol
+        )
+    }
+}
+
+///The severities of the lints and the root rule that [BackusNaurForm::lint] uses.
+#[derive(PartialEq, Clone, Debug, Default)]
+pub struct LintConfig {
+    severities: HashMap<Lint, Severity>,
+    root: Option<String>,
+}
+
+impl LintConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    ///Changes the severity of the lint. [Severity::Allow] turns the lint off.
+    pub fn set_severity(&mut self, lint: Lint, severity: Severity) {
+        self.severities.insert(lint, severity);
+    }
+
+    ///Returns the severity of the lint, its [default severity](Lint::default_severity) if it wasn't changed.
+    pub fn severity(&self, lint: Lint) -> Severity {
+        self.severities
+            .get(&lint)
+            .copied()
+            .unwrap_or(lint.default_severity())
+    }
+
+    ///Sets the name of the root rule, which is used to find unused symbols.
+    pub fn set_root(&mut self, name: &str) {
+        self.root = Some(name.to_string());
+    }
+}
+
+impl FromStr for LintConfig {
+    type Err = String;
+
+    ///Parses lines like `unused-symbol = allow` or `root = program`. Empty lines and lines that start with `#` are skipped.
+    fn from_str(string: &str) -> Result<Self, Self::Err> {
+        let mut config = LintConfig::new();
+        for (index, line) in string.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let Some((key, value)) = line.split_once('=') else {
+                return Err(format!("line {} is not a `name = value` pair", index + 1));
+            };
+            let (key, value) = (key.trim(), value.trim());
+            if key == "root" {
+                config.set_root(value);
+                continue;
+            }
+            let Some(lint) = Lint::ALL.into_iter().find(|lint| lint.name() == key) else {
+                return Err(format!("line {}: unknown lint \"{key}\"", index + 1));
+            };
+            let severity = match value {
+                "allow" => Severity::Allow,
+                "warning" => Severity::Warning,
+                "error" => Severity::Error,
+                _ => return Err(format!("line {}: unknown severity \"{value}\"", index + 1)),
+            };
+            config.set_severity(lint, severity);
+        }
+        Ok(config)
+    }
+}
+
+impl BackusNaurForm<'_> {
+    ///Checks the rules for the lints of the config that aren't allowed and returns a [Diagnostic] for every problem, ordered by the lint.
+    ///More information can be found in the [lint](self) module.
+    pub fn lint(&self, config: &LintConfig) -> Vec<Diagnostic> {
+        let mut diagnostics = vec![];
+        let mut report = |lint: Lint, symbol: Option<&str>, message: String| {
+            let severity = config.severity(lint);
+            if severity != Severity::Allow {
+                diagnostics.push(Diagnostic {
+                    lint,
+                    severity,
+                    symbol: symbol.map(str::to_string),
+                    message,
+                });
+            }
+        };
+
+        //the names of the rules in the order they were added and the non terminal symbols every rule uses
+        let mut names: Vec<&str> = vec![];
+        let mut uses: HashMap<&str, Vec<&str>> = HashMap::new();
+        for (non_terminal_symbol, _) in &self.rules {
+            let name = non_terminal_symbol.get_name();
+            if !names.contains(&name) {
+                names.push(name);
+            }
+            let used = uses.entry(name).or_default();
+            for symbol in non_terminal_symbol.get_rule().iter().flatten() {
+                if let Symbol::NonTerminal(used_name) = symbol {
+                    used.push(used_name);
+                }
+            }
+        }
+
+        let root = match &config.root {
+            Some(root) => names.iter().find(|name| **name == root).copied(),
+            None => names.iter().rev().copied().find(|name| {
+                !uses
+                    .iter()
+                    .any(|(user, used)| user != name && used.contains(name))
+            }),
+        };
+
+        if let Some(root) = root {
+            let mut reachable = HashSet::from([root]);
+            let mut pending = vec![root];
+            while let Some(name) = pending.pop() {
+                for used in uses.get(name).into_iter().flatten() {
+                    if reachable.insert(used) {
+                        pending.push(used);
+                    }
+                }
+            }
+            for name in &names {
+                if !reachable.contains(name) && !self.is_hidden_symbol(name) {
+                    report(
+                        Lint::UnusedSymbol,
+                        Some(name),
+                        format!("<{name}> is not used by the root rule <{root}>"),
+                    );
+                }
+            }
+        }
+
+        let sorted_rules = sort_rules(&self.rules);
+        for (index, (non_terminal_symbol, _)) in sorted_rules.iter().enumerate() {
+            let name = non_terminal_symbol.get_name();
+            for choice in non_terminal_symbol.get_rule() {
+                let only_terminals = !choice.is_empty()
+                    && choice
+                        .iter()
+                        .all(|symbol| matches!(symbol, Symbol::Terminal(_)));
+                if !only_terminals {
+                    continue;
+                }
+                let shadowing = sorted_rules[..index].iter().find(|(other, _)| {
+                    other.get_name() != name && other.get_rule().contains(choice)
+                });
+                if let Some((other, _)) = shadowing {
+                    report(
+                        Lint::ShadowedTerminal,
+                        Some(name),
+                        format!(
+                            "the choice {} of <{name}> never matches because <{}> matches it first",
+                            format_choice(choice),
+                            other.get_name()
+                        ),
+                    );
+                }
+            }
+        }
+
+        for (non_terminal_symbol, _) in &self.rules {
+            let name = non_terminal_symbol.get_name();
+            let choices = non_terminal_symbol.get_rule();
+            for (index, choice) in choices.iter().enumerate() {
+                let subsuming = choices[..index]
+                    .iter()
+                    .find(|earlier| !earlier.is_empty() && choice.starts_with(earlier));
+                if let Some(earlier) = subsuming {
+                    report(
+                        Lint::SubsumedChoice,
+                        Some(name),
+                        format!(
+                            "the choice {} of <{name}> never matches because the choice {} matches first",
+                            format_choice(choice),
+                            format_choice(earlier)
+                        ),
+                    );
+                }
+            }
+        }
+
+        for (non_terminal_symbol, _) in &self.rules {
+            let name = non_terminal_symbol.get_name();
+            let choices = non_terminal_symbol.get_rule();
+            //the symbols that are turned into this symbol on their own, like <digit> in <sum> ::= <digit>
+            let renamed = choices
+                .iter()
+                .filter_map(|choice| match choice.as_slice() {
+                    [Symbol::NonTerminal(renamed)] if renamed != name => Some(renamed),
+                    _ => None,
+                })
+                .collect::<Vec<_>>();
+            for choice in choices {
+                if choice.len() < 2 || choice[0] != Symbol::NonTerminal(name.to_string()) {
+                    continue;
+                }
+                let lost = choice[1..].iter().find_map(|symbol| match symbol {
+                    Symbol::NonTerminal(used) => renamed.iter().find(|renamed| **renamed == used),
+                    Symbol::Terminal(_) => None,
+                });
+                if let Some(lost) = lost {
+                    report(
+                        Lint::UnsupportedRecursion,
+                        Some(name),
+                        format!(
+                            "the left recursive choice {} of <{name}> never matches because every <{lost}> is turned into <{name}> first",
+                            format_choice(choice)
+                        ),
+                    );
+                }
+            }
+        }
+
+        if root.is_none() {
+            let message = match &config.root {
+                Some(root) => format!("the root rule <{root}> doesn't exist"),
+                None if names.is_empty() => "there are no rules".to_string(),
+                None => {
+                    "every rule is used by another rule, so no rule can be the root".to_string()
+                }
+            };
+            report(Lint::MissingRootRule, None, message);
+        }
+
+        for unknown in self.unknown_symbols() {
+            report(
+                Lint::UnknownSymbol,
+                Some(&unknown.rule),
+                unknown.to_string(),
+            );
+        }
+
+        diagnostics
+    }
+
+    ///Returns every use of a non terminal symbol that has no rule, in the order the rules were added.
+    ///The choices are counted as they are written, starting at 0.
+    pub fn unknown_symbols(&self) -> Vec<UnknownSymbol> {
+        let mut unknown_symbols = vec![];
+        for (non_terminal_symbol, _) in &self.rules {
+            for (index, choice) in non_terminal_symbol.get_rule().iter().enumerate() {
+                for symbol in choice {
+                    let Symbol::NonTerminal(name) = symbol else {
+                        continue;
+                    };
+                    #[cfg(feature = "unicode")]
+                    if self.is_unicode_class(name) {
+                        continue;
+                    }
+                    if !self.contains_symbol(name) {
+                        unknown_symbols.push(UnknownSymbol {
+                            rule: non_terminal_symbol.get_name().to_string(),
+                            choice: index,
+                            symbol: name.to_string(),
+                        });
+                    }
+                }
+            }
+        }
+        unknown_symbols
+    }
+
+    ///Sets what happens when a input is symbolized while a choice uses a non terminal symbol without a rule:
+    /// - [Severity::Allow] ignores it, which is the default since the tokens of the symbol may come from a lexer.
+    /// - [Severity::Warning] passes a [Diagnostic] for every [UnknownSymbol] to the function set with
+    ///   [set_diagnostic_handler](BackusNaurForm::set_diagnostic_handler) every time a input is symbolized.
+    /// - [Severity::Error] stops the symbolization with a [SymbolizeError::UnknownSymbol] for the first of them.
+    pub fn set_unknown_symbol_severity(&mut self, severity: Severity) {
+        self.unknown_symbol_severity = severity;
+    }
+
+    //Checks the unknown symbols with the severity of set_unknown_symbol_severity before a input is symbolized.
+    pub(super) fn check_unknown_symbols(&self) -> Result<(), SymbolizeError> {
+        match self.unknown_symbol_severity {
+            Severity::Allow => Ok(()),
+            Severity::Warning => {
+                let Some(handler) = &self.diagnostic_handler else {
+                    return Ok(());
+                };
+                for unknown in self.unknown_symbols() {
+                    handler(&Diagnostic {
+                        lint: Lint::UnknownSymbol,
+                        severity: Severity::Warning,
+                        symbol: Some(unknown.rule.clone()),
+                        message: unknown.to_string(),
+                    });
+                }
+                Ok(())
+            }
+            Severity::Error => match self.unknown_symbols().into_iter().next() {
+                Some(unknown) => Err(SymbolizeError::UnknownSymbol(unknown)),
+                None => Ok(()),
+            },
+        }
+    }
+}
+
+impl<'a> BackusNaurForm<'a> {
+    ///Sets the function that receives the warnings that are found while a input is symbolized
+    ///(for now the unknown symbols of [set_unknown_symbol_severity](BackusNaurForm::set_unknown_symbol_severity)).
+    ///Without a function the warnings are dropped.
+    pub fn set_diagnostic_handler<F>(&mut self, handler: F)
+    where
+        F: Fn(&Diagnostic) + 'a,
+    {
+        self.diagnostic_handler = Some(Box::new(handler));
+    }
+}
+
+fn format_choice(choice: &Choice) -> String {
+    stringify_choice(choice, 0).trim_end().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::backus_naur_form::test_helpers::DIGIT_RULE;
+    use std::cell::RefCell;
+
+    use crate::backus_naur_form;
+
+    use super::*;
+
+    #[test]
+    fn test_lint() {
+        let bnf = backus_naur_form!(
+            priority 2 => r#"<keyword> ::= "i" "f""#
+            priority 1 => DIGIT_RULE
+            priority 1 => r#"<letter> ::= "a" | "i" "f""#
+            priority 0 => r#"<_whitespace> ::= " ""#
+            priority 0 => r#"<sum> ::= <sum> "+" <digit> | <digit> | <digit> <digit> | <keyword>"#
+        );
+        let messages = |config: &LintConfig| {
+            bnf.lint(config)
+                .iter()
+                .map(Diagnostic::to_string)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            messages(&LintConfig::new()),
+            [
+                "warning[unused-symbol]: <letter> is not used by the root rule <sum>",
+                "warning[shadowed-terminal]: the choice \"i\" \"f\" of <letter> never matches because <keyword> matches it first",
+                "warning[subsumed-choice]: the choice <digit> <digit> of <sum> never matches because the choice <digit> matches first",
+                "error[unsupported-recursion]: the left recursive choice <sum> \"+\" <digit> of <sum> never matches because every <digit> is turned into <sum> first",
+            ]
+        );
+
+        let config = "# project lints\nroot = letter\nshadowed-terminal = allow\nsubsumed-choice = error\nunsupported-recursion = allow"
+            .parse::<LintConfig>()
+            .unwrap();
+        let diagnostics = bnf.lint(&config);
+        assert_eq!(
+            diagnostics
+                .iter()
+                .map(|diagnostic| (diagnostic.lint, diagnostic.symbol.as_deref()))
+                .collect::<Vec<_>>(),
+            [
+                (Lint::UnusedSymbol, Some("keyword")),
+                (Lint::UnusedSymbol, Some("digit")),
+                (Lint::UnusedSymbol, Some("sum")),
+                (Lint::SubsumedChoice, Some("sum")),
+            ]
+        );
+        assert_eq!(diagnostics[3].severity, Severity::Error);
+
+        let mut config = LintConfig::new();
+        config.set_root("program");
+        assert_eq!(
+            messages(&config).last().unwrap(),
+            "warning[missing-root-rule]: the root rule <program> doesn't exist"
+        );
+        let cyclic = backus_naur_form!(
+            priority 0 => r#"<a> ::= <b> "x""#
+            priority 0 => r#"<b> ::= <a> "y" | "z""#
+        );
+        assert_eq!(
+            cyclic.lint(&LintConfig::new())[0].message,
+            "every rule is used by another rule, so no rule can be the root"
+        );
+        assert!("unused = allow".parse::<LintConfig>().is_err());
+    }
+
+    #[test]
+    fn test_unknown_symbols() {
+        let warnings = RefCell::new(vec![]);
+        let mut bnf = backus_naur_form!(
+            priority 1 => DIGIT_RULE
+            priority 0 => r#"<sum> ::= <digit> | <digit> "+" <digt>"#
+        );
+        let unknown = UnknownSymbol {
+            rule: "sum".to_string(),
+            choice: 1,
+            symbol: "digt".to_string(),
+        };
+        assert_eq!(bnf.unknown_symbols(), vec![unknown.clone()]);
+        assert_eq!(
+            bnf.lint(&LintConfig::new()).last().unwrap().to_string(),
+            "warning[unknown-symbol]: the choice 1 of <sum> uses <digt> which has no rule"
+        );
+
+        //by default the choice just never matches
+        assert!(bnf.try_symbolize_string("1+2").is_ok());
+        bnf.set_unknown_symbol_severity(Severity::Warning);
+        assert!(bnf.try_symbolize_string("1+2").is_ok());
+        bnf.set_diagnostic_handler(|diagnostic| warnings.borrow_mut().push(diagnostic.clone()));
+        assert!(bnf.try_symbolize_string("1+2").is_ok());
+        //the same diagnostics as the lint
+        let unknown_symbol_diagnostics = bnf
+            .lint(&LintConfig::new())
+            .into_iter()
+            .filter(|diagnostic| diagnostic.lint == Lint::UnknownSymbol)
+            .collect::<Vec<_>>();
+        assert_eq!(*warnings.borrow(), unknown_symbol_diagnostics);
+        bnf.set_unknown_symbol_severity(Severity::Error);
+        assert_eq!(
+            bnf.try_symbolize_string("1+2"),
+            Err(SymbolizeError::UnknownSymbol(unknown))
+        );
+
+        bnf.add_non_terminal_symbol_from_rule(r#"<digt> ::= "3""#, 2);
+        assert!(bnf.unknown_symbols().is_empty());
+        assert!(bnf.try_symbolize_string("1+3").is_ok());
+    }
+}
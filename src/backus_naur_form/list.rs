@@ -0,0 +1,197 @@
+//! Flat lists for recursive "array" symbols like `<number> ::= <digit> | <number> <number>`.
+//! Those rules nest the tokens in a unbalanced binary tree, so which `<number>` contains which digit depends on the order
+//! the rule is applied in. With [set_flat_lists](BackusNaurForm::set_flat_lists) the nested tokens are merged into one
+//! list token that contains the elements from left to right:
+//! ```text
+//! 123 without flat lists:        with flat lists:
+//! <number>                       <number>
+//!   <number>                       <digit> 1
+//!     <number> <digit> 1           <digit> 2
+//!     <number> <digit> 2           <digit> 3
+//!   <number> <digit> 3
+//! ```
+//! A symbol is a list symbol if one of its choices only consists of the symbol itself (at least twice).
+//!
+//! Compile functions of lists can join the compiled elements with a separator, the nested tokens of the list are searched too,
+//! so this works with and without flat lists:
+//! ```rust, ignore
+//! //<list> ::= <digit> | <list> "," <list>
+//! bnf.add_compile_function("list", &|list, bnf| {
+//!     format!("[{}]", bnf.compile_children_joined(list, "digit", ", "))
+//! });
+//! ```
+
+use std::collections::HashSet;
+
+use super::{
+    node_context::NodeContext,
+    symbol::Symbol,
+    token::{non_terminal_token::NonTerminalToken, Token},
+    BackusNaurForm,
+};
+
+impl BackusNaurForm<'_> {
+    ///Turns the nested tokens of list symbols (symbols with a choice like `<number> ::= <number> <number>`) into flat lists:
+    ///every token of a list symbol contains the elements of the list from left to right instead of other tokens of the symbol.
+    ///More information can be found in the [list](self) module. Flat lists are disabled by default.
+    pub fn set_flat_lists(&mut self, enabled: bool) {
+        self.clear_compile_cache();
+        self.flat_lists = enabled;
+    }
+
+    ///Returns the names of the list symbols: the symbols with a choice that only consists of the symbol itself (at least twice).
+    pub(crate) fn list_symbols(&self) -> HashSet<&str> {
+        self.rules
+            .iter()
+            .filter(|(non_terminal_symbol, _)| {
+                non_terminal_symbol.get_rule().iter().any(|choice| {
+                    choice.len() >= 2
+                        && choice.iter().all(|symbol| {
+                            symbol == &Symbol::non_terminal(non_terminal_symbol.get_name())
+                        })
+                })
+            })
+            .map(|(non_terminal_symbol, _)| non_terminal_symbol.get_name())
+            .collect()
+    }
+
+    ///Compiles the child tokens of the symbol with the specified name and joins them with the separator, from left to right.
+    ///Child tokens of the same symbol as the token are nested parts of the list, their child tokens are compiled too.
+    ///Tokens without a compile function are turned into their [escaped](BackusNaurForm::escape) terminals.  
+    ///This assumes that the angle brackets are not included in the name. More information can be found in the [list](self) module.
+    pub fn compile_children_joined(
+        &self,
+        token: &NonTerminalToken,
+        name: &str,
+        separator: &str,
+    ) -> String {
+        self.compile_children_joined_with_context(token, name, separator, &NodeContext::default())
+    }
+
+    ///The same as [compile_children_joined](BackusNaurForm::compile_children_joined) but the elements are compiled
+    ///with their [NodeContext], the context is the context of the token.
+    pub fn compile_children_joined_with_context(
+        &self,
+        token: &NonTerminalToken,
+        name: &str,
+        separator: &str,
+        context: &NodeContext,
+    ) -> String {
+        let mut elements = vec![];
+        self.compile_elements(token, name, context, &mut elements);
+        elements.join(separator)
+    }
+
+    //Helper function for compile_children_joined_with_context.
+    //Adds the compiled child tokens of the symbol to elements and searches the child tokens of the same symbol as the token.
+    fn compile_elements(
+        &self,
+        token: &NonTerminalToken,
+        name: &str,
+        context: &NodeContext,
+        elements: &mut Vec<String>,
+    ) {
+        for (index, child) in token.get_child_tokens().iter().enumerate() {
+            let Token::NonTerminalToken(child) = child else {
+                continue;
+            };
+            let child_context = context.child(token, index);
+            if child.non_terminal_symbol == name {
+                elements.push(
+                    self.compile_token_with_context(child, &child_context)
+                        .unwrap_or_else(|| self.escape(&child.get_terminals())),
+                );
+            } else if child.non_terminal_symbol == token.non_terminal_symbol {
+                self.compile_elements(child, name, &child_context, elements);
+            }
+        }
+    }
+}
+
+//Replaces every token of a list symbol that is a child of a token of the same symbol with its child tokens,
+//so only the outermost token of every list is left.
+pub(crate) fn flatten_lists(tokens: &mut [Token], list_symbols: &HashSet<&str>) {
+    for token in tokens {
+        let Token::NonTerminalToken(non_terminal) = token else {
+            continue;
+        };
+        flatten_lists(non_terminal.get_child_tokens_mut(), list_symbols);
+        if !list_symbols.contains(non_terminal.non_terminal_symbol.as_str()) {
+            continue;
+        }
+        let name = non_terminal.non_terminal_symbol.to_string();
+        //the child tokens are flat already, so the child tokens of the same symbol only contain elements
+        let child_tokens = std::mem::take(non_terminal.get_child_tokens_mut());
+        *non_terminal.get_child_tokens_mut() = child_tokens
+            .into_iter()
+            .flat_map(|child| match child {
+                Token::NonTerminalToken(mut child) if child.non_terminal_symbol == name => {
+                    std::mem::take(child.get_child_tokens_mut())
+                }
+                child => vec![child],
+            })
+            .collect();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::backus_naur_form::test_helpers::digit;
+    use crate::backus_naur_form::token::Token;
+
+    #[test]
+    fn test_compile_children_joined() {
+        let mut bnf = crate::backus_naur_form!(
+            priority 2 => r#"<digit> ::= "1" | "2" | "3""#
+            priority 1 => r#"<list> ::= <digit> | <list> "," <list>"#
+        );
+        bnf.add_compile_function("list", &|list, bnf| {
+            format!("[{}]", bnf.compile_children_joined(list, "digit", ", "))
+        });
+        assert_eq!(bnf.compile_string("1,2,3"), "[1, 2, 3]");
+        assert_eq!(bnf.compile_string("2"), "[2]");
+
+        bnf.add_context_compile_function("digit", &|digit, context, _bnf| {
+            format!("{}@{:?}", digit.get_terminals(), context.sibling_index())
+        });
+        //every digit is the first child of a nested <list>
+        assert_eq!(bnf.compile_string("1,2"), "[1@Some(0), 2@Some(0)]");
+    }
+
+    #[test]
+    fn test_flat_lists() {
+        let mut bnf = crate::backus_naur_form!(
+            priority 2 => r#"<digit> ::= "1" | "2" | "3""#
+            priority 1 => r#"<number> ::= <digit> | <number> <number>"#
+            priority 0 => r#"<sum> ::= <number> "+" <number>"#
+        );
+        bnf.set_flat_lists(true);
+
+        assert_eq!(
+            bnf.symbolize_string("123+21"),
+            vec![Token::from_non_terminal(
+                "sum",
+                vec![
+                    Token::from_non_terminal("number", vec![digit("1"), digit("2"), digit("3")]),
+                    Token::from_terminal("+"),
+                    Token::from_non_terminal("number", vec![digit("2"), digit("1")])
+                ]
+            )]
+        );
+        //a single element is a list too
+        assert_eq!(
+            bnf.symbolize_string("3"),
+            vec![Token::from_non_terminal("number", vec![digit("3")])]
+        );
+
+        let number = |child_tokens| Token::from_non_terminal("number", child_tokens);
+        bnf.set_flat_lists(false);
+        assert_eq!(
+            bnf.symbolize_string("123"),
+            vec![number(vec![
+                number(vec![number(vec![digit("1")]), number(vec![digit("2")])]),
+                number(vec![digit("3")])
+            ])]
+        );
+    }
+}
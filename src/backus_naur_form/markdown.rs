@@ -0,0 +1,177 @@
+//! Generates a Markdown reference of a grammar, for example to publish the documentation of a DSL:
+//! ````text
+//! ## `<digit>`
+//!
+//! A decimal digit
+//!
+//! ```bnf
+//! <digit> ::= "1"
+//!           | "2"
+//! ```
+//!
+//! - Priority: 1
+//! - Used by: `<number>`
+//! - Examples: `1`, `2`
+//! ````
+//! Every non terminal symbol gets a section with its doc string (see [set_rule_doc](BackusNaurForm::set_rule_doc)),
+//! its choices, its priority, the symbols whose rules use it and [examples](BackusNaurForm::examples).
+//! Hidden symbols (see [hide_symbol](BackusNaurForm::hide_symbol)) don't get a section.
+
+use super::{symbol::Symbol, BackusNaurForm, Choice};
+
+//The maximum amount of examples of a symbol.
+const MAX_EXAMPLES: usize = 3;
+
+impl BackusNaurForm<'_> {
+    ///Returns a Markdown page with a section for every non terminal symbol, in the order their rules were added.
+    ///More information can be found in the [markdown](self) module.
+    pub fn to_markdown(&self) -> String {
+        let mut names: Vec<&str> = vec![];
+        for (non_terminal_symbol, _) in &self.rules {
+            let name = non_terminal_symbol.get_name();
+            if !names.contains(&name) && !self.is_hidden_symbol(name) {
+                names.push(name);
+            }
+        }
+
+        let mut markdown = "# Grammar\n".to_string();
+        for name in names {
+            markdown.push_str(&self.markdown_section(name));
+        }
+        markdown
+    }
+
+    //Returns the section of the non terminal symbol with the name.
+    fn markdown_section(&self, name: &str) -> String {
+        let rules = self
+            .rules
+            .iter()
+            .filter(|(non_terminal_symbol, _)| non_terminal_symbol.get_name() == name)
+            .collect::<Vec<_>>();
+
+        let mut section = format!("\n## `<{name}>`\n\n");
+        if let Some(doc) = self.rule_docs.get(name) {
+            section.push_str(&format!("{doc}\n\n"));
+        }
+        section.push_str("```bnf\n");
+        //the choices are aligned under the first one
+        let indent = " ".repeat(name.len() + 5);
+        for (non_terminal_symbol, _) in &rules {
+            for (index, choice) in non_terminal_symbol.get_rule().iter().enumerate() {
+                if index == 0 {
+                    section.push_str(&format!("<{name}> ::= "));
+                } else {
+                    section.push_str(&format!("{indent}| "));
+                }
+                section.push_str(&format!("{}\n", format_choice(choice)));
+            }
+        }
+        section.push_str("```\n\n");
+
+        let priorities = rules
+            .iter()
+            .map(|(_, priority)| priority.to_string())
+            .collect::<Vec<_>>();
+        section.push_str(&format!("- Priority: {}\n", priorities.join(", ")));
+
+        let mut used_by: Vec<&str> = vec![];
+        for (non_terminal_symbol, _) in &self.rules {
+            let user = non_terminal_symbol.get_name();
+            let uses = non_terminal_symbol
+                .get_rule()
+                .iter()
+                .flatten()
+                .any(|symbol| matches!(symbol, Symbol::NonTerminal(used) if used == name));
+            if uses && user != name && !used_by.contains(&user) {
+                used_by.push(user);
+            }
+        }
+        if !used_by.is_empty() {
+            section.push_str(&format!("- Used by: {}\n", code_list(used_by, "<", ">")));
+        }
+
+        let examples = self.examples(name, MAX_EXAMPLES);
+        if !examples.is_empty() {
+            section.push_str(&format!("- Examples: {}\n", code_list(examples, "", "")));
+        }
+        section
+    }
+}
+
+//Returns the choice the way it is written in a rule, ε for a empty choice.
+fn format_choice(choice: &Choice) -> String {
+    if choice.is_empty() {
+        return "ε".to_string();
+    }
+    choice
+        .iter()
+        .map(|symbol| match symbol {
+            Symbol::Terminal(terminal) => format!("\"{terminal}\""),
+            Symbol::NonTerminal(name) => format!("<{name}>"),
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+//Returns the items as inline code, separated by commas.
+fn code_list<S: AsRef<str>>(items: Vec<S>, prefix: &str, suffix: &str) -> String {
+    items
+        .iter()
+        .map(|item| format!("`{prefix}{}{suffix}`", item.as_ref()))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::backus_naur_form;
+    use crate::backus_naur_form::test_helpers::DIGIT_RULE;
+
+    #[test]
+    fn test_to_markdown() {
+        let bnf = backus_naur_form!(
+            priority 1 => doc "A decimal digit" => DIGIT_RULE
+            priority 0 => r#"<number> ::= <digit> | <number> <number>"#
+            priority 0 => r#"<signed> ::= ("+" | "-" | ε) <number>"#
+        );
+
+        assert_eq!(
+            bnf.to_markdown(),
+            r#"# Grammar
+
+## `<digit>`
+
+A decimal digit
+
+```bnf
+<digit> ::= "1"
+          | "2"
+```
+
+- Priority: 1
+- Used by: `<number>`
+- Examples: `1`, `2`
+
+## `<number>`
+
+```bnf
+<number> ::= <digit>
+           | <number> <number>
+```
+
+- Priority: 0
+- Used by: `<signed>`
+- Examples: `1`, `11`
+
+## `<signed>`
+
+```bnf
+<signed> ::= <_signed-group-0> <number>
+```
+
+- Priority: 0
+- Examples: `1`
+"#
+        );
+    }
+}
@@ -0,0 +1,129 @@
+//! Contains [NodeContext], the position of a [NonTerminalToken] in the AST.
+//! It is passed to the compile functions that are added with [BackusNaurForm::add_context_compile_function],
+//! so a token can be compiled differently depending on where it is (for example a `<ident>` inside of a `<declaration>` or a `<call>`).
+//!
+//! The context isn't stored in the tokens, it is passed down while compiling.
+//! A compile function that compiles its children passes it on with [BackusNaurForm::compile_child]:
+//! ```rust, ignore
+//! bnf.add_context_compile_function("ident", &|ident, context, _bnf| {
+//!     match context.parent_symbol() {
+//!         Some("declaration") => format!("let {}", ident.get_terminals()),
+//!         _ => ident.get_terminals(),
+//!     }
+//! });
+//! bnf.add_context_compile_function("declaration", &|declaration, context, bnf| {
+//!     bnf.compile_child(declaration, 0, context).unwrap_or_default()
+//! });
+//! ```
+
+use super::{token::non_terminal_token::NonTerminalToken, BackusNaurForm, Token};
+
+///The position of a [NonTerminalToken] in the AST. More information can be found in the [node_context](self) module.
+#[derive(PartialEq, Eq, Hash, Clone, Default, Debug)]
+pub struct NodeContext {
+    //the names of the ancestors, the root first and the parent last
+    ancestors: Vec<String>,
+    sibling_index: Option<usize>,
+}
+
+impl NodeContext {
+    ///The context of a token at the uppermost level of the AST (a token that [BackusNaurForm::symbolize_string] returns).
+    pub fn top_level(sibling_index: usize) -> Self {
+        Self {
+            ancestors: vec![],
+            sibling_index: Some(sibling_index),
+        }
+    }
+
+    ///Returns the context of the child token at the index of the token this context belongs to.
+    pub fn child(&self, parent: &NonTerminalToken, sibling_index: usize) -> Self {
+        let mut ancestors = self.ancestors.to_vec();
+        ancestors.push(parent.non_terminal_symbol.to_string());
+        Self {
+            ancestors,
+            sibling_index: Some(sibling_index),
+        }
+    }
+
+    ///Returns the name of the symbol of the parent token. Returns None for tokens at the uppermost level or if the context is unknown.
+    pub fn parent_symbol(&self) -> Option<&str> {
+        self.ancestors.last().map(String::as_str)
+    }
+
+    ///Returns the index of the token among the children of its parent (or among the uppermost tokens).
+    ///Returns None if the context is unknown, which is the case if the token is compiled with [BackusNaurForm::compile_token].
+    pub fn sibling_index(&self) -> Option<usize> {
+        self.sibling_index
+    }
+
+    ///Returns the names of the symbols of the ancestors, the uppermost ancestor first and the parent last.
+    pub fn ancestors(&self) -> &[String] {
+        &self.ancestors
+    }
+
+    ///Returns true if one of the ancestors is a token of the symbol.
+    pub fn is_inside(&self, symbol: &str) -> bool {
+        self.ancestors.iter().any(|ancestor| ancestor == symbol)
+    }
+}
+
+impl BackusNaurForm<'_> {
+    ///Compiles the child token at the index of the parent with the context of the child.
+    ///The context is the context of the parent.
+    ///Returns None if the child doesn't exist, is a terminal or has no compile function.
+    pub fn compile_child(
+        &self,
+        parent: &NonTerminalToken,
+        index: usize,
+        context: &NodeContext,
+    ) -> Option<String> {
+        match parent.get_child_tokens().get(index)? {
+            Token::NonTerminalToken(child) => {
+                self.compile_token_with_context(child, &context.child(parent, index))
+            }
+            Token::Terminal(_) => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_node_context() {
+        fn compile_children(
+            token: &NonTerminalToken,
+            context: &NodeContext,
+            bnf: &BackusNaurForm,
+        ) -> String {
+            (0..token.get_child_tokens().len())
+                .filter_map(|index| bnf.compile_child(token, index, context))
+                .collect()
+        }
+        let mut bnf = crate::backus_naur_form!(
+            priority 2 => r#"<ident> ::= "a" | "b""#
+            priority 1 => r#"<call> ::= <ident> "(" ")""#
+            priority 0 => r#"<declaration> ::= "$" <ident> | "$" <call>"#
+        );
+        bnf.add_context_compile_function("ident", &|ident, context, _bnf| {
+            let name = ident.get_terminals();
+            match context.parent_symbol() {
+                Some("declaration") => format!("var {name}"),
+                Some("call") if context.is_inside("declaration") => format!("{name}(1)"),
+                Some("call") => format!("{name}(0)"),
+                _ => format!("{name}@{:?}", context.sibling_index()),
+            }
+        });
+        bnf.add_context_compile_function("call", &compile_children);
+        bnf.add_context_compile_function("declaration", &compile_children);
+
+        assert_eq!(bnf.compile_string("$a"), "var a");
+        assert_eq!(bnf.compile_string("$b()"), "b(1)");
+        assert_eq!(bnf.compile_string("a()"), "a(0)");
+        assert_eq!(bnf.compile_string("a()b"), "a(0)b@Some(1)");
+
+        let ident = NonTerminalToken::new("ident", vec![Token::from_terminal("a")]);
+        assert_eq!(bnf.compile_token(&ident), Some("a@None".to_string()));
+    }
+}
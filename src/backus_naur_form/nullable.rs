@@ -0,0 +1,217 @@
+//! Support for empty choices (for example `<maybe-sign> ::= "+" | "-" | ε`).
+//! Empty choices can't be matched by themselves since they don't contain a token,
+//! so before a string is symbolized every choice that uses a nullable symbol gets a copy where that symbol is left out.
+//! For example `<signed> ::= <maybe-sign> <number>` is symbolized as `<signed> ::= <maybe-sign> <number> | <number>`.
+//! A choice with n nullable symbols gets up to 2^n versions, and every version is expanded again if the rule
+//! [skips](super::skip), so symbolizing fails with [SymbolizeError::TooManyChoiceVersions]
+//! if a choice would get more than [MAX_CHOICE_VERSIONS] versions.
+
+use std::{borrow::Cow, collections::HashMap};
+
+use super::{
+    error::SymbolizeError,
+    symbol::{non_terminal_symbol::NonTerminalSymbol, Symbol},
+    BackusNaurForm, Choice,
+};
+
+//the maximum amount of versions a single choice is expanded into
+pub(crate) const MAX_CHOICE_VERSIONS: usize = 4096;
+
+//Returns the names of the symbols that can match nothing.
+pub(super) fn nullable_symbols(rules: &[(NonTerminalSymbol, usize)]) -> Vec<&str> {
+    let mut nullable: Vec<&str> = vec![];
+    loop {
+        let new_nullable = rules
+            .iter()
+            .map(|(non_terminal_symbol, _)| non_terminal_symbol)
+            .filter(|non_terminal_symbol| !nullable.contains(&non_terminal_symbol.get_name()))
+            .find(|non_terminal_symbol| {
+                non_terminal_symbol.get_rule().iter().any(|choice| {
+                    choice.iter().all(|symbol| match symbol {
+                        Symbol::NonTerminal(name) => nullable.contains(&name.as_str()),
+                        Symbol::Terminal(_) => false,
+                    })
+                })
+            });
+        match new_nullable {
+            Some(non_terminal_symbol) => nullable.push(non_terminal_symbol.get_name()),
+            None => return nullable,
+        }
+    }
+}
+
+//Returns every version of the choice where some of the nullable symbols are left out, the choice itself first.
+//Versions that are empty or appeared before are skipped. Returns None if there would be more than MAX_CHOICE_VERSIONS versions.
+fn expand_choice(choice: &Choice, nullable: &[&str]) -> Option<Vec<Choice>> {
+    let nullable_positions = choice
+        .iter()
+        .enumerate()
+        .filter(|(_, symbol)| matches!(symbol, Symbol::NonTerminal(name) if nullable.contains(&name.as_str())))
+        .map(|(position, _)| position)
+        .collect::<Vec<_>>();
+    if nullable_positions.len() >= usize::BITS as usize
+        || 1usize << nullable_positions.len() > MAX_CHOICE_VERSIONS
+    {
+        return None;
+    }
+
+    //every bit of the mask stands for a nullable symbol that is left out, versions that leave out less symbols come first
+    let mut masks = (0..1usize << nullable_positions.len()).collect::<Vec<_>>();
+    masks.sort_by_key(|mask| mask.count_ones());
+
+    let mut versions: Vec<Choice> = vec![];
+    for mask in masks {
+        let version = choice
+            .iter()
+            .enumerate()
+            .filter(|(position, _)| {
+                nullable_positions
+                    .iter()
+                    .position(|nullable_position| nullable_position == position)
+                    .is_none_or(|bit| mask & (1 << bit) == 0)
+            })
+            .map(|(_, symbol)| symbol.clone())
+            .collect::<Choice>();
+        if !version.is_empty() && !versions.contains(&version) {
+            versions.push(version);
+        }
+    }
+    Some(versions)
+}
+
+impl BackusNaurForm<'_> {
+    ///Returns the rules the way they are symbolized: if a rule has a empty choice,
+    ///every choice that uses a nullable symbol is expanded and the empty choices are removed.
+    ///If a [skip symbol](BackusNaurForm::set_skip_symbol) is set, the choices of the rules that skip are expanded too.
+    ///Otherwise the rules are returned as they are.
+    ///Returns [SymbolizeError::TooManyChoiceVersions] if a choice would be expanded into more than [MAX_CHOICE_VERSIONS] versions.
+    pub(crate) fn symbolizing_rules(
+        &self,
+    ) -> Result<Cow<'_, [(NonTerminalSymbol, usize)]>, SymbolizeError> {
+        if !self.has_empty_choices() && !self.has_skip_symbol() {
+            return Ok(Cow::Borrowed(&self.rules));
+        }
+
+        let nullable = nullable_symbols(&self.rules);
+        let rules = self
+            .rules
+            .iter()
+            .map(|(non_terminal_symbol, priority)| {
+                let name = non_terminal_symbol.get_name();
+                let mut choices = vec![];
+                for choice in non_terminal_symbol.get_rule() {
+                    choices.extend(self.choice_versions(name, choice, &nullable)?);
+                }
+                Ok((NonTerminalSymbol::new(name.to_string(), choices), *priority))
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Cow::Owned(rules))
+    }
+
+    ///Returns the inline choices the way they are symbolized: like in [symbolizing_rules](BackusNaurForm::symbolizing_rules)
+    ///every version of a choice where nullable symbols are left out or skip symbols are added is inline too.
+    pub(crate) fn symbolizing_inline_choices(
+        &self,
+    ) -> Result<Cow<'_, HashMap<String, Vec<Choice>>>, SymbolizeError> {
+        if !self.has_empty_choices() && !self.has_skip_symbol() {
+            return Ok(Cow::Borrowed(&self.inline_choices));
+        }
+
+        let nullable = nullable_symbols(&self.rules);
+        let inline_choices = self
+            .inline_choices
+            .iter()
+            .map(|(name, choices)| {
+                let mut versions = vec![];
+                for choice in choices {
+                    versions.extend(self.choice_versions(name, choice, &nullable)?);
+                }
+                Ok((name.to_string(), versions))
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Cow::Owned(inline_choices))
+    }
+
+    //Returns every version of a choice of the rule of the non terminal symbol the way it is symbolized,
+    //or a error if there would be more than MAX_CHOICE_VERSIONS of them.
+    fn choice_versions(
+        &self,
+        name: &str,
+        choice: &Choice,
+        nullable: &[&str],
+    ) -> Result<Vec<Choice>, SymbolizeError> {
+        let too_many_versions = || SymbolizeError::TooManyChoiceVersions {
+            rule: name.to_string(),
+            max: MAX_CHOICE_VERSIONS,
+        };
+        let mut versions = vec![];
+        for choice in expand_choice(choice, nullable).ok_or_else(too_many_versions)? {
+            versions.extend(
+                self.skipping_choices(name, &choice)
+                    .ok_or_else(too_many_versions)?,
+            );
+            if versions.len() > MAX_CHOICE_VERSIONS {
+                return Err(too_many_versions());
+            }
+        }
+        Ok(versions)
+    }
+
+    //Returns true if a rule has a empty choice.
+    fn has_empty_choices(&self) -> bool {
+        self.rules.iter().any(|(non_terminal_symbol, _)| {
+            non_terminal_symbol
+                .get_rule()
+                .iter()
+                .any(|choice| choice.is_empty())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expand_choice() {
+        let choice = NonTerminalSymbol::from_rule(r#"<signed> ::= <sign> <digit> <sign>"#)
+            .get_rule()[0]
+            .clone();
+        let expanded = expand_choice(&choice, &["sign"])
+            .unwrap()
+            .iter()
+            .map(|choice| NonTerminalSymbol::new("signed".to_string(), vec![choice.clone()]))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            expanded,
+            vec![
+                NonTerminalSymbol::from_rule("<signed> ::= <sign> <digit> <sign>"),
+                NonTerminalSymbol::from_rule("<signed> ::= <digit> <sign>"),
+                NonTerminalSymbol::from_rule("<signed> ::= <sign> <digit>"),
+                NonTerminalSymbol::from_rule("<signed> ::= <digit>"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_too_many_choice_versions() {
+        let bnf = |nullable_symbols| {
+            let rule = format!("<long> ::= {}", "<maybe> ".repeat(nullable_symbols));
+            crate::backus_naur_form!(
+                priority 1 => r#"<maybe> ::= "a" | ε"#
+                priority 0 => &rule
+            )
+        };
+        assert_eq!(
+            bnf(13).try_symbolize_string("a"),
+            Err(SymbolizeError::TooManyChoiceVersions {
+                rule: "long".to_string(),
+                max: MAX_CHOICE_VERSIONS
+            })
+        );
+
+        //12 nullable symbols are still fine
+        assert!(bnf(12).try_symbolize_string("a").is_ok());
+    }
+}
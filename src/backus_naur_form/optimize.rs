@@ -0,0 +1,350 @@
+//! Contains [BackusNaurForm::optimize], which rewrites the rules of a [BackusNaurForm] so fewer symbols have to be compared while symbolizing.
+//! The following optimizations are applied:
+//! - Duplicate choices of a rule are removed.
+//! - Choices that start with the same symbols are factored into one choice and a hidden rule for the rest of the choices.
+//!   For example `<stmt> ::= "l" "e" "t" <ident> | "l" "e" "t" <number>` turns into
+//!   `<stmt> ::= "l" "e" "t" <_stmt-tail-0>` and `<_stmt-tail-0> ::= <ident> | <number>`.
+//!   This is only done if no other choice uses the first symbols of the rests, so the hidden rule can't take symbols away from other rules.
+//!   Since the rule is hidden, the AST stays the same.
+//! - Rules that only rename another non terminal symbol (for example `<value> ::= <number>`) are inlined into the rules that use them.
+//!   This changes the AST, so it has to be enabled with [OptimizeOptions::inline_unit_rules].
+
+use std::fmt::Display;
+
+use super::{
+    stringify_choice,
+    symbol::{non_terminal_symbol::NonTerminalSymbol, Symbol},
+    BackusNaurForm, Choice,
+};
+
+///Configures which optimizations [BackusNaurForm::optimize_with] applies.
+#[derive(PartialEq, Clone, Debug, Default)]
+pub struct OptimizeOptions {
+    ///Inline rules like `<value> ::= <number>` into the rules that use them.
+    ///Those rules don't appear in the AST anymore. Rules with a compile function are never inlined.
+    pub inline_unit_rules: bool,
+}
+
+///A change that was made by [BackusNaurForm::optimize].
+#[derive(PartialEq, Clone, Debug)]
+pub enum Optimization {
+    ///A choice appeared more than once in a rule and was removed.
+    RemovedDuplicateChoice { rule: String, choice: String },
+    ///The choices of a rule that start with the prefix were factored into the prefix and the new hidden rule.
+    FactoredPrefix {
+        rule: String,
+        prefix: String,
+        helper_rule: String,
+    },
+    ///The rule only renamed the replacement, so every use of it was replaced and the rule was removed.
+    InlinedUnitRule { rule: String, replacement: String },
+}
+
+impl Display for Optimization {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Optimization::RemovedDuplicateChoice { rule, choice } => {
+                write!(f, "removed the duplicate choice {choice} of <{rule}>")
+            }
+            Optimization::FactoredPrefix {
+                rule,
+                prefix,
+                helper_rule,
+            } => write!(
+                f,
+                "factored the prefix {prefix} of <{rule}> into <{helper_rule}>"
+            ),
+            Optimization::InlinedUnitRule { rule, replacement } => {
+                write!(f, "inlined <{rule}> ::= <{replacement}>")
+            }
+        }
+    }
+}
+
+///The changes that were made by [BackusNaurForm::optimize] in the order they were made.
+#[derive(PartialEq, Clone, Debug, Default)]
+pub struct OptimizationReport {
+    pub changes: Vec<Optimization>,
+}
+
+impl OptimizationReport {
+    ///Returns true if the optimization didn't change anything.
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+}
+
+impl Display for OptimizationReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let changes = self
+            .changes
+            .iter()
+            .map(|change| change.to_string())
+            .collect::<Vec<_>>()
+            .join("\n");
+        write!(f, "{changes}")
+    }
+}
+
+//Used for the report.
+fn choice_to_string(choice: &[Symbol]) -> String {
+    stringify_choice(&Choice::from(choice), 0)
+        .trim_end()
+        .to_string()
+}
+
+impl BackusNaurForm<'_> {
+    ///Optimizes the rules with the default [OptimizeOptions] and returns what was changed.
+    ///More information can be found in the [optimize](self) module.
+    pub fn optimize(&mut self) -> OptimizationReport {
+        self.optimize_with(&OptimizeOptions::default())
+    }
+
+    ///Optimizes the rules with the options and returns what was changed.
+    pub fn optimize_with(&mut self, options: &OptimizeOptions) -> OptimizationReport {
+        let mut report = OptimizationReport::default();
+        if options.inline_unit_rules {
+            self.inline_unit_rules(&mut report);
+        }
+        self.remove_duplicate_choices(&mut report);
+        self.factor_prefixes(&mut report);
+        report
+    }
+
+    fn remove_duplicate_choices(&mut self, report: &mut OptimizationReport) {
+        for (non_terminal_symbol, _) in &mut self.rules {
+            let mut choices: Vec<Choice> = vec![];
+            for choice in non_terminal_symbol.get_rule() {
+                if choices.contains(choice) {
+                    report.changes.push(Optimization::RemovedDuplicateChoice {
+                        rule: non_terminal_symbol.get_name().to_string(),
+                        choice: choice_to_string(choice),
+                    });
+                } else {
+                    choices.push(Choice::from(&choice[..]));
+                }
+            }
+            *non_terminal_symbol =
+                NonTerminalSymbol::new(non_terminal_symbol.get_name().to_string(), choices);
+        }
+    }
+
+    //Returns true if a choice of any rule except the skipped choices of the rule at rule_index contains the symbol.
+    fn is_used_elsewhere(&self, symbol: &Symbol, rule_index: usize, skipped: &[&Choice]) -> bool {
+        self.rules
+            .iter()
+            .enumerate()
+            .flat_map(|(index, (non_terminal_symbol, _))| {
+                non_terminal_symbol
+                    .get_rule()
+                    .iter()
+                    .filter(move |choice| index != rule_index || !skipped.contains(choice))
+            })
+            .any(|choice| choice.contains(symbol))
+    }
+
+    fn factor_prefixes(&mut self, report: &mut OptimizationReport) {
+        let mut rule_index = 0;
+        //rules that are added while factoring are checked too, since their choices can share prefixes as well
+        while rule_index < self.rules.len() {
+            let (non_terminal_symbol, priority) = &self.rules[rule_index];
+            let name = non_terminal_symbol.get_name().to_string();
+            let priority = *priority;
+            let own_symbol = Symbol::NonTerminal(name.to_string());
+            let choices = non_terminal_symbol.get_rule();
+
+            //recursive choices are handled differently while symbolizing, so they are left alone
+            let factorable = choices
+                .iter()
+                .enumerate()
+                .filter(|(_, choice)| !choice.contains(&own_symbol))
+                .find_map(|(index, choice)| {
+                    let group = choices[index..]
+                        .iter()
+                        .filter(|other| other.first() == choice.first())
+                        .filter(|other| !other.contains(&own_symbol))
+                        .collect::<Vec<_>>();
+                    if group.len() < 2 {
+                        return None;
+                    }
+                    let prefix_length = (0..choice.len())
+                        .take_while(|&length| {
+                            group
+                                .iter()
+                                .all(|other| other.get(length) == choice.get(length))
+                        })
+                        .count();
+                    //every choice needs a rest since a rule can't have empty choices
+                    let rests = group
+                        .iter()
+                        .map(|other| other.get(prefix_length))
+                        .collect::<Option<Vec<_>>>()?;
+                    if rests
+                        .iter()
+                        .any(|rest| self.is_used_elsewhere(rest, rule_index, &group))
+                    {
+                        return None;
+                    }
+                    Some((
+                        choice[..prefix_length].to_vec(),
+                        group.into_iter().cloned().collect::<Vec<_>>(),
+                    ))
+                });
+
+            let Some((prefix, group)) = factorable else {
+                rule_index += 1;
+                continue;
+            };
+
+            let helper_rule = (0..)
+                .map(|index| format!("_{name}-tail-{index}"))
+                .find(|helper_rule| !self.contains_symbol(helper_rule))
+                .expect("there are infinitely many names");
+            let helper_choices = group
+                .iter()
+                .map(|choice| Choice::from(&choice[prefix.len()..]))
+                .collect();
+
+            //the factored choice takes the place of the first choice of the group
+            let mut new_choices: Vec<Choice> = vec![];
+            for choice in choices {
+                if !group.contains(choice) {
+                    new_choices.push(Choice::from(&choice[..]));
+                } else if choice == &group[0] {
+                    let mut factored_choice = Choice::from(&prefix[..]);
+                    factored_choice.push(Symbol::NonTerminal(helper_rule.to_string()));
+                    new_choices.push(factored_choice);
+                }
+            }
+
+            report.changes.push(Optimization::FactoredPrefix {
+                rule: name.to_string(),
+                prefix: choice_to_string(&prefix),
+                helper_rule: helper_rule.to_string(),
+            });
+            self.rules[rule_index].0 = NonTerminalSymbol::new(name, new_choices);
+            self.rules.push((
+                NonTerminalSymbol::new(helper_rule, helper_choices),
+                priority,
+            ));
+        }
+    }
+
+    fn inline_unit_rules(&mut self, report: &mut OptimizationReport) {
+        loop {
+            let unit_rule = self.rules.iter().find_map(|(non_terminal_symbol, _)| {
+                let name = non_terminal_symbol.get_name();
+                let [choice] = non_terminal_symbol.get_rule().as_slice() else {
+                    return None;
+                };
+                let [Symbol::NonTerminal(replacement)] = choice.as_slice() else {
+                    return None;
+                };
+                let has_one_rule = self
+                    .rules
+                    .iter()
+                    .filter(|(other, _)| other.get_name() == name)
+                    .count()
+                    == 1;
+                let is_used_by_template = self
+                    .templates
+                    .iter()
+                    .any(|template| template.refers_to(name));
+                (replacement != name
+                    && has_one_rule
+                    && !is_used_by_template
+                    && !self.compile_functions.contains_key(name)
+                    && !self.context_compile_functions.contains_key(name))
+                .then(|| (name.to_string(), replacement.to_string()))
+            });
+            let Some((rule, replacement)) = unit_rule else {
+                break;
+            };
+
+            self.rules
+                .retain(|(non_terminal_symbol, _)| non_terminal_symbol.get_name() != rule);
+            for (non_terminal_symbol, _) in &mut self.rules {
+                let choices = non_terminal_symbol
+                    .get_rule()
+                    .iter()
+                    .map(|choice| {
+                        choice
+                            .iter()
+                            .map(|symbol| match symbol {
+                                Symbol::NonTerminal(name) if *name == rule => {
+                                    Symbol::NonTerminal(replacement.to_string())
+                                }
+                                symbol => symbol.clone(),
+                            })
+                            .collect()
+                    })
+                    .collect();
+                *non_terminal_symbol =
+                    NonTerminalSymbol::new(non_terminal_symbol.get_name().to_string(), choices);
+            }
+            report
+                .changes
+                .push(Optimization::InlinedUnitRule { rule, replacement });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backus_naur_form::test_helpers::DIGIT_RULE;
+
+    #[test]
+    fn test_optimize() {
+        let mut bnf = crate::backus_naur_form!(
+            priority 0 => r#"<digit> ::= "1" | "2" | "1""#
+            priority 0 => r#"<letter> ::= "a" | "b""#
+            priority 0 => r#"<stmt> ::= "l" "e" "t" <digit> | "x" | "l" "e" "t" <letter>"#
+        );
+        let report = bnf.optimize();
+        let expected = crate::backus_naur_form!(
+            priority 0 => DIGIT_RULE
+            priority 0 => r#"<letter> ::= "a" | "b""#
+            priority 0 => r#"<stmt> ::= "l" "e" "t" <_stmt-tail-0> | "x""#
+            priority 0 => r#"<_stmt-tail-0> ::= <digit> | <letter>"#
+        );
+
+        assert_eq!(bnf, expected);
+        assert_eq!(
+            report.to_string(),
+            "removed the duplicate choice \"1\" of <digit>\nfactored the prefix \"l\" \"e\" \"t\" of <stmt> into <_stmt-tail-0>"
+        );
+        //the AST is the same as before, the hidden rule doesn't appear in it
+        let tokens = bnf.symbolize_string("leta");
+        assert_eq!(tokens.len(), 1);
+        assert_eq!(tokens[0].get_symbol(), "stmt");
+        assert!(bnf.optimize().is_empty());
+    }
+
+    #[test]
+    fn test_inline_unit_rules() {
+        let mut bnf = crate::backus_naur_form!(
+            priority 0 => DIGIT_RULE
+            priority 0 => r#"<value> ::= <digit>"#
+            priority 0 => r#"<sum> ::= <value> "+" <value>"#
+        );
+        let report = bnf.optimize_with(&OptimizeOptions {
+            inline_unit_rules: true,
+        });
+
+        assert_eq!(
+            bnf,
+            crate::backus_naur_form!(
+                priority 0 => DIGIT_RULE
+                priority 0 => r#"<sum> ::= <digit> "+" <digit>"#
+            )
+        );
+        assert_eq!(
+            report.changes,
+            vec![Optimization::InlinedUnitRule {
+                rule: "value".to_string(),
+                replacement: "digit".to_string()
+            }]
+        );
+    }
+}
@@ -0,0 +1,221 @@
+//! Contains [ParseForest], a shared packed parse forest that contains every AST of a input.
+//! It is made by the GLR parser (see the [glr](super::glr) module).
+//!
+//! Every node of the forest is a symbol that spans a part of the input. A node can have several alternatives
+//! (different ways to derive its part of the input), and nodes are shared between the alternatives.
+//! This way a forest stays small even if the input has exponentially many ASTs.
+
+use std::collections::{HashMap, HashSet};
+
+use super::{remove_hidden_tokens, token::Token};
+
+#[derive(PartialEq, Eq, Hash, Clone, Debug)]
+pub(crate) enum ForestSymbol {
+    Terminal(char),
+    NonTerminal(String),
+}
+
+#[derive(PartialEq, Clone, Debug)]
+pub(crate) struct ForestNode {
+    pub symbol: ForestSymbol,
+    //every alternative is a list of the child nodes
+    pub alternatives: Vec<Vec<usize>>,
+}
+
+///A shared packed parse forest. Created with [BackusNaurForm::parse_glr](super::BackusNaurForm::parse_glr).
+///More information can be found in the [parse_forest](self) module.
+#[derive(PartialEq, Clone, Debug)]
+pub struct ParseForest {
+    nodes: Vec<ForestNode>,
+    root: usize,
+    hidden_symbols: HashSet<String>,
+    //the number of derivations of every node, None if the number is too big for a u128
+    counts: Vec<Option<u128>>,
+}
+
+//Used by the GLR parser to build the forest.
+#[derive(Default)]
+pub(crate) struct ForestBuilder {
+    nodes: Vec<ForestNode>,
+    indexes: HashMap<(ForestSymbol, usize, usize), usize>,
+}
+
+impl ForestBuilder {
+    ///Returns the node of the symbol that spans start..end and creates it if it doesn't exist yet.
+    pub(crate) fn node(&mut self, symbol: ForestSymbol, start: usize, end: usize) -> usize {
+        let nodes = &mut self.nodes;
+        *self
+            .indexes
+            .entry((symbol.clone(), start, end))
+            .or_insert_with(|| {
+                nodes.push(ForestNode {
+                    symbol,
+                    alternatives: vec![],
+                });
+                nodes.len() - 1
+            })
+    }
+
+    ///Returns the node of the symbol that spans start..end if it exists.
+    pub(crate) fn find(&self, symbol: ForestSymbol, start: usize, end: usize) -> Option<usize> {
+        self.indexes.get(&(symbol, start, end)).copied()
+    }
+
+    ///Adds a alternative to the node unless the node already has it.
+    pub(crate) fn add_alternative(&mut self, node: usize, children: Vec<usize>) {
+        let alternatives = &mut self.nodes[node].alternatives;
+        if !alternatives.contains(&children) {
+            alternatives.push(children);
+        }
+    }
+
+    ///Creates the forest of the root node.
+    ///Alternatives that lead back to a node they are part of (through a cycle of unit rules like `<a> ::= <b>` and `<b> ::= <a>`) are removed,
+    ///since they would make infinitely many derivations.
+    pub(crate) fn build(self, root: usize, hidden_symbols: HashSet<String>) -> ParseForest {
+        let mut nodes = self.nodes;
+        let mut visited = vec![false; nodes.len()];
+        remove_cycles(&mut nodes, root, &mut visited, &mut vec![]);
+        let mut counts = vec![None; nodes.len()];
+        let mut counted = vec![false; nodes.len()];
+        count(&nodes, root, &mut counts, &mut counted);
+        ParseForest {
+            nodes,
+            root,
+            hidden_symbols,
+            counts,
+        }
+    }
+}
+
+//Removes the alternatives of the nodes reachable from node that contain a node on the path.
+fn remove_cycles(
+    nodes: &mut [ForestNode],
+    node: usize,
+    visited: &mut [bool],
+    path: &mut Vec<usize>,
+) {
+    if visited[node] {
+        return;
+    }
+    visited[node] = true;
+    path.push(node);
+    let alternatives = std::mem::take(&mut nodes[node].alternatives)
+        .into_iter()
+        .filter(|children| !children.iter().any(|child| path.contains(child)))
+        .collect::<Vec<_>>();
+    for child in alternatives.iter().flatten() {
+        remove_cycles(nodes, *child, visited, path);
+    }
+    nodes[node].alternatives = alternatives;
+    path.pop();
+}
+
+//Counts the derivations of the node and every node below it.
+fn count(
+    nodes: &[ForestNode],
+    node: usize,
+    counts: &mut [Option<u128>],
+    counted: &mut [bool],
+) -> Option<u128> {
+    if counted[node] {
+        return counts[node];
+    }
+    let result = match nodes[node].symbol {
+        ForestSymbol::Terminal(_) => Some(1),
+        ForestSymbol::NonTerminal(_) => nodes[node]
+            .alternatives
+            .iter()
+            .map(|children| {
+                children.iter().try_fold(1u128, |product, child| {
+                    product.checked_mul(count(nodes, *child, counts, counted)?)
+                })
+            })
+            .try_fold(0u128, |sum, alternative| sum.checked_add(alternative?)),
+    };
+    counts[node] = result;
+    counted[node] = true;
+    result
+}
+
+impl ParseForest {
+    ///Returns the number of nodes in the forest.
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    ///Returns the number of distinct ASTs in the forest. Returns None if the number is too big for a u128.
+    pub fn derivation_count(&self) -> Option<u128> {
+        self.counts[self.root]
+    }
+
+    ///Returns true if the forest contains more than one AST.
+    pub fn is_ambiguous(&self) -> bool {
+        self.derivation_count() != Some(1)
+    }
+
+    ///Returns a iterator over every AST in the forest.
+    ///Like [BackusNaurForm::symbolize_string](super::BackusNaurForm::symbolize_string), every AST is a vector since hidden symbols are removed.
+    ///If there are more ASTs than fit in a u128, only the first u128::MAX ASTs are returned.
+    pub fn derivations(&self) -> Derivations<'_> {
+        Derivations {
+            forest: self,
+            index: 0,
+        }
+    }
+
+    //Returns the number of derivations of the node, u128::MAX if the number is too big.
+    fn saturated_count(&self, node: usize) -> u128 {
+        self.counts[node].unwrap_or(u128::MAX)
+    }
+
+    //Builds the derivation of the node with the index. Every derivation of a node has its own index.
+    fn build(&self, node: usize, mut index: u128) -> Token {
+        let name = match &self.nodes[node].symbol {
+            ForestSymbol::Terminal(ch) => return Token::from_terminal(&ch.to_string()),
+            ForestSymbol::NonTerminal(name) => name,
+        };
+        for children in &self.nodes[node].alternatives {
+            let alternative_count = children.iter().fold(1u128, |product, child| {
+                product.saturating_mul(self.saturated_count(*child))
+            });
+            if index >= alternative_count {
+                index -= alternative_count;
+                continue;
+            }
+            //the index of every child is a digit of the index of the alternative
+            let child_tokens = children
+                .iter()
+                .map(|child| {
+                    let child_count = self.saturated_count(*child);
+                    let child_index = index % child_count;
+                    index /= child_count;
+                    self.build(*child, child_index)
+                })
+                .collect();
+            return Token::from_non_terminal(name, child_tokens);
+        }
+        unreachable!("the index is smaller than the number of derivations of the node")
+    }
+}
+
+///A iterator over the ASTs of a [ParseForest]. Created with [ParseForest::derivations].
+pub struct Derivations<'a> {
+    forest: &'a ParseForest,
+    index: u128,
+}
+
+impl Iterator for Derivations<'_> {
+    type Item = Vec<Token>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.index >= self.forest.saturated_count(self.forest.root) {
+            return None;
+        }
+        let tree = self.forest.build(self.forest.root, self.index);
+        self.index += 1;
+        Some(remove_hidden_tokens(vec![tree], &|name| {
+            name.starts_with('_') || self.forest.hidden_symbols.contains(name)
+        }))
+    }
+}
@@ -0,0 +1,254 @@
+//! Chains the steps that turn a input into a result, instead of symbolizing, walking and compiling by hand:
+//! ```rust, ignore
+//! let pipeline = Pipeline::new(&bnf)
+//!     //removes the whitespace tokens
+//!     .then_transform(|tokens| Ok::<_, String>(strip_whitespace(tokens)))
+//!     //checks that every variable is declared
+//!     .then_analyze(|tokens| declared_variables(tokens))
+//!     .then_compile(|bnf, analyzed: Analyzed<Vec<Token>, HashSet<String>>| Ok::<_, String>(bnf.compile_tokens(&analyzed.artifact)));
+//! let output = pipeline.run("let x = 1; x + 1")?;
+//! ```
+//! Every step receives the artifact of the step before it and returns the artifact of the next step (or a error), so the types of the artifacts are checked at compile time.
+//! The first step symbolizes the input into a `Vec<Token>`. The first error stops the pipeline and is returned as a [PipelineError] that tells which step failed.
+//! [SourceTarget] is a compile target that compiles the tokens with the compile functions of the [BackusNaurForm].
+
+use std::{error::Error, fmt::Display};
+
+use super::{error::SymbolizeError, token::Token, BackusNaurForm};
+
+///Errors that can occur while running a [Pipeline].
+///The steps after the symbolization are counted from 1, in the order they were added.
+#[derive(PartialEq, Debug, Clone)]
+pub enum PipelineError {
+    ///The input could not be symbolized.
+    Symbolize(SymbolizeError),
+    ///A step added with [then_transform](Pipeline::then_transform) failed.
+    Transform { step: usize, message: String },
+    ///A step added with [then_analyze](Pipeline::then_analyze) failed.
+    Analyze { step: usize, message: String },
+    ///A step added with [then_compile](Pipeline::then_compile) failed.
+    Compile { step: usize, message: String },
+}
+
+impl Display for PipelineError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PipelineError::Symbolize(error) => write!(f, "{error}"),
+            PipelineError::Transform { step, message } => {
+                write!(f, "the transformation in step {step} failed: {message}")
+            }
+            PipelineError::Analyze { step, message } => {
+                write!(f, "the analysis in step {step} failed: {message}")
+            }
+            PipelineError::Compile { step, message } => {
+                write!(f, "the compilation in step {step} failed: {message}")
+            }
+        }
+    }
+}
+
+impl Error for PipelineError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            PipelineError::Symbolize(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+///The artifact of a step together with the result of analyzing it.
+#[derive(PartialEq, Debug, Clone)]
+pub struct Analyzed<T, A> {
+    pub artifact: T,
+    pub analysis: A,
+}
+
+///Something that compiles the artifact of a [Pipeline] into its output.
+///Every closure that takes the [BackusNaurForm] and the artifact and returns a [Result] is a compile target.
+pub trait CompileTarget<T> {
+    type Output;
+    type Error: Display;
+
+    fn compile(&self, bnf: &BackusNaurForm<'_>, artifact: T) -> Result<Self::Output, Self::Error>;
+}
+
+impl<T, O, E, F> CompileTarget<T> for F
+where
+    F: Fn(&BackusNaurForm<'_>, T) -> Result<O, E>,
+    E: Display,
+{
+    type Output = O;
+    type Error = E;
+
+    fn compile(&self, bnf: &BackusNaurForm<'_>, artifact: T) -> Result<O, E> {
+        self(bnf, artifact)
+    }
+}
+
+///Compiles the tokens with [compile_tokens](BackusNaurForm::compile_tokens), so with the compile functions of the [BackusNaurForm].
+#[derive(PartialEq, Debug, Clone, Copy, Default)]
+pub struct SourceTarget;
+
+impl CompileTarget<Vec<Token>> for SourceTarget {
+    type Output = String;
+    type Error = std::convert::Infallible;
+
+    fn compile(
+        &self,
+        bnf: &BackusNaurForm<'_>,
+        artifact: Vec<Token>,
+    ) -> Result<String, Self::Error> {
+        Ok(bnf.compile_tokens(&artifact))
+    }
+}
+
+//A step of a pipeline that produces a artifact of type T from the input.
+type Step<'p, T> = Box<dyn Fn(&str) -> Result<T, PipelineError> + 'p>;
+
+///The steps that turn a input into a artifact of type T. More information can be found in the [pipeline](self) module.
+pub struct Pipeline<'p, T> {
+    bnf: &'p BackusNaurForm<'p>,
+    run: Step<'p, T>,
+    //the amount of steps after the symbolization
+    steps: usize,
+}
+
+impl<'p> Pipeline<'p, Vec<Token>> {
+    ///Creates a pipeline that symbolizes the input with [try_symbolize_string](BackusNaurForm::try_symbolize_string).
+    pub fn new(bnf: &'p BackusNaurForm<'p>) -> Self {
+        Self {
+            bnf,
+            run: Box::new(move |input| {
+                bnf.try_symbolize_string(input)
+                    .map_err(PipelineError::Symbolize)
+            }),
+            steps: 0,
+        }
+    }
+}
+
+impl<'p, T: 'p> Pipeline<'p, T> {
+    ///Adds a step that turns the artifact into a new one, for example to simplify the tokens.
+    pub fn then_transform<U, E, F>(self, transform: F) -> Pipeline<'p, U>
+    where
+        F: Fn(T) -> Result<U, E> + 'p,
+        E: Display,
+    {
+        let step = self.steps + 1;
+        let run = self.run;
+        Pipeline {
+            bnf: self.bnf,
+            run: Box::new(move |input| {
+                transform(run(input)?).map_err(|error| PipelineError::Transform {
+                    step,
+                    message: error.to_string(),
+                })
+            }),
+            steps: step,
+        }
+    }
+
+    ///Adds a step that analyzes the artifact without changing it, for example to check the types.
+    ///The next step receives the artifact together with the analysis.
+    pub fn then_analyze<A, E, F>(self, analyze: F) -> Pipeline<'p, Analyzed<T, A>>
+    where
+        F: Fn(&T) -> Result<A, E> + 'p,
+        E: Display,
+    {
+        let step = self.steps + 1;
+        let run = self.run;
+        Pipeline {
+            bnf: self.bnf,
+            run: Box::new(move |input| {
+                let artifact = run(input)?;
+                let analysis = analyze(&artifact).map_err(|error| PipelineError::Analyze {
+                    step,
+                    message: error.to_string(),
+                })?;
+                Ok(Analyzed { artifact, analysis })
+            }),
+            steps: step,
+        }
+    }
+
+    ///Adds a step that compiles the artifact with the target.
+    pub fn then_compile<C>(self, target: C) -> Pipeline<'p, C::Output>
+    where
+        C: CompileTarget<T> + 'p,
+    {
+        let step = self.steps + 1;
+        let run = self.run;
+        let bnf = self.bnf;
+        Pipeline {
+            bnf,
+            run: Box::new(move |input| {
+                target
+                    .compile(bnf, run(input)?)
+                    .map_err(|error| PipelineError::Compile {
+                        step,
+                        message: error.to_string(),
+                    })
+            }),
+            steps: step,
+        }
+    }
+
+    ///Runs every step on the input and returns the artifact of the last one.
+    pub fn run(&self, input: &str) -> Result<T, PipelineError> {
+        (self.run)(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::backus_naur_form;
+
+    use super::*;
+
+    #[test]
+    fn test_pipeline() {
+        let bnf = backus_naur_form!(
+            priority 1 => r#"<digit> ::= "1" | "2" | "3""# => |digit, _| {
+                format!("[{}]", digit.get_terminals())
+            }
+            priority 0 => r#"<whitespace> ::= " ""#
+        );
+        let pipeline = Pipeline::new(&bnf)
+            .then_transform(|tokens: Vec<Token>| {
+                Ok::<_, String>(
+                    tokens
+                        .into_iter()
+                        .filter(|token| token.get_symbol() != "whitespace")
+                        .collect::<Vec<_>>(),
+                )
+            })
+            .then_analyze(
+                |tokens| match tokens.iter().find(|token| token.is_terminal()) {
+                    Some(token) => Err(format!("unexpected {}", token.get_terminals())),
+                    None => Ok(tokens.len()),
+                },
+            )
+            .then_compile(
+                |bnf: &BackusNaurForm<'_>, analyzed: Analyzed<Vec<Token>, usize>| {
+                    Ok::<_, String>(format!(
+                        "{} digits: {}",
+                        analyzed.analysis,
+                        SourceTarget.compile(bnf, analyzed.artifact).unwrap()
+                    ))
+                },
+            );
+
+        assert_eq!(pipeline.run("1 2 3"), Ok("3 digits: [1][2][3]".to_string()));
+        assert_eq!(
+            pipeline.run("1 x"),
+            Err(PipelineError::Analyze {
+                step: 2,
+                message: "unexpected x".to_string()
+            })
+        );
+        assert_eq!(
+            Pipeline::new(&bnf).then_compile(SourceTarget).run("1 2"),
+            Ok("[1] [2]".to_string())
+        );
+    }
+}
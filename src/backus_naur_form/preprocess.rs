@@ -0,0 +1,261 @@
+//! Preprocessors change the input before it is symbolized, for example to normalize line endings or to strip a byte order mark:
+//! ```rust, ignore
+//! bnf.add_preprocessor(strip_bom)
+//!     .add_preprocessor(normalize_line_endings)
+//!     .add_preprocessor(expand_tabs(4));
+//! ```
+//! Every preprocessor gets the output of the one before it and returns the [Replacement]s it wants to make.
+//! Since the preprocessors only describe what they replace, every position in the preprocessed input can be mapped back into the original input.
+//! The positions in errors ([SymbolizeError] and [ParseError](super::error::ParseError)) and the input ranges of
+//! [source maps](super::source_map) point into the original input, but the terminals of the [Token](super::token::Token)s
+//! contain the preprocessed input.
+
+use std::ops::Range;
+
+use super::{error::SymbolizeError, BackusNaurForm};
+
+///Replaces the chars in the range (char indexes, not byte offsets) with the text.
+///A empty range inserts the text and a empty text removes the chars.
+#[derive(PartialEq, Debug, Clone)]
+pub struct Replacement {
+    pub range: Range<usize>,
+    pub text: String,
+}
+
+impl Replacement {
+    pub fn new(range: Range<usize>, text: &str) -> Self {
+        Self {
+            range,
+            text: text.to_string(),
+        }
+    }
+}
+
+///A function that returns the [Replacement]s it makes to the input.
+///The replacements have to be sorted by their start and they can't overlap.
+pub type Preprocessor<'a> = Box<dyn Fn(&str) -> Vec<Replacement> + 'a>;
+
+///A preprocessed input that knows where its chars came from in the original input.
+#[derive(PartialEq, Debug, Clone)]
+pub struct Preprocessed {
+    text: String,
+    //the position in the original input of every char of the text and of the end of the text.
+    //None if the text is the original input.
+    original_positions: Option<Vec<usize>>,
+}
+
+impl Preprocessed {
+    ///Returns the preprocessed input.
+    pub fn get_text(&self) -> &str {
+        &self.text
+    }
+
+    ///Returns the char index in the original input that the char index in the preprocessed input came from.
+    ///Chars that a [Replacement] inserted map to the start of the replaced range.
+    pub fn original_position(&self, position: usize) -> usize {
+        match &self.original_positions {
+            Some(original_positions) => original_positions[position],
+            None => position,
+        }
+    }
+
+    ///Returns the range of chars in the original input that the range of chars in the preprocessed input came from.
+    pub fn original_range(&self, range: Range<usize>) -> Range<usize> {
+        self.original_position(range.start)..self.original_position(range.end)
+    }
+
+    ///The same as [original_range](Preprocessed::original_range) but for byte offsets.
+    ///original has to be the input that was preprocessed.
+    pub fn original_byte_range(&self, original: &str, range: Range<usize>) -> Range<usize> {
+        if self.original_positions.is_none() {
+            return range;
+        }
+        let char_position = |byte: usize| self.text[..byte].chars().count();
+        let byte_offset = |position: usize| {
+            original
+                .char_indices()
+                .nth(position)
+                .map_or(original.len(), |(byte, _)| byte)
+        };
+        let original_range =
+            self.original_range(char_position(range.start)..char_position(range.end));
+        byte_offset(original_range.start)..byte_offset(original_range.end)
+    }
+
+    //Returns the error with its positions mapped into the original input.
+    pub(crate) fn map_error(&self, error: SymbolizeError) -> SymbolizeError {
+        match error {
+            SymbolizeError::MismatchedBracket {
+                expected,
+                found,
+                position,
+            } => SymbolizeError::MismatchedBracket {
+                expected,
+                found,
+                position: self.original_position(position),
+            },
+            SymbolizeError::UnexpectedClosingBracket { bracket, position } => {
+                SymbolizeError::UnexpectedClosingBracket {
+                    bracket,
+                    position: self.original_position(position),
+                }
+            }
+            SymbolizeError::UnclosedBracket { bracket, position } => {
+                SymbolizeError::UnclosedBracket {
+                    bracket,
+                    position: self.original_position(position),
+                }
+            }
+            error => error,
+        }
+    }
+
+    //Applies the replacements of a preprocessor to the text.
+    fn replace(self, replacements: &[Replacement]) -> Self {
+        let chars = self.text.chars().collect::<Vec<_>>();
+        let mut text = String::with_capacity(self.text.len());
+        let mut original_positions = Vec::with_capacity(chars.len() + 1);
+        let mut position = 0;
+        for replacement in replacements {
+            assert!(
+                position <= replacement.range.start && replacement.range.end <= chars.len(),
+                "the replacements of a preprocessor have to be sorted, can't overlap and have to be inside of the input"
+            );
+            for (offset, char) in chars[position..replacement.range.start].iter().enumerate() {
+                text.push(*char);
+                original_positions.push(self.original_position(position + offset));
+            }
+            let replaced_start = self.original_position(replacement.range.start);
+            for char in replacement.text.chars() {
+                text.push(char);
+                original_positions.push(replaced_start);
+            }
+            position = replacement.range.end;
+        }
+        for (offset, char) in chars[position..].iter().enumerate() {
+            text.push(*char);
+            original_positions.push(self.original_position(position + offset));
+        }
+        original_positions.push(self.original_position(chars.len()));
+        Self {
+            text,
+            original_positions: Some(original_positions),
+        }
+    }
+}
+
+///Removes the byte order mark at the start of the input.
+pub fn strip_bom(input: &str) -> Vec<Replacement> {
+    if input.starts_with('\u{feff}') {
+        vec![Replacement::new(0..1, "")]
+    } else {
+        vec![]
+    }
+}
+
+///Turns every `\r\n` and every single `\r` into `\n`.
+pub fn normalize_line_endings(input: &str) -> Vec<Replacement> {
+    let chars = input.chars().collect::<Vec<_>>();
+    chars
+        .iter()
+        .enumerate()
+        .filter(|(_, char)| **char == '\r')
+        .map(|(position, _)| {
+            let end = if chars.get(position + 1) == Some(&'\n') {
+                position + 2
+            } else {
+                position + 1
+            };
+            Replacement::new(position..end, "\n")
+        })
+        .collect()
+}
+
+///Returns a preprocessor that replaces every tab with spaces up to the next tab stop. Tab stops are width columns apart.
+pub fn expand_tabs(width: usize) -> impl Fn(&str) -> Vec<Replacement> {
+    move |input| {
+        let mut replacements = vec![];
+        let mut column = 0;
+        for (position, char) in input.chars().enumerate() {
+            match char {
+                '\t' => {
+                    let spaces = width - column % width;
+                    replacements.push(Replacement::new(
+                        position..position + 1,
+                        &" ".repeat(spaces),
+                    ));
+                    column += spaces;
+                }
+                '\n' => column = 0,
+                _ => column += 1,
+            }
+        }
+        replacements
+    }
+}
+
+impl<'a> BackusNaurForm<'a> {
+    ///Adds a preprocessor that runs after the ones that were added before. More information can be found in the [preprocess](self) module.
+    ///Returns the [BackusNaurForm] so several preprocessors can be added in a chain.
+    pub fn add_preprocessor<F>(&mut self, preprocessor: F) -> &mut Self
+    where
+        F: Fn(&str) -> Vec<Replacement> + 'a,
+    {
+        self.preprocessors.push(Box::new(preprocessor));
+        self
+    }
+
+    ///Runs every preprocessor on the string, in the order they were added.
+    pub fn preprocess(&self, string: &str) -> Preprocessed {
+        self.preprocessors.iter().fold(
+            Preprocessed {
+                text: string.to_string(),
+                original_positions: None,
+            },
+            |preprocessed, preprocessor| {
+                let replacements = preprocessor(&preprocessed.text);
+                if replacements.is_empty() {
+                    preprocessed
+                } else {
+                    preprocessed.replace(&replacements)
+                }
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{backus_naur_form, ParseError};
+
+    use super::*;
+
+    #[test]
+    fn test_preprocessors() {
+        let mut bnf = backus_naur_form!(
+            //not a raw string, the terminals are line breaks
+            priority 1 => "<line> ::= \"a\" \"\n\" | \"b\" \"\n\""
+            priority 0 => r#"<lines> ::= <line> | <lines> <lines>"#
+        );
+        bnf.add_preprocessor(strip_bom)
+            .add_preprocessor(normalize_line_endings)
+            .add_preprocessor(expand_tabs(4));
+
+        let input = "\u{feff}a\r\nb\r\n";
+        let preprocessed = bnf.preprocess(input);
+        assert_eq!(preprocessed.get_text(), "a\nb\n");
+        assert_eq!(preprocessed.original_range(1..3), 2..5);
+        assert_eq!(preprocessed.original_byte_range(input, 1..3), 4..7);
+        assert_eq!(bnf.parse(input).unwrap().get_terminals(), "a\nb\n");
+
+        //the span of the trailing input points into the original input
+        assert_eq!(
+            bnf.parse("\u{feff}a\r\nb\r\n\tc"),
+            Err(ParseError::TrailingInput {
+                root: "lines".to_string(),
+                span: 7..9
+            })
+        );
+        assert_eq!(bnf.preprocess("ab\tc\n\td").get_text(), "ab  c\n    d");
+    }
+}
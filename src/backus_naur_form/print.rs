@@ -0,0 +1,144 @@
+//! Prints the rules of a [BackusNaurForm] as a grammar document (see the [grammar](super::grammar) module),
+//! so grammars that are built in code can be exported, reviewed and loaded again:
+//! ```text
+//! ## A decimal digit
+//! priority 1 => <digit> ::= "0" | "1" | "2" | "3" | "4"
+//!                         | "5" | "6" | "7" | "8" | "9"
+//! <number> ::= <digit> | <number> <number>
+//! ```
+//! Choices are added to a line until it would be longer than the column width, the next choice starts a continuation line
+//! whose `|` is aligned with the `=` of the replacement operator. A choice that is longer than the width on its own gets a line of its own.
+//! Since every rule is printed the same way, a changed choice only changes the lines it is on, which keeps diffs of exported grammars small.
+//!
+//! The priorities (unless they are 0), the node labels, the doc strings, `@inline` and the weights of the choices are printed too.
+//! Terminals that are a single control char or a double quote are printed as byte values like `%x0A`.
+
+use super::{symbol::Symbol, BackusNaurForm, Choice};
+
+///The column width of the [Debug] output of a [BackusNaurForm].
+pub(super) const DEFAULT_WIDTH: usize = 80;
+
+impl BackusNaurForm<'_> {
+    ///Returns the rules as a grammar document whose lines are at most width chars long (unless a single choice is longer).
+    ///More information can be found in the [print](self) module.
+    pub fn to_bnf_string(&self, width: usize) -> String {
+        let mut printed = String::new();
+        for (non_terminal_symbol, priority) in &self.rules {
+            let name = non_terminal_symbol.get_name();
+            if let Some(doc) = self.rule_docs.get(name) {
+                for line in doc.lines() {
+                    printed.push_str(&format!("## {line}\n"));
+                }
+            }
+
+            let mut head = match priority {
+                0 => String::new(),
+                priority => format!("priority {priority} => "),
+            };
+            head.push_str(&format!("<{name}> "));
+            if let Some(label) = self.node_labels.get(name) {
+                head.push_str(&format!("-> {label} "));
+            }
+            //the pipes of the continuation lines are below the `=` of `::=`
+            let indent = head.chars().count() + 2;
+            head.push_str("::= ");
+
+            let inline_choices = self.inline_choices.get(name);
+            let mut line = head;
+            for (index, choice) in non_terminal_symbol.get_rule().iter().enumerate() {
+                let is_inline = inline_choices.is_some_and(|choices| choices.contains(choice));
+                let weight = self.choice_weight(name, choice);
+                let choice = print_choice(choice, is_inline, weight);
+                if index == 0 {
+                    line.push_str(&choice);
+                } else if line.chars().count() + 3 + choice.chars().count() <= width {
+                    line.push_str(&format!(" | {choice}"));
+                } else {
+                    printed.push_str(&line);
+                    printed.push('\n');
+                    line = format!("{}| {choice}", " ".repeat(indent));
+                }
+            }
+            printed.push_str(&line);
+            printed.push('\n');
+        }
+        printed
+    }
+}
+
+//Returns the symbols of the choice separated by spaces, `ε` if it is empty.
+fn print_choice(choice: &Choice, is_inline: bool, weight: Option<f64>) -> String {
+    let symbols = choice
+        .iter()
+        .map(|symbol| match symbol {
+            Symbol::NonTerminal(name) => format!("<{name}>"),
+            Symbol::Terminal(terminal) => print_terminal(terminal),
+        })
+        .collect::<Vec<_>>();
+    let symbols = if symbols.is_empty() {
+        "ε".to_string()
+    } else {
+        symbols.join(" ")
+    };
+    let symbols = if is_inline {
+        format!("@inline {symbols}")
+    } else {
+        symbols
+    };
+    match weight {
+        Some(weight) => format!("{weight}% {symbols}"),
+        None => symbols,
+    }
+}
+
+//Returns the terminal in double quotes, or as a byte value if it can't be written in double quotes.
+fn print_terminal(terminal: &str) -> String {
+    let mut chars = terminal.chars();
+    match (chars.next(), chars.next()) {
+        (Some(char), None) if char == '"' || (char.is_control() && u32::from(char) <= 0xFF) => {
+            format!("%x{:02X}", u32::from(char))
+        }
+        _ => format!("\"{terminal}\""),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backus_naur_form::grammar::FileResolver;
+
+    #[test]
+    fn test_to_bnf_string() {
+        let mut bnf = crate::backus_naur_form!(
+            priority 1 => r#"<digit> ::= "0" | "1" | "2" | "3" | "4" | "5" | "6" | "7" | "8" | "9""#
+            priority 0 => r#"<number> -> Num ::= @inline <digit> | <number> <number>"#
+            priority 0 => r#"<line> ::= <number> %x0A | 2.5% <number> %x22 | ε"#
+        );
+        bnf.set_rule_doc("digit", "A decimal digit\nfrom 0 to 9");
+        let printed = bnf.to_bnf_string(55);
+        assert_eq!(
+            printed,
+            r#"## A decimal digit
+## from 0 to 9
+priority 1 => <digit> ::= "0" | "1" | "2" | "3" | "4"
+                        | "5" | "6" | "7" | "8" | "9"
+<number> -> Num ::= @inline <digit> | <number> <number>
+<line> ::= <number> %x0A | 2.5% <number> %x22 | ε
+"#
+        );
+
+        //every choice gets its own line if the width is too small for two
+        assert_eq!(
+            bnf.to_bnf_string(0).lines().nth(3),
+            Some(r#"                        | "1""#)
+        );
+
+        let loaded = BackusNaurForm::from_grammar_document(&printed, &FileResolver::new()).unwrap();
+        assert_eq!(loaded, bnf);
+        assert_eq!(
+            loaded.choice_weight("line", &bnf.rule("line").unwrap().get_expression()[1]),
+            Some(2.5)
+        );
+        assert_eq!(loaded.to_bnf_string(55), printed);
+    }
+}
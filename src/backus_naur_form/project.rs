@@ -0,0 +1,255 @@
+//! Parses several inputs with the same grammar, for DSLs where the definitions in one file are referenced in another:
+//! ```rust, ignore
+//! let project = bnf.parse_project(&[Source::new("main.dsl", &main), Source::new("lib.dsl", &lib)]);
+//! let definitions = project.index_symbols(|token| {
+//!     match_token!(token.to_non_terminal_ref()? => definition("=", name, ";") => name.get_terminals())
+//! });
+//! for location in definitions.get("helper").into_iter().flatten() {
+//!     println!("helper is defined in {} at {:?}", location.source, location.span);
+//! }
+//! ```
+//! Every tree of a [ProjectAst] is tagged with the id of its [Source]. A source that can't be symbolized doesn't stop the others,
+//! its [SymbolizeError] is kept in its [SourceAst].
+//!
+//! [index_symbols](ProjectAst::index_symbols) is the hook for cross-file references: it is called with every non terminal token
+//! of every source and the names it returns are collected with the [SymbolLocation]s of their tokens.
+
+use std::{collections::HashMap, ops::Range};
+
+use super::{
+    error::SymbolizeError, preprocess::Preprocessed, rewrite::byte_span, token::TokenIndex,
+    BackusNaurForm, Token,
+};
+
+///A input of a project and the id it is referred to by, for example its path.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Source {
+    pub id: String,
+    pub text: String,
+}
+
+impl Source {
+    pub fn new(id: &str, text: &str) -> Self {
+        Self {
+            id: id.to_string(),
+            text: text.to_string(),
+        }
+    }
+}
+
+///The uppermost tokens of a [Source], or the error if it couldn't be symbolized.
+#[derive(PartialEq, Debug, Clone)]
+pub struct SourceAst {
+    id: String,
+    text: String,
+    tokens: Result<Vec<Token>, SymbolizeError>,
+    //maps the spans of the tokens into the text
+    preprocessed: Preprocessed,
+}
+
+impl SourceAst {
+    ///Returns the id of the [Source] the tokens were made from.
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    ///Returns the text of the [Source].
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    ///Returns the uppermost tokens, or the error if the text couldn't be symbolized.
+    pub fn tokens(&self) -> Result<&[Token], &SymbolizeError> {
+        self.tokens.as_deref()
+    }
+
+    ///Returns the token at the index, whose first index selects the uppermost token (like in the [rewrite](super::rewrite) module).
+    ///Returns None if there is no token at the index or if the text couldn't be symbolized.
+    pub fn get(&self, index: &TokenIndex) -> Option<&Token> {
+        let tokens = self.tokens.as_ref().ok()?;
+        let (first, rest) = index.indexes().split_first()?;
+        let token = tokens.get(*first)?;
+        if rest.is_empty() {
+            Some(token)
+        } else {
+            token.get(&TokenIndex::from(rest.to_vec()))
+        }
+    }
+
+    ///Returns the byte range of the text that the token at the index was made from.
+    ///If preprocessors are set, the range is mapped into the original text.
+    pub fn span(&self, index: &TokenIndex) -> Option<Range<usize>> {
+        let tokens = self.tokens.as_ref().ok()?;
+        let span = byte_span(tokens, index.indexes())?;
+        Some(self.preprocessed.original_byte_range(&self.text, span))
+    }
+}
+
+///A token that [index_symbols](ProjectAst::index_symbols) returned a name for.
+#[derive(PartialEq, Debug, Clone)]
+pub struct SymbolLocation {
+    ///The id of the [Source] the token is in.
+    pub source: String,
+    ///The index of the token in its [SourceAst].
+    pub index: TokenIndex,
+    ///The byte range of the text of the [Source] the token was made from.
+    pub span: Range<usize>,
+}
+
+///The trees of every [Source] of a project, returned by [parse_project](BackusNaurForm::parse_project).
+///More information can be found in the [project](self) module.
+#[derive(PartialEq, Debug, Clone)]
+pub struct ProjectAst {
+    sources: Vec<SourceAst>,
+}
+
+impl ProjectAst {
+    ///Returns the trees in the order of the sources.
+    pub fn sources(&self) -> &[SourceAst] {
+        &self.sources
+    }
+
+    ///Returns the tree of the source with the id, None if there is no such source.
+    pub fn source(&self, id: &str) -> Option<&SourceAst> {
+        self.sources.iter().find(|source| source.id == id)
+    }
+
+    ///Returns the ids of the sources that couldn't be symbolized with their errors.
+    pub fn errors(&self) -> Vec<(&str, &SymbolizeError)> {
+        self.sources
+            .iter()
+            .filter_map(|source| Some((source.id(), source.tokens().err()?)))
+            .collect()
+    }
+
+    ///Returns the token at the location, None if the location doesn't belong to this project.
+    pub fn resolve(&self, location: &SymbolLocation) -> Option<&Token> {
+        self.source(&location.source)?.get(&location.index)
+    }
+
+    ///Calls name_of with every non terminal token of every source (the parents before their children)
+    ///and returns the locations of the tokens it returned a name for, keyed by the name.
+    ///The locations of a name are in the order of the sources and of the tokens.
+    pub fn index_symbols<F>(&self, mut name_of: F) -> HashMap<String, Vec<SymbolLocation>>
+    where
+        F: FnMut(&Token) -> Option<String>,
+    {
+        let mut index = HashMap::new();
+        for source in &self.sources {
+            let Ok(tokens) = source.tokens() else {
+                continue;
+            };
+            let mut path = vec![];
+            index_tokens(source, tokens, &mut path, &mut name_of, &mut index);
+        }
+        index
+    }
+}
+
+//Adds the tokens that name_of returns a name for and their descendants to the index, path is the index of the parent of the tokens.
+fn index_tokens<F>(
+    source: &SourceAst,
+    tokens: &[Token],
+    path: &mut Vec<usize>,
+    name_of: &mut F,
+    index: &mut HashMap<String, Vec<SymbolLocation>>,
+) where
+    F: FnMut(&Token) -> Option<String>,
+{
+    for (position, token) in tokens.iter().enumerate() {
+        let Token::NonTerminalToken(non_terminal) = token else {
+            continue;
+        };
+        path.push(position);
+        if let Some(name) = name_of(token) {
+            let token_index = TokenIndex::from(path.clone());
+            let span = source
+                .span(&token_index)
+                .expect("the path points to the token");
+            index.entry(name).or_default().push(SymbolLocation {
+                source: source.id.to_string(),
+                index: token_index,
+                span,
+            });
+        }
+        index_tokens(
+            source,
+            non_terminal.get_child_tokens(),
+            path,
+            name_of,
+            index,
+        );
+        path.pop();
+    }
+}
+
+impl BackusNaurForm<'_> {
+    ///Symbolizes every source with this grammar and tags the trees with the ids of the sources.
+    ///More information can be found in the [project](self) module.
+    pub fn parse_project(&self, sources: &[Source]) -> ProjectAst {
+        let sources = sources
+            .iter()
+            .map(|source| SourceAst {
+                id: source.id.to_string(),
+                text: source.text.to_string(),
+                tokens: self.try_symbolize_string(&source.text),
+                preprocessed: self.preprocess(&source.text),
+            })
+            .collect();
+        ProjectAst { sources }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_project() {
+        let mut bnf = crate::backus_naur_form!(
+            priority 3 => r#"<letter> ::= "a" | "b" | "c""#
+            priority 2 => r#"<name> ::= <letter> | <name> <name>"#
+            priority 1 => r#"<statement> ::= "=" <name> ";" | "@" <name> ";""#
+            priority 0 => r#"<program> ::= <statement> | <program> <program>"#
+        );
+        bnf.set_max_input_length(Some(13));
+        let project = bnf.parse_project(&[
+            Source::new("lib", "=ab;=c;"),
+            Source::new("main", "@ab;"),
+            Source::new("broken", "@abcabcabcabc;"),
+        ]);
+        assert_eq!(project.sources().len(), 3);
+        assert_eq!(project.source("main").unwrap().text(), "@ab;");
+        assert_eq!(
+            project.errors(),
+            vec![(
+                "broken",
+                &SymbolizeError::InputTooLong {
+                    length: 14,
+                    max: 13
+                }
+            )]
+        );
+
+        let definitions = project.index_symbols(|token| {
+            crate::match_token!(token.to_non_terminal_ref()? => statement("=", name, ";") => name.get_terminals())
+        });
+        assert_eq!(
+            definitions["ab"],
+            vec![SymbolLocation {
+                source: "lib".to_string(),
+                index: TokenIndex::from(vec![0, 0, 0]),
+                span: 0..4
+            }]
+        );
+        assert_eq!(definitions["c"][0].span, 4..7);
+        assert!(!definitions.contains_key("abcabcabcabc"));
+
+        let uses = project.index_symbols(|token| {
+            crate::match_token!(token.to_non_terminal_ref()? => statement("@", name, ";") => name.get_terminals())
+        });
+        let definition = project.resolve(&definitions["ab"][0]).unwrap();
+        assert_eq!(definition.get_terminals(), "=ab;");
+        assert_eq!(uses["ab"][0].source, "main");
+    }
+}
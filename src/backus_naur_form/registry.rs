@@ -0,0 +1,232 @@
+//! Stores several versions of several grammars (needs the `registry` feature).
+//! This is for applications that have to parse documents written against different versions of a DSL:
+//! ```rust, ignore
+//! let mut registry = GrammarRegistry::new();
+//! registry.register("config", Version::new(1, 0, 0), CompiledGrammar::compile_file("config-1.bnf", &resolver)?);
+//! registry.register("config", Version::new(1, 1, 0), CompiledGrammar::compile_file("config-1.1.bnf", &resolver)?);
+//! //the newest version that matches the requirement, 1.1.0
+//! let grammar = registry.get("config", &VersionReq::parse("^1.0")?).unwrap();
+//! ```
+//! Versions and requirements are the ones of the [semver] crate, they are reexported here.
+//!
+//! The symbols of every registered grammar are interned in one pool.
+//! Versions of a grammar usually share most of their symbols, so every symbol name is only stored once
+//! and two [RegisteredGrammar]s use the same symbol if their names point to the same [Arc].
+
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
+
+pub use semver::{Version, VersionReq};
+
+use super::{compiled::CompiledGrammar, symbol::Symbol};
+
+///A version of a grammar in a [GrammarRegistry].
+#[derive(Debug, Clone)]
+pub struct RegisteredGrammar {
+    version: Version,
+    grammar: Arc<CompiledGrammar>,
+    //the names of the terminal and non terminal symbols of the grammar, interned in the registry
+    symbols: Vec<Arc<str>>,
+}
+
+impl RegisteredGrammar {
+    pub fn get_version(&self) -> &Version {
+        &self.version
+    }
+
+    pub fn get_grammar(&self) -> &Arc<CompiledGrammar> {
+        &self.grammar
+    }
+
+    ///Returns the names of the terminal and non terminal symbols of the grammar, each name once, in the order they first appear in the rules.
+    pub fn get_symbols(&self) -> &[Arc<str>] {
+        &self.symbols
+    }
+}
+
+///Stores grammars by their name and version.
+#[derive(Debug, Clone, Default)]
+pub struct GrammarRegistry {
+    //the versions of every grammar, sorted from the oldest to the newest
+    grammars: HashMap<String, Vec<RegisteredGrammar>>,
+    symbols: HashSet<Arc<str>>,
+}
+
+impl GrammarRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    ///Adds the version of the grammar with the name.
+    ///If the registry already contains that version, it is replaced and the old one is returned.
+    pub fn register(
+        &mut self,
+        name: &str,
+        version: Version,
+        grammar: CompiledGrammar,
+    ) -> Option<RegisteredGrammar> {
+        let mut seen = HashSet::new();
+        let symbols = grammar
+            .get_symbols()
+            .map(|symbol| self.intern(&symbol))
+            .filter(|name| seen.insert(Arc::clone(name)))
+            .collect();
+        let registered = RegisteredGrammar {
+            version,
+            grammar: Arc::new(grammar),
+            symbols,
+        };
+
+        let versions = self.grammars.entry(name.to_string()).or_default();
+        match versions.binary_search_by(|other| other.version.cmp(&registered.version)) {
+            Ok(index) => Some(std::mem::replace(&mut versions[index], registered)),
+            Err(index) => {
+                versions.insert(index, registered);
+                None
+            }
+        }
+    }
+
+    ///Removes the version of the grammar with the name and returns it.
+    pub fn unregister(&mut self, name: &str, version: &Version) -> Option<RegisteredGrammar> {
+        let versions = self.grammars.get_mut(name)?;
+        let index = versions
+            .iter()
+            .position(|registered| &registered.version == version)?;
+        let removed = versions.remove(index);
+        if versions.is_empty() {
+            self.grammars.remove(name);
+        }
+        //symbols that no other grammar uses are only referenced by the pool and the removed grammar
+        let removed_symbols = removed
+            .symbols
+            .iter()
+            .map(Arc::as_ptr)
+            .collect::<HashSet<_>>();
+        self.symbols.retain(|symbol| {
+            Arc::strong_count(symbol) > 1 + removed_symbols.contains(&Arc::as_ptr(symbol)) as usize
+        });
+        Some(removed)
+    }
+
+    ///Returns the newest version of the grammar with the name that matches the requirement.
+    pub fn get(&self, name: &str, requirement: &VersionReq) -> Option<&RegisteredGrammar> {
+        self.versions(name)
+            .iter()
+            .rev()
+            .find(|registered| requirement.matches(&registered.version))
+    }
+
+    ///Returns exactly this version of the grammar with the name.
+    pub fn get_exact(&self, name: &str, version: &Version) -> Option<&RegisteredGrammar> {
+        self.versions(name)
+            .iter()
+            .find(|registered| &registered.version == version)
+    }
+
+    ///Returns every version of the grammar with the name, from the oldest to the newest.
+    pub fn versions(&self, name: &str) -> &[RegisteredGrammar] {
+        self.grammars.get(name).map_or(&[], |versions| versions)
+    }
+
+    ///Returns the names of the grammars in the registry.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.grammars.keys().map(|name| name.as_str())
+    }
+
+    ///Returns the interned symbol with the name if any registered grammar uses it.
+    pub fn get_symbol(&self, name: &str) -> Option<&Arc<str>> {
+        self.symbols.get(name)
+    }
+
+    //Returns the interned version of the symbol name.
+    fn intern(&mut self, symbol: &Symbol) -> Arc<str> {
+        let name = match symbol {
+            Symbol::Terminal(name) | Symbol::NonTerminal(name) => name.as_str(),
+        };
+        match self.symbols.get(name) {
+            Some(interned) => Arc::clone(interned),
+            None => {
+                let interned: Arc<str> = Arc::from(name);
+                self.symbols.insert(Arc::clone(&interned));
+                interned
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::backus_naur_form::test_helpers::DIGIT_RULE;
+    use std::collections::HashMap;
+
+    use super::*;
+
+    fn compile(document: &str) -> CompiledGrammar {
+        CompiledGrammar::compile(document, &HashMap::new()).unwrap()
+    }
+
+    #[test]
+    fn test_grammar_registry() {
+        let mut registry = GrammarRegistry::new();
+        let old = compile(r#"<digit> ::= "1""#);
+        let new = compile(DIGIT_RULE);
+        registry.register("digits", Version::new(1, 2, 0), new.clone());
+        registry.register("digits", Version::new(1, 0, 0), old.clone());
+        registry.register(
+            "digits",
+            Version::new(2, 0, 0),
+            compile(r#"<number> ::= "1""#),
+        );
+
+        let get = |requirement: &str| {
+            registry
+                .get("digits", &VersionReq::parse(requirement).unwrap())
+                .map(|registered| registered.get_version().to_string())
+        };
+        assert_eq!(get("^1.0").as_deref(), Some("1.2.0"));
+        assert_eq!(get("~1.0").as_deref(), Some("1.0.0"));
+        assert_eq!(get("*").as_deref(), Some("2.0.0"));
+        assert_eq!(get("^3"), None);
+        assert_eq!(
+            registry
+                .get_exact("digits", &Version::new(1, 0, 0))
+                .map(|registered| registered.get_grammar().as_ref()),
+            Some(&old)
+        );
+
+        //the versions share the symbols they have in common
+        let [first, second, third] = registry.versions("digits") else {
+            panic!("there are three versions");
+        };
+        assert!(Arc::ptr_eq(
+            &first.get_symbols()[0],
+            &second.get_symbols()[0]
+        ));
+        assert_eq!(
+            second
+                .get_symbols()
+                .iter()
+                .map(|symbol| symbol.as_ref())
+                .collect::<Vec<_>>(),
+            vec!["digit", "1", "2"]
+        );
+        assert!(Arc::ptr_eq(
+            &first.get_symbols()[1],
+            &third.get_symbols()[1]
+        ));
+
+        //symbols of removed grammars are removed from the pool
+        registry.unregister("digits", &Version::new(2, 0, 0));
+        assert!(registry.get_symbol("number").is_none());
+        assert!(registry.get_symbol("digit").is_some());
+        assert_eq!(
+            registry
+                .register("digits", Version::new(1, 0, 0), new)
+                .map(|registered| registered.get_grammar().as_ref().clone()),
+            Some(old)
+        );
+    }
+}
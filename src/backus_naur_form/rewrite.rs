@@ -0,0 +1,128 @@
+//! Rewrites parts of a input while leaving the rest of it byte for byte as it is, which is the basis of refactoring tools for a DSL:
+//! ```rust, ignore
+//! //renames the variable of the first statement
+//! let rewritten = bnf.rewrite("let x = 1; // keep me", vec![(TokenIndex::from(vec![0, 1]), "y".to_string())]);
+//! assert_eq!(rewritten, "let y = 1; // keep me");
+//! ```
+//! The first index of a [TokenIndex] selects the top level token (as returned by [symbolize_string](BackusNaurForm::symbolize_string)),
+//! the other indexes select the descendants like with [Token::get]. The replaced text of a token is the span of the input it was made from,
+//! so formatting and comments outside of the edited tokens are kept. If preprocessors are set, the spans are mapped into the original input.
+
+use std::ops::Range;
+
+use super::{error::RewriteError, token::TokenIndex, BackusNaurForm, Token};
+
+impl BackusNaurForm<'_> {
+    ///Symbolizes the input and replaces the text of every edited token with its replacement, everything else stays as it is.
+    ///More information can be found in the [rewrite](self) module.
+    ///
+    /// # Panics
+    /// Panics if the input can't be symbolized, if a index doesn't point to a token or if two edits overlap.
+    /// Use [try_rewrite](BackusNaurForm::try_rewrite) to get the error instead.
+    pub fn rewrite(&self, input: &str, edits: Vec<(TokenIndex, String)>) -> String {
+        self.try_rewrite(input, edits)
+            .unwrap_or_else(|error| panic!("failed to rewrite the string: {error}"))
+    }
+
+    ///The same as [rewrite](BackusNaurForm::rewrite) but returns a [RewriteError] instead of panicking.
+    pub fn try_rewrite(
+        &self,
+        input: &str,
+        edits: Vec<(TokenIndex, String)>,
+    ) -> Result<String, RewriteError> {
+        let tokens = self.try_symbolize_string(input)?;
+        let preprocessed = self.preprocess(input);
+        let mut spans = edits
+            .into_iter()
+            .map(|(index, replacement)| {
+                let span = byte_span(&tokens, index.indexes()).ok_or_else(|| {
+                    RewriteError::InvalidIndex {
+                        index: index.clone(),
+                    }
+                })?;
+                Ok((
+                    preprocessed.original_byte_range(input, span),
+                    index,
+                    replacement,
+                ))
+            })
+            .collect::<Result<Vec<_>, RewriteError>>()?;
+        spans.sort_by_key(|(span, _, _)| span.start);
+        if let Some(overlap) = spans
+            .windows(2)
+            .find(|edits| edits[1].0.start < edits[0].0.end)
+        {
+            return Err(RewriteError::OverlappingEdits {
+                first: overlap[0].1.clone(),
+                second: overlap[1].1.clone(),
+            });
+        }
+
+        let mut rewritten = String::with_capacity(input.len());
+        let mut copied = 0;
+        for (span, _, replacement) in spans {
+            rewritten.push_str(&input[copied..span.start]);
+            rewritten.push_str(&replacement);
+            copied = span.end;
+        }
+        rewritten.push_str(&input[copied..]);
+        Ok(rewritten)
+    }
+}
+
+///Returns the byte span in the symbolized string of the token at the indexes, None if there is no token at them.
+pub(super) fn byte_span(tokens: &[Token], indexes: &[usize]) -> Option<Range<usize>> {
+    let (&index, indexes) = indexes.split_first()?;
+    let token = tokens.get(index)?;
+    let start = tokens[..index]
+        .iter()
+        .map(|token| token.get_terminals().len())
+        .sum::<usize>();
+    if indexes.is_empty() {
+        return Some(start..start + token.get_terminals().len());
+    }
+    let Token::NonTerminalToken(non_terminal) = token else {
+        return None;
+    };
+    let span = byte_span(non_terminal.get_child_tokens(), indexes)?;
+    Some(start + span.start..start + span.end)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::backus_naur_form::{error::RewriteError, token::TokenIndex};
+
+    #[test]
+    fn test_rewrite() {
+        let bnf = crate::backus_naur_form!(
+            priority 2 => r#"<name> ::= "a" | "b" | "c""#
+            priority 1 => r#"<call> ::= <name> "(" <name> ")""#
+        );
+        let edit = |indexes: Vec<usize>, replacement: &str| {
+            (TokenIndex::from(indexes), replacement.to_string())
+        };
+
+        //the text between and around the edited tokens isn't touched
+        assert_eq!(
+            bnf.rewrite(
+                "a(b) ; c(a)  ",
+                vec![edit(vec![4, 2], "xyz"), edit(vec![0, 0], "ä")]
+            ),
+            "ä(b) ; c(xyz)  "
+        );
+        assert_eq!(bnf.rewrite("a(b)", vec![]), "a(b)");
+        assert_eq!(
+            bnf.try_rewrite("a(b)", vec![edit(vec![0, 7], "c")]),
+            Err(RewriteError::InvalidIndex {
+                index: TokenIndex::from(vec![0, 7])
+            })
+        );
+        assert_eq!(
+            bnf.try_rewrite("a(b)", vec![edit(vec![0], "c"), edit(vec![0, 2], "c")]),
+            Err(RewriteError::OverlappingEdits {
+                first: TokenIndex::from(vec![0]),
+                second: TokenIndex::from(vec![0, 2])
+            })
+        );
+    }
+}
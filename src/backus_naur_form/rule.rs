@@ -1,77 +1,180 @@
+use backus_naur_form_rule_parser::{parse_rules, ParsedRule, ParsedRules, ParsedSymbol};
+
 use super::{
     symbol::{non_terminal_symbol::NonTerminalSymbol, Symbol},
+    token::Token,
     Choice, Expression,
 };
 
-//used to get a certain choice from a symbolized expression
-fn get_choice(expr: &mut Expression, choice_index: usize) -> &mut Choice {
-    match expr.get(choice_index) {
-        Some(_) => expr.get_mut(choice_index).unwrap(),
-        None => {
-            expr.insert(choice_index, Vec::new());
-            expr.get_mut(choice_index).unwrap()
+///A read-only view of a rule of a [BackusNaurForm](super::BackusNaurForm), returned by [rule](super::BackusNaurForm::rule)
+///and [rules](super::BackusNaurForm::rules). Tools can inspect the rules with it instead of parsing the rule strings again.
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub struct Rule<'b> {
+    non_terminal_symbol: &'b NonTerminalSymbol,
+    priority: usize,
+    doc: Option<&'b str>,
+}
+
+impl<'b> Rule<'b> {
+    pub(super) fn new(
+        non_terminal_symbol: &'b NonTerminalSymbol,
+        priority: usize,
+        doc: Option<&'b str>,
+    ) -> Self {
+        Self {
+            non_terminal_symbol,
+            priority,
+            doc,
         }
     }
+
+    ///Returns the name of the rule without the angle brackets.
+    pub fn get_name(&self) -> &'b str {
+        self.non_terminal_symbol.get_name()
+    }
+
+    ///Returns the choices of the rule.
+    pub fn get_expression(&self) -> &'b Expression {
+        self.non_terminal_symbol.get_rule()
+    }
+
+    pub fn get_priority(&self) -> usize {
+        self.priority
+    }
+
+    ///Returns the doc string of the rule, see [set_rule_doc](super::BackusNaurForm::set_rule_doc).
+    pub fn doc(&self) -> Option<&'b str> {
+        self.doc
+    }
+
+    ///Returns the choices that contain the symbol of the rule itself, like `<number> <number>` in `<number> ::= <digit> | <number> <number>`.
+    ///Recursion through other rules (for example `<a> ::= <b>` and `<b> ::= <a>`) is not included,
+    ///those cycles are listed by [stats](super::BackusNaurForm::stats).
+    pub fn recursive_choices(&self) -> Vec<&'b Choice> {
+        self.get_expression()
+            .iter()
+            .filter(|choice| choice.iter().any(|symbol| self.is_self(symbol)))
+            .collect()
+    }
+
+    ///Returns true if a choice of the rule contains the symbol of the rule itself.
+    pub fn is_recursive(&self) -> bool {
+        !self.recursive_choices().is_empty()
+    }
+
+    ///Returns true if a choice of the rule starts with the symbol of the rule itself, like `<sum> ::= <sum> "+" <digit>`.
+    pub fn is_left_recursive(&self) -> bool {
+        self.get_expression()
+            .iter()
+            .any(|choice| choice.first().is_some_and(|symbol| self.is_self(symbol)))
+    }
+
+    ///Returns which choice of the rule would reduce exactly these tokens, without symbolizing anything.
+    ///This is for tools like debuggers and tests that want to know why a rule does or doesn't apply somewhere.
+    ///Like while symbolizing, the first choice that matches wins and empty choices never match.
+    ///Every symbol of the choice has to match a token: nullable symbols that were left out of the tokens aren't skipped.
+    pub fn try_match(&self, tokens: &[Token]) -> Option<MatchInfo<'b>> {
+        self.get_expression()
+            .iter()
+            .enumerate()
+            .find(|(_, choice)| {
+                !choice.is_empty()
+                    && choice.len() == tokens.len()
+                    && choice
+                        .iter()
+                        .zip(tokens)
+                        .all(|(symbol, token)| token.matches(symbol))
+            })
+            .map(|(index, choice)| MatchInfo {
+                choice: index,
+                symbols: choice,
+            })
+    }
+
+    ///Returns the choices of a "array" rule that never match, like `<number> <digit>` in `<number> ::= <digit> | <number> <digit>`
+    ///(or `<digit> <number>`): every `<digit>` is turned into a `<number>` on its own first, so there is never a `<digit>` next to a `<number>`.
+    ///Such choices have to be written as `<number> <number>`, see [check_recursion](super::BackusNaurForm::check_recursion).
+    pub fn unsupported_array_choices(&self) -> Vec<&'b Choice> {
+        let expression = self.get_expression();
+        //the symbols that are turned into this symbol on their own, like <digit> in <number> ::= <digit>
+        let renamed = expression
+            .iter()
+            .filter_map(|choice| match choice.as_slice() {
+                [symbol @ Symbol::NonTerminal(_)] if !self.is_self(symbol) => Some(symbol),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        expression
+            .iter()
+            .filter(|choice| match choice.as_slice() {
+                [first, second] => {
+                    (self.is_self(first) && renamed.contains(&second))
+                        || (renamed.contains(&first) && self.is_self(second))
+                }
+                _ => false,
+            })
+            .collect()
+    }
+
+    //Returns true if the symbol is the non terminal symbol of the rule.
+    fn is_self(&self, symbol: &Symbol) -> bool {
+        matches!(symbol, Symbol::NonTerminal(name) if name == self.get_name())
+    }
+}
+
+///The choice of a [Rule] that matches tokens, returned by [Rule::try_match].
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub struct MatchInfo<'b> {
+    ///The index of the choice in the expression of the rule.
+    pub choice: usize,
+    ///The symbols of the choice.
+    pub symbols: &'b Choice,
 }
 
-///creates a new rule from a string
+///Parses a rule like `<stmt> ::= ("let" | "const") <ident>` with the rule parser that the static_backus_naur_form! macro uses too.
 ///Rules are built like this: `<symbol>` ::= expression
-///The expression may contain any ammoutn of symbols
+///A choice can be empty (for example `"+" | "-" |` or `"+" | "-" | ε`), it matches the empty string.
+///Bytes can be written as byte values (for example `%x0D.0A` or `%d13`), every byte becomes a terminal of its own.
+///
+/// # Panics
+/// Panics if the rule is malformed, see [check_rule].
+pub(super) fn parse(string: &str) -> ParsedRules {
+    parse_rules(string).unwrap_or_else(|error| panic!("{error} in the rule {string}"))
+}
+
+///Turns a rule returned by [parse] into a [NonTerminalSymbol].
+pub(super) fn non_terminal_symbol(rule: ParsedRule) -> NonTerminalSymbol {
+    let expression = rule
+        .expression
+        .into_iter()
+        .map(|choice| {
+            choice
+                .into_iter()
+                .map(|symbol| match symbol {
+                    ParsedSymbol::Terminal(terminal) => Symbol::Terminal(terminal),
+                    ParsedSymbol::NonTerminal(name) => Symbol::NonTerminal(name),
+                })
+                .collect()
+        })
+        .collect();
+    NonTerminalSymbol::new(rule.name, expression)
+}
+
+///Creates the [NonTerminalSymbol] of a rule without inline groups.
+///
+/// # Panics
+/// Panics if the rule is malformed, see [check_rule].
 pub(super) fn non_terminal_symbol_from_rule(string: &str) -> NonTerminalSymbol {
-    let Some((symbol_name, expression)) = string.split_once("::=") else {
-        panic!("the replacement operator (::=) is missing or invalid in the rule {string}");
-    };
-    //trim the whitespace
-    let symbol_name = symbol_name.trim();
-    //remove the angle brackets
-    let symbol_name = &symbol_name[1..symbol_name.len() - 1];
-    //indicates wether we are going through a string.
-    //for example: (a "|" pipe indicates the current index)
-    //"this is a se|ntence" would mean its in a string aka true
-    //<sym|bol> "string literal" would mean its not in a string aka false
-    //this is to ensure that brackets and pipes can be put into strings
-    let mut in_string: bool = false;
-    //used to indicate the beginning of a string if in_string is true
-    let mut last_string_indice: usize = 0;
-    //used to indicate the beginning of a symbol if in_string is false
-    let mut last_opening_bracket_indice: usize = 0;
-    //stores the symbolized expression
-    let mut symbolized_expression: Expression = Vec::new();
-    //stores the current choice
-    //for example the expression: <symbol1> "abc" | "def"
-    //if it was currently on the left side of the pipe, it would be choice_index 0
-    //if it was currently on the right side of the pipe, it would be choice_index 1
-    let mut choice_index: usize = 0;
-    for (index, ch) in expression.char_indices() {
-        match ch {
-            //opening double quote
-            '"' if !in_string => {
-                last_string_indice = index;
-                in_string = true
-            }
-            //closing double quote
-            '"' if in_string => {
-                let choice = get_choice(&mut symbolized_expression, choice_index);
-                choice.push(Symbol::Terminal(
-                    expression[last_string_indice + 1..index].to_string(),
-                ));
-                in_string = false
-            }
-            //opening bracket
-            '<' if !in_string => last_opening_bracket_indice = index,
-            //closing bracket
-            '>' => {
-                let choice = get_choice(&mut symbolized_expression, choice_index);
-                choice.push(Symbol::NonTerminal(
-                    expression[last_opening_bracket_indice + 1..index].to_string(),
-                ));
-            }
-            //choice symbol
-            '|' if !in_string => choice_index += 1,
-            _ => (),
-        }
-    }
-    NonTerminalSymbol::new(symbol_name.to_string(), symbolized_expression)
+    let rule = parse(string).rules.into_iter().next();
+    non_terminal_symbol(rule.expect("the first rule is the rule itself"))
+}
+
+///Checks that a rule (with its node label and the weights of its choices) can be parsed,
+///so grammar documents can report a error for a malformed rule instead of panicking while it is added.
+pub(super) fn check_rule(string: &str) -> Result<(), String> {
+    parse_rules(string)
+        .map(|_| ())
+        .map_err(|error| error.to_string())
 }
 
 #[cfg(test)]
@@ -79,6 +182,10 @@ mod tests {
 
     use super::*;
 
+    fn choice(symbols: Vec<Symbol>) -> Choice {
+        symbols.into_iter().collect()
+    }
+
     #[test]
     fn test_non_terminal_symbol_from_rule() {
         let rule = r#"<test> ::= "a" "b" "c" | "c" "b" "a" | <abc>"#;
@@ -88,19 +195,154 @@ mod tests {
             NonTerminalSymbol::new(
                 "test".to_string(),
                 vec![
-                    vec![
-                        Symbol::Terminal("a".to_string()),
-                        Symbol::Terminal("b".to_string()),
-                        Symbol::Terminal("c".to_string())
-                    ],
-                    vec![
-                        Symbol::Terminal("c".to_string()),
-                        Symbol::Terminal("b".to_string()),
-                        Symbol::Terminal("a".to_string())
-                    ],
-                    vec![Symbol::NonTerminal("abc".to_string())]
+                    choice(vec![
+                        Symbol::terminal("a"),
+                        Symbol::terminal("b"),
+                        Symbol::terminal("c")
+                    ]),
+                    choice(vec![
+                        Symbol::terminal("c"),
+                        Symbol::terminal("b"),
+                        Symbol::terminal("a")
+                    ]),
+                    choice(vec![Symbol::non_terminal("abc")])
                 ]
             )
         )
     }
+
+    #[test]
+    fn test_try_match() {
+        let non_terminal_symbol = non_terminal_symbol_from_rule(
+            r#"<sum> ::= <digit> | <digit> "+" <digit> | <sum> "+" <digit> |"#,
+        );
+        let rule = Rule::new(&non_terminal_symbol, 0, None);
+        let digit = Token::from_non_terminal("digit", vec![Token::from_terminal("1")]);
+
+        let info = rule
+            .try_match(&[digit.clone(), Token::from_terminal("+"), digit.clone()])
+            .unwrap();
+        assert_eq!(info.choice, 1);
+        assert_eq!(info.symbols, &rule.get_expression()[1]);
+        assert_eq!(
+            rule.try_match(std::slice::from_ref(&digit)).unwrap().choice,
+            0
+        );
+        //the slice has to match as a whole
+        assert_eq!(
+            rule.try_match(&[digit.clone(), Token::from_terminal("+")]),
+            None
+        );
+        assert_eq!(rule.try_match(&[]), None);
+    }
+
+    #[test]
+    fn test_unsupported_array_choices() {
+        let non_terminal_symbol = non_terminal_symbol_from_rule(
+            r#"<number> ::= <digit> | <number> <digit> | <digit> <number> | <number> <number> | <number> "0""#,
+        );
+        let rule = Rule::new(&non_terminal_symbol, 0, None);
+        assert_eq!(
+            rule.unsupported_array_choices(),
+            [&rule.get_expression()[1], &rule.get_expression()[2]]
+        );
+    }
+
+    #[test]
+    fn test_empty_choices() {
+        let rule = r#"<sign> ::= "+" | | "-" | ε"#;
+        assert_eq!(
+            non_terminal_symbol_from_rule(rule),
+            NonTerminalSymbol::new(
+                "sign".to_string(),
+                vec![
+                    choice(vec![Symbol::terminal("+")]),
+                    Choice::new(),
+                    choice(vec![Symbol::terminal("-")]),
+                    Choice::new()
+                ]
+            )
+        );
+        assert_eq!(
+            non_terminal_symbol_from_rule(r#"<sign> ::= "ε" |"#).get_rule(),
+            &vec![choice(vec![Symbol::terminal("ε")]), Choice::new()]
+        );
+    }
+
+    #[test]
+    fn test_node_labels() {
+        assert_eq!(
+            non_terminal_symbol_from_rule(r#"<additive-expression> -> Expr ::= <term>"#),
+            non_terminal_symbol_from_rule(r#"<additive-expression> ::= <term>"#)
+        );
+        assert_eq!(
+            non_terminal_symbol_from_rule(r#"<a->b> ::= "->""#).get_name(),
+            "a->b"
+        );
+    }
+
+    #[test]
+    fn test_inline_choices() {
+        let rule = r#"<expr> ::= @inline <term> | "@inline" | ("a" | "b") | @inline <_paren> "@""#;
+        assert_eq!(parse(rule).inline_choices, vec![0, 3]);
+        //the annotation is ignored while parsing
+        assert_eq!(
+            non_terminal_symbol_from_rule(r#"<expr> ::= @inline <term> | "1""#),
+            non_terminal_symbol_from_rule(r#"<expr> ::= <term> | "1""#)
+        );
+    }
+
+    #[test]
+    fn test_check_rule() {
+        assert_eq!(check_rule(r#"<a> -> A ::= 50% ("x" | %x0A) | "y""#), Ok(()));
+        assert_eq!(
+            check_rule(r#"<a> "x""#),
+            Err("the replacement operator (::=) is missing in the rule at position 0".to_string())
+        );
+        assert_eq!(
+            check_rule(r#"a ::= "x""#),
+            Err(
+                "the name of the rule must be in angle brackets (for example <digit>) at position 0"
+                    .to_string()
+            )
+        );
+        assert!(check_rule(r#"<a> ::= ("x""#).is_err());
+        assert!(check_rule(r#"<a> ::= "x")"#).is_err());
+        assert!(check_rule(r#"<a> ::= % "x""#).is_err());
+        assert!(check_rule(r#"<a> ::= "x" ?"#).is_err());
+    }
+
+    #[test]
+    fn test_choice_weights() {
+        assert_eq!(
+            parse(r#"<digit> ::= 90% "0" | "1" | 2.5%"2" | "3%" | ("4" | "5")"#).weights,
+            vec![(0, 90.0), (2, 2.5)]
+        );
+        assert_eq!(parse(r#"<crlf> ::= %x0D.0A"#).weights, vec![]);
+    }
+
+    #[test]
+    fn test_inline_groups() {
+        let symbols = parse(r#"<stmt> ::= ("let" | "const" ("!" | "?")) <ident> ")""#)
+            .rules
+            .into_iter()
+            .map(non_terminal_symbol)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            symbols,
+            vec![
+                non_terminal_symbol_from_rule(r#"<stmt> ::= <_stmt-group-1> <ident> ")""#),
+                non_terminal_symbol_from_rule(r#"<_stmt-group-0> ::= "!" | "?""#),
+                non_terminal_symbol_from_rule(
+                    r#"<_stmt-group-1> ::= "let" | "const" <_stmt-group-0>"#
+                ),
+            ]
+        );
+
+        let rule = parse(r#"<list(x)> ::= <x> ("," <x>)"#).rules.swap_remove(0);
+        assert_eq!(
+            non_terminal_symbol(rule),
+            non_terminal_symbol_from_rule(r#"<list(x)> ::= <x> <_list-group-0(x)>"#)
+        );
+    }
 }
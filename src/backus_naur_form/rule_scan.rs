@@ -0,0 +1,87 @@
+//! Finds the ranges of the tokens the rules match during one iteration of the symbolization.
+//! Scanning the tokens for the choices of a rule doesn't depend on the other rules,
+//! so with the `parallel` feature the rules are scanned on multiple threads and their matches are kept until the tokens change.
+//! The reductions are still applied one rule after another in the same order, so the AST is the same either way.
+
+use super::symbol_id::{RuleMatches, SymbolId, SymbolTable};
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+//Remembers the ranges of the tokens the rules match.
+//Once a rule modified the tokens, the rules after it are scanned again.
+pub(crate) struct RuleScan<'r> {
+    table: &'r SymbolTable,
+    //the index of the first rule that was scanned and the matches of the rules from there on.
+    //None if the tokens were modified since the rules were scanned.
+    #[cfg(feature = "parallel")]
+    matches: Option<(usize, Vec<RuleMatches>)>,
+}
+
+impl<'r> RuleScan<'r> {
+    pub(crate) fn new(table: &'r SymbolTable) -> Self {
+        Self {
+            table,
+            #[cfg(feature = "parallel")]
+            matches: None,
+        }
+    }
+
+    //Returns the ranges of the tokens the rule at the index matches, or None if the rule can't be applied to the tokens.
+    //The rules have to be asked for in order.
+    #[cfg(not(feature = "parallel"))]
+    pub(crate) fn matches(&mut self, index: usize, ids: &[SymbolId]) -> Option<RuleMatches> {
+        let matches = self.table.rules()[index].matches(ids);
+        (!matches.is_empty()).then_some(matches)
+    }
+
+    //Returns the ranges of the tokens the rule at the index matches, or None if the rule can't be applied to the tokens.
+    //The rules have to be asked for in order.
+    #[cfg(feature = "parallel")]
+    pub(crate) fn matches(&mut self, index: usize, ids: &[SymbolId]) -> Option<RuleMatches> {
+        let table = self.table;
+        let (first, matches) = self.matches.get_or_insert_with(|| {
+            let matches = table.rules()[index..]
+                .par_iter()
+                .map(|rule| rule.matches(ids))
+                .collect();
+            (index, matches)
+        });
+        let matches = std::mem::take(&mut matches[index - *first]);
+        (!matches.is_empty()).then_some(matches)
+    }
+
+    //Has to be called after the tokens were modified.
+    pub(crate) fn tokens_modified(&mut self) {
+        #[cfg(feature = "parallel")]
+        {
+            self.matches = None;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::backus_naur_form;
+
+    #[test]
+    fn test_rule_scan() {
+        //the result has to be the same with and without the parallel feature
+        let bnf = backus_naur_form!(
+            priority 3 => r#"<digit> ::= "1" | "2" | "3""#
+            priority 2 => r#"<number> ::= <digit> | <number> <number>"#
+            priority 1 => r#"<product> ::= <number> "*" <number>"#
+            priority 0 => r#"<sum> ::= <number> "+" <number> | <product> "+" <number> | <sum> "+" <number>"#
+        );
+        let tokens = bnf.symbolize_string("12*3+2+31");
+
+        assert_eq!(tokens.len(), 1);
+        assert_eq!(tokens[0].get_symbol(), "sum");
+        let product = tokens[0].to_non_terminal_ref().unwrap().get_child_tokens()[0]
+            .to_non_terminal_ref()
+            .unwrap()
+            .get_child_tokens()[0]
+            .get_terminals();
+        assert_eq!(product, "12*3");
+    }
+}
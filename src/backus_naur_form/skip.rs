@@ -0,0 +1,184 @@
+//! Whitespace-flexible sequencing: the symbols of a choice may be separated by a token of a designated skip symbol,
+//! so whitespace doesn't have to be threaded into every rule:
+//! ```rust, ignore
+//! let mut bnf = backus_naur_form!(
+//!     priority 2 => r#"<_ws> ::= " " | <_ws> <_ws>"#
+//!     priority 1 => r#"<number> ::= "1" | "2" | <number> <number>"#
+//!     priority 0 => r#"<sum> ::= <number> "+" <number>"#
+//! );
+//! bnf.set_skip_symbol(Some("_ws"));
+//! //"1 + 2" is a <sum> now, the whitespace is attached to it since <_ws> is hidden
+//! ```
+//! Before a string is symbolized every choice gets a version for every combination of gaps between its symbols
+//! that contain the skip symbol (`<number> <_ws> "+" <number>`, `<number> "+" <_ws> <number>` and so on).
+//! Only one token fits into a gap, so the rule of the skip symbol should merge consecutive skip tokens and needs a higher priority
+//! than the rules that skip it. A hidden skip symbol keeps the skipped tokens out of the AST, their terminals stay in the parent.
+//!
+//! By default every rule skips, except the rule of the skip symbol itself and the list choices like `<number> <number>`,
+//! so `1 2` doesn't become one number. Once rules are added with [add_skipping_rule](BackusNaurForm::add_skipping_rule),
+//! only those rules skip (all of their choices, list choices included).
+//! A choice with n symbols gets 2^(n-1) versions, so a skipping choice with more than 13 symbols
+//! fails with a [SymbolizeError::TooManyChoiceVersions](super::SymbolizeError::TooManyChoiceVersions).
+
+use super::{
+    nullable::MAX_CHOICE_VERSIONS, symbol::Symbol, template::canonical_name, BackusNaurForm, Choice,
+};
+
+//Returns every version of the choice where some of the gaps between its symbols contain the skip symbol,
+//the choice itself first and the versions with fewer skip symbols before the ones with more.
+//Returns None if there would be more than MAX_CHOICE_VERSIONS versions.
+fn expand_choice(choice: &Choice, skip: &str) -> Option<Vec<Choice>> {
+    let gaps = choice.len().saturating_sub(1);
+    if gaps >= usize::BITS as usize || 1usize << gaps > MAX_CHOICE_VERSIONS {
+        return None;
+    }
+    //every bit of the mask stands for a gap that contains the skip symbol
+    let mut masks = (0..1usize << gaps).collect::<Vec<_>>();
+    masks.sort_by_key(|mask| mask.count_ones());
+
+    let versions = masks
+        .into_iter()
+        .map(|mask| {
+            let mut version = Choice::new();
+            for (position, symbol) in choice.iter().enumerate() {
+                if position > 0 && mask & (1 << (position - 1)) != 0 {
+                    version.push(Symbol::non_terminal(skip));
+                }
+                version.push(symbol.clone());
+            }
+            version
+        })
+        .collect();
+    Some(versions)
+}
+
+impl BackusNaurForm<'_> {
+    ///Sets the symbol (without the angle brackets) whose tokens may appear between the symbols of a choice,
+    ///for example `_ws` for whitespace. None removes it, which is the default.
+    ///More information can be found in the [skip](self) module.
+    pub fn set_skip_symbol(&mut self, name: Option<&str>) {
+        self.clear_compile_cache();
+        self.skip_symbol = name.map(canonical_name);
+    }
+
+    ///Restricts skipping to the rule of the non terminal symbol with the specified name and the other rules added with this function.
+    ///Without any such rule every rule skips. This assumes that the angle brackets are not included in the name.
+    pub fn add_skipping_rule(&mut self, name: &str) {
+        self.clear_compile_cache();
+        self.skipping_rules.insert(canonical_name(name));
+    }
+
+    ///Returns the versions of a choice of the rule of the non terminal symbol the way it is symbolized:
+    ///the choice itself and, if the rule skips, every version with skip symbols in its gaps.
+    ///Returns None if there would be more versions than a choice may have.
+    pub(crate) fn skipping_choices(&self, name: &str, choice: &Choice) -> Option<Vec<Choice>> {
+        let Some(skip) = &self.skip_symbol else {
+            return Some(vec![choice.clone()]);
+        };
+        let skips = if self.skipping_rules.is_empty() {
+            let is_list_choice = choice.len() >= 2
+                && choice
+                    .iter()
+                    .all(|symbol| symbol == &Symbol::non_terminal(name));
+            name != skip && !is_list_choice
+        } else {
+            self.skipping_rules.contains(name)
+        };
+        if skips {
+            expand_choice(choice, skip)
+        } else {
+            Some(vec![choice.clone()])
+        }
+    }
+
+    //Returns true if a skip symbol is set, so the choices have to be expanded before they are symbolized.
+    pub(super) fn has_skip_symbol(&self) -> bool {
+        self.skip_symbol.is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backus_naur_form::{
+        symbol::non_terminal_symbol::NonTerminalSymbol, SymbolizeError, Token,
+    };
+
+    #[test]
+    fn test_skip_symbol() {
+        let choice = NonTerminalSymbol::from_rule(r#"<sum> ::= <number> "+" <number>"#).get_rule()
+            [0]
+        .clone();
+        let expanded = expand_choice(&choice, "_ws")
+            .unwrap()
+            .into_iter()
+            .map(|choice| NonTerminalSymbol::new("sum".to_string(), vec![choice]))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            expanded,
+            vec![
+                NonTerminalSymbol::from_rule(r#"<sum> ::= <number> "+" <number>"#),
+                NonTerminalSymbol::from_rule(r#"<sum> ::= <number> <_ws> "+" <number>"#),
+                NonTerminalSymbol::from_rule(r#"<sum> ::= <number> "+" <_ws> <number>"#),
+                NonTerminalSymbol::from_rule(r#"<sum> ::= <number> <_ws> "+" <_ws> <number>"#),
+            ]
+        );
+
+        let mut bnf = crate::backus_naur_form!(
+            priority 2 => r#"<_ws> ::= " " | <_ws> <_ws>"#
+            priority 1 => r#"<number> ::= "1" | "2" | <number> <number>"#
+            priority 0 => r#"<sum> ::= <number> "+" <number>"#
+        );
+        let sum = |tokens| Token::from_non_terminal("sum", tokens);
+        let number = |digit| Token::from_non_terminal("number", vec![Token::from_terminal(digit)]);
+        let terminal = Token::from_terminal;
+        assert_ne!(bnf.symbolize_string("1  + 2").len(), 1);
+
+        bnf.set_skip_symbol(Some("_ws"));
+        assert_eq!(
+            bnf.symbolize_string("1  + 2"),
+            vec![sum(vec![
+                number("1"),
+                terminal(" "),
+                terminal(" "),
+                terminal("+"),
+                terminal(" "),
+                number("2")
+            ])]
+        );
+        assert_eq!(
+            bnf.symbolize_string("1+2"),
+            vec![sum(vec![number("1"), terminal("+"), number("2")])]
+        );
+        //list choices don't skip, so the numbers stay apart
+        assert_eq!(bnf.symbolize_string("1 2").len(), 3);
+
+        //only the rules added with add_skipping_rule skip
+        bnf.add_skipping_rule("number");
+        assert_ne!(bnf.symbolize_string("1 + 2").len(), 1);
+        assert_eq!(bnf.symbolize_string("1 2").len(), 1);
+    }
+
+    #[test]
+    fn test_too_many_skip_versions() {
+        let bnf = |symbols| {
+            let rule = format!("<long> ::= {}", "<number> ".repeat(symbols));
+            let mut bnf = crate::backus_naur_form!(
+                priority 2 => r#"<_ws> ::= " " | <_ws> <_ws>"#
+                priority 1 => r#"<number> ::= "1" | "2""#
+                priority 0 => &rule
+            );
+            bnf.set_skip_symbol(Some("_ws"));
+            bnf
+        };
+        assert_eq!(
+            bnf(14).try_symbolize_string("1 2"),
+            Err(SymbolizeError::TooManyChoiceVersions {
+                rule: "long".to_string(),
+                max: MAX_CHOICE_VERSIONS
+            })
+        );
+        //13 symbols have 12 gaps, which is still fine
+        assert!(bnf(13).try_symbolize_string("1 2").is_ok());
+    }
+}
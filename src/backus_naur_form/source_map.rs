@@ -0,0 +1,396 @@
+//! Contains the [SourceMap] which converts byte offsets in a symbolized string into lines and columns and back.
+//! This is used to show users where something in their input is (for example in error messages or editors).
+//!
+//! It also contains [Mapping]s, which link the output of [BackusNaurForm::compile_string_with_source_map] to the input,
+//! so generated code can be traced back to the source in debuggers.
+//! They can be encoded as a [Source Map v3](https://sourcemaps.info/spec.html) with [encode_source_map_v3].
+
+use std::ops::Range;
+
+use super::{
+    node_context::NodeContext, token::non_terminal_token::NonTerminalToken, BackusNaurForm, Token,
+};
+
+///A position in a string given as line and column.
+///Both the line and the column start at 0. The column is counted in characters, not in bytes.
+///To show a position to a user, 1 is usually added to both of them.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Debug)]
+pub struct LineColumn {
+    pub line: usize,
+    pub column: usize,
+}
+
+///Converts byte offsets of a string into [LineColumn]s and back.
+///Lines are separated by `\n`. A `\r` in front of the `\n` counts as the last character of the line.
+#[derive(PartialEq, Clone, Debug)]
+pub struct SourceMap {
+    source: String,
+    //the byte offsets at which the lines start. The first line always starts at 0.
+    line_starts: Vec<usize>,
+}
+
+impl SourceMap {
+    pub fn new(source: &str) -> Self {
+        let line_starts = std::iter::once(0)
+            .chain(source.match_indices('\n').map(|(index, _)| index + 1))
+            .collect();
+        Self {
+            source: source.to_string(),
+            line_starts,
+        }
+    }
+
+    ///Returns the amount of lines. A string without any `\n` has exactly one line.
+    pub fn line_count(&self) -> usize {
+        self.line_starts.len()
+    }
+
+    ///Converts a byte offset into a [LineColumn].
+    ///The offset right after the last character (the length of the string) is valid too.
+    ///Returns None if the offset is out of bounds or not on a character boundary.
+    pub fn line_column(&self, offset: usize) -> Option<LineColumn> {
+        if !self.source.is_char_boundary(offset) {
+            return None;
+        }
+        //the last line that starts at or before the offset
+        let line = self.line_starts.partition_point(|&start| start <= offset) - 1;
+        let column = self.source[self.line_starts[line]..offset].chars().count();
+        Some(LineColumn { line, column })
+    }
+
+    ///Converts a [LineColumn] back into a byte offset.
+    ///The column right after the last character of a line is valid too.
+    ///Returns None if the line or the column doesn't exist.
+    pub fn offset(&self, line_column: LineColumn) -> Option<usize> {
+        let line_start = *self.line_starts.get(line_column.line)?;
+        let line = self.line(line_column.line)?;
+
+        line.char_indices()
+            .map(|(index, _)| index)
+            .chain(std::iter::once(line.len()))
+            .nth(line_column.column)
+            .map(|index| line_start + index)
+    }
+
+    ///Converts a character index (like the positions in a [SymbolizeError](super::error::SymbolizeError)) into a byte offset.
+    ///The index right after the last character is valid too.
+    pub fn char_index_to_offset(&self, char_index: usize) -> Option<usize> {
+        self.source
+            .char_indices()
+            .map(|(index, _)| index)
+            .chain(std::iter::once(self.source.len()))
+            .nth(char_index)
+    }
+
+    ///Returns the text of a line without the trailing `\n`.
+    ///Returns None if the line doesn't exist.
+    pub fn line(&self, line: usize) -> Option<&str> {
+        let line_start = *self.line_starts.get(line)?;
+        let line_end = self
+            .line_starts
+            .get(line + 1)
+            .map(|next_line_start| next_line_start - 1)
+            .unwrap_or(self.source.len());
+        Some(&self.source[line_start..line_end])
+    }
+
+    ///Returns the string that this [SourceMap] was made from.
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+}
+
+///Links a range of the compiled output to the span of the input that it was compiled from.
+///Both ranges are byte offsets.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct Mapping {
+    pub output: Range<usize>,
+    pub input: Range<usize>,
+    ///The name of the symbol of the [NonTerminalToken] that was compiled.
+    pub symbol: String,
+}
+
+impl BackusNaurForm<'_> {
+    ///The same as [compile_string](BackusNaurForm::compile_string), but also returns a [Mapping] for every compiled [NonTerminalToken].  
+    ///The mappings are in pre-order, so a parent comes before its children.  
+    ///Compile functions only return a [String], so the output of a child is searched in the output of its parent
+    ///(after the output of the previous child). Children whose output can't be found there aren't mapped.
+    ///
+    /// # Panics
+    /// Panics in the same cases as [symbolize_string](BackusNaurForm::symbolize_string).
+    pub fn compile_string_with_source_map(&self, string: &str) -> (String, Vec<Mapping>) {
+        let mut output = String::new();
+        let mut mappings = vec![];
+        let mut input_start = 0;
+        for (index, token) in self.symbolize_string(string).into_iter().enumerate() {
+            let terminals = token.get_terminals();
+            if let Token::NonTerminalToken(non_terminal) = &token {
+                let context = NodeContext::top_level(index);
+                let compiled = self.compile_token_with_context(non_terminal, &context);
+                let is_compiled = compiled.is_some();
+                let token_output = compiled.unwrap_or_else(|| terminals.to_string());
+                self.map_token(
+                    non_terminal,
+                    &context,
+                    (input_start, output.len()),
+                    (&token_output, is_compiled),
+                    &mut mappings,
+                );
+                output.push_str(&token_output);
+            } else {
+                output.push_str(&terminals);
+            }
+            input_start += terminals.len();
+        }
+        //the input ranges are offsets into the preprocessed string, but they have to point into the original one
+        if !self.preprocessors.is_empty() {
+            let preprocessed = self.preprocess(string);
+            for mapping in &mut mappings {
+                mapping.input = preprocessed.original_byte_range(string, mapping.input.clone());
+            }
+        }
+        (output, mappings)
+    }
+
+    //Adds the mapping of the token and its descendants.
+    //start is (input start, output start), output is (the output of the token, wether it was compiled by a function).
+    //The output of a token that wasn't compiled is its terminals, so the output of its children is at the same place as their input.
+    fn map_token(
+        &self,
+        non_terminal: &NonTerminalToken,
+        context: &NodeContext,
+        (input_start, output_start): (usize, usize),
+        (output, is_compiled): (&str, bool),
+        mappings: &mut Vec<Mapping>,
+    ) {
+        mappings.push(Mapping {
+            output: output_start..output_start + output.len(),
+            input: input_start..input_start + non_terminal.get_terminals().len(),
+            symbol: non_terminal.non_terminal_symbol.to_string(),
+        });
+
+        let mut child_input_start = input_start;
+        //the part of the output that was already searched
+        let mut cursor = 0;
+        for (index, child) in non_terminal.get_child_tokens().iter().enumerate() {
+            let terminals = child.get_terminals();
+            if let Token::NonTerminalToken(child) = child {
+                let context = context.child(non_terminal, index);
+                if is_compiled {
+                    let compiled = self.compile_token_with_context(child, &context);
+                    let is_compiled = compiled.is_some();
+                    let child_output = compiled.unwrap_or_else(|| terminals.to_string());
+                    let position = (!child_output.is_empty())
+                        .then(|| output[cursor..].find(&child_output))
+                        .flatten();
+                    if let Some(position) = position {
+                        let child_output_start = cursor + position;
+                        self.map_token(
+                            child,
+                            &context,
+                            (child_input_start, output_start + child_output_start),
+                            (&child_output, is_compiled),
+                            mappings,
+                        );
+                        cursor = child_output_start + child_output.len();
+                    }
+                } else {
+                    let child_output_start = output_start + child_input_start - input_start;
+                    self.map_token(
+                        child,
+                        &context,
+                        (child_input_start, child_output_start),
+                        (&terminals, false),
+                        mappings,
+                    );
+                }
+            }
+            child_input_start += terminals.len();
+        }
+    }
+}
+
+///Encodes the mappings as a [Source Map v3](https://sourcemaps.info/spec.html) in JSON.  
+///source_name is the name of the input file that is written into `sources`.  
+///Every mapping becomes a segment at the start of its output. If several mappings start at the same place, the innermost one is used.
+///Columns are counted in characters.
+pub fn encode_source_map_v3(
+    output: &str,
+    input: &str,
+    source_name: &str,
+    mappings: &[Mapping],
+) -> String {
+    let output_map = SourceMap::new(output);
+    let input_map = SourceMap::new(input);
+    let mut mappings = mappings.iter().collect::<Vec<_>>();
+    //the sort is stable, so the innermost mapping is the last of the mappings that start at the same place
+    mappings.sort_by_key(|mapping| mapping.output.start);
+
+    let mut encoded = String::new();
+    let mut line = 0;
+    //the previous values, most of the fields are relative to them
+    let (mut column, mut input_line, mut input_column) = (0, 0, 0);
+    for (index, mapping) in mappings.iter().enumerate() {
+        if mappings
+            .get(index + 1)
+            .is_some_and(|next| next.output.start == mapping.output.start)
+        {
+            continue;
+        }
+        let (Some(generated), Some(original)) = (
+            output_map.line_column(mapping.output.start),
+            input_map.line_column(mapping.input.start),
+        ) else {
+            continue;
+        };
+        if generated.line > line {
+            encoded.push_str(&";".repeat(generated.line - line));
+            line = generated.line;
+            column = 0;
+        } else if !encoded.is_empty() && !encoded.ends_with(';') {
+            encoded.push(',');
+        }
+        //the fields are the column, the index of the source, the line and the column in the source
+        for value in [
+            generated.column as i64 - column,
+            0,
+            original.line as i64 - input_line,
+            original.column as i64 - input_column,
+        ] {
+            encode_vlq(value, &mut encoded);
+        }
+        column = generated.column as i64;
+        input_line = original.line as i64;
+        input_column = original.column as i64;
+    }
+
+    format!(
+        r#"{{"version":3,"sources":["{}"],"names":[],"mappings":"{}"}}"#,
+        escape_json(source_name),
+        encoded
+    )
+}
+
+//Appends the value as a base 64 VLQ, the number format of the source map mappings.
+fn encode_vlq(value: i64, encoded: &mut String) {
+    const BASE_64: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    //the lowest bit is the sign
+    let mut value = (value.unsigned_abs() << 1) | (value < 0) as u64;
+    loop {
+        let mut digit = value & 0b11111;
+        value >>= 5;
+        //the 6th bit means that more digits follow
+        if value > 0 {
+            digit |= 0b100000;
+        }
+        encoded.push(BASE_64[digit as usize] as char);
+        if value == 0 {
+            break;
+        }
+    }
+}
+
+fn escape_json(string: &str) -> String {
+    let mut escaped = String::new();
+    for char in string.chars() {
+        match char {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            char if char.is_control() => escaped.push_str(&format!("\\u{:04x}", char as u32)),
+            char => escaped.push(char),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_line_column() {
+        let source_map = SourceMap::new("ab\nc\n\nä1");
+        let line_column = |line, column| Some(LineColumn { line, column });
+
+        assert_eq!(source_map.line_count(), 4);
+        assert_eq!(source_map.line_column(0), line_column(0, 0));
+        assert_eq!(source_map.line_column(2), line_column(0, 2));
+        assert_eq!(source_map.line_column(3), line_column(1, 0));
+        assert_eq!(source_map.line_column(6), line_column(3, 0));
+        //ä is 2 bytes long
+        assert_eq!(source_map.line_column(8), line_column(3, 1));
+        assert_eq!(source_map.line_column(7), None);
+        assert_eq!(source_map.line_column(10), None);
+    }
+
+    #[test]
+    fn test_offset() {
+        let source = "ab\nc\n\nä1";
+        let source_map = SourceMap::new(source);
+
+        //every offset has to survive the round trip
+        for (offset, _) in source.char_indices() {
+            let line_column = source_map.line_column(offset).unwrap();
+            assert_eq!(source_map.offset(line_column), Some(offset));
+        }
+        assert_eq!(source_map.offset(LineColumn { line: 0, column: 3 }), None);
+        assert_eq!(source_map.offset(LineColumn { line: 4, column: 0 }), None);
+        assert_eq!(source_map.line(3), Some("ä1"));
+        assert_eq!(source_map.char_index_to_offset(7), Some(8));
+    }
+
+    #[test]
+    fn test_compile_string_with_source_map() {
+        let mut bnf = crate::backus_naur_form!(
+            priority 1 => r#"<digit> ::= "1" | "2" | "3" | "4""#
+            priority 0 => r#"<sum> ::= <digit> "+" <digit>"#
+        );
+        bnf.add_compile_function("sum", &|sum, _bnf| {
+            let digits = sum.get_child_tokens();
+            format!(
+                "add({}, {})",
+                digits[0].get_terminals(),
+                digits[2].get_terminals()
+            )
+        });
+        let mapping = |output: Range<usize>, input: Range<usize>, symbol: &str| Mapping {
+            output,
+            input,
+            symbol: symbol.to_string(),
+        };
+
+        let input = "1+2;\n3+4";
+        let (output, mappings) = bnf.compile_string_with_source_map(input);
+        assert_eq!(output, "add(1, 2);\nadd(3, 4)");
+        assert_eq!(
+            mappings,
+            vec![
+                mapping(0..9, 0..3, "sum"),
+                mapping(4..5, 0..1, "digit"),
+                mapping(7..8, 2..3, "digit"),
+                mapping(11..20, 5..8, "sum"),
+                mapping(15..16, 5..6, "digit"),
+                mapping(18..19, 7..8, "digit"),
+            ]
+        );
+        assert_eq!(
+            encode_source_map_v3(&output, input, "in.\"dsl\"", &mappings),
+            r#"{"version":3,"sources":["in.\"dsl\""],"names":[],"mappings":"AAAA,IAAA,GAAE;AACF,IAAA,GAAE"}"#
+        );
+    }
+
+    #[test]
+    fn test_encode_vlq() {
+        let encode = |value| {
+            let mut encoded = String::new();
+            encode_vlq(value, &mut encoded);
+            encoded
+        };
+
+        assert_eq!(encode(0), "A");
+        assert_eq!(encode(1), "C");
+        assert_eq!(encode(-1), "D");
+        assert_eq!(encode(16), "gB");
+        assert_eq!(encode(-123), "3H");
+    }
+}
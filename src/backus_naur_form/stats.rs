@@ -0,0 +1,116 @@
+//! Contains [GrammarStats], a report about the size and the complexity of a [BackusNaurForm].
+//! It is meant to guide the optimization of a grammar, for example by showing which rules are recursive
+//! and how much work every char of the input causes.
+
+use std::fmt::Display;
+
+use super::BackusNaurForm;
+
+///Statistics about the rules of a [BackusNaurForm]. Created with [BackusNaurForm::stats].
+#[derive(PartialEq, Clone, Debug)]
+pub struct GrammarStats {
+    ///The number of rules.
+    pub rule_count: usize,
+    ///The number of choices of all rules.
+    pub choice_count: usize,
+    ///The average number of symbols in a choice.
+    pub average_choice_length: f64,
+    ///The groups of non terminal symbols that refer to each other (directly or indirectly).
+    ///Every cycle is sorted by name and the cycles are sorted by their first name.
+    ///A rule that only refers to itself (for example `<number> ::= <number> <number>`) is a cycle too.
+    pub recursion_cycles: Vec<Vec<String>>,
+    ///The most choices a single rule has.
+    pub max_fan_out: usize,
+    ///The estimated number of symbol comparisons for every char of the input in one pass over the tokens.
+    ///Every position of the input is compared against every symbol of every choice, so this is the number of symbols in all choices.
+    pub estimated_cost_per_char: usize,
+}
+
+impl Display for GrammarStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "rules: {}", self.rule_count)?;
+        writeln!(f, "choices: {}", self.choice_count)?;
+        writeln!(
+            f,
+            "average choice length: {:.2}",
+            self.average_choice_length
+        )?;
+        writeln!(f, "maximum fan-out: {}", self.max_fan_out)?;
+        writeln!(
+            f,
+            "estimated cost per char: {}",
+            self.estimated_cost_per_char
+        )?;
+        write!(f, "recursion cycles: {}", self.recursion_cycles.len())?;
+        for cycle in &self.recursion_cycles {
+            let cycle = cycle
+                .iter()
+                .map(|name| format!("<{name}>"))
+                .collect::<Vec<_>>()
+                .join(", ");
+            write!(f, "\n  {cycle}")?;
+        }
+        Ok(())
+    }
+}
+
+impl BackusNaurForm<'_> {
+    ///Returns statistics about the rules of this [BackusNaurForm].
+    ///Parameterized rules are only counted through their uses. More information can be found in the [stats](self) module.
+    pub fn stats(&self) -> GrammarStats {
+        let choices = self
+            .rules
+            .iter()
+            .flat_map(|(non_terminal_symbol, _)| non_terminal_symbol.get_rule())
+            .collect::<Vec<_>>();
+        let symbol_count = choices.iter().map(|choice| choice.len()).sum::<usize>();
+        let average_choice_length = match choices.len() {
+            0 => 0.0,
+            choice_count => symbol_count as f64 / choice_count as f64,
+        };
+
+        let recursion_cycles = self.dependency_graph().cycles().to_vec();
+
+        GrammarStats {
+            rule_count: self.rules.len(),
+            choice_count: choices.len(),
+            average_choice_length,
+            recursion_cycles,
+            max_fan_out: self
+                .rules
+                .iter()
+                .map(|(non_terminal_symbol, _)| non_terminal_symbol.get_rule().len())
+                .max()
+                .unwrap_or(0),
+            estimated_cost_per_char: symbol_count,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_stats() {
+        let bnf = crate::backus_naur_form!(
+            priority 0 => r#"<digit> ::= "1" | "2" | "3""#
+            priority 0 => r#"<number> ::= <digit> | <number> <number>"#
+            priority 0 => r#"<sum> ::= <number> "+" <number> | <value> "+" <number>"#
+            priority 0 => r#"<value> ::= <sum> | <number>"#
+        );
+        let stats = bnf.stats();
+
+        assert_eq!(stats.rule_count, 4);
+        assert_eq!(stats.choice_count, 9);
+        assert_eq!(stats.average_choice_length, 14.0 / 9.0);
+        assert_eq!(
+            stats.recursion_cycles,
+            vec![
+                vec!["number".to_string()],
+                vec!["sum".to_string(), "value".to_string()]
+            ]
+        );
+        assert_eq!(stats.max_fan_out, 3);
+        assert_eq!(stats.estimated_cost_per_char, 14);
+        assert!(stats.to_string().contains("  <sum>, <value>"));
+    }
+}
@@ -1,15 +1,81 @@
 pub mod non_terminal_symbol;
 
+use std::str::FromStr;
+
 ///A [Symbol] can be the following:  
 /// - A terminal. For example `"abc"`.
 /// - A non_terminal. This is the name between the angle brackets of a non terminal symbol. For example `"number"`.
 ///   
 ///This is intended to be used as a "type" to filter for specific [Token](super::token::Token)s.  
 ///In the case of a [Symbol::NonTerminal] the angle brackets here are excluded.  
-///For example, if you filter the [Token](super::token::Token) tree for a non terminal symbols of type `<number>` you would use `Symbol::NonTerminal("number".to_string())`.  
-///Another example: If you filter the [Token](super::token::Token) tree for terminals "a" you would use `Symbol::Terminal("a".to_string())`.  
+///For example, if you filter the [Token](super::token::Token) tree for a non terminal symbols of type `<number>` you would use `Symbol::non_terminal("number")`
+///(or `"number".into()`).  
+///Another example: If you filter the [Token](super::token::Token) tree for terminals "a" you would use `Symbol::terminal("a")`.  
 #[derive(PartialEq, Debug, Clone)]
 pub enum Symbol {
     Terminal(String),
     NonTerminal(String),
 }
+
+impl Symbol {
+    ///Creates a [Symbol::Terminal], for example `Symbol::terminal("+")`.
+    pub fn terminal(terminal: &str) -> Self {
+        Symbol::Terminal(terminal.to_string())
+    }
+
+    ///Creates a [Symbol::NonTerminal] from the name without the angle brackets, for example `Symbol::non_terminal("digit")`.
+    pub fn non_terminal(name: &str) -> Self {
+        Symbol::NonTerminal(name.to_string())
+    }
+}
+
+///A &str is the name of a non terminal symbol, so `"digit".into()` is `Symbol::non_terminal("digit")`.
+///Terminals have to be created with [Symbol::terminal] or parsed from `"\"+\""`.
+impl From<&str> for Symbol {
+    fn from(name: &str) -> Self {
+        Symbol::non_terminal(name)
+    }
+}
+
+///Parses a symbol the way it is written in a rule: `<digit>` is a non terminal and `"+"` is a terminal.
+impl FromStr for Symbol {
+    type Err = String;
+
+    fn from_str(string: &str) -> Result<Self, Self::Err> {
+        if let Some(name) = string
+            .strip_prefix('<')
+            .and_then(|rest| rest.strip_suffix('>'))
+        {
+            return Ok(Symbol::non_terminal(name));
+        }
+        if let Some(terminal) = string
+            .strip_prefix('"')
+            .and_then(|rest| rest.strip_suffix('"'))
+            .filter(|_| string.len() >= 2)
+        {
+            return Ok(Symbol::terminal(terminal));
+        }
+        Err(format!(
+            "\"{string}\" is neither a <non terminal> nor a \"terminal\""
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_symbol_conversions() {
+        assert_eq!(
+            Symbol::from("digit"),
+            Symbol::NonTerminal("digit".to_string())
+        );
+        assert_eq!(Symbol::terminal("+"), Symbol::Terminal("+".to_string()));
+        assert_eq!("<digit>".parse(), Ok(Symbol::non_terminal("digit")));
+        assert_eq!("\"+\"".parse(), Ok(Symbol::terminal("+")));
+        assert_eq!("\"\"".parse(), Ok(Symbol::terminal("")));
+        assert!("digit".parse::<Symbol>().is_err());
+        assert!("\"".parse::<Symbol>().is_err());
+    }
+}
@@ -1,12 +1,19 @@
-use std::ops::Range;
+use std::{collections::HashSet, ops::Range};
 
 use crate::backus_naur_form::{
-    range_from_slice, replace_ranges, rule::non_terminal_symbol_from_rule, token::Token, Choice,
-    Expression,
+    error::SymbolizeError,
+    replace_ranges,
+    rule::non_terminal_symbol_from_rule,
+    symbol_id::{RuleIds, RuleMatches, SymbolId},
+    token::Token,
+    Choice, Expression,
 };
 
 use super::Symbol;
 
+//Called for every reduction of symbolize_vec with the range of the reduced tokens and the tokens themselves.
+type Trace<'t> = dyn FnMut(Range<usize>, &[Token]) -> Result<(), SymbolizeError> + 't;
+
 ///Represents a non terminal symbol.
 #[derive(PartialEq, Debug, Clone)]
 pub(crate) struct NonTerminalSymbol {
@@ -26,7 +33,7 @@ impl NonTerminalSymbol {
     }
 
     ///Returns the choices that contain the [NonTerminalSymbol] itself.
-    fn get_recursive_choices(&self) -> Vec<&Choice> {
+    pub(crate) fn get_recursive_choices(&self) -> Vec<&Choice> {
         self.rule
             .iter()
             .filter(|choice| choice.contains(&Symbol::NonTerminal(self.name.to_string())))
@@ -34,7 +41,7 @@ impl NonTerminalSymbol {
     }
 
     ///Returns the choices that don't contain the [NonTerminalSymbol] itself.
-    fn get_non_recursive_choices(&self) -> Vec<&Choice> {
+    pub(crate) fn get_non_recursive_choices(&self) -> Vec<&Choice> {
         self.rule
             .iter()
             .filter(|choice| !choice.contains(&Symbol::NonTerminal(self.name.to_string())))
@@ -49,78 +56,69 @@ impl NonTerminalSymbol {
     ///The only thing you can really be sure of is that if you terminalize the vec it will turn back into its original string.
     ///if you have a symbol (like number) where one is choice is just a different name for a symbol, always use <symbol> <symbol> as recursive option.
     ///otherwise it wont match.
-    pub(crate) fn symbolize_vec(&self, vec: &mut Vec<Token>) {
+    ///
+    ///rule_ids are the choices of this [NonTerminalSymbol] as [SymbolId]s and ids are the [SymbolId]s of the [Token]s of the vec.
+    ///The choices are matched against the ids, which are kept in sync with the vec.
+    ///matches are the ranges the choices match in the ids before anything was replaced (see [RuleIds::matches]).
+    ///
+    ///trace is called for every reduction with the range of the reduced [Token]s and the [Token]s themselves, before they are replaced.
+    ///If it returns a error, the symbolization stops and the error is returned.
+    ///The reductions of one step are passed from left to right, the ranges index into the vec as it was before that step.  
+    ///Returns a [SymbolizeError::NonTermination] if the recursive choices would be applied forever (for example `<x> ::= <x>`).
+    pub(crate) fn symbolize_vec(
+        &self,
+        rule_ids: &RuleIds,
+        matches: RuleMatches,
+        vec: &mut Vec<Token>,
+        ids: &mut Vec<SymbolId>,
+        trace: &mut Trace,
+    ) -> Result<(), SymbolizeError> {
         //this is for non_recursive cases
-        let mut ranges = self.get_ranges_of_possible_non_recursive_symbolization(vec);
-        replace_ranges(vec, &mut ranges, |replaced_tokens| {
-            Token::from_non_terminal(&self.name, replaced_tokens)
-        });
-
-        let mut recursive_ranges = self.get_ranges_of_possible_recursive_symbolization(vec);
+        let RuleMatches {
+            non_recursive: mut ranges,
+            recursive: mut recursive_ranges,
+        } = matches;
+        if !ranges.is_empty() {
+            trace_ranges(vec, &ranges, trace)?;
+            self.replace_ranges(rule_ids, vec, ids, &mut ranges);
+            //the recursive ranges have to be searched again in the replaced vec
+            recursive_ranges = rule_ids.recursive_ranges(ids);
+        }
+        //the symbols of the vec after every step. if they come up again, the steps would repeat forever
+        let mut seen_states = HashSet::from([ids.clone()]);
 
         loop {
-            replace_ranges(vec, &mut recursive_ranges, |replaced_tokens| {
-                Token::from_non_terminal(&self.name, replaced_tokens)
-            });
+            trace_ranges(vec, &recursive_ranges, trace)?;
+            self.replace_ranges(rule_ids, vec, ids, &mut recursive_ranges);
             //get new recursive ranges after the ranges in the vec have been replaced
-            recursive_ranges = self.get_ranges_of_possible_recursive_symbolization(vec);
+            recursive_ranges = rule_ids.recursive_ranges(ids);
             //if there is no more recursive symbolization possible, then stop recursive symbolization
             if recursive_ranges.is_empty() {
-                break;
+                return Ok(());
+            }
+            if !seen_states.insert(ids.clone()) {
+                return Err(SymbolizeError::NonTermination {
+                    rule: self.name.to_string(),
+                });
             }
         }
     }
 
-    ///Returns a vector of [Range]s where the [Token]s of the tokenized_vec could be turned into a [NonTerminalToken](super::super::NonTerminalToken)
-    ///which is of the type of this [NonTerminalSymbol].  
-    ///Each range would index into atleast one [Token] which is of the type of this [NonTerminalSymbol]
-    fn get_ranges_of_possible_recursive_symbolization(
+    //Replaces the ranges of the vec with tokens of this symbol and the same ranges of the ids with the id of this symbol.
+    fn replace_ranges(
         &self,
-        tokenized_vec: &[Token],
-    ) -> Vec<Range<usize>> {
-        let recursive_choices = self.get_recursive_choices();
-
-        Self::get_ranges_from_choices(tokenized_vec, &recursive_choices)
-    }
-
-    ///Returns a vector of [Range]s where the [Token]s of the tokenized_vec could be turned into a [NonTerminalToken](super::super::NonTerminalToken)
-    ///which is of the type of this [NonTerminalSymbol].  
-    ///If indexes into tokenized_vec using the [Range]s, no indexed [Token] would be of the type of this [NonTerminalSymbol].
-    fn get_ranges_of_possible_non_recursive_symbolization(
-        &self,
-        tokenized_vec: &[Token],
-    ) -> Vec<Range<usize>> {
-        Self::get_ranges_from_choices(tokenized_vec, &self.get_non_recursive_choices())
-    }
-
-    ///Returns a vector of [Range]s where the [Token]s of the tokenized_vec could be turned into a [NonTerminalToken](super::super::NonTerminalToken)
-    ///which is of the type of this [NonTerminalSymbol].  
-    fn get_ranges_of_possible_symbolization(&self, tokenized_vec: &[Token]) -> Vec<Range<usize>> {
-        Self::get_ranges_from_choices(tokenized_vec, &self.rule.iter().collect::<Vec<&Choice>>())
-    }
-
-    ///Returns true if the a range of [Token]s in the tokenized_vec could be turned into a [NonTerminalToken](super::super::NonTerminalToken)
-    ///which is of the type of this [NonTerminalSymbol].
-    pub(crate) fn further_symbolization_possible(&self, tokenized_vec: &[Token]) -> bool {
-        !self
-            .get_ranges_of_possible_symbolization(tokenized_vec)
-            .is_empty()
-    }
-
-    ///Returns a vector of [Range]s where the [Token]s of each [Range] of it could be summarized using one of the choices.
-    fn get_ranges_from_choices(
-        tokenized_vec: &[Token],
-        choices: &[&Vec<Symbol>],
-    ) -> Vec<Range<usize>> {
-        choices
-            .iter()
-            .flat_map(|choice| {
-                tokenized_vec
-                    .windows(choice.len())
-                    .filter(move |window| window == choice)
-                    .map(|slice| range_from_slice(tokenized_vec, slice))
-            })
-            .collect()
+        rule_ids: &RuleIds,
+        vec: &mut Vec<Token>,
+        ids: &mut Vec<SymbolId>,
+        ranges: &mut [Range<usize>],
+    ) {
+        replace_ranges(vec, ranges, |replaced_tokens| {
+            Token::from_non_terminal(&self.name, replaced_tokens)
+        });
+        //replace_ranges sorted the ranges from the last to the first one, so replacing one doesn't move the ones after it
+        for range in ranges.iter() {
+            ids.splice(range.clone(), [rule_ids.id]);
+        }
     }
 
     ///Gets the rule that contains the choices that contain the [Symbol]s that can be turned into this [NonTerminalSymbol].
@@ -135,6 +133,18 @@ impl NonTerminalSymbol {
     }
 }
 
+//Calls trace for every range (which are sorted by their start) with the tokens of the range.
+fn trace_ranges(
+    vec: &[Token],
+    ranges: &[Range<usize>],
+    trace: &mut Trace,
+) -> Result<(), SymbolizeError> {
+    for range in ranges {
+        trace(range.clone(), &vec[range.clone()])?;
+    }
+    Ok(())
+}
+
 impl PartialEq<NonTerminalSymbol> for Symbol {
     fn eq(&self, other: &NonTerminalSymbol) -> bool {
         match self {
@@ -154,15 +164,35 @@ impl PartialEq<Symbol> for NonTerminalSymbol {
 mod tests {
     use crate::backus_naur_form::characterize_string;
     use crate::backus_naur_form::rule::non_terminal_symbol_from_rule;
+    use crate::backus_naur_form::symbol_id::SymbolTable;
 
     use super::*;
 
+    //Symbolizes the vec with only the non terminal symbol as rule.
+    fn symbolize(non_terminal_symbol: &NonTerminalSymbol, vec: &mut Vec<Token>) {
+        let rules = [(non_terminal_symbol.clone(), 0)];
+        let table = SymbolTable::new(&rules.iter().collect::<Vec<_>>());
+        let mut ids = table.token_ids(vec);
+        let matches = table.rules()[0].matches(&ids);
+        non_terminal_symbol
+            .symbolize_vec(
+                &table.rules()[0],
+                matches,
+                vec,
+                &mut ids,
+                &mut |_, _| Ok(()),
+            )
+            .unwrap();
+    }
+
     #[test]
     fn test_get_ranges_of_possible_symbolization() {
         let digit = non_terminal_symbol_from_rule(r#"<digit> ::= "1" | "2" | "3""#);
+        let rules = [(digit, 0)];
+        let table = SymbolTable::new(&rules.iter().collect::<Vec<_>>());
         let tokenized_string = characterize_string("12 3");
         assert_eq!(
-            digit.get_ranges_of_possible_symbolization(&tokenized_string),
+            table.rules()[0].non_recursive_ranges(&table.token_ids(&tokenized_string)),
             vec![0..1, 1..2, 3..4]
         );
     }
@@ -183,7 +213,7 @@ mod tests {
             ]
         );
         //a simple non recursive case
-        digit.symbolize_vec(&mut tokenized_string);
+        symbolize(&digit, &mut tokenized_string);
         assert_eq!(
             tokenized_string,
             vec![
@@ -201,7 +231,7 @@ mod tests {
         let number = non_terminal_symbol_from_rule("<number> ::= <digit> | <number> <number>");
         //a simple case
         let mut tokenized_string = characterize_string("12 3");
-        digit.symbolize_vec(&mut tokenized_string);
+        symbolize(&digit, &mut tokenized_string);
         assert_eq!(
             tokenized_string,
             vec![
@@ -212,7 +242,7 @@ mod tests {
             ]
         );
 
-        number.symbolize_vec(&mut tokenized_string);
+        symbolize(&number, &mut tokenized_string);
 
         assert_eq!(
             tokenized_string,
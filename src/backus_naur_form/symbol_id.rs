@@ -0,0 +1,207 @@
+//! Symbols as integers for matching the choices of the rules against the tokens.
+//! Comparing the symbols by their names compares a `String` for every token of every window,
+//! which adds up for grammars with a lot of terminals like keywords.
+//! Instead every symbol of the rules gets a id once per symbolization and every token carries the id of its symbol,
+//! so matching a window against a choice is just comparing integers.
+
+use std::{collections::HashMap, ops::Range};
+
+use super::{
+    range_from_slice,
+    symbol::{non_terminal_symbol::NonTerminalSymbol, Symbol},
+    token::Token,
+    Choice,
+};
+
+pub(crate) type SymbolId = u32;
+
+//The id of the tokens whose symbol doesn't appear in any rule. No choice contains it, so they never match.
+const UNKNOWN: SymbolId = SymbolId::MAX;
+
+//Assigns ids to the symbols of the rules. Terminals and non terminals with the same name get different ids.
+pub(crate) struct SymbolTable {
+    terminals: HashMap<String, SymbolId>,
+    non_terminals: HashMap<String, SymbolId>,
+    //the rules with their choices as ids, in the same order as the rules the table was created from
+    rules: Vec<RuleIds>,
+}
+
+//A rule whose choices are made out of ids.
+pub(crate) struct RuleIds {
+    //the id of the non terminal symbol of the rule
+    pub(crate) id: SymbolId,
+    //the choices that contain the rule itself and the ones that don't, both in the order of the rule
+    recursive: Vec<Vec<SymbolId>>,
+    non_recursive: Vec<Vec<SymbolId>>,
+}
+
+//The ranges of the ids that the choices of a rule match.
+#[derive(PartialEq, Debug, Default)]
+pub(crate) struct RuleMatches {
+    pub(crate) non_recursive: Vec<Range<usize>>,
+    pub(crate) recursive: Vec<Range<usize>>,
+}
+
+impl RuleMatches {
+    //Returns true if no choice of the rule matches, so the rule can't be applied.
+    pub(crate) fn is_empty(&self) -> bool {
+        self.non_recursive.is_empty() && self.recursive.is_empty()
+    }
+}
+
+impl SymbolTable {
+    //Creates the table for the rules. rules() returns the ids of every rule.
+    pub(crate) fn new(rules: &[&(NonTerminalSymbol, usize)]) -> Self {
+        let mut table = Self {
+            terminals: HashMap::new(),
+            non_terminals: HashMap::new(),
+            rules: Vec::with_capacity(rules.len()),
+        };
+        for (non_terminal_symbol, _) in rules {
+            let id = table.insert(&Symbol::NonTerminal(
+                non_terminal_symbol.get_name().to_string(),
+            ));
+            //a empty choice would match a zero-length window at every position without removing a token,
+            //so the rule could be applied forever. symbolizing_rules expands them away, the ones that are left are skipped
+            let mut choice_ids = |choices: Vec<&Choice>| {
+                choices
+                    .into_iter()
+                    .filter(|choice| !choice.is_empty())
+                    .map(|choice| choice.iter().map(|symbol| table.insert(symbol)).collect())
+                    .collect()
+            };
+            let recursive = choice_ids(non_terminal_symbol.get_recursive_choices());
+            let non_recursive = choice_ids(non_terminal_symbol.get_non_recursive_choices());
+            table.rules.push(RuleIds {
+                id,
+                recursive,
+                non_recursive,
+            });
+        }
+        table
+    }
+
+    //Returns the id of the symbol and gives it one if it doesn't have one yet.
+    fn insert(&mut self, symbol: &Symbol) -> SymbolId {
+        let next_id = (self.terminals.len() + self.non_terminals.len()) as SymbolId;
+        let (ids, name) = match symbol {
+            Symbol::Terminal(name) => (&mut self.terminals, name),
+            Symbol::NonTerminal(name) => (&mut self.non_terminals, name),
+        };
+        match ids.get(name) {
+            Some(id) => *id,
+            None => *ids.entry(name.to_string()).or_insert(next_id),
+        }
+    }
+
+    //Returns the ids of the rules, in the same order as the rules the table was created from.
+    pub(crate) fn rules(&self) -> &[RuleIds] {
+        &self.rules
+    }
+
+    //Returns the id of the symbol of the token.
+    pub(crate) fn token_id(&self, token: &Token) -> SymbolId {
+        let ids = if token.is_terminal() {
+            &self.terminals
+        } else {
+            &self.non_terminals
+        };
+        ids.get(token.get_symbol()).copied().unwrap_or(UNKNOWN)
+    }
+
+    //Returns the ids of the symbols of the tokens.
+    pub(crate) fn token_ids(&self, tokens: &[Token]) -> Vec<SymbolId> {
+        tokens.iter().map(|token| self.token_id(token)).collect()
+    }
+}
+
+impl RuleIds {
+    //Returns the ranges of the ids that could be reduced using one of the recursive choices.
+    pub(crate) fn recursive_ranges(&self, ids: &[SymbolId]) -> Vec<Range<usize>> {
+        ranges_from_choices(ids, &self.recursive)
+    }
+
+    //Returns the ranges of the ids that could be reduced using one of the non recursive choices.
+    pub(crate) fn non_recursive_ranges(&self, ids: &[SymbolId]) -> Vec<Range<usize>> {
+        ranges_from_choices(ids, &self.non_recursive)
+    }
+
+    //Returns the ranges of the ids that could be reduced using the non recursive and the recursive choices.
+    pub(crate) fn matches(&self, ids: &[SymbolId]) -> RuleMatches {
+        RuleMatches {
+            non_recursive: self.non_recursive_ranges(ids),
+            recursive: self.recursive_ranges(ids),
+        }
+    }
+}
+
+//Returns the ranges where the ids match one of the choices.
+//The ranges are sorted by their start. If matches overlap, the leftmost one wins and if they start at the same place, the earlier choice wins.
+fn ranges_from_choices(ids: &[SymbolId], choices: &[Vec<SymbolId>]) -> Vec<Range<usize>> {
+    //every match of every choice, the leftmost first and the earlier choice first if they start at the same place
+    let mut matches = choices
+        .iter()
+        .enumerate()
+        .flat_map(|(choice_index, choice)| {
+            ids.windows(choice.len())
+                .filter(move |window| *window == &choice[..])
+                .map(move |slice| (range_from_slice(ids, slice), choice_index))
+        })
+        .collect::<Vec<_>>();
+    matches.sort_by_key(|(range, choice_index)| (range.start, *choice_index));
+
+    //matches that overlap a match that was chosen before are skipped, since they can't both be replaced.
+    //the chosen ranges are sorted and don't overlap, so only the last one can overlap the next match
+    let mut ranges: Vec<Range<usize>> = vec![];
+    for (range, _) in matches {
+        if ranges.last().is_none_or(|last| last.end <= range.start) {
+            ranges.push(range);
+        }
+    }
+    ranges
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backus_naur_form::characterize_string;
+
+    #[test]
+    fn test_symbol_ids() {
+        let rules = [
+            (NonTerminalSymbol::from_rule(r#"<a> ::= "a" | <a> <a>"#), 1),
+            (
+                NonTerminalSymbol::from_rule(r#"<b> ::= "a" "b" | <a> "b""#),
+                0,
+            ),
+        ];
+        let table = SymbolTable::new(&rules.iter().collect::<Vec<_>>());
+
+        //the terminal "a" and the non terminal <a> are different symbols, "c" isn't in any rule
+        let mut tokens = characterize_string("abc");
+        tokens.push(Token::from_non_terminal("a", vec![]));
+        let ids = table.token_ids(&tokens);
+        assert_ne!(ids[0], ids[3]);
+        assert_eq!(ids[2], UNKNOWN);
+        assert_eq!(ids[3], table.rules()[0].id);
+
+        assert_eq!(table.rules()[1].non_recursive_ranges(&ids), vec![0..2]);
+        assert!(table.rules()[0].recursive_ranges(&ids).is_empty());
+        assert!(table.rules()[1].matches(&ids[2..]).is_empty());
+    }
+
+    #[test]
+    fn test_empty_choices_are_skipped() {
+        let rules = [(
+            NonTerminalSymbol::from_rule(r#"<a> ::= ε | "a" | <a> |"#),
+            0,
+        )];
+        let table = SymbolTable::new(&rules.iter().collect::<Vec<_>>());
+
+        //a empty choice would match before every token, but only the choices with symbols are matched
+        let ids = table.token_ids(&characterize_string("ba"));
+        assert_eq!(table.rules()[0].non_recursive_ranges(&ids), vec![1..2]);
+        assert!(table.rules()[0].recursive_ranges(&ids).is_empty());
+        assert!(table.rules()[0].matches(&ids[..1]).is_empty());
+    }
+}
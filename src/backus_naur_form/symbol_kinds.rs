@@ -0,0 +1,198 @@
+//! Contains [SymbolKinds], a list of every non terminal symbol of a [BackusNaurForm] that can appear in a AST.
+//! It can generate the source code of a rust enum with one variant per non terminal symbol.
+//! This is meant to be used in a build script, so matching on [Token::get_symbol](super::token::Token::get_symbol) doesn't have to use strings:
+//! ```rust, ignore
+//! //build.rs
+//! let bnf = BackusNaurForm::load_grammar("grammar.bnf", &FileResolver::new()).unwrap();
+//! let code = bnf.symbol_kinds().to_rust_enum("SymbolKind");
+//! std::fs::write(Path::new(&std::env::var("OUT_DIR").unwrap()).join("symbol_kind.rs"), code).unwrap();
+//!
+//! //lib.rs
+//! include!(concat!(env!("OUT_DIR"), "/symbol_kind.rs"));
+//!
+//! match token.get_symbol().parse::<SymbolKind>() {
+//!     Ok(SymbolKind::Digit) => ...,
+//!     ...
+//! }
+//! ```
+
+use super::BackusNaurForm;
+
+///The names of the non terminal symbols of a [BackusNaurForm] that can appear in a AST, sorted by name.
+#[derive(PartialEq, Clone, Debug)]
+pub struct SymbolKinds {
+    names: Vec<String>,
+}
+
+impl SymbolKinds {
+    ///Returns the names of the non terminal symbols (without the angle brackets).
+    pub fn names(&self) -> &[String] {
+        &self.names
+    }
+
+    ///Returns the name of the enum variant for every non terminal symbol in the same order as [names](SymbolKinds::names).
+    ///The names are turned into upper camel case (for example `mul-or-div-operator` turns into `MulOrDivOperator`).
+    ///If two names would get the same variant name, the later one gets a number appended.
+    pub fn variant_names(&self) -> Vec<String> {
+        let mut variant_names: Vec<String> = vec![];
+        for name in &self.names {
+            let variant_name = variant_name(name);
+            let mut unique_variant_name = variant_name.to_string();
+            let mut suffix = 1;
+            while variant_names.contains(&unique_variant_name) {
+                suffix += 1;
+                unique_variant_name = format!("{variant_name}{suffix}");
+            }
+            variant_names.push(unique_variant_name);
+        }
+        variant_names
+    }
+
+    ///Generates the source code of a enum with the name enum_name and one variant for every non terminal symbol.
+    ///The enum has a `as_str` method that returns the name of the non terminal symbol, a `ALL` constant containing every variant
+    ///and implements [FromStr](std::str::FromStr) to turn a name into a variant.
+    pub fn to_rust_enum(&self, enum_name: &str) -> String {
+        let variant_names = self.variant_names();
+        let names_and_variants = self.names.iter().zip(&variant_names);
+
+        let variants = variant_names
+            .iter()
+            .map(|variant| format!("    {variant},\n"))
+            .collect::<String>();
+        let all = variant_names
+            .iter()
+            .map(|variant| format!("{enum_name}::{variant}"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let as_str_arms = names_and_variants
+            .clone()
+            .map(|(name, variant)| format!("            {enum_name}::{variant} => {name:?},\n"))
+            .collect::<String>();
+        let from_str_arms = names_and_variants
+            .map(|(name, variant)| format!("            {name:?} => Ok({enum_name}::{variant}),\n"))
+            .collect::<String>();
+
+        format!(
+            "#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum {enum_name} {{
+{variants}}}
+
+impl {enum_name} {{
+    pub const ALL: &'static [{enum_name}] = &[{all}];
+
+    pub fn as_str(&self) -> &'static str {{
+        match self {{
+{as_str_arms}        }}
+    }}
+}}
+
+impl std::str::FromStr for {enum_name} {{
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {{
+        match name {{
+{from_str_arms}            _ => Err(format!(\"<{{name}}> is not a symbol of the grammar\")),
+        }}
+    }}
+}}
+"
+        )
+    }
+}
+
+//Turns a non terminal symbol name into a upper camel case identifier.
+fn variant_name(name: &str) -> String {
+    let variant_name = name
+        .split(|ch: char| !ch.is_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let mut chars = part.chars();
+            chars
+                .next()
+                .map(|first| first.to_uppercase().chain(chars).collect::<String>())
+                .unwrap_or_default()
+        })
+        .collect::<String>();
+
+    match variant_name.chars().next() {
+        Some(first) if !first.is_numeric() => variant_name,
+        _ => format!("Symbol{variant_name}"),
+    }
+}
+
+impl BackusNaurForm<'_> {
+    ///Returns the [SymbolKinds] of this [BackusNaurForm]. Hidden symbols are excluded since they never appear in a AST.
+    ///More information can be found in the [symbol_kinds](self) module.
+    pub fn symbol_kinds(&self) -> SymbolKinds {
+        let mut names = self
+            .rules
+            .iter()
+            .map(|(non_terminal_symbol, _)| non_terminal_symbol.get_name().to_string())
+            .filter(|name| !self.is_hidden_symbol(name))
+            .collect::<Vec<_>>();
+        names.sort();
+        names.dedup();
+        SymbolKinds { names }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_variant_names() {
+        let bnf = crate::backus_naur_form!(
+            priority 0 => r#"<mul-or-div> ::= "*" | "/""#
+            priority 0 => r#"<mul_or_div> ::= "*""#
+            priority 0 => r#"<_whitespace> ::= " ""#
+            priority 0 => r#"<list(x)> ::= <x>"#
+            priority 0 => r#"<3d> ::= <list(mul-or-div)>"#
+        );
+        let symbol_kinds = bnf.symbol_kinds();
+
+        assert_eq!(
+            symbol_kinds.names(),
+            &["3d", "list(mul-or-div)", "mul-or-div", "mul_or_div"]
+        );
+        assert_eq!(
+            symbol_kinds.variant_names(),
+            vec!["Symbol3d", "ListMulOrDiv", "MulOrDiv", "MulOrDiv2"]
+        );
+    }
+
+    #[test]
+    fn test_to_rust_enum() {
+        let bnf = crate::backus_naur_form!(
+            priority 0 => r#"<digit> ::= "1""#
+        );
+
+        assert_eq!(
+            bnf.symbol_kinds().to_rust_enum("Kind"),
+            r#"#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Kind {
+    Digit,
+}
+
+impl Kind {
+    pub const ALL: &'static [Kind] = &[Kind::Digit];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Kind::Digit => "digit",
+        }
+    }
+}
+
+impl std::str::FromStr for Kind {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "digit" => Ok(Kind::Digit),
+            _ => Err(format!("<{name}> is not a symbol of the grammar")),
+        }
+    }
+}
+"#
+        );
+    }
+}
@@ -0,0 +1,248 @@
+//! Contains [SymbolizeResult], the uppermost tokens of a symbolized string together with the spans of the input they were made from,
+//! so tools like editors and linters don't have to add up the lengths of the tokens themselves:
+//! ```rust, ignore
+//! let result = bnf.symbolize_with_spans(input)?;
+//! for unreduced in result.unreduced() {
+//!     println!("no rule matched {:?} at {:?}", unreduced.token.get_terminals(), unreduced.span);
+//! }
+//! ```
+//! The spans are byte ranges in the input. If preprocessors are set, they are mapped into the original input.
+//!
+//! Two results can be compared with [semantic_diff](SymbolizeResult::semantic_diff), which ignores the trivia (whitespace) and the spans,
+//! so a formatter or a refactoring tool can check that it didn't change what the input means:
+//! ```rust, ignore
+//! let before = bnf.symbolize_with_spans(&source)?;
+//! let after = bnf.symbolize_with_spans(&format(&source))?;
+//! assert!(before.semantic_diff(&after).is_empty());
+//! ```
+
+use std::{fmt::Display, ops::Range};
+
+use super::{error::SymbolizeError, token::Token, BackusNaurForm};
+
+///A uppermost token of a [SymbolizeResult] and the byte range of the input it was made from.
+#[derive(PartialEq, Debug, Clone)]
+pub struct SpannedToken {
+    pub token: Token,
+    pub span: Range<usize>,
+}
+
+impl SpannedToken {
+    ///Returns true if a rule was applied to the token, so it is a [NonTerminalToken](super::token::non_terminal_token::NonTerminalToken)
+    ///and not a terminal that no rule matched.
+    pub fn is_reduced(&self) -> bool {
+        !self.token.is_terminal()
+    }
+}
+
+///The uppermost tokens of a symbolized string with their spans, returned by [symbolize_with_spans](BackusNaurForm::symbolize_with_spans).
+///More information can be found in the [symbolize_result](self) module.
+#[derive(PartialEq, Debug, Clone)]
+pub struct SymbolizeResult {
+    tokens: Vec<SpannedToken>,
+}
+
+impl SymbolizeResult {
+    ///Returns the uppermost tokens from left to right.
+    pub fn tokens(&self) -> &[SpannedToken] {
+        &self.tokens
+    }
+
+    ///Returns the uppermost tokens that no rule was applied to, from left to right.
+    pub fn unreduced(&self) -> impl Iterator<Item = &SpannedToken> {
+        self.tokens.iter().filter(|token| !token.is_reduced())
+    }
+
+    ///Returns true if a rule was applied to every uppermost token.
+    pub fn is_fully_reduced(&self) -> bool {
+        self.tokens.iter().all(SpannedToken::is_reduced)
+    }
+
+    ///Returns the tokens without their spans, the same tokens [try_symbolize_string](BackusNaurForm::try_symbolize_string) returns.
+    pub fn into_tokens(self) -> Vec<Token> {
+        self.tokens.into_iter().map(|token| token.token).collect()
+    }
+
+    ///Compares the tokens with the tokens of the other result and returns where they differ, ignoring the spans
+    ///and the trivia: the tokens whose text is only whitespace. Returns no differences if both mean the same.
+    pub fn semantic_diff(&self, other: &SymbolizeResult) -> Vec<SemanticDifference> {
+        self.semantic_diff_by(other, &|token| {
+            let terminals = token.get_terminals();
+            !terminals.is_empty() && terminals.chars().all(char::is_whitespace)
+        })
+    }
+
+    ///The same as [semantic_diff](SymbolizeResult::semantic_diff) but the tokens for which is_trivia returns true are ignored,
+    ///for example to ignore comments too.
+    pub fn semantic_diff_by(
+        &self,
+        other: &SymbolizeResult,
+        is_trivia: &dyn Fn(&Token) -> bool,
+    ) -> Vec<SemanticDifference> {
+        let left = self
+            .tokens
+            .iter()
+            .map(|token| &token.token)
+            .collect::<Vec<_>>();
+        let right = other
+            .tokens
+            .iter()
+            .map(|token| &token.token)
+            .collect::<Vec<_>>();
+        let mut differences = vec![];
+        diff_tokens(&left, &right, &mut vec![], is_trivia, &mut differences);
+        differences
+    }
+}
+
+///A place where two [SymbolizeResult]s differ, found by [semantic_diff](SymbolizeResult::semantic_diff).
+#[derive(PartialEq, Debug, Clone)]
+pub struct SemanticDifference {
+    ///The indexes of the tokens that lead to the differing tokens, the index among the uppermost tokens first.
+    ///The trivia isn't counted, so the indexes are the same in both results.
+    pub path: Vec<usize>,
+    ///The token of the result [semantic_diff](SymbolizeResult::semantic_diff) was called on, None if it has fewer tokens there.
+    pub left: Option<Token>,
+    ///The token of the other result, None if it has fewer tokens there.
+    pub right: Option<Token>,
+}
+
+impl Display for SemanticDifference {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let describe = |token: &Option<Token>| match token {
+            Some(Token::NonTerminalToken(non_terminal)) => format!(
+                "<{}> \"{}\"",
+                non_terminal.non_terminal_symbol,
+                non_terminal.get_terminals()
+            ),
+            Some(Token::Terminal(terminal)) => format!("\"{}\"", terminal.get_terminals()),
+            None => "nothing".to_string(),
+        };
+        write!(
+            f,
+            "at {:?}: {} became {}",
+            self.path,
+            describe(&self.left),
+            describe(&self.right)
+        )
+    }
+}
+
+//Compares the tokens that aren't trivia one by one and descends into the non terminal tokens of the same symbol.
+//The differences are added to differences, path is the path of the parent of the tokens.
+fn diff_tokens(
+    left: &[&Token],
+    right: &[&Token],
+    path: &mut Vec<usize>,
+    is_trivia: &dyn Fn(&Token) -> bool,
+    differences: &mut Vec<SemanticDifference>,
+) {
+    let left = left
+        .iter()
+        .filter(|token| !is_trivia(token))
+        .collect::<Vec<_>>();
+    let right = right
+        .iter()
+        .filter(|token| !is_trivia(token))
+        .collect::<Vec<_>>();
+    for index in 0..left.len().max(right.len()) {
+        path.push(index);
+        match (left.get(index), right.get(index)) {
+            (Some(Token::NonTerminalToken(a)), Some(Token::NonTerminalToken(b)))
+                if a.non_terminal_symbol == b.non_terminal_symbol =>
+            {
+                let a = a.get_child_tokens().iter().collect::<Vec<_>>();
+                let b = b.get_child_tokens().iter().collect::<Vec<_>>();
+                diff_tokens(&a, &b, path, is_trivia, differences);
+            }
+            (Some(Token::Terminal(a)), Some(Token::Terminal(b)))
+                if a.get_terminals() == b.get_terminals() => {}
+            (left, right) => differences.push(SemanticDifference {
+                path: path.clone(),
+                left: left.map(|token| (**token).clone()),
+                right: right.map(|token| (**token).clone()),
+            }),
+        }
+        path.pop();
+    }
+}
+
+impl BackusNaurForm<'_> {
+    ///Symbolizes the string like [try_symbolize_string](BackusNaurForm::try_symbolize_string)
+    ///and returns the uppermost tokens with the byte ranges of the string they were made from.
+    ///More information can be found in the [symbolize_result](self) module.
+    pub fn symbolize_with_spans(&self, string: &str) -> Result<SymbolizeResult, SymbolizeError> {
+        let tokens = self.try_symbolize_string(string)?;
+        let preprocessed = self.preprocess(string);
+        let mut start = 0;
+        let tokens = tokens
+            .into_iter()
+            .map(|token| {
+                //the spans are found in the preprocessed string and mapped back into the original one
+                let end = start + token.get_terminals().len();
+                let span = preprocessed.original_byte_range(string, start..end);
+                start = end;
+                SpannedToken { token, span }
+            })
+            .collect();
+        Ok(SymbolizeResult { tokens })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backus_naur_form::test_helpers::DIGIT_RULE;
+
+    #[test]
+    fn test_symbolize_with_spans() {
+        let bnf = crate::backus_naur_form!(
+            priority 1 => r#"<letter> ::= "ä" | "b""#
+            priority 0 => r#"<word> ::= <letter> | <word> <word>"#
+        );
+        let result = bnf.symbolize_with_spans("äb?b").unwrap();
+        let spans = result
+            .tokens()
+            .iter()
+            .map(|token| (token.span.clone(), token.is_reduced()))
+            .collect::<Vec<_>>();
+        assert_eq!(spans, vec![(0..3, true), (3..4, false), (4..5, true)]);
+        assert!(!result.is_fully_reduced());
+        assert_eq!(
+            result
+                .unreduced()
+                .map(|token| &token.token)
+                .collect::<Vec<_>>(),
+            vec![&Token::from_terminal("?")]
+        );
+        assert_eq!(result.into_tokens(), bnf.symbolize_string("äb?b"));
+
+        assert!(bnf.symbolize_with_spans("bä").unwrap().is_fully_reduced());
+    }
+
+    #[test]
+    fn test_semantic_diff() {
+        let bnf = crate::backus_naur_form!(
+            priority 2 => DIGIT_RULE
+            priority 1 => r#"<_ws> ::= " " | %x0A"#
+            priority 0 => r#"<sum> ::= <digit> "+" <digit> | <digit> <_ws> "+" <_ws> <digit>"#
+        );
+        let result = |input| bnf.symbolize_with_spans(input).unwrap();
+        //the newline is skipped by the rule of <_ws> and is trivia at the top level
+        assert_eq!(result("1 +\n2").tokens().len(), 1);
+        assert!(result("1+2").semantic_diff(&result(" 1 +\n2\n")).is_empty());
+
+        let differences = result("1+2 2").semantic_diff(&result("1+1"));
+        assert_eq!(
+            differences
+                .iter()
+                .map(SemanticDifference::to_string)
+                .collect::<Vec<_>>(),
+            vec![
+                "at [0, 2, 0]: \"2\" became \"1\"",
+                "at [1]: <digit> \"2\" became nothing"
+            ]
+        );
+        assert_eq!(differences[0].path, vec![0, 2, 0]);
+    }
+}
@@ -0,0 +1,236 @@
+//! Contains the syntax macros of grammar documents, like `%macro list(item, sep) ::= <item> | <list(item, sep)> "sep" <list(item, sep)>`.
+//! A macro is used like a non terminal symbol with arguments (`<list(expr, ",")>`), every argument is either a non terminal symbol or a terminal.
+//! In the body of the macro, every `<item>` and every `"item"` is replaced with the argument of the parameter.
+//!
+//! Unlike parameterized rules (see the [template](super::template) module) macros are expanded before the rules are parsed,
+//! so their parameters can be terminals too. Every distinct use becomes a rule of its own (for example `<list-0>`)
+//! whose tokens are labeled with the name of the macro, so they appear as `<list>` in the AST and share the compile function of `list`.
+
+use std::collections::HashMap;
+
+//Uses that are nested deeper than this are assumed to never end.
+//For example `%macro m(x) ::= <m(m(x))>` would create infinitely many rules.
+const MAX_EXPANSION_DEPTH: usize = 16;
+
+//A macro without its name.
+struct SyntaxMacro {
+    parameters: Vec<String>,
+    body: String,
+}
+
+///The macros of grammar documents and the rules that were created for their uses.
+#[derive(Default)]
+pub(super) struct SyntaxMacros {
+    macros: HashMap<String, SyntaxMacro>,
+    //the names of the rules of the uses that were expanded, keyed by the use (for example `list(expr, ",")`)
+    instances: HashMap<String, String>,
+}
+
+//A part of a rule.
+enum Piece<'r> {
+    //a string like `"sep"` without the quotes
+    Terminal(&'r str),
+    //a non terminal symbol like `<list(item, sep)>` without the angle brackets
+    NonTerminal(&'r str),
+    //everything else, for example whitespace, pipes and the replacement operator
+    Text(&'r str),
+}
+
+impl SyntaxMacros {
+    ///Defines a macro. Returns a error if a macro with the name already exists.
+    pub(super) fn define(
+        &mut self,
+        name: &str,
+        parameters: Vec<String>,
+        body: &str,
+    ) -> Result<(), String> {
+        if self.macros.contains_key(name) {
+            return Err(format!("the macro %{name} is already defined"));
+        }
+        let syntax_macro = SyntaxMacro {
+            parameters,
+            body: body.to_string(),
+        };
+        self.macros.insert(name.to_string(), syntax_macro);
+        Ok(())
+    }
+
+    ///Replaces the uses of macros in the rule with the names of their rules.
+    ///Returns the rule and the rules of the uses that weren't expanded before, which have to be added after the rule.
+    pub(super) fn expand(&mut self, rule: &str) -> Result<(String, Vec<String>), String> {
+        let mut instances = vec![];
+        let expanded = self.expand_uses(rule, &mut instances, 0)?;
+        Ok((expanded, instances))
+    }
+
+    //Replaces the uses of macros in the text with the names of their rules and adds the rules of new uses to instances.
+    fn expand_uses(
+        &mut self,
+        text: &str,
+        instances: &mut Vec<String>,
+        depth: usize,
+    ) -> Result<String, String> {
+        let mut expanded = String::with_capacity(text.len());
+        for piece in split_pieces(text)? {
+            match piece {
+                Piece::NonTerminal(name) => {
+                    let name = self.expand_use(name, instances, depth)?;
+                    expanded.push_str(&format!("<{name}>"));
+                }
+                Piece::Terminal(terminal) => expanded.push_str(&format!("\"{terminal}\"")),
+                Piece::Text(text) => expanded.push_str(text),
+            }
+        }
+        Ok(expanded)
+    }
+
+    //Returns the name of the rule of the use, the name itself if it isn't the use of a macro.
+    fn expand_use(
+        &mut self,
+        name: &str,
+        instances: &mut Vec<String>,
+        depth: usize,
+    ) -> Result<String, String> {
+        let Some((macro_name, arguments)) = split_use(name) else {
+            return Ok(name.to_string());
+        };
+        let Some(syntax_macro) = self.macros.get(macro_name) else {
+            return Ok(name.to_string());
+        };
+        if arguments.len() != syntax_macro.parameters.len() {
+            return Err(format!(
+                "the macro %{macro_name} has {} parameters but is used with {} arguments",
+                syntax_macro.parameters.len(),
+                arguments.len()
+            ));
+        }
+        let key = format!("{macro_name}({})", arguments.join(", "));
+        if let Some(instance) = self.instances.get(&key) {
+            return Ok(instance.to_string());
+        }
+        if depth >= MAX_EXPANSION_DEPTH {
+            return Err(format!(
+                "the macro %{macro_name} is expanded recursively without end"
+            ));
+        }
+
+        let body = substitute(&syntax_macro.body, &syntax_macro.parameters, &arguments)?;
+        let instance = format!("{macro_name}-{}", self.instances.len());
+        self.instances.insert(key, instance.to_string());
+        //the rule is added before the rules of the uses in its body, so those are applied first
+        let index = instances.len();
+        instances.push(String::new());
+        let body = self.expand_uses(&body, instances, depth + 1)?;
+        instances[index] = format!("<{instance}> -> {macro_name} ::= {body}");
+        Ok(instance)
+    }
+}
+
+//Splits the text into strings, non terminal symbols and the text between them.
+fn split_pieces(text: &str) -> Result<Vec<Piece<'_>>, String> {
+    let mut pieces = vec![];
+    let mut rest = text;
+    while !rest.is_empty() {
+        let Some(start) = rest.find(['"', '<']) else {
+            pieces.push(Piece::Text(rest));
+            break;
+        };
+        if start > 0 {
+            pieces.push(Piece::Text(&rest[..start]));
+        }
+        rest = &rest[start..];
+        if rest.starts_with('"') {
+            let end = rest[1..]
+                .find('"')
+                .ok_or(format!("the string {rest} is never closed"))?;
+            pieces.push(Piece::Terminal(&rest[1..end + 1]));
+            rest = &rest[end + 2..];
+        } else {
+            //the arguments of a use can be strings that contain a closing angle bracket
+            let mut in_string = false;
+            let end = rest
+                .char_indices()
+                .find(|(_, char)| match char {
+                    '"' => {
+                        in_string = !in_string;
+                        false
+                    }
+                    '>' => !in_string,
+                    _ => false,
+                })
+                .map(|(end, _)| end)
+                .ok_or(format!("the non terminal symbol {rest} is never closed"))?;
+            pieces.push(Piece::NonTerminal(&rest[1..end]));
+            rest = &rest[end + 1..];
+        }
+    }
+    Ok(pieces)
+}
+
+//Splits a use like `list(expr, ",")` into its name and its arguments. Returns None if the name has no arguments.
+fn split_use(name: &str) -> Option<(&str, Vec<String>)> {
+    let (macro_name, rest) = name.split_once('(')?;
+    let arguments = rest.trim_end().strip_suffix(')')?;
+    let mut split_arguments = vec![];
+    let mut depth = 0;
+    let mut in_string = false;
+    let mut last_comma = 0;
+    for (index, char) in arguments.char_indices() {
+        match char {
+            '"' => in_string = !in_string,
+            '(' if !in_string => depth += 1,
+            ')' if !in_string => depth -= 1,
+            ',' if !in_string && depth == 0 => {
+                split_arguments.push(arguments[last_comma..index].trim().to_string());
+                last_comma = index + 1;
+            }
+            _ => {}
+        }
+    }
+    split_arguments.push(arguments[last_comma..].trim().to_string());
+    Some((macro_name.trim(), split_arguments))
+}
+
+//Replaces every `<parameter>` and `"parameter"` in the body with the argument of the parameter,
+//the parameters that are arguments of other uses are replaced too.
+fn substitute(body: &str, parameters: &[String], arguments: &[String]) -> Result<String, String> {
+    let argument = |name: &str| {
+        parameters
+            .iter()
+            .position(|parameter| parameter == name)
+            .map(|index| &arguments[index])
+    };
+    //the argument as a symbol, a terminal argument already is one
+    let symbol = |argument: &str| {
+        if argument.starts_with('"') {
+            argument.to_string()
+        } else {
+            format!("<{argument}>")
+        }
+    };
+
+    let mut substituted = String::with_capacity(body.len());
+    for piece in split_pieces(body)? {
+        match piece {
+            Piece::Terminal(terminal) => match argument(terminal) {
+                Some(argument) => substituted.push_str(&symbol(argument)),
+                None => substituted.push_str(&format!("\"{terminal}\"")),
+            },
+            Piece::NonTerminal(name) => match (argument(name), split_use(name)) {
+                (Some(argument), _) => substituted.push_str(&symbol(argument)),
+                (None, Some((used, used_arguments))) => {
+                    let used_arguments = used_arguments
+                        .iter()
+                        .map(|used_argument| {
+                            argument(used_argument).map_or(used_argument.as_str(), String::as_str)
+                        })
+                        .collect::<Vec<_>>();
+                    substituted.push_str(&format!("<{used}({})>", used_arguments.join(", ")));
+                }
+                (None, None) => substituted.push_str(&format!("<{name}>")),
+            },
+            Piece::Text(text) => substituted.push_str(text),
+        }
+    }
+    Ok(substituted)
+}
@@ -0,0 +1,232 @@
+//! Templates are parameterized rules like `<list(x)> ::= <x> | <list(x)> "," <list(x)>`.
+//! A template is never applied itself. Instead, every use of it (for example `<args> ::= <list(expr)>`)
+//! creates a new rule named `list(expr)` where every `<x>` is replaced by `<expr>`.
+//! Recursive templates have to follow the same rules as other recursive rules (see the [backus_naur_form](super) module).
+//!
+//! Names of templates and their uses are written in a canonical form (`name(first, second)`)
+//! so `<pair(a,b)>` and `<pair(a, b)>` refer to the same rule.
+
+use super::symbol::{non_terminal_symbol::NonTerminalSymbol, Symbol};
+
+//Instantiations that are nested deeper than this are assumed to never end.
+//For example `<list(x)> ::= <list(list(x))>` would create infinitely many rules.
+const MAX_INSTANTIATION_DEPTH: usize = 16;
+
+///A parameterized rule.
+#[derive(PartialEq, Debug, Clone)]
+pub(crate) struct Template {
+    //the name without the parameters (for example "list" for `<list(x)>`)
+    name: String,
+    parameters: Vec<String>,
+    symbol: NonTerminalSymbol,
+    priority: usize,
+}
+
+impl Template {
+    ///Returns a [Template] if the [NonTerminalSymbol] has parameters (for example `<list(x)>`).
+    ///Returns None if the [NonTerminalSymbol] is a normal rule.
+    pub(crate) fn from_symbol(symbol: &NonTerminalSymbol, priority: usize) -> Option<Self> {
+        let (name, parameters) = parse_application(symbol.get_name())?;
+        Some(Self {
+            name: name.to_string(),
+            parameters,
+            symbol: symbol.clone(),
+            priority,
+        })
+    }
+
+    ///Returns the name the template was written with (for example "list(x)").
+    pub(crate) fn get_full_name(&self) -> &str {
+        self.symbol.get_name()
+    }
+
+    ///Returns the rule of the template with the parameters (for example `<list(x)> ::= <x> | <list(x)> "," <list(x)>`).
+    pub(crate) fn get_symbol(&self) -> &NonTerminalSymbol {
+        &self.symbol
+    }
+
+    ///Returns the priority the rules of the template get.
+    pub(crate) fn get_priority(&self) -> usize {
+        self.priority
+    }
+
+    ///Returns true if the use of a template (for example "list(expr)") refers to this template.
+    pub(crate) fn is_used_by(&self, name: &str) -> bool {
+        parse_application(name).is_some_and(|(template_name, arguments)| {
+            template_name == self.name && arguments.len() == self.parameters.len()
+        })
+    }
+
+    ///Returns true if the rule of the template uses the non terminal symbol with the name.
+    pub(crate) fn refers_to(&self, name: &str) -> bool {
+        self.symbol
+            .get_rule()
+            .iter()
+            .flatten()
+            .any(|symbol| symbol == &Symbol::NonTerminal(name.to_string()))
+    }
+
+    //Creates the rule for a use of this template.
+    fn instantiate(&self, arguments: &[String]) -> (NonTerminalSymbol, usize) {
+        let substitute = |name: &str| {
+            self.parameters
+                .iter()
+                .position(|parameter| parameter == name)
+                .map(|index| arguments[index].to_string())
+                .unwrap_or(name.to_string())
+        };
+        let symbol = map_non_terminal_names(&self.symbol, &|name| {
+            substitute_arguments(name, &substitute)
+        });
+        (symbol, self.priority)
+    }
+}
+
+///Splits a name like "list(x, y)" into "list" and the arguments ["x", "y"].
+///Arguments can be nested (for example "list(pair(a, b))").
+///Returns None if the name has no arguments.
+pub(crate) fn parse_application(name: &str) -> Option<(&str, Vec<String>)> {
+    let (template_name, rest) = name.split_once('(')?;
+    let arguments = rest.strip_suffix(')')?;
+
+    let mut depth = 0;
+    let mut last_comma = 0;
+    let mut split_arguments = vec![];
+    for (index, ch) in arguments.char_indices() {
+        match ch {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            ',' if depth == 0 => {
+                split_arguments.push(arguments[last_comma..index].trim().to_string());
+                last_comma = index + 1;
+            }
+            _ => (),
+        }
+    }
+    split_arguments.push(arguments[last_comma..].trim().to_string());
+
+    Some((template_name.trim(), split_arguments))
+}
+
+//Applies substitute to the name and (recursively) to all of its arguments.
+//The result is in the canonical form.
+fn substitute_arguments<F: Fn(&str) -> String>(name: &str, substitute: &F) -> String {
+    match parse_application(name) {
+        Some((template_name, arguments)) => {
+            let arguments = arguments
+                .iter()
+                .map(|argument| substitute_arguments(argument, substitute))
+                .collect::<Vec<_>>()
+                .join(", ");
+            format!("{template_name}({arguments})")
+        }
+        None => substitute(name),
+    }
+}
+
+///Returns the canonical form of a non terminal symbol name (for example "pair(a,b)" turns into "pair(a, b)").
+pub(crate) fn canonical_name(name: &str) -> String {
+    substitute_arguments(name, &|name| name.to_string())
+}
+
+///Returns a copy of the [NonTerminalSymbol] where the name and every non terminal in the rule are mapped using f.
+pub(crate) fn map_non_terminal_names(
+    symbol: &NonTerminalSymbol,
+    f: &dyn Fn(&str) -> String,
+) -> NonTerminalSymbol {
+    let rule = symbol
+        .get_rule()
+        .iter()
+        .map(|choice| {
+            choice
+                .iter()
+                .map(|symbol| match symbol {
+                    Symbol::NonTerminal(name) => Symbol::NonTerminal(f(name)),
+                    terminal => terminal.clone(),
+                })
+                .collect()
+        })
+        .collect();
+    NonTerminalSymbol::new(f(symbol.get_name()), rule)
+}
+
+///Adds a rule for every use of a template that doesn't have a rule yet.
+///Rules that are created this way can use templates too, so this repeats until every use has a rule.
+///
+/// # Panics
+/// Panics if the templates would create infinitely many rules (for example `<list(x)> ::= <list(list(x))>`).
+pub(crate) fn instantiate_templates(
+    rules: &mut Vec<(NonTerminalSymbol, usize)>,
+    templates: &[Template],
+) {
+    loop {
+        let missing_rules = rules
+            .iter()
+            .flat_map(|(symbol, _)| symbol.get_rule().iter().flatten())
+            .filter_map(|symbol| match symbol {
+                Symbol::NonTerminal(name) => Some(name),
+                Symbol::Terminal(_) => None,
+            })
+            .filter(|name| !rules.iter().any(|(symbol, _)| symbol.get_name() == *name))
+            .filter_map(|name| {
+                let template = templates
+                    .iter()
+                    .find(|template| template.is_used_by(name))?;
+                let (_, arguments) = parse_application(name)?;
+                Some((name.to_string(), template, arguments))
+            })
+            .collect::<Vec<_>>();
+
+        if missing_rules.is_empty() {
+            break;
+        }
+
+        for (name, template, arguments) in missing_rules {
+            if name.matches('(').count() > MAX_INSTANTIATION_DEPTH {
+                panic!(
+                    "the parameterized rule <{}> is instantiated recursively without end",
+                    template.get_full_name()
+                );
+            }
+            //a rule can be missing several times in this iteration
+            if !rules.iter().any(|(symbol, _)| symbol.get_name() == name) {
+                rules.push(template.instantiate(&arguments));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_application() {
+        assert_eq!(parse_application("digit"), None);
+        assert_eq!(
+            parse_application("list(pair(a,b), c)"),
+            Some(("list", vec!["pair(a,b)".to_string(), "c".to_string()]))
+        );
+        assert_eq!(canonical_name("list(pair(a,b),c)"), "list(pair(a, b), c)");
+    }
+
+    #[test]
+    fn test_instantiate_templates() {
+        let list = NonTerminalSymbol::from_rule(r#"<list(x)> ::= <x> | <list(x)> "," <x>"#);
+        let templates = vec![Template::from_symbol(&list, 1).unwrap()];
+        let mut rules = vec![(NonTerminalSymbol::from_rule("<args> ::= <list(expr)>"), 0)];
+
+        instantiate_templates(&mut rules, &templates);
+
+        assert_eq!(
+            rules[1],
+            (
+                NonTerminalSymbol::from_rule(
+                    r#"<list(expr)> ::= <expr> | <list(expr)> "," <expr>"#
+                ),
+                1
+            )
+        );
+        assert_eq!(rules.len(), 2);
+    }
+}
@@ -0,0 +1,11 @@
+//! Helpers that the tests of several modules share.
+
+use super::token::Token;
+
+///The rule of the digits most tests symbolize.
+pub(crate) const DIGIT_RULE: &str = r#"<digit> ::= "1" | "2""#;
+
+///Returns the token of a digit, for example `digit("1")` for the tokens of [DIGIT_RULE].
+pub(crate) fn digit(digit: &str) -> Token {
+    Token::from_non_terminal("digit", vec![Token::from_terminal(digit)])
+}
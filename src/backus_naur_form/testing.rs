@@ -0,0 +1,605 @@
+//! Helpers to test grammars, for example to check that a refactored grammar still builds the same trees:
+//! ```rust, ignore
+//! let mut optimized = grammar();
+//! optimized.optimize();
+//! assert_eq!(testing::differential(&grammar(), &optimized, 42, 1000), None);
+//! ```
+//! The inputs are generated from the rules of the first grammar with a seeded pseudo random generator,
+//! so the same seed always produces the same inputs and a failing run can be repeated.
+//!
+//! Every choice of a symbol is picked equally often, unless its choices have weights.
+//! Weights are percentages, written in front of the choice or set with [set_choice_weight](BackusNaurForm::set_choice_weight):
+//! ```rust, ignore
+//! backus_naur_form!(
+//!     //"0" is picked 90% of the time, the other digits share the remaining 10%
+//!     priority 0 => r#"<digit> ::= 90% "0" | "1" | "2" | "3""#
+//! )
+//! ```
+//! The choices without a weight share what is left of 100% evenly. The weights don't change how anything is symbolized.
+//!
+//! [covering_inputs] generates inputs until every choice was used, to build corpora that cover the whole grammar:
+//! ```rust, ignore
+//! let coverage = testing::covering_inputs(&grammar(), 42, 1000);
+//! assert!(coverage.uncovered().is_empty(), "{coverage}");
+//! ```
+//! It picks the choices no input used yet before the others and starts every input at a symbol that still has such choices.
+//!
+//! [shrink_failing_input] turns a input that makes something fail into a minimal reproducer for a bug report:
+//! ```rust, ignore
+//! let reproducer = testing::shrink_failing_input(&bnf, &input, |input| {
+//!     std::panic::catch_unwind(|| bnf.compile_string(input)).is_err()
+//! });
+//! ```
+//! It removes the spans of tokens, replaces tokens with nested tokens of the same symbol or with the shortest example of their symbol
+//! and finally removes chunks of chars, as long as the predicate still returns true.
+
+use std::{collections::HashMap, fmt::Display, ops::Range};
+
+use super::{
+    error::SymbolizeError,
+    generate::example_of_choice,
+    symbol::{non_terminal_symbol::NonTerminalSymbol, Symbol},
+    template::{canonical_name, map_non_terminal_names},
+    token::Token,
+    BackusNaurForm, Choice,
+};
+
+//How deep the choices of a generated input are nested before only the shortest examples are used, so every input ends.
+const MAX_DEPTH: usize = 8;
+
+///A input that two grammars symbolize differently, found by [differential].
+#[derive(PartialEq, Debug, Clone)]
+pub struct Mismatch {
+    pub input: String,
+    ///The result of [try_symbolize_string](BackusNaurForm::try_symbolize_string) with the first grammar.
+    pub a: Result<Vec<Token>, SymbolizeError>,
+    ///The result of [try_symbolize_string](BackusNaurForm::try_symbolize_string) with the second grammar.
+    pub b: Result<Vec<Token>, SymbolizeError>,
+}
+
+impl BackusNaurForm<'_> {
+    ///Sets the weight (in percent) of a choice of the rule of the non terminal symbol with the specified name,
+    ///which is how often the choice is picked when [random inputs](random_inputs) are generated.
+    ///More information can be found in the [testing](self) module.  
+    ///The weight can also be written in the rule: `<digit> ::= 90% "0" | "1"`.
+    pub fn set_choice_weight(&mut self, name: &str, choice: Choice, weight: f64) {
+        //the names are stored the same way as the names of the rules
+        let symbol = map_non_terminal_names(
+            &NonTerminalSymbol::new(name.to_string(), vec![choice]),
+            &canonical_name,
+        );
+        let choice = symbol.get_rule()[0].clone();
+        let weights = self
+            .choice_weights
+            .entry(symbol.get_name().to_string())
+            .or_default();
+        weights.retain(|(weighted, _)| *weighted != choice);
+        weights.push((choice, weight));
+    }
+
+    ///Returns the weight of a choice of the rule of the non terminal symbol with the specified name,
+    ///None if it has no weight of its own.
+    pub fn choice_weight(&self, name: &str, choice: &Choice) -> Option<f64> {
+        self.choice_weights
+            .get(name)?
+            .iter()
+            .find(|(weighted, _)| weighted == choice)
+            .map(|(_, weight)| *weight)
+    }
+}
+
+///A choice of a rule and how often [covering_inputs] used it.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct ChoiceUse {
+    pub rule: String,
+    ///The index of the choice in the rule.
+    pub choice: usize,
+    pub uses: usize,
+}
+
+///The result of [covering_inputs]: the generated inputs and how often they used every choice of every rule.
+#[derive(PartialEq, Eq, Debug, Clone, Default)]
+pub struct ChoiceCoverage {
+    pub inputs: Vec<String>,
+    ///Every choice of every rule, in the order the rules were added.
+    pub choices: Vec<ChoiceUse>,
+}
+
+impl ChoiceCoverage {
+    ///Returns the choices that no input used.
+    pub fn uncovered(&self) -> Vec<&ChoiceUse> {
+        self.choices
+            .iter()
+            .filter(|choice| choice.uses == 0)
+            .collect()
+    }
+
+    ///Returns the share of the choices that were used, from 0.0 to 1.0 (1.0 if there are no choices).
+    pub fn ratio(&self) -> f64 {
+        if self.choices.is_empty() {
+            return 1.0;
+        }
+        let covered = self.choices.len() - self.uncovered().len();
+        covered as f64 / self.choices.len() as f64
+    }
+}
+
+impl Display for ChoiceCoverage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let uncovered = self.uncovered();
+        write!(
+            f,
+            "{} of {} choices covered by {} inputs",
+            self.choices.len() - uncovered.len(),
+            self.choices.len(),
+            self.inputs.len()
+        )?;
+        for choice in uncovered {
+            write!(
+                f,
+                "\nthe choice {} of <{}> is never used",
+                choice.choice, choice.rule
+            )?;
+        }
+        Ok(())
+    }
+}
+
+//How often every choice was used, by the index of its rule and its index in the rule.
+type ChoiceUses = HashMap<(usize, usize), usize>;
+
+///Generates n inputs from the rules of the grammar. The same seed always returns the same inputs.
+///The inputs are derived from the visible non terminal symbols in turn, in the order their rules were added.
+///Symbols whose rules never end are skipped, so fewer than n inputs are returned if no symbol has a end.
+pub fn random_inputs(bnf: &BackusNaurForm<'_>, seed: u64, n: usize) -> Vec<String> {
+    let shortest = bnf.shortest_examples();
+    let names = start_symbols(bnf, &shortest);
+    if names.is_empty() {
+        return vec![];
+    }
+
+    let mut random = Random(seed);
+    (0..n)
+        .map(|index| {
+            let mut input = String::new();
+            derive(
+                bnf,
+                &shortest,
+                names[index % names.len()],
+                0,
+                &mut random,
+                None,
+                &mut input,
+            );
+            input
+        })
+        .collect()
+}
+
+///Generates up to max inputs from the rules of the grammar until every choice that can end was used,
+///and returns the inputs and how often every choice was used. The same seed always returns the same inputs.
+///More information can be found in the [testing](self) module.
+pub fn covering_inputs(bnf: &BackusNaurForm<'_>, seed: u64, max: usize) -> ChoiceCoverage {
+    let shortest = bnf.shortest_examples();
+    //the choices that can be used, choices with a symbol that never ends can't
+    let coverable = bnf
+        .rules
+        .iter()
+        .enumerate()
+        .flat_map(|(rule, (non_terminal_symbol, _))| {
+            non_terminal_symbol
+                .get_rule()
+                .iter()
+                .enumerate()
+                .filter(|(_, choice)| example_of_choice(choice, &shortest).is_some())
+                .map(move |(choice, _)| (rule, choice))
+        })
+        .collect::<Vec<_>>();
+
+    let mut uses = ChoiceUses::new();
+    let mut random = Random(seed);
+    let mut inputs = vec![];
+    for _ in 0..max {
+        //every input starts at the symbol of the first choice that wasn't used yet
+        let Some((rule, _)) = coverable.iter().find(|choice| !uses.contains_key(choice)) else {
+            break;
+        };
+        let name = bnf.rules[*rule].0.get_name();
+        let mut input = String::new();
+        derive(
+            bnf,
+            &shortest,
+            name,
+            0,
+            &mut random,
+            Some(&mut uses),
+            &mut input,
+        );
+        inputs.push(input);
+    }
+
+    let choices = bnf
+        .rules
+        .iter()
+        .enumerate()
+        .flat_map(|(rule, (non_terminal_symbol, _))| {
+            (0..non_terminal_symbol.get_rule().len()).map(move |choice| (rule, choice))
+        })
+        .map(|(rule, choice)| ChoiceUse {
+            rule: bnf.rules[rule].0.get_name().to_string(),
+            choice,
+            uses: uses.get(&(rule, choice)).copied().unwrap_or(0),
+        })
+        .collect();
+    ChoiceCoverage { inputs, choices }
+}
+
+//Returns the visible non terminal symbols that have a example, in the order their rules were added.
+fn start_symbols<'b>(
+    bnf: &'b BackusNaurForm<'_>,
+    shortest: &HashMap<&str, String>,
+) -> Vec<&'b str> {
+    let mut names: Vec<&str> = vec![];
+    for (non_terminal_symbol, _) in &bnf.rules {
+        let name = non_terminal_symbol.get_name();
+        if !names.contains(&name) && !bnf.is_hidden_symbol(name) && shortest.contains_key(name) {
+            names.push(name);
+        }
+    }
+    names
+}
+
+///Symbolizes n [random inputs](random_inputs) of the first grammar with both grammars and returns the first input
+///for which the results differ (None if they are the same for every input). The results don't contain hidden symbols,
+///so grammars that only differ in hidden rules (for example after [optimize](BackusNaurForm::optimize)) are equal.
+pub fn differential(
+    bnf_a: &BackusNaurForm<'_>,
+    bnf_b: &BackusNaurForm<'_>,
+    seed: u64,
+    n: usize,
+) -> Option<Mismatch> {
+    for input in random_inputs(bnf_a, seed, n) {
+        let a = bnf_a.try_symbolize_string(&input);
+        let b = bnf_b.try_symbolize_string(&input);
+        if a != b {
+            return Some(Mismatch { input, a, b });
+        }
+    }
+    None
+}
+
+///Shrinks a input for which the predicate returns true (for example because it fails to parse or makes a compile function panic)
+///into a input with fewer chars for which the predicate still returns true, until no smaller version is found.
+///Returns the input itself if the predicate returns false for it. More information can be found in the [testing](self) module.
+pub fn shrink_failing_input(
+    bnf: &BackusNaurForm<'_>,
+    input: &str,
+    mut predicate: impl FnMut(&str) -> bool,
+) -> String {
+    let mut shrunk = input.to_string();
+    if !predicate(&shrunk) {
+        return shrunk;
+    }
+    let shortest = bnf.shortest_examples();
+    'shrinking: loop {
+        for candidate in shrink_candidates(bnf, &shrunk, &shortest) {
+            if predicate(&candidate) {
+                shrunk = candidate;
+                continue 'shrinking;
+            }
+        }
+        return shrunk;
+    }
+}
+
+//Returns the versions of the input with fewer chars that shrink_failing_input tries, in the order they are tried:
+//the ones that follow the tokens of the input first and then the ones without a chunk of chars, the largest chunks first.
+fn shrink_candidates(
+    bnf: &BackusNaurForm<'_>,
+    input: &str,
+    shortest: &HashMap<&str, String>,
+) -> Vec<String> {
+    let chars = input.chars().collect::<Vec<_>>();
+    let text = |range: Range<usize>| chars[range].iter().collect::<String>();
+    let replace = |range: Range<usize>, replacement: &str| {
+        format!(
+            "{}{replacement}{}",
+            text(0..range.start),
+            text(range.end..chars.len())
+        )
+    };
+
+    //the symbols and the char ranges of the non terminal tokens, the outer tokens first
+    let mut spans = vec![];
+    if let Ok(tokens) = bnf.try_symbolize_string(input) {
+        //the ranges are only right if the tokens contain the whole input unchanged
+        if tokens.iter().map(Token::get_terminals).collect::<String>() == input {
+            token_spans(&tokens, 0, &mut spans);
+        }
+    }
+
+    let mut candidates = vec![];
+    for (symbol, span) in &spans {
+        candidates.push(replace(span.clone(), ""));
+        //a nested token of the same symbol can take the place of the token
+        for (nested, nested_span) in &spans {
+            if nested == symbol
+                && nested_span != span
+                && span.start <= nested_span.start
+                && nested_span.end <= span.end
+            {
+                candidates.push(replace(span.clone(), &text(nested_span.clone())));
+            }
+        }
+        if let Some(example) = shortest.get(symbol.as_str()) {
+            candidates.push(replace(span.clone(), example));
+        }
+    }
+    let mut size = chars.len() / 2;
+    while size > 0 {
+        for start in (0..chars.len()).step_by(size) {
+            candidates.push(replace(start..(start + size).min(chars.len()), ""));
+        }
+        size /= 2;
+    }
+    if chars.len() == 1 {
+        candidates.push(String::new());
+    }
+    candidates.retain(|candidate| candidate.chars().count() < chars.len());
+    candidates
+}
+
+//Adds the symbols and the char ranges of the non terminal tokens to spans, the parents before their children.
+//The tokens start at the char index start, returns the char index after them.
+fn token_spans(tokens: &[Token], start: usize, spans: &mut Vec<(String, Range<usize>)>) -> usize {
+    let mut end = start;
+    for token in tokens {
+        match token {
+            Token::NonTerminalToken(non_terminal) => {
+                let index = spans.len();
+                spans.push((non_terminal.non_terminal_symbol.to_string(), end..end));
+                end = token_spans(non_terminal.get_child_tokens(), end, spans);
+                spans[index].1.end = end;
+            }
+            Token::Terminal(terminal) => end += terminal.get_terminals().chars().count(),
+        }
+    }
+    end
+}
+
+//Appends a random derivation of the non terminal symbol to the input.
+//Only choices that can end are picked and from MAX_DEPTH on the shortest example is used.
+//If uses is Some, the choices that weren't used yet are picked first and every picked choice is counted.
+fn derive(
+    bnf: &BackusNaurForm<'_>,
+    shortest: &HashMap<&str, String>,
+    name: &str,
+    depth: usize,
+    random: &mut Random,
+    mut uses: Option<&mut ChoiceUses>,
+    input: &mut String,
+) {
+    if depth >= MAX_DEPTH {
+        input.push_str(&shortest[name]);
+        return;
+    }
+    //the choices with the index of their rule and their index in the rule
+    let (keys, choices): (Vec<_>, Vec<_>) = bnf
+        .rules
+        .iter()
+        .enumerate()
+        .filter(|(_, (non_terminal_symbol, _))| non_terminal_symbol.get_name() == name)
+        .flat_map(|(rule, (non_terminal_symbol, _))| {
+            non_terminal_symbol
+                .get_rule()
+                .iter()
+                .enumerate()
+                .map(move |(index, choice)| ((rule, index), choice))
+        })
+        .filter(|(_, choice)| example_of_choice(choice, shortest).is_some())
+        .unzip();
+    let unused = uses.as_deref().map_or(vec![], |uses| {
+        (0..keys.len())
+            .filter(|index| !uses.contains_key(&keys[*index]))
+            .collect::<Vec<_>>()
+    });
+    let picked = if unused.is_empty() {
+        pick_choice(bnf, name, &choices, random)
+    } else {
+        unused[random.below(unused.len())]
+    };
+    if let Some(uses) = uses.as_deref_mut() {
+        *uses.entry(keys[picked]).or_default() += 1;
+    }
+
+    for symbol in choices[picked] {
+        match symbol {
+            Symbol::Terminal(terminal) => input.push_str(terminal),
+            Symbol::NonTerminal(name) => derive(
+                bnf,
+                shortest,
+                name,
+                depth + 1,
+                random,
+                uses.as_deref_mut(),
+                input,
+            ),
+        }
+    }
+}
+
+//Returns the index of a random choice. Without weights every choice is picked equally often,
+//otherwise the choices without a weight share what is left of 100% (see the module documentation).
+fn pick_choice(
+    bnf: &BackusNaurForm<'_>,
+    name: &str,
+    choices: &[&Choice],
+    random: &mut Random,
+) -> usize {
+    if !bnf.choice_weights.contains_key(name) {
+        return random.below(choices.len());
+    }
+    let weights = choices
+        .iter()
+        .map(|choice| bnf.choice_weight(name, choice))
+        .collect::<Vec<_>>();
+    let unweighted = weights.iter().filter(|weight| weight.is_none()).count();
+    let weighted_sum = weights.iter().flatten().sum::<f64>();
+    let remaining = (100.0 - weighted_sum).max(0.0) / unweighted.max(1) as f64;
+    let weights = weights
+        .into_iter()
+        .map(|weight| weight.unwrap_or(remaining).max(0.0))
+        .collect::<Vec<_>>();
+    let total = weights.iter().sum::<f64>();
+    if total <= 0.0 {
+        return random.below(choices.len());
+    }
+
+    let mut target = random.unit() * total;
+    for (index, weight) in weights.iter().enumerate() {
+        if target < *weight {
+            return index;
+        }
+        target -= weight;
+    }
+    //rounding errors can leave a tiny rest
+    weights
+        .iter()
+        .rposition(|weight| *weight > 0.0)
+        .unwrap_or(0)
+}
+
+//A splitmix64 generator, which is enough to pick choices and keeps the crate free of dependencies.
+struct Random(u64);
+
+impl Random {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    //Returns a number in 0..n.
+    fn below(&mut self, n: usize) -> usize {
+        (self.next() % n as u64) as usize
+    }
+
+    //Returns a number in 0.0..1.0, made of the upper 53 bits so every value is exact.
+    fn unit(&mut self) -> f64 {
+        (self.next() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::backus_naur_form::optimize::OptimizeOptions;
+    use crate::backus_naur_form::test_helpers::DIGIT_RULE;
+
+    use super::*;
+
+    fn grammar() -> BackusNaurForm<'static> {
+        crate::backus_naur_form!(
+            priority 2 => DIGIT_RULE
+            priority 1 => r#"<value> ::= <digit>"#
+            priority 0 => r#"<stmt> ::= "l" "e" "t" <value> | "x" | "l" "e" "t" "(" <value> ")""#
+        )
+    }
+
+    #[test]
+    fn test_differential() {
+        let bnf = grammar();
+        let inputs = random_inputs(&bnf, 7, 30);
+        assert_eq!(inputs.len(), 30);
+        assert_eq!(inputs, random_inputs(&bnf, 7, 30));
+        assert_ne!(inputs, random_inputs(&bnf, 8, 30));
+        assert!(inputs.contains(&"let(2)".to_string()));
+
+        let mut optimized = grammar();
+        optimized.optimize();
+        assert_eq!(differential(&bnf, &optimized, 7, 100), None);
+
+        //inlining <value> removes it from the trees
+        let mut inlined = grammar();
+        inlined.optimize_with(&OptimizeOptions {
+            inline_unit_rules: true,
+        });
+        let mismatch = differential(&bnf, &inlined, 7, 100).unwrap();
+        assert_ne!(mismatch.a, mismatch.b);
+    }
+
+    #[test]
+    fn test_choice_weights() {
+        let mut bnf = crate::backus_naur_form!(
+            priority 0 => r#"<digit> ::= 90% "0" | "1" | "2""#
+        );
+        let choice = |terminal: &str| [Symbol::terminal(terminal)].into_iter().collect::<Choice>();
+        assert_eq!(bnf.choice_weight("digit", &choice("0")), Some(90.0));
+        assert_eq!(bnf.choice_weight("digit", &choice("1")), None);
+
+        let inputs = random_inputs(&bnf, 3, 1000);
+        let zeros = inputs.iter().filter(|input| *input == "0").count();
+        assert!((850..950).contains(&zeros), "{zeros} zeros");
+        assert!(inputs.contains(&"1".to_string()) && inputs.contains(&"2".to_string()));
+
+        //the unweighted choices share nothing if the weights add up to 100%
+        bnf.set_choice_weight("digit", choice("0"), 50.0);
+        bnf.set_choice_weight("digit", choice("2"), 50.0);
+        let inputs = random_inputs(&bnf, 3, 1000);
+        assert!(!inputs.contains(&"1".to_string()));
+        assert!(inputs.contains(&"0".to_string()) && inputs.contains(&"2".to_string()));
+    }
+
+    #[test]
+    fn test_covering_inputs() {
+        let mut bnf = grammar();
+        bnf.add_non_terminal_symbol_from_rule(r#"<endless> ::= "a" <endless> | "b""#, 0);
+        bnf.add_non_terminal_symbol_from_rule(r#"<never> ::= "a" <never>"#, 0);
+        let coverage = covering_inputs(&bnf, 7, 100);
+        assert_eq!(coverage, covering_inputs(&bnf, 7, 100));
+        //every input uses at least one choice that wasn't used before, so the 8 choices that can end need at most 8 inputs
+        assert!(coverage.inputs.len() <= 8, "{:?}", coverage.inputs);
+        let uncovered = coverage.uncovered();
+        assert_eq!(uncovered.len(), 1);
+        assert_eq!(
+            (uncovered[0].rule.as_str(), uncovered[0].choice),
+            ("never", 0)
+        );
+        assert_eq!(coverage.ratio(), 8.0 / 9.0);
+        assert!(coverage
+            .to_string()
+            .starts_with("8 of 9 choices covered by"));
+        assert!(coverage
+            .to_string()
+            .ends_with("\nthe choice 0 of <never> is never used"));
+        for input in &coverage.inputs {
+            assert!(bnf.try_symbolize_string(input).is_ok());
+        }
+    }
+
+    #[test]
+    fn test_shrink_failing_input() {
+        let bnf = crate::backus_naur_form!(
+            priority 1 => r#"<digit> ::= "1" | "2" | "3""#
+            priority 0 => r#"<expr> ::= <digit> | "(" <expr> ")" | <expr> "+" <expr>"#
+        );
+        //fails for every expression with a 3 right after a opening bracket
+        let mut calls = 0;
+        let predicate = |input: &str| {
+            calls += 1;
+            input.contains("(3")
+                && bnf
+                    .try_symbolize_string(input)
+                    .is_ok_and(|tokens| tokens.len() == 1)
+        };
+        assert_eq!(
+            shrink_failing_input(&bnf, "1+(2+(3+1))+2", predicate),
+            "(3)"
+        );
+        assert!(calls > 1);
+
+        assert_eq!(shrink_failing_input(&bnf, "1+2", |_| false), "1+2");
+        assert_eq!(shrink_failing_input(&bnf, "1+2", |_| true), "");
+    }
+}
@@ -10,9 +10,17 @@
 //!this module offers functions to retrieve the singular digits from number symbols and the actual digits as strings from the digit symbols
 //!the comments and documentation below will take the bnf listed above for examples
 
+pub mod cow;
+pub mod html;
 pub mod non_terminal_token;
+pub mod pattern;
+pub mod shared_tree;
+pub mod traversal;
 
-use std::fmt::{Debug, Display};
+use std::{
+    fmt::{Debug, Display},
+    hash::{DefaultHasher, Hash, Hasher},
+};
 
 use non_terminal_token::NonTerminalToken;
 
@@ -32,23 +40,66 @@ use super::symbol::Symbol;
 #[derive(PartialEq, Clone, Debug)]
 pub struct TokenIndex(Vec<usize>);
 
+impl TokenIndex {
+    ///Returns the indexes of the sub tokens from the outermost to the innermost one.
+    pub fn indexes(&self) -> &[usize] {
+        &self.0
+    }
+}
+
+impl From<Vec<usize>> for TokenIndex {
+    fn from(indexes: Vec<usize>) -> Self {
+        Self(indexes)
+    }
+}
+
 ///[TerminalToken]s are the leaves of the AST.  
 ///They contain the actual strings.  
-#[derive(PartialEq, Clone, Debug)]
-pub struct TerminalToken(String);
+///The offset is only metadata, two [TerminalToken]s with the same string are equal even if they come from different places.
+#[derive(Clone, Debug)]
+pub struct TerminalToken {
+    terminals: String,
+    offset: Option<usize>,
+}
 
 impl TerminalToken {
     ///Returns the terminals it contains as a &str.  
     ///For example, this may return be "2" or "b" or "hello".
     pub fn get_terminals(&self) -> &str {
-        &self.0
+        &self.terminals
+    }
+
+    ///Returns the char index in the symbolized [String] that the terminals start at.
+    ///If the [String] was changed by preprocessors, this is the index in the preprocessed [String],
+    ///which [original_position](super::preprocess::Preprocessed::original_position) maps back.
+    ///Returns None if the token wasn't made from a [String], for example with [Token::from_terminal].
+    pub fn offset(&self) -> Option<usize> {
+        self.offset
+    }
+
+    ///Returns the amount of chars of the terminals.
+    pub fn length(&self) -> usize {
+        self.terminals.chars().count()
+    }
+}
+
+impl PartialEq for TerminalToken {
+    fn eq(&self, other: &Self) -> bool {
+        self.terminals == other.terminals
+    }
+}
+
+impl Eq for TerminalToken {}
+
+impl Hash for TerminalToken {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.terminals.hash(state);
     }
 }
 
 impl Display for TerminalToken {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let string = self.0.to_string();
-        write!(f, "{string}")
+        write!(f, "{}", self.terminals)
     }
 }
 
@@ -67,7 +118,7 @@ impl Display for TerminalToken {
 /// ```
 ///In this case, `<expression>` is a [NonTerminalToken] that has the child [Token]s `<expression>`, `<operator>` and `<expression>`.  
 ///Those in turn contain [TerminalToken]s that is the actual string that got turned into syntax tree.
-#[derive(PartialEq, Clone)]
+#[derive(PartialEq, Eq, Hash, Clone)]
 pub enum Token {
     //a terminal token just is a slice of the string it represents
     Terminal(TerminalToken),
@@ -78,7 +129,18 @@ pub enum Token {
 impl Token {
     ///Omit the (nested) double quotes on the terminal or it will mess up stuff.
     pub fn from_terminal(terminal: &str) -> Self {
-        Self::Terminal(TerminalToken(terminal.to_string()))
+        Self::Terminal(TerminalToken {
+            terminals: terminal.to_string(),
+            offset: None,
+        })
+    }
+
+    ///Same as [Token::from_terminal], but the [TerminalToken] knows the char index it starts at (see [TerminalToken::offset]).
+    pub fn from_terminal_at(terminal: &str, offset: usize) -> Self {
+        Self::Terminal(TerminalToken {
+            terminals: terminal.to_string(),
+            offset: Some(offset),
+        })
     }
 
     ///Omit the angle brackets on non_terminal_symbol or it will mess up stuff.
@@ -124,6 +186,14 @@ impl Token {
         }
     }
 
+    ///Returns the [Symbol] of this [Token], a [Symbol::Terminal] or a [Symbol::NonTerminal].
+    pub fn get_type(&self) -> Symbol {
+        match self {
+            Token::Terminal(terminal) => Symbol::Terminal(terminal.get_terminals().to_string()),
+            Token::NonTerminalToken(inner) => inner.get_type(),
+        }
+    }
+
     ///Returns true if self is a [TerminalToken].
     ///Returns false if self is not a [TerminalToken] (aka self is a [NonTerminalToken]).
     pub fn is_terminal(&self) -> bool {
@@ -137,7 +207,35 @@ impl Token {
     ///and it will return true if self is that symbol.
     ///Returns false if self is not that symbol.
     pub fn is_of_type(&self, t: &Symbol) -> bool {
-        t == self
+        self.matches(t)
+    }
+
+    ///Returns true if self is a [NonTerminalToken] of the non terminal symbol with the name (without angle brackets).
+    ///A [TerminalToken] never matches, even if its text is the name.
+    pub fn is_non_terminal_named(&self, name: &str) -> bool {
+        match self {
+            Token::Terminal(_) => false,
+            Token::NonTerminalToken(inner) => inner.non_terminal_symbol == name,
+        }
+    }
+
+    ///Returns true if self is a [TerminalToken] that contains exactly the text.
+    ///A [NonTerminalToken] never matches, even if its terminals are the text.
+    pub fn is_terminal_text(&self, text: &str) -> bool {
+        match self {
+            Token::Terminal(terminal) => terminal.get_terminals() == text,
+            Token::NonTerminalToken(_) => false,
+        }
+    }
+
+    ///Returns true if self is the [Symbol]: a [TerminalToken] with the text of a [Symbol::Terminal]
+    ///or a [NonTerminalToken] with the name of a [Symbol::NonTerminal].
+    ///This is what `token == symbol` compares too, but spelled out.
+    pub fn matches(&self, symbol: &Symbol) -> bool {
+        match symbol {
+            Symbol::Terminal(text) => self.is_terminal_text(text),
+            Symbol::NonTerminal(name) => self.is_non_terminal_named(name),
+        }
     }
 
     ///Returns the terminals it contains as a type of [String].
@@ -151,6 +249,45 @@ impl Token {
         }
     }
 
+    ///Returns a hash of the whole subtree (the symbols and terminals of the token and all of its descendants).
+    ///Tokens with the same structure always have the same hash, so this can be used to find repeated subtrees.
+    ///The hash is not meant to be stored, it may change between versions of the crate.
+    pub fn structural_hash(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    ///Replaces every token of the symbol (self and its descendants) with the result of f.  
+    ///The tokens are replaced bottom-up in a single traversal: the descendants of a token are replaced before the token itself,
+    ///so f receives tokens whose descendants were already replaced. The tokens f returns are not searched again.  
+    ///Returns the number of replaced tokens.
+    pub fn replace_all<F>(&mut self, symbol: &Symbol, mut f: F) -> usize
+    where
+        F: FnMut(Token) -> Token,
+    {
+        self.replace_all_with(symbol, &mut f)
+    }
+
+    //The recursive part of replace_all.
+    fn replace_all_with(&mut self, symbol: &Symbol, f: &mut dyn FnMut(Token) -> Token) -> usize {
+        let mut replaced = match self {
+            Token::NonTerminalToken(non_terminal) => non_terminal
+                .get_child_tokens_mut()
+                .iter_mut()
+                .map(|child| child.replace_all_with(symbol, f))
+                .sum(),
+            Token::Terminal(_) => 0,
+        };
+        if self.is_of_type(symbol) {
+            //f takes the token by value, so a empty terminal takes its place until f returns
+            let token = std::mem::replace(self, Token::from_terminal(""));
+            *self = f(token);
+            replaced += 1;
+        }
+        replaced
+    }
+
     ///Turns self into a [TerminalToken].
     ///Returns None if self is not a [TerminalToken].
     pub fn to_terminal(self) -> Option<TerminalToken> {
@@ -254,7 +391,7 @@ impl From<NonTerminalToken> for Token {
 
 impl PartialEq<String> for TerminalToken {
     fn eq(&self, other: &String) -> bool {
-        self.0 == *other
+        self.terminals == *other
     }
 }
 
@@ -266,7 +403,7 @@ impl PartialEq<TerminalToken> for String {
 
 impl PartialEq<&str> for TerminalToken {
     fn eq(&self, other: &&str) -> bool {
-        self.0 == *other
+        self.terminals == *other
     }
 }
 
@@ -293,16 +430,7 @@ impl PartialEq<Symbol> for TerminalToken {
 
 impl PartialEq<Symbol> for Token {
     fn eq(&self, other: &Symbol) -> bool {
-        match other {
-            Symbol::Terminal(inner) => match self {
-                Token::Terminal(token_inner) => inner == token_inner,
-                Token::NonTerminalToken(_) => false,
-            },
-            Symbol::NonTerminal(inner) => match self {
-                Token::Terminal(_) => false,
-                Token::NonTerminalToken(token_inner) => inner == &token_inner.non_terminal_symbol,
-            },
-        }
+        self.matches(other)
     }
 }
 
@@ -345,4 +473,57 @@ mod tests {
 
         assert_eq!(token_tree.get_terminals(), "1243".to_string())
     }
+
+    #[test]
+    fn test_predicates() {
+        let digit = Token::from_non_terminal("digit", vec![Token::from_terminal("1")]);
+        let named = Token::from_terminal("digit");
+
+        assert!(digit.is_non_terminal_named("digit"));
+        assert!(!named.is_non_terminal_named("digit"));
+        assert!(named.is_terminal_text("digit"));
+        assert!(!digit.is_terminal_text("1"));
+        assert!(digit.matches(&Symbol::non_terminal("digit")));
+        assert!(!digit.matches(&Symbol::terminal("digit")));
+        assert!(named.matches(&Symbol::terminal("digit")));
+    }
+
+    #[test]
+    fn test_replace_all() {
+        let string = |content: &str| {
+            Token::from_non_terminal("string-literal", vec![Token::from_terminal(content)])
+        };
+        let call = |args| Token::from_non_terminal("call", args);
+        let mut token_tree = call(vec![
+            string("a"),
+            call(vec![string("b"), string("a")]),
+            Token::from_terminal(";"),
+        ]);
+
+        let mut interned: Vec<String> = vec![];
+        let replaced = token_tree.replace_all(&Symbol::non_terminal("string-literal"), |token| {
+            let content = token.get_terminals();
+            let id = match interned.iter().position(|other| *other == content) {
+                Some(id) => id,
+                None => {
+                    interned.push(content);
+                    interned.len() - 1
+                }
+            };
+            Token::from_non_terminal("string-id", vec![Token::from_terminal(&id.to_string())])
+        });
+
+        assert_eq!(replaced, 3);
+        assert_eq!(interned, ["a", "b"]);
+        assert_eq!(token_tree.get_terminals(), "010;");
+
+        //the children are replaced before their parents
+        let mut order = vec![];
+        token_tree.replace_all(&Symbol::non_terminal("call"), |token| {
+            order.push(token.get_terminals());
+            Token::from_terminal("x")
+        });
+        assert_eq!(order, ["10", "0x;"]);
+        assert_eq!(token_tree, Token::from_terminal("x"));
+    }
 }
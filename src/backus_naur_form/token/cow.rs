@@ -0,0 +1,154 @@
+//! Contains [CowToken], a version of a AST whose subtrees are shared between copies until they are changed.
+//! Transformation passes often keep the original AST while building a changed copy of it. With [Token]s every copy clones the whole tree,
+//! a [CowToken] only clones the tokens on the path to the token that is changed (copy-on-write):
+//! ```rust, ignore
+//! let original = CowToken::from(bnf.symbolize_string("1+2").remove(0));
+//! let mut changed = original.clone(); //doesn't clone any token
+//! *changed.get_mut(&TokenIndex::from(vec![2, 0])).unwrap() = CowToken::from(Token::from_terminal("3"));
+//! //the root and the third child are copied, the first two children are still shared with the original
+//! ```
+
+use std::sync::Arc;
+
+use super::{non_terminal_token::NonTerminalToken, TerminalToken, Token, TokenIndex};
+
+///A token of a [CowToken] tree. The children are [CowToken]s themselves, so they can be shared too.
+#[derive(PartialEq, Clone, Debug)]
+pub enum CowNode {
+    Terminal(TerminalToken),
+    NonTerminal {
+        non_terminal_symbol: String,
+        children: Vec<CowToken>,
+    },
+}
+
+///A AST whose subtrees are shared between clones and only copied when they are changed.
+///Cloning a [CowToken] is cheap, it never clones a token.
+///More information can be found in the [cow](self) module.
+#[derive(PartialEq, Clone, Debug)]
+pub struct CowToken(Arc<CowNode>);
+
+impl CowToken {
+    ///Returns the node of the token.
+    pub fn node(&self) -> &CowNode {
+        &self.0
+    }
+
+    ///Returns the node of the token to change it. If the node is shared with another [CowToken], it is copied first
+    ///(without copying its children, they stay shared until they are changed too).
+    pub fn make_mut(&mut self) -> &mut CowNode {
+        Arc::make_mut(&mut self.0)
+    }
+
+    ///Returns true if both tokens share the same node, so no copy was made between them.
+    pub fn ptr_eq(&self, other: &CowToken) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+
+    ///Returns the children of the token, terminals have none.
+    pub fn children(&self) -> &[CowToken] {
+        match self.node() {
+            CowNode::Terminal(_) => &[],
+            CowNode::NonTerminal { children, .. } => children,
+        }
+    }
+
+    ///Returns a descendant of the token like [Token::get].
+    pub fn get(&self, token_index: &TokenIndex) -> Option<&CowToken> {
+        let (first, rest) = token_index.indexes().split_first()?;
+        rest.iter()
+            .try_fold(self.children().get(*first)?, |token, index| {
+                token.children().get(*index)
+            })
+    }
+
+    ///Returns a descendant of the token to change it, like [Token::get_mut].
+    ///The tokens on the path to the descendant that are shared are copied, every other token stays shared.
+    pub fn get_mut(&mut self, token_index: &TokenIndex) -> Option<&mut CowToken> {
+        let indexes = token_index.indexes();
+        if indexes.is_empty() || self.get(token_index).is_none() {
+            //nothing is copied if there is no descendant at the index
+            return None;
+        }
+        let mut token = self;
+        for index in indexes {
+            let CowNode::NonTerminal { children, .. } = token.make_mut() else {
+                unreachable!("the descendant exists, so every token on the path has children");
+            };
+            token = &mut children[*index];
+        }
+        Some(token)
+    }
+
+    ///Returns the terminals of the token and its descendants like [Token::get_terminals].
+    pub fn get_terminals(&self) -> String {
+        match self.node() {
+            CowNode::Terminal(terminal) => terminal.get_terminals().to_string(),
+            CowNode::NonTerminal { children, .. } => {
+                children.iter().map(CowToken::get_terminals).collect()
+            }
+        }
+    }
+
+    ///Turns the tree back into [Token]s, which clones every token.
+    pub fn to_token(&self) -> Token {
+        match self.node() {
+            CowNode::Terminal(terminal) => Token::Terminal(terminal.clone()),
+            CowNode::NonTerminal {
+                non_terminal_symbol,
+                children,
+            } => Token::NonTerminalToken(NonTerminalToken::new(
+                non_terminal_symbol,
+                children.iter().map(CowToken::to_token).collect(),
+            )),
+        }
+    }
+}
+
+impl From<Token> for CowToken {
+    fn from(token: Token) -> Self {
+        let node = match token {
+            Token::Terminal(terminal) => CowNode::Terminal(terminal),
+            Token::NonTerminalToken(mut non_terminal) => CowNode::NonTerminal {
+                children: std::mem::take(non_terminal.get_child_tokens_mut())
+                    .into_iter()
+                    .map(CowToken::from)
+                    .collect(),
+                non_terminal_symbol: non_terminal.non_terminal_symbol,
+            },
+        };
+        Self(Arc::new(node))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backus_naur_form::test_helpers::digit;
+
+    #[test]
+    fn test_copy_on_write() {
+        let token = Token::from_non_terminal(
+            "sum",
+            vec![digit("1"), Token::from_terminal("+"), digit("2")],
+        );
+        let original = CowToken::from(token.clone());
+        let mut changed = original.clone();
+        assert!(changed.ptr_eq(&original));
+
+        let index = TokenIndex::from(vec![2, 0]);
+        *changed.get_mut(&index).unwrap() = CowToken::from(Token::from_terminal("3"));
+        assert_eq!(changed.get_terminals(), "1+3");
+        assert_eq!(original.to_token(), token);
+        //only the path to the changed token was copied
+        assert!(!changed.ptr_eq(&original));
+        assert!(!changed.children()[2].ptr_eq(&original.children()[2]));
+        assert!(changed.children()[0].ptr_eq(&original.children()[0]));
+
+        assert!(changed.get_mut(&TokenIndex::from(vec![1, 0])).is_none());
+        assert_eq!(
+            original.get(&TokenIndex::from(vec![0])).unwrap().to_token(),
+            digit("1")
+        );
+    }
+}
@@ -0,0 +1,88 @@
+//! Renders a AST as HTML, so parse results can be shared in bug reports or embedded in documentation:
+//! ```rust, ignore
+//! let html = bnf.symbolize_string("12+3")[0].to_html();
+//! std::fs::write("ast.html", html)?;
+//! ```
+//! Every [NonTerminalToken](super::non_terminal_token::NonTerminalToken) is a `<details>` element that can be collapsed,
+//! with the name of its symbol as summary. The color of a symbol is derived from its name, so every token of a symbol has the same color.
+//! Terminals are highlighted `<code>` elements. The HTML is a fragment with its own styles and doesn't need any scripts.
+
+use crate::backus_naur_form::escape::Escaper;
+
+use super::Token;
+
+//The styles of the fragment. The children of a token are indented below it.
+const STYLE: &str = ".bnf-ast details > details, .bnf-ast details > code { margin-left: 1.5em; } \
+.bnf-ast summary { font-weight: bold; font-family: monospace; } \
+.bnf-ast code { display: block; width: fit-content; background: #fff3b0; white-space: pre; }";
+
+impl Token {
+    ///Returns the token and its descendants as collapsible HTML. More information can be found in the [html](self) module.
+    pub fn to_html(&self) -> String {
+        let mut html = format!("<div class=\"bnf-ast\"><style>{STYLE}</style>");
+        write_html(self, &mut html);
+        html.push_str("</div>");
+        html
+    }
+}
+
+fn write_html(token: &Token, html: &mut String) {
+    match token {
+        Token::Terminal(terminal) => {
+            html.push_str(&format!(
+                "<code>{}</code>",
+                Escaper::html().escape(terminal.get_terminals())
+            ));
+        }
+        Token::NonTerminalToken(non_terminal) => {
+            let name = &non_terminal.non_terminal_symbol;
+            html.push_str(&format!(
+                "<details open><summary style=\"color: hsl({}, 70%, 35%)\">&lt;{}&gt;</summary>",
+                hue(name),
+                Escaper::html().escape(name)
+            ));
+            for child in non_terminal.get_child_tokens() {
+                write_html(child, html);
+            }
+            html.push_str("</details>");
+        }
+    }
+}
+
+//Returns the hue of the color of the symbol. The hash is spelled out, so a symbol has the same color with every version of rust.
+fn hue(name: &str) -> u32 {
+    name.bytes().fold(0u32, |hash, byte| {
+        hash.wrapping_mul(31).wrapping_add(byte as u32)
+    }) % 360
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_html() {
+        let token = Token::from_non_terminal(
+            "sum",
+            vec![
+                Token::from_non_terminal("digit", vec![Token::from_terminal("1")]),
+                Token::from_terminal("<"),
+            ],
+        );
+        let html = token.to_html();
+
+        assert!(html.starts_with("<div class=\"bnf-ast\"><style>"));
+        assert!(html.ends_with(&format!(
+            "<details open><summary style=\"color: hsl({}, 70%, 35%)\">&lt;sum&gt;</summary>\
+<details open><summary style=\"color: hsl({}, 70%, 35%)\">&lt;digit&gt;</summary><code>1</code></details>\
+<code>&lt;</code></details></div>",
+            hue("sum"),
+            hue("digit")
+        )));
+        assert_ne!(hue("sum"), hue("digit"));
+        assert_eq!(
+            Token::from_terminal("a&b").to_html(),
+            format!("<div class=\"bnf-ast\"><style>{STYLE}</style><code>a&amp;b</code></div>")
+        );
+    }
+}
@@ -2,6 +2,7 @@ use crate::backus_naur_form::symbol::Symbol;
 
 use super::{Token, TokenIndex};
 
+//unlike Choice this can't be a SmallVec, a Token can't contain Tokens inline since it would have a infinite size.
 type SubTokens = Vec<Token>;
 
 impl FromIterator<usize> for TokenIndex {
@@ -34,7 +35,7 @@ impl<'a> FromIterator<&'a usize> for TokenIndex {
 /// ```
 ///In this case, `<expression>` is a [NonTerminalToken] that has the child [Token]s `<expression>`, `<operator>` and `<expression>`.  
 ///Those in turn contain [TerminalToken]s that is the actual string that got turned into syntax tree.
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Eq, Hash, Clone, Debug)]
 pub struct NonTerminalToken {
     ///this is the non terminal it is (for example <number> or <digit>).  
     ///the angle brackets are excluded in this property.  
@@ -126,7 +127,9 @@ impl NonTerminalToken {
     ///that has a choice where its recursive and atleast one choice contains only tokens of itself (like `... | <number> <number> | ...`)
     ///is not recommended.  
     ///   
-    ///To get the actual terminals that the token consists of, use [NonTerminalToken::get_terminals] instead.
+    ///To get the actual terminals that the token consists of, use [NonTerminalToken::get_terminals] instead.  
+    ///The descendants are in pre-order, like [descendants_preorder](NonTerminalToken::descendants_preorder) returns them.
+    ///The [traversal](super::traversal) module has iterators for other orders.
     pub fn get_descendant_tokens(&self) -> Vec<&Token> {
         self.get_child_tokens()
             .iter()
@@ -141,23 +144,67 @@ impl NonTerminalToken {
             .collect()
     }
 
-    ///This function returns child [Token]s of self that are of a specific [Symbol].
+    ///This function returns child [Token]s of self that are of a specific [Symbol] (see [Token::matches]).
     pub fn get_child_tokens_of_type(&self, symbol_type: &Symbol) -> Vec<&Token> {
+        self.filter_children(|sub_token| sub_token.matches(symbol_type))
+    }
+
+    ///This function returns descendant [Token]s of self that are of a specific [Symbol] (see [Token::matches]).
+    pub fn get_descendant_tokens_of_type(&self, symbol_type: &Symbol) -> Vec<&Token> {
+        self.filter_descendants(|sub_token| sub_token.matches(symbol_type))
+    }
+
+    ///Returns the child [Token]s of self for which the predicate returns true.
+    ///For example `filter_children(|token| token.is_non_terminal_named("digit"))`.
+    pub fn filter_children<F>(&self, predicate: F) -> Vec<&Token>
+    where
+        F: Fn(&Token) -> bool,
+    {
         self.get_child_tokens()
             .iter()
-            .filter(|&sub_token| sub_token == symbol_type)
+            .filter(|sub_token| predicate(sub_token))
             .collect()
     }
 
-    ///This function returns descendant [Token]s of self that are of a specific [Symbol].
-    pub fn get_descendant_tokens_of_type(&self, symbol_type: &Symbol) -> Vec<&Token> {
+    ///Returns the descendant [Token]s of self for which the predicate returns true, in pre-order.
+    pub fn filter_descendants<F>(&self, predicate: F) -> Vec<&Token>
+    where
+        F: Fn(&Token) -> bool,
+    {
         self.get_descendant_tokens()
-            .iter()
-            .filter(|&&sub_token| sub_token == symbol_type)
-            .cloned()
+            .into_iter()
+            .filter(|sub_token| predicate(sub_token))
             .collect()
     }
 
+    ///Same as [NonTerminalToken::get_descendant_tokens_of_type] but every [Token] is paired with its [TokenIndex],
+    ///so it can be changed later with [NonTerminalToken::get_at_index_mut] without searching again.  
+    ///The [Token]s are in pre-order.
+    pub fn find_descendants_with_index(&self, symbol_type: &Symbol) -> Vec<(TokenIndex, &Token)> {
+        let mut found = vec![];
+        self.collect_descendants_with_index(symbol_type, &mut vec![], &mut found);
+        found
+    }
+
+    //Adds the descendants of the type to found. path is the index of self.
+    fn collect_descendants_with_index<'a>(
+        &'a self,
+        symbol_type: &Symbol,
+        path: &mut Vec<usize>,
+        found: &mut Vec<(TokenIndex, &'a Token)>,
+    ) {
+        for (index, sub_token) in self.get_child_tokens().iter().enumerate() {
+            path.push(index);
+            if sub_token == symbol_type {
+                found.push((TokenIndex(path.to_vec()), sub_token));
+            }
+            if let Token::NonTerminalToken(inner) = sub_token {
+                inner.collect_descendants_with_index(symbol_type, path, found);
+            }
+            path.pop();
+        }
+    }
+
     ///This function checks if any child of self is of type sub_token_type.
     pub fn contains_child(&self, sub_token_type: &Symbol) -> bool {
         self.get_child_tokens()
@@ -213,6 +260,67 @@ impl NonTerminalToken {
         })
     }
 
+    ///Unlike [NonTerminalToken::find_descendant], this returns the matching descendant itself instead of the child that contains it.
+    ///The [TokenIndex] of the descendant is returned too.  
+    ///The descendants are searched in pre-order, so a token is found before its descendants and left siblings before right siblings.  
+    ///Returns None if no such [Token] exists.
+    pub fn find_descendant_deep(&self, sub_token_type: &Symbol) -> Option<(TokenIndex, &Token)> {
+        let token_index = self.find_descendant_index(sub_token_type)?;
+        let token = self.get_at_index(&token_index)?;
+        Some((token_index, token))
+    }
+
+    ///Same as [NonTerminalToken::find_descendant_deep] but returns a mutable reference.
+    pub fn find_descendant_deep_mut(
+        &mut self,
+        sub_token_type: &Symbol,
+    ) -> Option<(TokenIndex, &mut Token)> {
+        let token_index = self.find_descendant_index(sub_token_type)?;
+        let token = self.get_at_index_mut(token_index.clone())?;
+        Some((token_index, token))
+    }
+
+    //Returns the index of the first descendant of the type in pre-order.
+    fn find_descendant_index(&self, sub_token_type: &Symbol) -> Option<TokenIndex> {
+        self.get_child_tokens()
+            .iter()
+            .enumerate()
+            .find_map(|(index, sub_token)| {
+                if sub_token == sub_token_type {
+                    return Some(TokenIndex(vec![index]));
+                }
+                let Token::NonTerminalToken(inner) = sub_token else {
+                    return None;
+                };
+                let mut token_index = inner.find_descendant_index(sub_token_type)?;
+                token_index.0.insert(0, index);
+                Some(token_index)
+            })
+    }
+
+    ///Evaluates the token bottom-up into a value of any type (for example a number or a list of instructions),
+    ///which makes it possible to compile a AST into something that isn't a [String].  
+    ///f is called for every [NonTerminalToken] (self included) with the values of its [NonTerminalToken] children.
+    ///The children are always evaluated from left to right and their values are in the same order as the children.
+    ///[TerminalToken](super::TerminalToken)s have no value, f can read them from the token.
+    pub fn evaluate<T, F>(&self, mut f: F) -> T
+    where
+        F: FnMut(&NonTerminalToken, Vec<T>) -> T,
+    {
+        self.evaluate_with(&mut f)
+    }
+
+    //The recursive part of evaluate.
+    fn evaluate_with<T>(&self, f: &mut dyn FnMut(&NonTerminalToken, Vec<T>) -> T) -> T {
+        let values = self
+            .get_child_tokens()
+            .iter()
+            .filter_map(|child| child.to_non_terminal_ref())
+            .map(|child| child.evaluate_with(f))
+            .collect();
+        f(self, values)
+    }
+
     ///Returns the terminals that this [NonTerminalToken] consists of as a [String].  
     ///For example a `<function>` token may consist of a <function_name> and a <function_body>
     ///which in turn consist of a <word> or <instructions> respectively
@@ -259,6 +367,7 @@ impl PartialEq<NonTerminalToken> for Symbol {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::backus_naur_form::test_helpers::digit;
     use crate::backus_naur_form::token::{Token, TokenIndex};
 
     #[test]
@@ -292,9 +401,6 @@ mod tests {
 
     #[test]
     fn test_get_terminals() {
-        let digit = |terminal_digit: &str| {
-            Token::from_non_terminal("digit", vec![Token::from_terminal(terminal_digit)])
-        };
         let operator = Token::from_non_terminal("operator", vec![Token::from_terminal("+")]);
         let expression =
             |lhs, operator, rhs| Token::from_non_terminal("expression", vec![lhs, operator, rhs]);
@@ -345,9 +451,39 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_filter_tokens() {
+        let a = Token::from_terminal("a");
+        let nested = Token::from_non_terminal("nested", vec![a.clone()]);
+        let non_terminal = NonTerminalToken::new(
+            "test",
+            vec![
+                a.clone(),
+                Token::from_terminal("nested"),
+                Token::from_non_terminal("c", vec![nested.clone()]),
+            ],
+        );
+
+        assert_eq!(
+            non_terminal.filter_children(|token| token.is_terminal_text("nested")),
+            vec![&Token::from_terminal("nested")]
+        );
+        assert_eq!(
+            non_terminal.filter_descendants(|token| token.is_non_terminal_named("nested")),
+            vec![&nested]
+        );
+        assert_eq!(
+            non_terminal.filter_descendants(|token| token.is_terminal_text("a")),
+            vec![&a, &a]
+        );
+        assert_eq!(
+            non_terminal.get_child_tokens_of_type(&Symbol::non_terminal("nested")),
+            Vec::<&Token>::new()
+        );
+    }
+
     #[test]
     fn test_token_index() {
-        let digit = |digit| Token::from_non_terminal("digit", vec![Token::from_terminal(digit)]);
         let operator =
             |operator| Token::from_non_terminal("operator", vec![Token::from_terminal(operator)]);
         let expression =
@@ -393,4 +529,90 @@ mod tests {
         );
         assert_eq!(non_terminal.get_mut(TokenIndex(vec![2, 3, 4])), None);
     }
+
+    #[test]
+    fn test_find_descendant_deep() {
+        let sum =
+            |lhs, rhs| Token::from_non_terminal("sum", vec![lhs, Token::from_terminal("+"), rhs]);
+        let mut non_terminal = NonTerminalToken::new(
+            "expression",
+            vec![sum(sum(digit("1"), digit("2")), digit("3"))],
+        );
+        let digit_symbol = Symbol::non_terminal("digit");
+
+        assert_eq!(
+            non_terminal.find_descendant(&digit_symbol),
+            Some(&sum(sum(digit("1"), digit("2")), digit("3")))
+        );
+        assert_eq!(
+            non_terminal.find_descendant_deep(&digit_symbol),
+            Some((TokenIndex(vec![0, 0, 0]), &digit("1")))
+        );
+        assert_eq!(
+            non_terminal.find_descendant_deep(&Symbol::terminal("3")),
+            Some((TokenIndex(vec![0, 2, 0]), &Token::from_terminal("3")))
+        );
+        assert_eq!(
+            non_terminal.find_descendant_deep(&Symbol::non_terminal("product")),
+            None
+        );
+
+        let (token_index, token) = non_terminal
+            .find_descendant_deep_mut(&digit_symbol)
+            .unwrap();
+        *token = digit("4");
+        assert_eq!(non_terminal.get_at_index(&token_index), Some(&digit("4")));
+    }
+
+    #[test]
+    fn test_find_descendants_with_index() {
+        let sum =
+            |lhs, rhs| Token::from_non_terminal("sum", vec![lhs, Token::from_terminal("+"), rhs]);
+        let mut non_terminal = NonTerminalToken::new(
+            "expression",
+            vec![sum(sum(digit("1"), digit("2")), digit("3"))],
+        );
+        let digit_symbol = Symbol::non_terminal("digit");
+
+        let found = non_terminal.find_descendants_with_index(&digit_symbol);
+        assert_eq!(
+            found,
+            vec![
+                (TokenIndex(vec![0, 0, 0]), &digit("1")),
+                (TokenIndex(vec![0, 0, 2]), &digit("2")),
+                (TokenIndex(vec![0, 2]), &digit("3")),
+            ]
+        );
+
+        let indexes = found
+            .into_iter()
+            .map(|(token_index, _)| token_index)
+            .collect::<Vec<_>>();
+        for token_index in indexes {
+            *non_terminal.get_at_index_mut(token_index).unwrap() = digit("0");
+        }
+        assert_eq!(non_terminal.get_terminals(), "0+0+0");
+    }
+
+    #[test]
+    fn test_evaluate() {
+        let difference = |lhs, rhs| {
+            Token::from_non_terminal("difference", vec![lhs, Token::from_terminal("-"), rhs])
+        };
+        let non_terminal = difference(difference(digit("9"), digit("2")), digit("3"))
+            .to_non_terminal()
+            .unwrap();
+
+        let mut order = vec![];
+        let value = non_terminal.evaluate(|token, values: Vec<i32>| {
+            order.push(token.get_terminals());
+            match token.non_terminal_symbol.as_str() {
+                "digit" => token.get_terminals().parse().unwrap(),
+                _ => values[0] - values[1],
+            }
+        });
+
+        assert_eq!(value, 4);
+        assert_eq!(order, ["9", "2", "9-2", "3", "9-2-3"]);
+    }
 }
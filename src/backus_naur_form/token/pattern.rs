@@ -0,0 +1,334 @@
+//! Contains [ChildPattern], a small regex-like language to match the child [Token]s of a [NonTerminalToken].
+//! It makes compile functions that take apart the children of a choice shorter:
+//! ```rust, ignore
+//! use backus_naur_form_parser_and_compiler::backus_naur_form::token::pattern::{any, many, nt, t};
+//!
+//! //<sum> ::= <digit> "+" <digit> | <digit> "+" <digit> "+" ...
+//! let captures = sum.match_children(&[nt("digit"), t("+"), any(), many(any())])?;
+//! let lhs = &captures[0];
+//! let rhs = &captures[2];
+//! let rest = captures.get_all(3);
+//! ```
+//! Every element of the pattern captures the children it matched, in the same order as the pattern.
+//! [optional] and [many] try to match as many children as possible first and give some back if the rest of the pattern doesn't match.
+//!
+//! Children of a fixed shape can be destructured with [match_token!](crate::match_token) instead, which reads like a `match`:
+//! ```rust, ignore
+//! //<expression> ::= <digit> <operator> <digit>
+//! let compiled = match_token!(expression =>
+//!     expression(digit(a), "+", digit(b)) => format!("add({}, {})", a.get_terminals(), b.get_terminals()),
+//!     expression(_, op, _) => format!("unknown operator {}", op.get_terminals()),
+//! );
+//! ```
+
+use std::ops::Index;
+
+use super::{non_terminal_token::NonTerminalToken, Token};
+
+///A element of a pattern for [NonTerminalToken::match_children].
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub enum ChildPattern {
+    ///Matches a [NonTerminalToken] of the symbol (without angle brackets).
+    NonTerminal(String),
+    ///Matches a [TerminalToken](super::TerminalToken) with exactly this terminal.
+    Terminal(String),
+    ///Matches any single [Token].
+    Any,
+    ///Matches the pattern or nothing.
+    Optional(Box<ChildPattern>),
+    ///Matches the pattern any number of times (including zero times).
+    Many(Box<ChildPattern>),
+}
+
+///Matches a [NonTerminalToken] of the symbol (without angle brackets).
+pub fn nt(non_terminal_symbol: &str) -> ChildPattern {
+    ChildPattern::NonTerminal(non_terminal_symbol.to_string())
+}
+
+///Matches a [TerminalToken](super::TerminalToken) with exactly this terminal.
+pub fn t(terminal: &str) -> ChildPattern {
+    ChildPattern::Terminal(terminal.to_string())
+}
+
+///Matches any single [Token].
+pub fn any() -> ChildPattern {
+    ChildPattern::Any
+}
+
+///Matches the pattern or nothing.
+pub fn optional(pattern: ChildPattern) -> ChildPattern {
+    ChildPattern::Optional(Box::new(pattern))
+}
+
+///Matches the pattern any number of times (including zero times).
+pub fn many(pattern: ChildPattern) -> ChildPattern {
+    ChildPattern::Many(Box::new(pattern))
+}
+
+impl ChildPattern {
+    //Returns true if the pattern matches the single token. Optional and Many match what their pattern matches.
+    fn matches(&self, token: &Token) -> bool {
+        match self {
+            ChildPattern::NonTerminal(symbol) => {
+                !token.is_terminal() && token.get_symbol() == symbol
+            }
+            ChildPattern::Terminal(terminal) => {
+                token.is_terminal() && token.get_symbol() == terminal
+            }
+            ChildPattern::Any => true,
+            ChildPattern::Optional(pattern) | ChildPattern::Many(pattern) => pattern.matches(token),
+        }
+    }
+
+    //Returns the most tokens the pattern may match and the least.
+    fn repetitions(&self, available: usize) -> (usize, usize) {
+        match self {
+            ChildPattern::Optional(_) => (available.min(1), 0),
+            ChildPattern::Many(_) => (available, 0),
+            _ => (1, 1),
+        }
+    }
+}
+
+///The children that the elements of a pattern matched. Created with [NonTerminalToken::match_children].  
+///Indexing returns the first [Token] an element matched, [get_all](Captures::get_all) returns every [Token] it matched.
+#[derive(PartialEq, Debug)]
+pub struct Captures<'a> {
+    captures: Vec<&'a [Token]>,
+}
+
+impl<'a> Captures<'a> {
+    ///Returns the first [Token] the element of the pattern at the index matched.
+    ///Returns None if the element matched nothing (which [optional] and [many] can) or if there is no element at the index.
+    pub fn get(&self, index: usize) -> Option<&'a Token> {
+        self.captures.get(index)?.first()
+    }
+
+    ///Returns every [Token] the element of the pattern at the index matched.
+    ///
+    /// # Panics
+    /// Panics if the pattern has no element at the index.
+    pub fn get_all(&self, index: usize) -> &'a [Token] {
+        self.captures[index]
+    }
+
+    ///Returns the number of elements of the pattern.
+    pub fn len(&self) -> usize {
+        self.captures.len()
+    }
+
+    ///Returns true if the pattern was empty.
+    pub fn is_empty(&self) -> bool {
+        self.captures.is_empty()
+    }
+}
+
+impl Index<usize> for Captures<'_> {
+    type Output = Token;
+
+    ///Returns the first [Token] the element of the pattern at the index matched.
+    ///
+    /// # Panics
+    /// Panics if the element matched nothing or if there is no element at the index.
+    fn index(&self, index: usize) -> &Self::Output {
+        self.get(index)
+            .unwrap_or_else(|| panic!("the element {index} of the pattern matched nothing"))
+    }
+}
+
+//Matches the pattern against the tokens. The captures of the elements that matched are added to captures.
+fn match_sequence<'a>(
+    pattern: &[ChildPattern],
+    tokens: &'a [Token],
+    captures: &mut Vec<&'a [Token]>,
+) -> bool {
+    let Some((element, rest)) = pattern.split_first() else {
+        return tokens.is_empty();
+    };
+    //the number of leading tokens the element matches by itself
+    let matching = tokens
+        .iter()
+        .take_while(|token| element.matches(token))
+        .count();
+    let (most, least) = element.repetitions(matching);
+    if most > matching {
+        return false;
+    }
+    for count in (least..=most).rev() {
+        captures.push(&tokens[..count]);
+        if match_sequence(rest, &tokens[count..], captures) {
+            return true;
+        }
+        captures.pop();
+    }
+    false
+}
+
+impl NonTerminalToken {
+    ///Matches the children against the pattern and returns what every element of the pattern matched.
+    ///Returns None if the pattern doesn't match all of the children.
+    ///More information can be found in the [pattern](self) module.
+    pub fn match_children(&self, pattern: &[ChildPattern]) -> Option<Captures<'_>> {
+        let mut captures = vec![];
+        match_sequence(pattern, self.get_child_tokens(), &mut captures)
+            .then_some(Captures { captures })
+    }
+}
+
+///Destructures a [NonTerminalToken] by the positions of its children, the arms are tried from top to bottom.
+///Returns the value of the first arm whose pattern matches as [Some], [None] if no pattern matches.
+///More information can be found in the [pattern](crate::backus_naur_form::token::pattern) module.
+///
+///A pattern is the name of a symbol (an identifier or a string literal for names like `"mul-or-div"`)
+///with a pattern for every child in parentheses. A child pattern is one of:
+///- a nested pattern like `digit(a)`, which matches a [NonTerminalToken] of the symbol and its children
+///- a string literal like `"+"`, which matches a [TerminalToken](super::TerminalToken) with exactly this terminal
+///- `_`, which matches any [Token]
+///- a identifier like `op`, which matches any [Token] and binds it as a `&Token`
+///
+///The pattern only matches if the amount of children is the same as the amount of child patterns.
+#[macro_export]
+macro_rules! match_token {
+    ($token:expr => $($symbol:tt ($($children:tt)*) => $body:expr),+ $(,)?) => {{
+        let token: &$crate::backus_naur_form::token::non_terminal_token::NonTerminalToken = $token;
+        'matched: {
+            $(
+                let matched = 'arm: {
+                    $crate::__match_token_node!('arm, token, $symbol ($($children)*));
+                    Some($body)
+                };
+                if matched.is_some() {
+                    break 'matched matched;
+                }
+            )+
+            None
+        }
+    }};
+}
+
+//Checks the symbol and the children of the NonTerminalToken in the variable token, breaks the label with None if they don't match.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __match_token_node {
+    ($label:lifetime, $token:ident, $symbol:ident ($($children:tt)*)) => {
+        $crate::__match_token_node!($label, $token, (stringify!($symbol)) ($($children)*));
+    };
+    ($label:lifetime, $token:ident, $symbol:tt ($($children:tt)*)) => {
+        if $token.non_terminal_symbol != $symbol {
+            break $label None;
+        }
+        let mut children = $token.get_child_tokens().iter();
+        $crate::__match_token_children!($label, children, $($children)*);
+        if children.next().is_some() {
+            break $label None;
+        }
+    };
+}
+
+//Matches the child patterns one by one against the next children of the iterator.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __match_token_children {
+    ($label:lifetime, $children:ident $(,)?) => {};
+    ($label:lifetime, $children:ident, $symbol:tt ($($nested:tt)*) $(, $($rest:tt)*)?) => {
+        let Some($crate::backus_naur_form::token::Token::NonTerminalToken(child)) = $children.next() else {
+            break $label None;
+        };
+        $crate::__match_token_node!($label, child, $symbol ($($nested)*));
+        $crate::__match_token_children!($label, $children $(, $($rest)*)?);
+    };
+    ($label:lifetime, $children:ident, _ $(, $($rest:tt)*)?) => {
+        if $children.next().is_none() {
+            break $label None;
+        }
+        $crate::__match_token_children!($label, $children $(, $($rest)*)?);
+    };
+    ($label:lifetime, $children:ident, $terminal:literal $(, $($rest:tt)*)?) => {
+        match $children.next() {
+            Some($crate::backus_naur_form::token::Token::Terminal(terminal))
+                if terminal.get_terminals() == $terminal => {}
+            _ => break $label None,
+        }
+        $crate::__match_token_children!($label, $children $(, $($rest)*)?);
+    };
+    ($label:lifetime, $children:ident, $name:ident $(, $($rest:tt)*)?) => {
+        let Some($name) = $children.next() else {
+            break $label None;
+        };
+        $crate::__match_token_children!($label, $children $(, $($rest)*)?);
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backus_naur_form::test_helpers::digit;
+
+    #[test]
+    fn test_match_children() {
+        let plus = Token::from_terminal("+");
+        let sum = NonTerminalToken::new(
+            "sum",
+            vec![
+                digit("1"),
+                plus.clone(),
+                digit("2"),
+                plus.clone(),
+                digit("3"),
+            ],
+        );
+
+        let captures = sum
+            .match_children(&[nt("digit"), t("+"), any(), many(any())])
+            .unwrap();
+        assert_eq!(captures.len(), 4);
+        assert_eq!(captures[0], digit("1"));
+        assert_eq!(captures[2], digit("2"));
+        assert_eq!(captures.get_all(3), &[plus.clone(), digit("3")]);
+
+        //many gives tokens back so the rest of the pattern matches
+        let captures = sum
+            .match_children(&[many(any()), t("+"), nt("digit")])
+            .unwrap();
+        assert_eq!(captures.get_all(0).len(), 3);
+        assert_eq!(captures[2], digit("3"));
+
+        let captures = sum
+            .match_children(&[nt("digit"), optional(t("-")), many(any())])
+            .unwrap();
+        assert_eq!(captures.get(1), None);
+
+        assert_eq!(
+            sum.match_children(&[nt("digit"), t("+"), nt("digit")]),
+            None
+        );
+        assert_eq!(sum.match_children(&[t("1"), many(any())]), None);
+    }
+
+    #[test]
+    fn test_match_token() {
+        let expression = |operator| {
+            NonTerminalToken::new(
+                "expression",
+                vec![digit("1"), Token::from_terminal(operator), digit("2")],
+            )
+        };
+        let compile = |token: &NonTerminalToken| {
+            crate::match_token!(token =>
+                expression(digit("0"), _, _) => "zero".to_string(),
+                expression(digit(a), "+", digit(b)) => {
+                    format!("add({}, {})", a.get_terminals(), b.get_terminals())
+                },
+                "expression"(digit(a), op, _) => format!("{}{}", a.get_terminals(), op.get_terminals()),
+            )
+        };
+        assert_eq!(compile(&expression("+")), Some("add(1, 2)".to_string()));
+        assert_eq!(compile(&expression("-")), Some("1-".to_string()));
+
+        //the amount of children has to match
+        let sum = NonTerminalToken::new("expression", vec![digit("1"), digit("2")]);
+        assert_eq!(compile(&sum), None);
+        let product = NonTerminalToken::new("product", vec![digit("1")]);
+        assert_eq!(compile(&product), None);
+    }
+}
@@ -0,0 +1,161 @@
+//! Contains [SharedTree], a version of a AST where identical subtrees are stored only once.
+//! Inputs with a lot of repetition (for example generated inputs) make ASTs with many identical subtrees.
+//! [dedup_subtrees] turns such a AST into a directed acyclic graph whose nodes are stored in a arena and referred to by their index,
+//! so every distinct subtree takes up memory only once and a pass over the nodes only has to look at every distinct subtree once.
+//! ```rust, ignore
+//! let tree = dedup_subtrees(&bnf.symbolize_string(input));
+//! println!("{} tokens, {} distinct subtrees", tree.token_count(), tree.node_count());
+//! ```
+
+use std::collections::HashMap;
+
+use super::Token;
+
+///A node of a [SharedTree]. The children are the indexes of other nodes in the same [SharedTree].
+#[derive(PartialEq, Eq, Hash, Clone, Debug)]
+pub enum SharedNode {
+    Terminal(String),
+    NonTerminal {
+        non_terminal_symbol: String,
+        children: Vec<usize>,
+    },
+}
+
+///A AST where identical subtrees are shared. Created with [dedup_subtrees].
+///More information can be found in the [shared_tree](self) module.
+#[derive(PartialEq, Clone, Debug)]
+pub struct SharedTree {
+    //every child comes before its parent
+    nodes: Vec<SharedNode>,
+    roots: Vec<usize>,
+}
+
+///Turns the tokens (for example the result of [BackusNaurForm::symbolize_string](crate::BackusNaurForm::symbolize_string))
+///into a [SharedTree] where every distinct subtree is stored once.
+pub fn dedup_subtrees(tokens: &[Token]) -> SharedTree {
+    let mut nodes = vec![];
+    let mut indexes = HashMap::new();
+    let roots = tokens
+        .iter()
+        .map(|token| add_token(token, &mut nodes, &mut indexes))
+        .collect();
+    SharedTree { nodes, roots }
+}
+
+//Adds the token and its descendants to the nodes unless they already exist and returns the index of the token.
+fn add_token(
+    token: &Token,
+    nodes: &mut Vec<SharedNode>,
+    indexes: &mut HashMap<SharedNode, usize>,
+) -> usize {
+    let node = match token {
+        Token::Terminal(terminal) => SharedNode::Terminal(terminal.get_terminals().to_string()),
+        Token::NonTerminalToken(non_terminal) => SharedNode::NonTerminal {
+            non_terminal_symbol: non_terminal.non_terminal_symbol.to_string(),
+            children: non_terminal
+                .get_child_tokens()
+                .iter()
+                .map(|child| add_token(child, nodes, indexes))
+                .collect(),
+        },
+    };
+    //the children are already shared, so two nodes are the same subtree if they are equal
+    *indexes.entry(node).or_insert_with_key(|node| {
+        nodes.push(node.clone());
+        nodes.len() - 1
+    })
+}
+
+impl SharedTree {
+    ///Returns the node with the index.
+    ///
+    /// # Panics
+    /// Panics if the index is not the index of a node of this tree.
+    pub fn node(&self, index: usize) -> &SharedNode {
+        &self.nodes[index]
+    }
+
+    ///Returns every node. Children always come before their parents, so a pass over the nodes in this order
+    ///can use the results of the children when it reaches the parent.
+    pub fn nodes(&self) -> &[SharedNode] {
+        &self.nodes
+    }
+
+    ///Returns the indexes of the uppermost tokens in the order they had.
+    pub fn roots(&self) -> &[usize] {
+        &self.roots
+    }
+
+    ///Returns the number of distinct subtrees.
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    ///Returns the number of tokens the AST has without sharing.
+    pub fn token_count(&self) -> usize {
+        //the number of tokens of every node, children come before their parents
+        let mut counts: Vec<usize> = vec![];
+        for node in &self.nodes {
+            let count = match node {
+                SharedNode::Terminal(_) => 1,
+                SharedNode::NonTerminal { children, .. } => {
+                    1 + children.iter().map(|child| counts[*child]).sum::<usize>()
+                }
+            };
+            counts.push(count);
+        }
+        self.roots.iter().map(|root| counts[*root]).sum()
+    }
+
+    ///Turns the tree back into the tokens it was created from.
+    pub fn to_tokens(&self) -> Vec<Token> {
+        self.roots.iter().map(|root| self.to_token(*root)).collect()
+    }
+
+    ///Turns the node with the index and its descendants into a [Token].
+    pub fn to_token(&self, index: usize) -> Token {
+        match &self.nodes[index] {
+            SharedNode::Terminal(terminal) => Token::from_terminal(terminal),
+            SharedNode::NonTerminal {
+                non_terminal_symbol,
+                children,
+            } => Token::from_non_terminal(
+                non_terminal_symbol,
+                children.iter().map(|child| self.to_token(*child)).collect(),
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backus_naur_form::test_helpers::digit;
+
+    #[test]
+    fn test_dedup_subtrees() {
+        let pair = |first, second| Token::from_non_terminal("pair", vec![first, second]);
+        let tokens = vec![
+            pair(pair(digit("1"), digit("2")), pair(digit("1"), digit("2"))),
+            Token::from_terminal("1"),
+            pair(digit("1"), digit("2")),
+        ];
+        let tree = dedup_subtrees(&tokens);
+
+        //"1", "2", <digit> 1, <digit> 2, the inner <pair> and the outer <pair>
+        assert_eq!(tree.node_count(), 6);
+        assert_eq!(tree.token_count(), 17);
+        assert_eq!(tree.roots()[1], 0);
+        assert_eq!(tree.roots()[2], tree.roots()[0] - 1);
+        assert_eq!(tree.to_tokens(), tokens);
+
+        assert_eq!(
+            tokens[2].structural_hash(),
+            pair(digit("1"), digit("2")).structural_hash()
+        );
+        assert_ne!(
+            tokens[2].structural_hash(),
+            pair(digit("2"), digit("1")).structural_hash()
+        );
+    }
+}
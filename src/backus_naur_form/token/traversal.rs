@@ -0,0 +1,219 @@
+//! Contains iterators over the descendants of a [NonTerminalToken] in a fixed order.
+//! For the tree
+//! ```rust, ignore
+//!       a
+//!      / \
+//!     b   c
+//!    / \   \
+//!   d   e   f
+//! ```
+//! the descendants of `a` are returned in this order:
+//! - [pre-order](NonTerminalToken::descendants_preorder) (every token before its children): b, d, e, c, f
+//! - [post-order](NonTerminalToken::descendants_postorder) (every token after its children): d, e, b, f, c
+//! - [breadth-first](NonTerminalToken::descendants_bfs) (level by level): b, c, d, e, f
+//!
+//! Children are always returned from left to right and the token itself is never returned.
+//!
+//! The size of a tree can be measured with [Token::node_count], [Token::depth] and [Token::terminal_len],
+//! for example to enforce limits on the trees of untrusted input. Like the iterators they don't recurse,
+//! so they don't overflow the stack on deep trees.
+
+use std::collections::VecDeque;
+
+use super::{non_terminal_token::NonTerminalToken, Token};
+
+//Returns the children of the token, terminals have none.
+fn children(token: &Token) -> &[Token] {
+    match token {
+        Token::NonTerminalToken(non_terminal) => non_terminal.get_child_tokens(),
+        Token::Terminal(_) => &[],
+    }
+}
+
+///A iterator over the descendants of a [NonTerminalToken] in pre-order. Created with [NonTerminalToken::descendants_preorder].
+pub struct Preorder<'a> {
+    //the tokens that still have to be returned, the next one last
+    stack: Vec<&'a Token>,
+}
+
+impl<'a> Iterator for Preorder<'a> {
+    type Item = &'a Token;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let token = self.stack.pop()?;
+        self.stack.extend(children(token).iter().rev());
+        Some(token)
+    }
+}
+
+///A iterator over the descendants of a [NonTerminalToken] in post-order. Created with [NonTerminalToken::descendants_postorder].
+pub struct Postorder<'a> {
+    //the tokens whose children are being returned and the index of the next child
+    stack: Vec<(&'a [Token], usize)>,
+}
+
+impl<'a> Iterator for Postorder<'a> {
+    type Item = &'a Token;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (tokens, index) = self.stack.last_mut()?;
+            let Some(token) = tokens.get(*index) else {
+                self.stack.pop();
+                //every child of the parent was returned, so the parent is next
+                let (tokens, index) = self.stack.last_mut()?;
+                *index += 1;
+                return Some(&tokens[*index - 1]);
+            };
+            let token_children = children(token);
+            if token_children.is_empty() {
+                *index += 1;
+                return Some(token);
+            }
+            self.stack.push((token_children, 0));
+        }
+    }
+}
+
+///A iterator over the descendants of a [NonTerminalToken] in breadth-first order. Created with [NonTerminalToken::descendants_bfs].
+pub struct BreadthFirst<'a> {
+    queue: VecDeque<&'a Token>,
+}
+
+impl<'a> Iterator for BreadthFirst<'a> {
+    type Item = &'a Token;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let token = self.queue.pop_front()?;
+        self.queue.extend(children(token));
+        Some(token)
+    }
+}
+
+impl NonTerminalToken {
+    ///Returns a iterator over the descendants where every token comes before its children.
+    ///More information can be found in the [traversal](self) module.
+    pub fn descendants_preorder(&self) -> Preorder<'_> {
+        Preorder {
+            stack: self.get_child_tokens().iter().rev().collect(),
+        }
+    }
+
+    ///Returns a iterator over the descendants where every token comes after its children.
+    ///More information can be found in the [traversal](self) module.
+    pub fn descendants_postorder(&self) -> Postorder<'_> {
+        Postorder {
+            stack: vec![(self.get_child_tokens(), 0)],
+        }
+    }
+
+    ///Returns a iterator over the descendants that returns the children first, then the grandchildren and so on.
+    ///More information can be found in the [traversal](self) module.
+    pub fn descendants_bfs(&self) -> BreadthFirst<'_> {
+        BreadthFirst {
+            queue: self.get_child_tokens().iter().collect(),
+        }
+    }
+}
+
+impl Token {
+    ///Returns the amount of tokens in the tree: self and all of its descendants.
+    pub fn node_count(&self) -> usize {
+        Preorder { stack: vec![self] }.count()
+    }
+
+    ///Returns the amount of tokens on the longest path from self down to a leaf.
+    ///A [TerminalToken](super::TerminalToken) (or a [NonTerminalToken] without children) has a depth of 1.
+    pub fn depth(&self) -> usize {
+        let mut depth = 0;
+        let mut stack = vec![(self, 1)];
+        while let Some((token, token_depth)) = stack.pop() {
+            depth = depth.max(token_depth);
+            stack.extend(children(token).iter().map(|child| (child, token_depth + 1)));
+        }
+        depth
+    }
+
+    ///Returns the amount of chars of the terminals in the tree, which is the length of [get_terminals](Token::get_terminals)
+    ///without building the [String].
+    pub fn terminal_len(&self) -> usize {
+        Preorder { stack: vec![self] }
+            .filter_map(Token::to_terminal_token_ref)
+            .map(|terminal| terminal.length())
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_traversal_order() {
+        let leaf = |name| Token::from_non_terminal(name, vec![]);
+        let tree = NonTerminalToken::new(
+            "a",
+            vec![
+                Token::from_non_terminal("b", vec![leaf("d"), Token::from_terminal("e")]),
+                Token::from_non_terminal("c", vec![leaf("f")]),
+            ],
+        );
+        let symbols = |tokens: Vec<&Token>| {
+            tokens
+                .into_iter()
+                .map(|token| token.get_symbol().to_string())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            symbols(tree.descendants_preorder().collect()),
+            ["b", "d", "e", "c", "f"]
+        );
+        assert_eq!(
+            symbols(tree.descendants_postorder().collect()),
+            ["d", "e", "b", "f", "c"]
+        );
+        assert_eq!(
+            symbols(tree.descendants_bfs().collect()),
+            ["b", "c", "d", "e", "f"]
+        );
+        assert_eq!(
+            tree.descendants_preorder().collect::<Vec<_>>(),
+            tree.get_descendant_tokens()
+        );
+        assert_eq!(
+            NonTerminalToken::new("a", vec![])
+                .descendants_postorder()
+                .next(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_metrics() {
+        let tree = Token::from_non_terminal(
+            "a",
+            vec![
+                Token::from_non_terminal(
+                    "b",
+                    vec![Token::from_terminal("äb"), Token::from_terminal("c")],
+                ),
+                Token::from_terminal("d"),
+            ],
+        );
+        assert_eq!(tree.node_count(), 5);
+        assert_eq!(tree.depth(), 3);
+        assert_eq!(tree.terminal_len(), 4);
+        assert_eq!(Token::from_terminal("e").depth(), 1);
+
+        //deep trees don't overflow the stack
+        let deep = (0..100_000).fold(Token::from_terminal("x"), |token, _| {
+            Token::from_non_terminal("n", vec![token])
+        });
+        assert_eq!(deep.node_count(), 100_001);
+        assert_eq!(deep.depth(), 100_001);
+        assert_eq!(deep.terminal_len(), 1);
+        //dropping the tree is recursive, so it is leaked instead
+        std::mem::forget(deep);
+    }
+}
@@ -0,0 +1,169 @@
+//! Contains [BackusNaurForm::symbolize_with_trace], which returns every [Reduction] in the order it was applied.
+//! This is meant for debugging grammars: the trace shows why the AST has the shape it has.
+//!
+//! # Order of the reductions
+//! The reductions are applied in a fixed order, so the same input always results in the same AST:
+//! 1. The rules are applied in passes until no rule can be applied anymore.
+//!    Every pass applies the rules from the highest priority to the lowest.
+//!    Rules with the same priority are applied in the reverse order in which they were added.
+//! 2. When a rule is applied, its matches are chosen by position: the leftmost match always wins over the matches that overlap it.
+//!    Only if several choices match at the same position, the choice that is specified first wins.
+//!    All matches that don't overlap are reduced in one step.
+//! 3. Recursive choices (choices that contain the symbol itself) are applied after the other choices and repeated
+//!    until none of them matches anymore.
+//!
+//! [BackusNaurForm::effective_order] lists the rules in the order of step 1, so the order doesn't have to be worked out by hand.
+//!
+//! If brackets are added with [BackusNaurForm::add_bracket_pair], every bracketed region is symbolized on its own first.
+//! The [Reduction::range]s of the reductions in a region index into the tokens of that region.
+//! ```rust, ignore
+//! //with <digit> ::= "1" | "2" and <sum> ::= <digit> "+" <digit>
+//! let (_, trace) = bnf.symbolize_with_trace("1+2")?;
+//! //<digit> 0..1 "1", <digit> 2..3 "2", <sum> 0..3 "1+2"
+//! ```
+
+use std::ops::Range;
+
+use super::{error::SymbolizeError, symbol::Symbol, token::Token, BackusNaurForm};
+
+///A reduction of a range of [Token]s into a [NonTerminalToken](super::token::non_terminal_token::NonTerminalToken).
+#[derive(PartialEq, Clone, Debug)]
+pub struct Reduction {
+    ///The name of the symbol of the new token.
+    pub symbol: String,
+    ///The symbols of the choice that matched.
+    pub choice: Vec<Symbol>,
+    ///The priority of the rule.
+    pub priority: usize,
+    ///The range of the reduced tokens in the tokens as they were before the step the reduction is part of.
+    ///The reductions of one step are ordered from left to right, so their ranges are sorted.
+    pub range: Range<usize>,
+    ///The terminals of the reduced tokens.
+    pub terminals: String,
+}
+
+impl BackusNaurForm<'_> {
+    ///The same as [try_symbolize_string](BackusNaurForm::try_symbolize_string), but also returns every [Reduction] in the order it was applied.
+    ///More information about the order can be found in the [trace](self) module.
+    pub fn symbolize_with_trace(
+        &self,
+        string: &str,
+    ) -> Result<(Vec<Token>, Vec<Reduction>), SymbolizeError> {
+        let mut reductions = vec![];
+        let tokens =
+            self.symbolize_observed(string, true, &mut |(rule, priority), range, tokens| {
+                reductions.push(Reduction {
+                    symbol: rule.get_name().to_string(),
+                    choice: tokens.iter().map(Token::get_type).collect(),
+                    priority: *priority,
+                    range,
+                    terminals: tokens.iter().map(Token::get_terminals).collect(),
+                });
+                Ok(())
+            })?;
+        Ok((tokens, reductions))
+    }
+
+    ///Returns the name, the priority and the index (in the order the rules were added) of every rule,
+    ///in the order every pass tries them: the highest priority first and rules with the same priority in the reverse order in which they were added.
+    ///So of two rules with the same priority, the one that was added later wins if both match.
+    ///Inline groups and uses of parameterized rules are rules of their own and are listed too.
+    pub fn effective_order(&self) -> Vec<(String, usize, usize)> {
+        let mut order = self
+            .rules
+            .iter()
+            .enumerate()
+            .map(|(index, (non_terminal_symbol, priority))| {
+                (non_terminal_symbol.get_name().to_string(), *priority, index)
+            })
+            .collect::<Vec<_>>();
+        //the same order as sort_rules, which sorts by the priority and reverses the result
+        order.sort_by_key(|(_, priority, index)| std::cmp::Reverse((*priority, *index)));
+        order
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backus_naur_form::test_helpers::DIGIT_RULE;
+
+    #[test]
+    fn test_symbolize_with_trace() {
+        let bnf = crate::backus_naur_form!(
+            priority 2 => r#"<digit> ::= "1" | "2" | "3""#
+            priority 1 => r#"<signed> ::= <digit> | "-" <digit>"#
+            priority 0 => r#"<sum> ::= <signed> "+" <signed> | <sum> "+" <signed>"#
+        );
+        let terminal = |terminal: &str| Symbol::Terminal(terminal.to_string());
+        let non_terminal = |name: &str| Symbol::NonTerminal(name.to_string());
+
+        let (tokens, reductions) = bnf.symbolize_with_trace("-1+2+3").unwrap();
+        assert_eq!(tokens.len(), 1);
+        assert_eq!(
+            reductions
+                .iter()
+                .map(|reduction| (
+                    reduction.symbol.as_str(),
+                    reduction.range.clone(),
+                    reduction.terminals.as_str()
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                ("digit", 1..2, "1"),
+                ("digit", 3..4, "2"),
+                ("digit", 5..6, "3"),
+                //<digit> is the earlier choice, but "-" <digit> starts further left
+                ("signed", 0..2, "-1"),
+                ("signed", 3..4, "2"),
+                ("signed", 5..6, "3"),
+                ("sum", 0..3, "-1+2"),
+                ("sum", 0..3, "-1+2+3"),
+            ]
+        );
+        assert_eq!(
+            reductions[3].choice,
+            vec![terminal("-"), non_terminal("digit")]
+        );
+        assert_eq!(reductions[3].priority, 1);
+    }
+
+    #[test]
+    fn test_effective_order() {
+        let bnf = crate::backus_naur_form!(
+            priority 1 => DIGIT_RULE
+            priority 0 => r#"<a> ::= <digit>"#
+            priority 0 => r#"<b> ::= <digit>"#
+            priority 1 => r#"<letter> ::= "x""#
+        );
+
+        assert_eq!(
+            bnf.effective_order(),
+            vec![
+                ("letter".to_string(), 1, 3),
+                ("digit".to_string(), 1, 0),
+                ("b".to_string(), 0, 2),
+                ("a".to_string(), 0, 1),
+            ]
+        );
+        //<b> is tried before <a>, so it wins
+        assert_eq!(bnf.symbolize_string("1")[0].get_symbol(), "b");
+    }
+
+    #[test]
+    fn test_leftmost_match_wins() {
+        //the later choice matches further left, so it wins over the earlier choice
+        let bnf = crate::backus_naur_form!(
+            priority 1 => r#"<digit> ::= "1" | "2" | "3""#
+            priority 0 => r#"<pair> ::= <digit> "*" <digit> | <digit> "+" <digit>"#
+        );
+        let (_, reductions) = bnf.symbolize_with_trace("1+2*3").unwrap();
+        let pairs = reductions
+            .iter()
+            .filter(|reduction| reduction.symbol == "pair")
+            .map(|reduction| reduction.terminals.as_str())
+            .collect::<Vec<_>>();
+
+        assert_eq!(pairs, vec!["1+2"]);
+    }
+}
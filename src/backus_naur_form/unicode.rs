@@ -0,0 +1,192 @@
+//! Pseudo terminals for Unicode classes (`unicode` feature), so identifier rules work for non-ASCII languages
+//! without listing every char:
+//! ```rust, ignore
+//! let bnf = backus_naur_form!(
+//!     priority 0 => r#"<ident> ::= <ID_Start> | <ident> <ident> | <ident> <ID_Continue>"#
+//! );
+//! //"größe", "x_1" and "変数" are all a <ident>
+//! ```
+//! Every char belongs to a single class (see below), so the letters are `<ID_Start>` tokens and never `<ID_Continue>` tokens.
+//! A identifier of several letters needs the list choice `<ident> <ident>`, `<ident> ::= <ID_Start> | <ident> <ID_Continue>`
+//! on its own turns "ab" into two `<ident>`s.
+//! The pseudo terminals are `<L>` (letters), `<Nd>` (decimal digits), `<ID_Start>` and `<ID_Continue>`
+//! (the chars that can start or continue a identifier, see [UAX #31](https://www.unicode.org/reports/tr31/)).
+//! A symbol with one of these names is only a pseudo terminal if it has no rule of its own.
+//!
+//! Every char that no other rule turned into a token becomes a token of the first class that contains it
+//! in the order `<Nd>`, `<L>`, `<ID_Start>`, `<ID_Continue>` (only the classes the rules use count).
+//! So a grammar that uses `<L>` and `<ID_Continue>` gets the letters as `<L>` and the other identifier chars as `<ID_Continue>`.
+//! The class rules are applied after every other rule and their tokens are hidden, so the chars are children of the tokens that use them.
+
+use std::borrow::Cow;
+
+use unicode_properties::{GeneralCategory, GeneralCategoryGroup, UnicodeGeneralCategory};
+
+use super::{
+    symbol::{non_terminal_symbol::NonTerminalSymbol, Symbol},
+    token::Token,
+    BackusNaurForm, Choice,
+};
+
+///A class of Unicode chars that can be used as a pseudo terminal. More information can be found in the [unicode](self) module.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum UnicodeClass {
+    ///`<Nd>`, the chars of the general category decimal number.
+    DecimalNumber,
+    ///`<L>`, the chars of the general categories of letters.
+    Letter,
+    ///`<ID_Start>`, the chars that can start a identifier (XID_Start).
+    IdStart,
+    ///`<ID_Continue>`, the chars that can be in a identifier after its first char (XID_Continue).
+    ///If `<L>` or `<ID_Start>` are used too, it only gets the chars that aren't in them, like `_` and the digits (and the digits go to `<Nd>` if that is used).
+    IdContinue,
+}
+
+impl UnicodeClass {
+    ///Every class, in the order in which they take the chars.
+    pub const ALL: [UnicodeClass; 4] = [
+        UnicodeClass::DecimalNumber,
+        UnicodeClass::Letter,
+        UnicodeClass::IdStart,
+        UnicodeClass::IdContinue,
+    ];
+
+    ///Returns the name of the pseudo terminal without the angle brackets, for example `L`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            UnicodeClass::DecimalNumber => "Nd",
+            UnicodeClass::Letter => "L",
+            UnicodeClass::IdStart => "ID_Start",
+            UnicodeClass::IdContinue => "ID_Continue",
+        }
+    }
+
+    ///Returns the class with the name (without the angle brackets), None if there is no such class.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|class| class.name() == name)
+    }
+
+    ///Returns true if the char belongs to the class.
+    pub fn contains(&self, char: char) -> bool {
+        match self {
+            UnicodeClass::DecimalNumber => {
+                char.general_category() == GeneralCategory::DecimalNumber
+            }
+            UnicodeClass::Letter => char.general_category_group() == GeneralCategoryGroup::Letter,
+            UnicodeClass::IdStart => unicode_ident::is_xid_start(char),
+            UnicodeClass::IdContinue => unicode_ident::is_xid_continue(char),
+        }
+    }
+}
+
+impl BackusNaurForm<'_> {
+    ///Returns true if the symbol (without the angle brackets) is a Unicode class that is used as a pseudo terminal,
+    ///which is the case if it has no rule of its own.
+    pub fn is_unicode_class(&self, name: &str) -> bool {
+        UnicodeClass::from_name(name).is_some() && !self.contains_symbol(name)
+    }
+
+    //Adds the rules of the Unicode classes the choices use to the rules: every class gets the chars of the terminal tokens
+    //that it takes. The rules have the priority 0, so they are applied after the rules that were added before them.
+    pub(super) fn add_unicode_class_rules<'r>(
+        &self,
+        mut rules: Cow<'r, [(NonTerminalSymbol, usize)]>,
+        tokens: &[Token],
+    ) -> Cow<'r, [(NonTerminalSymbol, usize)]> {
+        let used = UnicodeClass::ALL
+            .into_iter()
+            .filter(|class| {
+                self.is_unicode_class(class.name())
+                    && self.rules.iter().any(|(non_terminal_symbol, _)| {
+                        non_terminal_symbol
+                            .get_rule()
+                            .iter()
+                            .any(|choice| choice.contains(&Symbol::non_terminal(class.name())))
+                    })
+            })
+            .collect::<Vec<_>>();
+        if used.is_empty() {
+            return rules;
+        }
+
+        let mut choices = vec![Vec::<Choice>::new(); used.len()];
+        for token in tokens {
+            let Token::Terminal(terminal) = token else {
+                continue;
+            };
+            let mut chars = terminal.get_terminals().chars();
+            let (Some(char), None) = (chars.next(), chars.next()) else {
+                continue;
+            };
+            let Some(index) = used.iter().position(|class| class.contains(char)) else {
+                continue;
+            };
+            let choice = [Symbol::terminal(&char.to_string())].into_iter().collect();
+            if !choices[index].contains(&choice) {
+                choices[index].push(choice);
+            }
+        }
+        for (class, choices) in used.into_iter().zip(choices) {
+            if !choices.is_empty() {
+                let class_rule = NonTerminalSymbol::new(class.name().to_string(), choices);
+                rules.to_mut().push((class_rule, 0));
+            }
+        }
+        rules
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unicode_classes() {
+        assert!(UnicodeClass::Letter.contains('ß'));
+        assert!(UnicodeClass::DecimalNumber.contains('٣'));
+        assert!(!UnicodeClass::IdStart.contains('1'));
+        assert!(UnicodeClass::IdContinue.contains('1'));
+        assert_eq!(
+            UnicodeClass::from_name("ID_Start"),
+            Some(UnicodeClass::IdStart)
+        );
+
+        let bnf = crate::backus_naur_form!(
+            priority 1 => r#"<keyword> ::= "i" "f""#
+            priority 0 => r#"<ident> ::= <L> | <ident> <ident> | <ident> <Nd>"#
+        );
+        let ident = |input: &str| {
+            let tokens = bnf.symbolize_string(input);
+            tokens.len() == 1 && tokens[0].get_symbol() == "ident"
+        };
+        assert!(ident("größe2"));
+        assert!(ident("変数"));
+        assert!(!ident("2a"));
+        //the chars are children of the tokens that use the classes
+        assert_eq!(
+            bnf.symbolize_string("ä"),
+            vec![Token::from_non_terminal(
+                "ident",
+                vec![Token::from_terminal("ä")]
+            )]
+        );
+        //the other rules are applied first
+        assert_eq!(bnf.symbolize_string("if")[0].get_symbol(), "keyword");
+        assert!(bnf.is_unicode_class("L"));
+        assert!(bnf.unknown_symbols().is_empty());
+
+        let bnf = crate::backus_naur_form!(
+            priority 0 => r#"<ident> ::= <ID_Start> | <ident> <ident> | <ident> <ID_Continue>"#
+        );
+        let ident = |input: &str| {
+            let tokens = bnf.symbolize_string(input);
+            tokens.len() == 1 && tokens[0].get_symbol() == "ident"
+        };
+        assert!(ident("ab"));
+        assert!(ident("größe"));
+        assert!(ident("x_1"));
+        assert!(ident("変数"));
+        assert!(!ident("1a"));
+        assert!(!ident("_a"));
+    }
+}
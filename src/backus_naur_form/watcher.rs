@@ -0,0 +1,227 @@
+//! Reloads a grammar file whenever it changes (needs the `notify` feature).
+//! A running REPL or server keeps a [GrammarHandle] and gets the newest grammar from it for every input:
+//! ```rust, ignore
+//! let watcher = GrammarWatcher::watch("grammar.bnf", FileResolver::new())?;
+//! let handle = watcher.handle();
+//! loop {
+//!     let bnf = handle.load().to_backus_naur_form();
+//!     //symbolize the next input with bnf
+//! }
+//! ```
+//! The grammar is swapped atomically, so every reader either gets the old or the new grammar and never has to wait for a reload.
+//! If the changed file is not a valid grammar, the old grammar is kept and the error is available through [GrammarWatcher::last_error].
+//!
+//! The grammar is reloaded once no change came in for a short time, so a file that is written in several steps is only loaded once it is complete.
+//! The directory of the grammar file is watched, so included documents in the same directory trigger a reload too.
+
+use std::{
+    path::{Path, PathBuf},
+    sync::{mpsc, Arc, Mutex},
+    time::Duration,
+};
+
+use arc_swap::ArcSwap;
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+
+use super::{
+    compiled::CompiledGrammar,
+    error::{CompiledGrammarError, GrammarError},
+    grammar::GrammarResolver,
+};
+
+//How long no event has to come in before the grammar is reloaded.
+const DEBOUNCE_TIME: Duration = Duration::from_millis(50);
+
+///A handle to the current version of a watched grammar. Cloning it is cheap and every clone sees the reloads.
+#[derive(Clone, Debug)]
+pub struct GrammarHandle {
+    grammar: Arc<ArcSwap<CompiledGrammar>>,
+}
+
+impl GrammarHandle {
+    ///Returns the current grammar. The returned grammar stays the same even if the file is reloaded while it is used.
+    pub fn load(&self) -> Arc<CompiledGrammar> {
+        self.grammar.load_full()
+    }
+}
+
+///Watches a grammar file and reloads it whenever it (or a document next to it) changes.
+///The file stops being watched when the [GrammarWatcher] is dropped.
+pub struct GrammarWatcher {
+    handle: GrammarHandle,
+    last_error: Arc<Mutex<Option<GrammarError>>>,
+    //kept so the file stays watched
+    _watcher: RecommendedWatcher,
+}
+
+impl GrammarWatcher {
+    ///Compiles the grammar file at the path (see [CompiledGrammar::compile_file]) and starts watching it.
+    ///Included documents are resolved using the resolver.
+    pub fn watch<R>(path: impl AsRef<Path>, resolver: R) -> Result<Self, CompiledGrammarError>
+    where
+        R: GrammarResolver + Send + 'static,
+    {
+        let path = path.as_ref().to_path_buf();
+        let grammar = CompiledGrammar::compile_file(&path.display().to_string(), &resolver)?;
+        let handle = GrammarHandle {
+            grammar: Arc::new(ArcSwap::from_pointee(grammar)),
+        };
+        let last_error = Arc::new(Mutex::new(None));
+
+        let reload = Reload {
+            path: path.to_path_buf(),
+            resolver,
+            handle: handle.clone(),
+            last_error: Arc::clone(&last_error),
+        };
+        let watch_error = |error: notify::Error| CompiledGrammarError::Io {
+            path: path.display().to_string(),
+            message: error.to_string(),
+        };
+        //the events are handled on a thread of their own, so a burst of events (for example truncating and writing the file)
+        //only reloads the grammar once and the half written file in between is never loaded
+        let (sender, receiver) = mpsc::channel();
+        std::thread::spawn(move || {
+            //ends once the watcher is dropped, since that drops the sender
+            while receiver.recv().is_ok() {
+                while receiver.recv_timeout(DEBOUNCE_TIME).is_ok() {}
+                reload.reload();
+            }
+        });
+        let mut watcher =
+            notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+                if let Ok(event) = event {
+                    if matches!(
+                        event.kind,
+                        EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)
+                    ) {
+                        _ = sender.send(());
+                    }
+                }
+            })
+            .map_err(watch_error)?;
+        //editors often replace the file instead of writing to it, so the directory is watched instead of the file
+        let directory = match path.parent() {
+            Some(directory) if !directory.as_os_str().is_empty() => directory,
+            _ => Path::new("."),
+        };
+        watcher
+            .watch(directory, RecursiveMode::NonRecursive)
+            .map_err(watch_error)?;
+
+        Ok(Self {
+            handle,
+            last_error,
+            _watcher: watcher,
+        })
+    }
+
+    ///Returns a handle to the current version of the grammar.
+    pub fn handle(&self) -> GrammarHandle {
+        self.handle.clone()
+    }
+
+    ///Returns the current grammar, the same as `self.handle().load()`.
+    pub fn grammar(&self) -> Arc<CompiledGrammar> {
+        self.handle.load()
+    }
+
+    ///Returns the error of the last reload, or None if it succeeded.
+    pub fn last_error(&self) -> Option<GrammarError> {
+        self.last_error
+            .lock()
+            .expect("reloading never panics while holding the lock")
+            .clone()
+    }
+}
+
+//Everything the watcher thread needs to reload the grammar.
+struct Reload<R> {
+    path: PathBuf,
+    resolver: R,
+    handle: GrammarHandle,
+    last_error: Arc<Mutex<Option<GrammarError>>>,
+}
+
+impl<R: GrammarResolver> Reload<R> {
+    //Compiles the grammar again and swaps it in if it changed.
+    //If it can't be compiled, the old grammar is kept and the error is stored.
+    fn reload(&self) {
+        let path = self.path.display().to_string();
+        //rules that can't be parsed panic, so a broken edit must not take the watcher thread down
+        let compiled = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            CompiledGrammar::compile_file(&path, &self.resolver)
+        }))
+        .unwrap_or_else(|_| {
+            Err(GrammarError::Syntax {
+                document: path.to_string(),
+                line: 0,
+                message: "a rule could not be parsed".to_string(),
+            })
+        });
+
+        let error = match compiled {
+            Ok(grammar) => {
+                if grammar.get_source_hash() != self.handle.load().get_source_hash() {
+                    self.handle.grammar.store(Arc::new(grammar));
+                }
+                None
+            }
+            Err(error) => Some(error),
+        };
+        *self
+            .last_error
+            .lock()
+            .expect("reloading never panics while holding the lock") = error;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::backus_naur_form::test_helpers::DIGIT_RULE;
+    use std::time::Instant;
+
+    use crate::backus_naur_form::grammar::FileResolver;
+
+    use super::*;
+
+    //Waits until the condition is true or panics after a few seconds.
+    fn wait_until(condition: impl Fn() -> bool) {
+        let start = Instant::now();
+        while !condition() {
+            assert!(
+                start.elapsed() < Duration::from_secs(10),
+                "the grammar wasn't reloaded"
+            );
+            std::thread::sleep(Duration::from_millis(20));
+        }
+    }
+
+    #[test]
+    fn test_grammar_watcher() {
+        let directory =
+            std::env::temp_dir().join(format!("test_grammar_watcher-{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+        let path = directory.join("grammar.bnf");
+        std::fs::write(&path, r#"<digit> ::= "1""#).unwrap();
+
+        let watcher = GrammarWatcher::watch(&path, FileResolver::new()).unwrap();
+        let handle = watcher.handle();
+        let old = handle.load();
+        assert!(old.to_backus_naur_form().parse("1").is_ok());
+
+        std::fs::write(&path, DIGIT_RULE).unwrap();
+        wait_until(|| handle.load().get_source_hash() != old.get_source_hash());
+        assert!(handle.load().to_backus_naur_form().parse("2").is_ok());
+        //the grammar that was loaded before is still usable
+        assert!(old.to_backus_naur_form().parse("2").is_err());
+
+        //a invalid grammar keeps the old one
+        std::fs::write(&path, "%import \"digits.bnf\"").unwrap();
+        wait_until(|| watcher.last_error().is_some());
+        assert!(watcher.grammar().to_backus_naur_form().parse("2").is_ok());
+
+        drop(watcher);
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+}
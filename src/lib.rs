@@ -1,14 +1,83 @@
+//makes the paths that the proc macros generate work inside of this crate too
+#[cfg(all(test, feature = "macros"))]
+extern crate self as backus_naur_form_parser_and_compiler;
+
 ///Contains everything relevant for the backus naur form, such as the creation of it
 ///and the tokenization aswell as possible compilation after the tokenization.
 pub mod backus_naur_form;
 
+///The parsed rules of a grammar document that can be saved and loaded.
+pub use backus_naur_form::compiled::CompiledGrammar;
+///The encodings bytes can be symbolized in.
+#[cfg(feature = "encoding")]
+pub use backus_naur_form::encoding::Encoding;
+///Errors that can occur while saving or loading a compiled grammar.
+pub use backus_naur_form::error::CompiledGrammarError;
+///Errors that can occur while validating a example corpus.
+pub use backus_naur_form::error::CorpusError;
+///Errors that can occur while loading a grammar document.
+pub use backus_naur_form::error::GrammarError;
+///Errors that can occur while parsing a string into a single root token.
+pub use backus_naur_form::error::ParseError;
+///Errors that can occur while rewriting parts of a string.
+pub use backus_naur_form::error::RewriteError;
+///Errors that can occur while getting the single root token of a string.
+pub use backus_naur_form::error::RootError;
+///Errors that can occur while symbolizing a string.
+pub use backus_naur_form::error::SymbolizeError;
+///Replaces chars with escape sequences in the compiled output, for example `<` with `&lt;` for HTML.
+pub use backus_naur_form::escape::Escaper;
+///Resolves grammar documents from the file system.
+pub use backus_naur_form::grammar::FileResolver;
+///Finds the grammar documents that are included with `%include`.
+pub use backus_naur_form::grammar::GrammarResolver;
+///The kinds of tokens of a lexer whose tokens are symbolized.
+pub use backus_naur_form::lexer::TerminalKind;
+///Something that turns the input into tokens, usually a lexer.
+pub use backus_naur_form::lexer::TerminalSource;
+///Chains the steps that turn a input into a result.
+pub use backus_naur_form::pipeline::Pipeline;
+///The trees of several inputs that were symbolized with the same grammar.
+pub use backus_naur_form::project::ProjectAst;
+///A input of a project and its id.
+pub use backus_naur_form::project::Source;
+///Stores several versions of several grammars.
+#[cfg(feature = "registry")]
+pub use backus_naur_form::registry::GrammarRegistry;
+///A read-only view of a rule of a backus naur form.
+pub use backus_naur_form::rule::Rule;
+///Converts byte offsets of a string into lines and columns and back.
+pub use backus_naur_form::source_map::SourceMap;
 ///Used as a "type" (for example `<number>`).
 pub use backus_naur_form::symbol::Symbol;
+///The uppermost tokens of a symbolized string with their spans.
+pub use backus_naur_form::symbolize_result::SymbolizeResult;
 ///Represents the nodes of the token tree that is made using a backus naur form.
 pub use backus_naur_form::token::non_terminal_token::NonTerminalToken;
 ///Represents the leaves of the token tree that is made using a backus naur form.
 pub use backus_naur_form::token::TerminalToken;
 ///Enum that contains either a terminal token or a non terminal token.
 pub use backus_naur_form::token::Token;
+///Reloads a grammar file whenever it changes.
+#[cfg(feature = "notify")]
+pub use backus_naur_form::watcher::GrammarWatcher;
 ///Contains the actual backus naur form.
 pub use backus_naur_form::BackusNaurForm;
+///The same as the backus_naur_form! macro, but the rules are parsed at compile time.
+///Malformed arms are compile errors too:
+///```compile_fail
+/////the arm doesn't start with `priority`
+///let bnf = backus_naur_form_parser_and_compiler::static_backus_naur_form!(0 => r#"<digit> ::= "1""#);
+///```
+///```compile_fail
+/////the `=>` between the priority and the rule is missing
+///let bnf = backus_naur_form_parser_and_compiler::static_backus_naur_form!(priority 0 r#"<digit> ::= "1""#);
+///```
+///```compile_fail
+/////the compile function has to take the token and the backus naur form
+///let bnf = backus_naur_form_parser_and_compiler::static_backus_naur_form!(
+///    priority 0 => r#"<digit> ::= "1""# => |digit| digit.get_terminals()
+///);
+///```
+#[cfg(feature = "macros")]
+pub use backus_naur_form_macros::static_backus_naur_form;